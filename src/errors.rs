@@ -59,6 +59,15 @@ impl From<base64::DecodeError> for UnknownCryptoError {
     }
 }
 
+#[cfg(feature = "safe_api")]
+impl From<UnknownCryptoError> for std::io::Error {
+    // std::io::Error::other() requires Rust 1.74.
+    #[allow(clippy::io_other_error)]
+    fn from(err: UnknownCryptoError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, err)
+    }
+}
+
 impl From<core::num::ParseIntError> for UnknownCryptoError {
     fn from(_: core::num::ParseIntError) -> Self {
         UnknownCryptoError
@@ -144,3 +153,11 @@ fn test_unknown_crypto_from_parseint_error() {
     );
     assert_eq!(err, "UnknownCryptoErrorUnknownCryptoError");
 }
+
+#[test]
+#[cfg(feature = "safe_api")]
+fn test_io_error_from_unknown_crypto() {
+    let err = std::io::Error::from(UnknownCryptoError);
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    assert_eq!(format!("{}", err), "UnknownCryptoError");
+}
@@ -102,7 +102,8 @@ use crate::{
     errors::UnknownCryptoError,
     hazardous::{
         mac::poly1305::{OneTimeKey, Poly1305, POLY1305_KEYSIZE, POLY1305_OUTSIZE},
-        stream::chacha20::{self, ChaCha20, CHACHA_BLOCKSIZE},
+        stream::chacha20::{self, ChaCha20State, CHACHA_BLOCKSIZE},
+        traits::Aead,
     },
     util,
 };
@@ -117,7 +118,7 @@ const AUTH_CTR: u32 = 0;
 
 /// Poly1305 key generation using IETF ChaCha20.
 pub(crate) fn poly1305_key_gen(
-    ctx: &mut ChaCha20,
+    ctx: &mut ChaCha20State,
     tmp_buffer: &mut Zeroizing<[u8; CHACHA_BLOCKSIZE]>,
 ) -> OneTimeKey {
     ctx.keystream_block(AUTH_CTR, tmp_buffer.as_mut());
@@ -163,7 +164,7 @@ pub fn seal(
     };
 
     let mut enc_ctx =
-        ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+        ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
     let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
     let pt_len = plaintext.len();
@@ -198,7 +199,7 @@ pub fn open(
     }
 
     let mut dec_ctx =
-        ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+        ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
     let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
     let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut dec_ctx, &mut tmp));
 
@@ -223,6 +224,92 @@ pub fn open(
     Ok(())
 }
 
+#[cfg(feature = "aead")]
+/// Encrypt `buffer` in place and return the tag, as needed by the `aead` crate.
+pub(crate) fn seal_in_place_detached(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ad: &[u8],
+    buffer: &mut [u8],
+) -> Result<crate::hazardous::mac::poly1305::Tag, UnknownCryptoError> {
+    let mut enc_ctx =
+        ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+    let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut enc_ctx, &mut tmp));
+
+    if !buffer.is_empty() {
+        chacha20::xor_keystream(&mut enc_ctx, ENC_CTR, tmp.as_mut(), buffer)?;
+    }
+    process_authentication(&mut auth_ctx, ad, buffer)?;
+
+    auth_ctx.finalize()
+}
+
+#[cfg(feature = "aead")]
+/// Verify `tag` and decrypt `buffer` in place, as needed by the `aead` crate.
+/// `buffer` is left untouched if verification fails.
+pub(crate) fn open_in_place_detached(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    let mut dec_ctx =
+        ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+    let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut dec_ctx, &mut tmp));
+
+    process_authentication(&mut auth_ctx, ad, buffer)?;
+    util::secure_cmp(auth_ctx.finalize()?.unprotected_as_bytes(), tag)?;
+
+    if !buffer.is_empty() {
+        chacha20::xor_keystream(&mut dec_ctx, ENC_CTR, tmp.as_mut(), buffer)?;
+    }
+
+    Ok(())
+}
+
+/// ChaCha20Poly1305 initialized with a secret key, which implements the [`Aead`] trait.
+///
+/// [`Aead`]: ../../traits/trait.Aead.html
+#[derive(Debug)]
+pub struct ChaCha20Poly1305 {
+    pub(crate) secret_key: SecretKey,
+}
+
+impl Aead for ChaCha20Poly1305 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+    const TAGSIZE: usize = POLY1305_OUTSIZE;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn seal(
+        &self,
+        nonce: &Nonce,
+        plaintext: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        seal(&self.secret_key, nonce, plaintext, ad, dst_out)
+    }
+
+    fn open(
+        &self,
+        nonce: &Nonce,
+        ciphertext_with_tag: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        open(&self.secret_key, nonce, ciphertext_with_tag, ad, dst_out)
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -266,7 +353,7 @@ mod test_vectors {
         ];
 
         let mut chacha20_ctx =
-            ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+            ChaCha20State::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
         let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
@@ -294,7 +381,7 @@ mod test_vectors {
         ];
 
         let mut chacha20_ctx =
-            ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+            ChaCha20State::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
         let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
@@ -322,7 +409,7 @@ mod test_vectors {
         ];
 
         let mut chacha20_ctx =
-            ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
+            ChaCha20State::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
         let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
@@ -26,5 +26,8 @@ pub mod chacha20poly1305;
 /// AEAD XChaCha20Poly1305 as specified in the [draft RFC](https://github.com/bikeshedders/xchacha-rfc).
 pub mod xchacha20poly1305;
 
+/// AEAD XSalsa20Poly1305 as in NaCl's [`crypto_secretbox`](https://nacl.cr.yp.to/secretbox.html).
+pub mod xsalsa20poly1305;
+
 /// Streaming AEAD based on XChaCha20Poly1305.
 pub mod streaming;
@@ -96,8 +96,8 @@ use crate::hazardous::aead::chacha20poly1305::poly1305_key_gen;
 use crate::hazardous::mac::poly1305::{Poly1305, Tag as Poly1305Tag, POLY1305_OUTSIZE};
 pub use crate::hazardous::stream::chacha20::SecretKey;
 use crate::hazardous::stream::chacha20::{
-    encrypt as chacha20_enc, encrypt_in_place as chacha20_xor_stream, ChaCha20, Nonce as IETFNonce,
-    CHACHA_BLOCKSIZE, CHACHA_KEYSIZE, HCHACHA_NONCESIZE, IETF_CHACHA_NONCESIZE,
+    encrypt as chacha20_enc, encrypt_in_place as chacha20_xor_stream, ChaCha20State,
+    Nonce as IETFNonce, CHACHA_BLOCKSIZE, CHACHA_KEYSIZE, HCHACHA_NONCESIZE, IETF_CHACHA_NONCESIZE,
 };
 use crate::hazardous::stream::xchacha20::subkey_and_nonce;
 pub use crate::hazardous::stream::xchacha20::Nonce;
@@ -216,7 +216,7 @@ impl StreamXChaCha20Poly1305 {
     ) -> Result<Poly1305Tag, UnknownCryptoError> {
         debug_assert!(text.len() >= textpos + msglen);
 
-        let mut chacha20_ctx = ChaCha20::new(
+        let mut chacha20_ctx = ChaCha20State::new(
             self.key.unprotected_as_bytes(),
             self.get_nonce().as_ref(),
             true,
@@ -89,7 +89,10 @@
 //! [`open()`]: fn.open.html
 use crate::hazardous::stream::xchacha20::subkey_and_nonce;
 pub use crate::hazardous::stream::{chacha20::SecretKey, xchacha20::Nonce};
-use crate::{errors::UnknownCryptoError, hazardous::aead::chacha20poly1305};
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::{aead::chacha20poly1305, mac::poly1305::POLY1305_OUTSIZE, traits::Aead},
+};
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// AEAD XChaCha20Poly1305 encryption as specified in the [draft RFC](https://github.com/bikeshedders/xchacha-rfc).
@@ -117,6 +120,71 @@ pub fn open(
     chacha20poly1305::open(&subkey, &ietf_nonce, ciphertext_with_tag, ad, dst_out)
 }
 
+#[cfg(feature = "aead")]
+/// Encrypt `buffer` in place and return the tag, as needed by the `aead` crate.
+pub(crate) fn seal_in_place_detached(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ad: &[u8],
+    buffer: &mut [u8],
+) -> Result<crate::hazardous::mac::poly1305::Tag, UnknownCryptoError> {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+    chacha20poly1305::seal_in_place_detached(&subkey, &ietf_nonce, ad, buffer)
+}
+
+#[cfg(feature = "aead")]
+/// Verify `tag` and decrypt `buffer` in place, as needed by the `aead` crate.
+pub(crate) fn open_in_place_detached(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+    chacha20poly1305::open_in_place_detached(&subkey, &ietf_nonce, ad, buffer, tag)
+}
+
+/// XChaCha20Poly1305 initialized with a secret key, which implements the [`Aead`] trait.
+///
+/// [`Aead`]: ../../traits/trait.Aead.html
+#[derive(Debug)]
+pub struct XChaCha20Poly1305 {
+    pub(crate) secret_key: SecretKey,
+}
+
+impl Aead for XChaCha20Poly1305 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+    const TAGSIZE: usize = POLY1305_OUTSIZE;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn seal(
+        &self,
+        nonce: &Nonce,
+        plaintext: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        seal(&self.secret_key, nonce, plaintext, ad, dst_out)
+    }
+
+    fn open(
+        &self,
+        nonce: &Nonce,
+        ciphertext_with_tag: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        open(&self.secret_key, nonce, ciphertext_with_tag, ad, dst_out)
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -0,0 +1,364 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `nonce`: The nonce value.
+//! - `ciphertext_with_tag`: The encrypted data with the corresponding 16 byte
+//!   Poly1305 tag prepended to it.
+//! - `plaintext`: The data to be encrypted.
+//! - `dst_out`: Destination array that will hold the
+//!   `ciphertext_with_tag`/`plaintext` after encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` + [`POLY1305_OUTSIZE`] when calling [`seal()`].
+//! - The length of `dst_out` is less than `ciphertext_with_tag` - [`POLY1305_OUTSIZE`] when
+//!   calling [`open()`].
+//! - The length of `ciphertext_with_tag` is not at least [`POLY1305_OUTSIZE`].
+//! - The received tag does not match the calculated tag when calling [`open()`].
+//! - `plaintext.len()` + [`POLY1305_OUTSIZE`] overflows when calling [`seal()`].
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than `2^32-1 * 64` bytes of data are processed.
+//!
+//! # Security:
+//! - It is critical for security that a given nonce is not re-used with a given
+//!   key. Should this happen, the security of all data that has been encrypted
+//!   with that given key is compromised.
+//! - The nonce for XSalsa20Poly1305 is big enough to be randomly generated
+//!   using a CSPRNG. [`Nonce::generate()`] can be used for this.
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//! - The length of the `plaintext` is not hidden, only its contents.
+//!
+//! # Recommendation:
+//! - XSalsa20Poly1305 is provided for compatibility with NaCl and libsodium.
+//!   For anything else, it is recommended to use [`XChaCha20Poly1305`], which
+//!   also authenticates additional data.
+//!
+//! # Note:
+//! This is the construction of NaCl's `crypto_secretbox`. The output of [`seal()`]
+//! is byte-compatible with libsodium's `crypto_secretbox_easy()`, which places the
+//! tag _before_ the ciphertext, and [`open()`] accepts the output of it.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::aead;
+//!
+//! let secret_key = aead::xsalsa20poly1305::SecretKey::generate();
+//! let nonce = aead::xsalsa20poly1305::Nonce::generate();
+//! let message = "Data to protect".as_bytes();
+//!
+//! // Length of the above message is 15 and then we accommodate 16 for the Poly1305
+//! // tag.
+//!
+//! let mut dst_out_ct = [0u8; 16 + 15];
+//! let mut dst_out_pt = [0u8; 15];
+//! // Encrypt and place tag + ciphertext in dst_out_ct
+//! aead::xsalsa20poly1305::seal(&secret_key, &nonce, message, &mut dst_out_ct)?;
+//! // Verify tag, if correct then decrypt and place message in dst_out_pt
+//! aead::xsalsa20poly1305::open(&secret_key, &nonce, &dst_out_ct, &mut dst_out_pt)?;
+//!
+//! assert_eq!(dst_out_pt.as_ref(), message.as_ref());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`SecretKey::generate()`]: ../../stream/salsa20/struct.SecretKey.html
+//! [`Nonce::generate()`]: ../../stream/xsalsa20/struct.Nonce.html
+//! [`XChaCha20Poly1305`]: ../xchacha20poly1305/index.html
+//! [`POLY1305_OUTSIZE`]: ../../mac/poly1305/constant.POLY1305_OUTSIZE.html
+//! [`seal()`]: fn.seal.html
+//! [`open()`]: fn.open.html
+pub use crate::hazardous::stream::{salsa20::SecretKey, xsalsa20::Nonce};
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::{
+        mac::poly1305::{OneTimeKey, Poly1305, POLY1305_KEYSIZE, POLY1305_OUTSIZE},
+        stream::{
+            salsa20::{self, Salsa20State, SALSA_BLOCKSIZE},
+            xsalsa20::subkey_and_nonce,
+        },
+    },
+    util,
+};
+use zeroize::Zeroizing;
+
+/// Initialize XSalsa20 and derive the Poly1305 key from the first half of the
+/// first keystream block. The second half is left in `tmp_buffer`, to encrypt
+/// the first bytes of the message with.
+fn init(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    tmp_buffer: &mut Zeroizing<[u8; SALSA_BLOCKSIZE]>,
+) -> (Salsa20State, OneTimeKey) {
+    let (subkey, salsa_nonce) = subkey_and_nonce(secret_key, nonce);
+    let mut ctx =
+        Salsa20State::new(subkey.unprotected_as_bytes(), salsa_nonce.as_ref(), false).unwrap();
+    ctx.keystream_block(0, tmp_buffer.as_mut());
+    let one_time_key = OneTimeKey::from_slice(&tmp_buffer[..POLY1305_KEYSIZE]).unwrap();
+
+    (ctx, one_time_key)
+}
+
+/// XOR the keystream into `bytes`, continuing from the second half of the
+/// first keystream block that is in `tmp_buffer`.
+fn xor_keystream(
+    ctx: &mut Salsa20State,
+    tmp_buffer: &mut Zeroizing<[u8; SALSA_BLOCKSIZE]>,
+    bytes: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let (first, rest) = bytes.split_at_mut(core::cmp::min(bytes.len(), POLY1305_KEYSIZE));
+    xor_slices!(tmp_buffer[POLY1305_KEYSIZE..], first);
+    if !rest.is_empty() {
+        salsa20::xor_keystream(ctx, 1, tmp_buffer.as_mut(), rest)?;
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// AEAD XSalsa20Poly1305 encryption and authentication as in NaCl's `crypto_secretbox`.
+pub fn seal(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let out_len = match plaintext.len().checked_add(POLY1305_OUTSIZE) {
+        Some(min_len) => min_len,
+        None => return Err(UnknownCryptoError),
+    };
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut tmp = Zeroizing::new([0u8; SALSA_BLOCKSIZE]);
+    let (mut enc_ctx, one_time_key) = init(secret_key, nonce, &mut tmp);
+
+    let (tag_out, ciphertext_out) = dst_out[..out_len].split_at_mut(POLY1305_OUTSIZE);
+    ciphertext_out.copy_from_slice(plaintext);
+    xor_keystream(&mut enc_ctx, &mut tmp, ciphertext_out)?;
+    tag_out
+        .copy_from_slice(Poly1305::poly1305(&one_time_key, ciphertext_out)?.unprotected_as_bytes());
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// AEAD XSalsa20Poly1305 decryption and authentication as in NaCl's `crypto_secretbox`.
+pub fn open(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ciphertext_with_tag: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if ciphertext_with_tag.len() < POLY1305_OUTSIZE {
+        return Err(UnknownCryptoError);
+    }
+    let ciphertext_len = ciphertext_with_tag.len() - POLY1305_OUTSIZE;
+    if dst_out.len() < ciphertext_len {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut tmp = Zeroizing::new([0u8; SALSA_BLOCKSIZE]);
+    let (mut dec_ctx, one_time_key) = init(secret_key, nonce, &mut tmp);
+
+    let (tag, ciphertext) = ciphertext_with_tag.split_at(POLY1305_OUTSIZE);
+    util::secure_cmp(
+        Poly1305::poly1305(&one_time_key, ciphertext)?.unprotected_as_bytes(),
+        tag,
+    )?;
+
+    dst_out[..ciphertext_len].copy_from_slice(ciphertext);
+    xor_keystream(&mut dec_ctx, &mut tmp, &mut dst_out[..ciphertext_len])
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+mod public {
+    use super::*;
+
+    mod test_seal_open {
+        use super::*;
+
+        #[test]
+        fn test_dst_out_length() {
+            let sk = SecretKey::generate();
+            let n = Nonce::generate();
+            let plaintext = [1u8; 40];
+
+            let mut dst_out_ct = [0u8; 40 + POLY1305_OUTSIZE];
+            assert!(seal(&sk, &n, &plaintext, &mut dst_out_ct[..55]).is_err());
+            assert!(seal(&sk, &n, &plaintext, &mut dst_out_ct).is_ok());
+            let mut dst_out_ct_more = [0u8; 40 + POLY1305_OUTSIZE + 1];
+            assert!(seal(&sk, &n, &plaintext, &mut dst_out_ct_more).is_ok());
+            assert_eq!(dst_out_ct[..], dst_out_ct_more[..56]);
+            assert_eq!(dst_out_ct_more[56], 0u8);
+
+            let mut dst_out_pt = [0u8; 40];
+            assert!(open(&sk, &n, &dst_out_ct, &mut dst_out_pt[..39]).is_err());
+            assert!(open(&sk, &n, &dst_out_ct, &mut dst_out_pt).is_ok());
+            let mut dst_out_pt_more = [0u8; 41];
+            assert!(open(&sk, &n, &dst_out_ct, &mut dst_out_pt_more).is_ok());
+            assert_eq!(dst_out_pt_more[..40], plaintext[..]);
+        }
+
+        #[test]
+        fn test_ciphertext_with_tag_length() {
+            let sk = SecretKey::generate();
+            let n = Nonce::generate();
+            let mut dst_out_pt = [0u8; 64];
+
+            assert!(open(&sk, &n, &[0u8; 0], &mut dst_out_pt).is_err());
+            assert!(open(&sk, &n, &[0u8; POLY1305_OUTSIZE - 1], &mut dst_out_pt).is_err());
+
+            let mut dst_out_ct = [0u8; POLY1305_OUTSIZE];
+            seal(&sk, &n, &[0u8; 0], &mut dst_out_ct).unwrap();
+            assert!(open(&sk, &n, &dst_out_ct, &mut [0u8; 0]).is_ok());
+        }
+
+        #[test]
+        fn test_modified_tag_or_ciphertext_err() {
+            let sk = SecretKey::generate();
+            let n = Nonce::generate();
+            let plaintext = [1u8; 100];
+            let mut dst_out_ct = [0u8; 100 + POLY1305_OUTSIZE];
+            let mut dst_out_pt = [0u8; 100];
+            seal(&sk, &n, &plaintext, &mut dst_out_ct).unwrap();
+
+            for idx in [0, POLY1305_OUTSIZE - 1, POLY1305_OUTSIZE, 115].iter() {
+                let mut modified = dst_out_ct;
+                modified[*idx] ^= 1;
+                assert!(open(&sk, &n, &modified, &mut dst_out_pt).is_err());
+                // Nothing is decrypted before the tag has been verified.
+                assert_eq!(dst_out_pt, [0u8; 100]);
+            }
+        }
+
+        #[test]
+        fn test_diff_params_err() {
+            let sk = SecretKey::generate();
+            let n = Nonce::generate();
+            let mut dst_out_ct = [0u8; 10 + POLY1305_OUTSIZE];
+            let mut dst_out_pt = [0u8; 10];
+            seal(&sk, &n, &[1u8; 10], &mut dst_out_ct).unwrap();
+
+            assert!(open(&SecretKey::generate(), &n, &dst_out_ct, &mut dst_out_pt).is_err());
+            assert!(open(&sk, &Nonce::generate(), &dst_out_ct, &mut dst_out_pt).is_err());
+            assert!(open(&sk, &n, &dst_out_ct, &mut dst_out_pt).is_ok());
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_seal_open_same_input(input: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let n = Nonce::generate();
+                    let mut dst_out_ct = vec![0u8; input.len() + POLY1305_OUTSIZE];
+                    let mut dst_out_pt = vec![0u8; input.len()];
+
+                    seal(&sk, &n, &input, &mut dst_out_ct).unwrap();
+                    open(&sk, &n, &dst_out_ct, &mut dst_out_pt).unwrap();
+
+                    dst_out_pt == input
+                }
+            }
+
+            quickcheck! {
+                // The ciphertext is the XSalsa20 keystream, starting at byte 32,
+                // XORed with the plaintext.
+                fn prop_ciphertext_is_xsalsa20_offset(input: Vec<u8>) -> bool {
+                    use crate::hazardous::stream::xsalsa20;
+
+                    let sk = SecretKey::generate();
+                    let n = Nonce::generate();
+                    let mut dst_out_ct = vec![0u8; input.len() + POLY1305_OUTSIZE];
+                    seal(&sk, &n, &input, &mut dst_out_ct).unwrap();
+
+                    let mut padded = vec![0u8; input.len() + 32];
+                    padded[32..].copy_from_slice(&input);
+                    let mut expected = vec![0u8; padded.len()];
+                    xsalsa20::encrypt(&sk, &n, 0, &padded, &mut expected).unwrap();
+
+                    dst_out_ct[POLY1305_OUTSIZE..] == expected[32..]
+                }
+            }
+        }
+    }
+}
+
+// Testing any test vectors that aren't put into library's /tests folder.
+#[cfg(test)]
+mod test_vectors {
+    use super::*;
+
+    // NaCl tests/secretbox.c, whose expected output is the ciphertext that
+    // libsodium's crypto_secretbox_easy() returns.
+    #[test]
+    fn nacl_secretbox() {
+        let key = SecretKey::from([
+            0x1b, 0x27, 0x55, 0x64, 0x73, 0xe9, 0x85, 0xd4, 0x62, 0xcd, 0x51, 0x19, 0x7a, 0x9a,
+            0x46, 0xc7, 0x60, 0x09, 0x54, 0x9e, 0xac, 0x64, 0x74, 0xf2, 0x06, 0xc4, 0xee, 0x08,
+            0x44, 0xf6, 0x83, 0x89,
+        ]);
+        let nonce = Nonce::from([
+            0x69, 0x69, 0x6e, 0xe9, 0x55, 0xb6, 0x2b, 0x73, 0xcd, 0x62, 0xbd, 0xa8, 0x75, 0xfc,
+            0x73, 0xd6, 0x82, 0x19, 0xe0, 0x03, 0x6b, 0x7a, 0x0b, 0x37,
+        ]);
+        let plaintext = [
+            0xbe, 0x07, 0x5f, 0xc5, 0x3c, 0x81, 0xf2, 0xd5, 0xcf, 0x14, 0x13, 0x16, 0xeb, 0xeb,
+            0x0c, 0x7b, 0x52, 0x28, 0xc5, 0x2a, 0x4c, 0x62, 0xcb, 0xd4, 0x4b, 0x66, 0x84, 0x9b,
+            0x64, 0x24, 0x4f, 0xfc, 0xe5, 0xec, 0xba, 0xaf, 0x33, 0xbd, 0x75, 0x1a, 0x1a, 0xc7,
+            0x28, 0xd4, 0x5e, 0x6c, 0x61, 0x29, 0x6c, 0xdc, 0x3c, 0x01, 0x23, 0x35, 0x61, 0xf4,
+            0x1d, 0xb6, 0x6c, 0xce, 0x31, 0x4a, 0xdb, 0x31, 0x0e, 0x3b, 0xe8, 0x25, 0x0c, 0x46,
+            0xf0, 0x6d, 0xce, 0xea, 0x3a, 0x7f, 0xa1, 0x34, 0x80, 0x57, 0xe2, 0xf6, 0x55, 0x6a,
+            0xd6, 0xb1, 0x31, 0x8a, 0x02, 0x4a, 0x83, 0x8f, 0x21, 0xaf, 0x1f, 0xde, 0x04, 0x89,
+            0x77, 0xeb, 0x48, 0xf5, 0x9f, 0xfd, 0x49, 0x24, 0xca, 0x1c, 0x60, 0x90, 0x2e, 0x52,
+            0xf0, 0xa0, 0x89, 0xbc, 0x76, 0x89, 0x70, 0x40, 0xe0, 0x82, 0xf9, 0x37, 0x76, 0x38,
+            0x48, 0x64, 0x5e, 0x07, 0x05,
+        ];
+        let expected = [
+            0xf3, 0xff, 0xc7, 0x70, 0x3f, 0x94, 0x00, 0xe5, 0x2a, 0x7d, 0xfb, 0x4b, 0x3d, 0x33,
+            0x05, 0xd9, 0x8e, 0x99, 0x3b, 0x9f, 0x48, 0x68, 0x12, 0x73, 0xc2, 0x96, 0x50, 0xba,
+            0x32, 0xfc, 0x76, 0xce, 0x48, 0x33, 0x2e, 0xa7, 0x16, 0x4d, 0x96, 0xa4, 0x47, 0x6f,
+            0xb8, 0xc5, 0x31, 0xa1, 0x18, 0x6a, 0xc0, 0xdf, 0xc1, 0x7c, 0x98, 0xdc, 0xe8, 0x7b,
+            0x4d, 0xa7, 0xf0, 0x11, 0xec, 0x48, 0xc9, 0x72, 0x71, 0xd2, 0xc2, 0x0f, 0x9b, 0x92,
+            0x8f, 0xe2, 0x27, 0x0d, 0x6f, 0xb8, 0x63, 0xd5, 0x17, 0x38, 0xb4, 0x8e, 0xee, 0xe3,
+            0x14, 0xa7, 0xcc, 0x8a, 0xb9, 0x32, 0x16, 0x45, 0x48, 0xe5, 0x26, 0xae, 0x90, 0x22,
+            0x43, 0x68, 0x51, 0x7a, 0xcf, 0xea, 0xbd, 0x6b, 0xb3, 0x73, 0x2b, 0xc0, 0xe9, 0xda,
+            0x99, 0x83, 0x2b, 0x61, 0xca, 0x01, 0xb6, 0xde, 0x56, 0x24, 0x4a, 0x9e, 0x88, 0xd5,
+            0xf9, 0xb3, 0x79, 0x73, 0xf6, 0x22, 0xa4, 0x3d, 0x14, 0xa6, 0x59, 0x9b, 0x1f, 0x65,
+            0x4c, 0xb4, 0x5a, 0x74, 0xe3, 0x55, 0xa5,
+        ];
+
+        let mut dst_out_ct = [0u8; 131 + POLY1305_OUTSIZE];
+        seal(&key, &nonce, &plaintext, &mut dst_out_ct).unwrap();
+        assert_eq!(dst_out_ct[..], expected[..]);
+
+        let mut dst_out_pt = [0u8; 131];
+        open(&key, &nonce, &expected, &mut dst_out_pt).unwrap();
+        assert_eq!(dst_out_pt[..], plaintext[..]);
+    }
+}
@@ -0,0 +1,574 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: An optional secret key.
+//! - `size`: The desired output length for the digest.
+//! - `data`: The data to be hashed.
+//! - `expected`: The expected digest when verifying.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `size` is 0 or greater than 64.
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - [`reset()`] is called with `Some(secret_key)` but the struct was
+//!   initialized with `None`.
+//! - [`reset()`] is called with `None` as `secret_key` but the struct was
+//!   initialized with `Some(secret_key)`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 4*2*(2^64-1) bytes of data are hashed.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - The minimum recommended size for a secret key is 32 bytes.
+//! - When using Blake2bp with a secret key, then the output can be used as a
+//!   MAC. If this is the intention, __**avoid using**__ [`as_ref()`]
+//!   to compare such MACs and use instead [`verify()`], which will compare
+//!   the MAC in constant time.
+//! - The recommended minimum output size is 32.
+//!
+//! # Note:
+//! BLAKE2bp splits the data into blocks of 128 bytes, which are distributed
+//! between four BLAKE2b leaves, and hashes the digests of the leaves with a
+//! BLAKE2b root. The digests therefore differ from those of BLAKE2b.
+//!
+//! The leaves are updated one after another on the calling thread. If the `parallel`
+//! feature is enabled, each leaf is instead updated on its own thread when [`update()`]
+//! is called with at least 64 KiB of data, which requires Rust 1.63. The digest is the
+//! same in both cases.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::blake2b::{blake2bp::Blake2bp, SecretKey};
+//!
+//! // Using the streaming interface without a key.
+//! let mut state = Blake2bp::new(None, 64)?;
+//! state.update(b"Some data")?;
+//! let digest = state.finalize()?;
+//!
+//! // Using the streaming interface with a key.
+//! let secret_key = SecretKey::generate();
+//! let mut state_keyed = Blake2bp::new(Some(&secret_key), 64)?;
+//! state_keyed.update(b"Some data")?;
+//! let mac = state_keyed.finalize()?;
+//! assert!(Blake2bp::verify(&mac, &secret_key, 64, b"Some data").is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Blake2bp.html
+//! [`reset()`]: struct.Blake2bp.html
+//! [`finalize()`]: struct.Blake2bp.html
+//! [`SecretKey::generate()`]: ../struct.SecretKey.html
+//! [`verify()`]: struct.Blake2bp.html
+//! [`as_ref()`]: ../struct.Digest.html
+use crate::hazardous::traits::IncrementalHash;
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::blake2b::{
+        Blake2b, Digest, Params, SecretKey, BLAKE2B_BLOCKSIZE, BLAKE2B_OUTSIZE,
+    },
+};
+use zeroize::Zeroize;
+
+/// The amount of leaves in BLAKE2bp.
+const BLAKE2BP_LEAVES: usize = 4;
+/// The amount of data BLAKE2bp distributes between its leaves at a time, which is
+/// one block for each leaf.
+const BLAKE2BP_STRIPESIZE: usize = BLAKE2BP_LEAVES * BLAKE2B_BLOCKSIZE;
+#[cfg(feature = "parallel")]
+/// The least amount of data for which the leaves are updated on their own threads.
+const BLAKE2BP_PARALLEL_MIN: usize = 64 * 1024;
+
+/// The parameter block of a BLAKE2bp node with a digest length of `size`.
+fn node_params(
+    size: usize,
+    node_offset: u64,
+    node_depth: u8,
+    last_node: bool,
+) -> Result<Params, UnknownCryptoError> {
+    Ok(Params::new(size)?
+        .with_fanout(BLAKE2BP_LEAVES as u8)
+        .with_max_depth(2)?
+        .with_inner_length(BLAKE2B_OUTSIZE)?
+        .with_node_offset(node_offset)
+        .with_node_depth(node_depth)
+        .with_last_node(last_node))
+}
+
+/// Update a leaf with its block of every stripe in `stripes`.
+fn update_leaf(leaf: &mut Blake2b, lane: usize, stripes: &[u8]) -> Result<(), UnknownCryptoError> {
+    debug_assert_eq!(stripes.len() % BLAKE2BP_STRIPESIZE, 0);
+    let start = lane * BLAKE2B_BLOCKSIZE;
+    for stripe in stripes.chunks_exact(BLAKE2BP_STRIPESIZE) {
+        leaf.update(&stripe[start..start + BLAKE2B_BLOCKSIZE])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "parallel"))]
+/// Update all leaves with `stripes`.
+fn update_leaves(leaves: &mut [Blake2b], stripes: &[u8]) -> Result<(), UnknownCryptoError> {
+    for (lane, leaf) in leaves.iter_mut().enumerate() {
+        update_leaf(leaf, lane, stripes)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+/// Update all leaves with `stripes`, using a thread for each leaf.
+fn update_leaves(leaves: &mut [Blake2b], stripes: &[u8]) -> Result<(), UnknownCryptoError> {
+    if stripes.len() < BLAKE2BP_PARALLEL_MIN {
+        for (lane, leaf) in leaves.iter_mut().enumerate() {
+            update_leaf(leaf, lane, stripes)?;
+        }
+
+        return Ok(());
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = leaves
+            .iter_mut()
+            .enumerate()
+            .map(|(lane, leaf)| scope.spawn(move || update_leaf(leaf, lane, stripes)))
+            .collect();
+
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().unwrap())
+    })
+}
+
+#[derive(Clone)]
+/// BLAKE2bp streaming state.
+pub struct Blake2bp {
+    leaves: [Blake2b; BLAKE2BP_LEAVES],
+    buffer: [u8; BLAKE2BP_STRIPESIZE],
+    leftover: usize,
+    key_length: usize,
+    is_finalized: bool,
+    size: usize,
+}
+
+impl Drop for Blake2bp {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for Blake2bp {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Blake2bp {{ leaves: [***OMITTED***], buffer: [***OMITTED***], leftover: {:?}, \
+             is_finalized: {:?}, is_keyed: {:?}, size: {:?} }}",
+            self.leftover,
+            self.is_finalized,
+            self.key_length != 0,
+            self.size
+        )
+    }
+}
+
+impl_io_write_trait!(Blake2bp);
+
+impl Blake2bp {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2bp` struct with a given size and an optional key.
+    pub fn new(secret_key: Option<&SecretKey>, size: usize) -> Result<Self, UnknownCryptoError> {
+        let klen = match secret_key {
+            Some(sk) => sk.len(),
+            None => 0,
+        };
+
+        let leaf = |lane: usize| -> Result<Blake2b, UnknownCryptoError> {
+            let params = node_params(size, lane as u64, 0, lane == BLAKE2BP_LEAVES - 1)?;
+            Blake2b::new_node(secret_key, klen, &params, BLAKE2B_OUTSIZE)
+        };
+
+        Ok(Self {
+            leaves: [leaf(0)?, leaf(1)?, leaf(2)?, leaf(3)?],
+            buffer: [0u8; BLAKE2BP_STRIPESIZE],
+            leftover: 0,
+            key_length: klen,
+            is_finalized: false,
+            size,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Reset to `new()` state.
+    pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
+        for leaf in self.leaves.iter_mut() {
+            leaf.reset(secret_key)?;
+        }
+
+        self.buffer = [0u8; BLAKE2BP_STRIPESIZE];
+        self.leftover = 0;
+        self.is_finalized = false;
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = data;
+
+        if self.leftover != 0 {
+            debug_assert!(self.leftover <= BLAKE2BP_STRIPESIZE);
+
+            let fill = BLAKE2BP_STRIPESIZE - self.leftover;
+
+            if bytes.len() < fill {
+                self.buffer[self.leftover..(self.leftover + bytes.len())].copy_from_slice(bytes);
+                self.leftover += bytes.len();
+                return Ok(());
+            }
+
+            self.buffer[self.leftover..].copy_from_slice(&bytes[..fill]);
+            for (lane, leaf) in self.leaves.iter_mut().enumerate() {
+                update_leaf(leaf, lane, &self.buffer)?;
+            }
+            self.leftover = 0;
+            bytes = &bytes[fill..];
+        }
+
+        let full_stripes = bytes.len() - (bytes.len() % BLAKE2BP_STRIPESIZE);
+        update_leaves(&mut self.leaves, &bytes[..full_stripes])?;
+        bytes = &bytes[full_stripes..];
+
+        if !bytes.is_empty() {
+            debug_assert!(self.leftover == 0);
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.leftover += bytes.len();
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a BLAKE2bp digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        let mut leaf_digests = [0u8; BLAKE2BP_LEAVES * BLAKE2B_OUTSIZE];
+        for (lane, (leaf, leaf_digest)) in self
+            .leaves
+            .iter_mut()
+            .zip(leaf_digests.chunks_exact_mut(BLAKE2B_OUTSIZE))
+            .enumerate()
+        {
+            let start = lane * BLAKE2B_BLOCKSIZE;
+            if self.leftover > start {
+                let end = core::cmp::min(self.leftover, start + BLAKE2B_BLOCKSIZE);
+                leaf.update(&self.buffer[start..end])?;
+            }
+            leaf_digest.copy_from_slice(leaf.finalize()?.as_ref());
+        }
+
+        let root_params = node_params(self.size, 0, 1, true)?;
+        let mut root = Blake2b::new_node(None, self.key_length, &root_params, self.size)?;
+        root.update(&leaf_digests)?;
+        leaf_digests.zeroize();
+
+        root.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a Blake2bp Digest in constant time.
+    pub fn verify(
+        expected: &Digest,
+        secret_key: &SecretKey,
+        size: usize,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(Some(secret_key), size)?;
+        state.update(data)?;
+
+        if expected == &state.finalize()? {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+}
+
+impl IncrementalHash for Blake2bp {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        // .unwrap() should not be able to panic because the size is valid.
+        Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        // The leaves keep their key, if any.
+        for leaf in self.leaves.iter_mut() {
+            IncrementalHash::reset(leaf)?;
+        }
+
+        self.buffer = [0u8; BLAKE2BP_STRIPESIZE];
+        self.leftover = 0;
+        self.is_finalized = false;
+
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::blake2b::compare_blake2b_states;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Blake2bp { leaves: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, is_finalized: false, is_keyed: false, size: 64 }";
+        assert_eq!(debug, expected);
+    }
+
+    fn compare_blake2bp_states(state_1: &Blake2bp, state_2: &Blake2bp) {
+        for (leaf_1, leaf_2) in state_1.leaves.iter().zip(state_2.leaves.iter()) {
+            compare_blake2b_states(leaf_1, leaf_2);
+        }
+        assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+        assert_eq!(state_1.leftover, state_2.leftover);
+        assert_eq!(state_1.key_length, state_2.key_length);
+        assert_eq!(state_1.is_finalized, state_2.is_finalized);
+        assert_eq!(state_1.size, state_2.size);
+    }
+
+    mod test_streaming_interface_no_key {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Blake2bp {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset(None)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                let mut state = Blake2bp::new(None, BLAKE2B_OUTSIZE)?;
+                state.update(input)?;
+                state.finalize()
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Blake2bp, state_2: &Blake2bp) {
+                compare_blake2bp_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Blake2bp = Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake2bp>::new(
+                initial_state,
+                BLAKE2BP_STRIPESIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Blake2bp = Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap();
+
+                    let test_runner = StreamingContextConsistencyTester::<Digest, Blake2bp>::new(
+                        initial_state,
+                        BLAKE2BP_STRIPESIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        fn test_init_size() {
+            assert!(Blake2bp::new(None, 0).is_err());
+            assert!(Blake2bp::new(None, 65).is_err());
+            assert!(Blake2bp::new(None, 64).is_ok());
+            assert!(Blake2bp::new(None, 1).is_ok());
+
+            let sk = SecretKey::from_slice(&[0u8; 64]).unwrap();
+            assert!(Blake2bp::new(Some(&sk), 0).is_err());
+            assert!(Blake2bp::new(Some(&sk), 65).is_err());
+            assert!(Blake2bp::new(Some(&sk), 64).is_ok());
+            assert!(Blake2bp::new(Some(&sk), 1).is_ok());
+        }
+
+        #[test]
+        fn test_diff_from_blake2b() {
+            let mut state = Blake2bp::new(None, 64).unwrap();
+            state.update(b"Some data").unwrap();
+            let mut state_blake2b = Blake2b::new(None, 64).unwrap();
+            state_blake2b.update(b"Some data").unwrap();
+
+            assert_ne!(state.finalize().unwrap(), state_blake2b.finalize().unwrap());
+        }
+    }
+
+    mod test_update {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_large_update_same_as_small_updates() {
+            // Large enough for the leaves to be updated on their own threads, when the
+            // `parallel` feature is enabled.
+            let data: Vec<u8> = (0..(3 * 64 * 1024 + 300)).map(|i| i as u8).collect();
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+            let mut state_large = Blake2bp::new(Some(&sk), 32).unwrap();
+            state_large.update(&data[..17]).unwrap();
+            state_large.update(&data[17..]).unwrap();
+
+            let mut state_small = Blake2bp::new(Some(&sk), 32).unwrap();
+            for chunk in data.chunks(1000) {
+                state_small.update(chunk).unwrap();
+            }
+
+            compare_blake2bp_states(&state_large, &state_small);
+            assert_eq!(
+                state_large.finalize().unwrap(),
+                state_small.finalize().unwrap()
+            );
+        }
+    }
+
+    mod test_reset {
+        use super::*;
+
+        #[test]
+        fn test_switching_keyed_modes_fails() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+
+            let mut state = Blake2bp::new(Some(&secret_key), 64).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(state.reset(None).is_err());
+            assert!(state.reset(Some(&secret_key)).is_ok());
+
+            let mut state_second = Blake2bp::new(None, 64).unwrap();
+            state_second.update(b"Tests").unwrap();
+            let _ = state_second.finalize().unwrap();
+            assert!(state_second.reset(Some(&secret_key)).is_err());
+            assert!(state_second.reset(None).is_ok());
+        }
+
+        #[test]
+        fn test_reset_same_state_keyed() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+            let initial_state = Blake2bp::new(Some(&secret_key), 32).unwrap();
+
+            let mut state = initial_state.clone();
+            state.update(&[0u8; 1000]).unwrap();
+            let _ = state.finalize().unwrap();
+            state.reset(Some(&secret_key)).unwrap();
+
+            compare_blake2bp_states(&initial_state, &state);
+        }
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut state = Blake2bp::new(Some(&sk), 64).unwrap();
+                    state.update(&data[..]).unwrap();
+                    let tag = state.finalize().unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Blake2bp::verify(&tag, &bad_sk, 64, &data[..]).is_err()
+                }
+            }
+        }
+    }
+}
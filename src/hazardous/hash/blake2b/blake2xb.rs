@@ -0,0 +1,555 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! # Parameters:
+//! - `secret_key`: An optional secret key.
+//! - `output_size`: The length of the output, if it is known in advance.
+//! - `data`: The data to be hashed.
+//! - `dest`: Destination buffer for the output read with [`squeeze()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `output_size` is `Some` and is 0 or greater than [`BLAKE2XB_MAX_OUTSIZE`].
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - [`reset()`] is called with `Some(secret_key)` but the struct was
+//!   initialized with `None`.
+//! - [`reset()`] is called with `None` as `secret_key` but the struct was
+//!   initialized with `Some(secret_key)`.
+//! - `dest` is empty.
+//! - More output is read with [`squeeze()`] than `output_size`, or more than
+//!   [`BLAKE2XB_MAX_UNKNOWN_OUTSIZE`] bytes if `output_size` is `None`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 2*(2^64-1) bytes of data are hashed.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - The minimum recommended size for a secret key is 32 bytes.
+//! - BLAKE2Xb provides at most 256 bits of security, no matter the length of
+//!   the output.
+//! - The length of the output is part of the input to every BLAKE2b
+//!   instance, so output of one length is unrelated to output of another.
+//!   This does not hold when `output_size` is `None`, in which case shorter
+//!   output is a prefix of longer output.
+//!
+//! # Note:
+//! BLAKE2Xb hashes the data with BLAKE2b into a 64-byte root digest, and then
+//! produces each 64-byte block of output by hashing the root digest with
+//! another BLAKE2b instance, whose node offset is the index of the block.
+//! The output can therefore be read incrementally with [`squeeze()`] without
+//! being buffered in full.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::blake2b::{blake2xb::Blake2xb, SecretKey};
+//!
+//! // Using the streaming interface with an output length known in advance.
+//! let mut state = Blake2xb::new(None, Some(128))?;
+//! state.update(b"Some data")?;
+//! let mut reader = state.finalize()?;
+//! let mut output = [0u8; 128];
+//! reader.squeeze(&mut output[..100])?;
+//! reader.squeeze(&mut output[100..])?;
+//!
+//! // Using the one-shot function, where the output length is that of `dst_out`.
+//! let mut output_one_shot = [0u8; 128];
+//! Blake2xb::digest(None, b"Some data", &mut output_one_shot)?;
+//! assert_eq!(output[..], output_one_shot[..]);
+//!
+//! // Using a secret key and an output length that is not known in advance.
+//! let secret_key = SecretKey::generate();
+//! let mut state_keyed = Blake2xb::new(Some(&secret_key), None)?;
+//! state_keyed.update(b"Some data")?;
+//! let mut reader_keyed = state_keyed.finalize()?;
+//! let mut subkey = [0u8; 32];
+//! reader_keyed.squeeze(&mut subkey)?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Blake2xb.html
+//! [`reset()`]: struct.Blake2xb.html
+//! [`finalize()`]: struct.Blake2xb.html
+//! [`squeeze()`]: struct.XofReader.html#method.squeeze
+//! [`BLAKE2XB_MAX_OUTSIZE`]: constant.BLAKE2XB_MAX_OUTSIZE.html
+//! [`BLAKE2XB_MAX_UNKNOWN_OUTSIZE`]: constant.BLAKE2XB_MAX_UNKNOWN_OUTSIZE.html
+//! [`SecretKey::generate()`]: ../struct.SecretKey.html
+use crate::hazardous::traits::{Xof, XofRead};
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::blake2b::{Blake2b, Params, SecretKey, BLAKE2B_OUTSIZE},
+};
+use zeroize::Zeroize;
+
+/// The maximum output length of BLAKE2Xb, when it is known in advance.
+pub const BLAKE2XB_MAX_OUTSIZE: usize = 0xFFFF_FFFE;
+/// The maximum output length of BLAKE2Xb, when it is not known in advance.
+pub const BLAKE2XB_MAX_UNKNOWN_OUTSIZE: u64 = 0x40_0000_0000;
+/// The value of the XOF digest length in the parameter block, which signals
+/// that the output length is not known in advance.
+const BLAKE2XB_UNKNOWN_OUTSIZE: u32 = 0xFFFF_FFFF;
+
+/// The XOF digest length of `output_size` to include in the parameter blocks.
+fn xof_length(output_size: Option<usize>) -> Result<u32, UnknownCryptoError> {
+    match output_size {
+        Some(size) if size == 0 || size > BLAKE2XB_MAX_OUTSIZE => Err(UnknownCryptoError),
+        Some(size) => Ok(size as u32),
+        None => Ok(BLAKE2XB_UNKNOWN_OUTSIZE),
+    }
+}
+
+#[derive(Clone)]
+/// BLAKE2Xb streaming state.
+pub struct Blake2xb {
+    root: Blake2b,
+    xof_length: u32,
+}
+
+impl core::fmt::Debug for Blake2xb {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Blake2xb {{ root: {:?}, xof_length: {:?} }}",
+            self.root, self.xof_length
+        )
+    }
+}
+
+impl_io_write_trait!(Blake2xb);
+
+impl Blake2xb {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2xb` struct with an optional output length and an
+    /// optional key.
+    pub fn new(
+        secret_key: Option<&SecretKey>,
+        output_size: Option<usize>,
+    ) -> Result<Self, UnknownCryptoError> {
+        let xof_length = xof_length(output_size)?;
+        // The XOF digest length occupies the four bytes of the parameter block
+        // that follow the node offset of BLAKE2b.
+        let params = Params::new(BLAKE2B_OUTSIZE)?.with_node_offset(u64::from(xof_length) << 32);
+
+        Ok(Self {
+            root: Blake2b::with_params(secret_key, &params)?,
+            xof_length,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Reset to `new()` state.
+    pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
+        self.root.reset(secret_key)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.root.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for the output.
+    pub fn finalize(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        let mut root_digest = [0u8; BLAKE2B_OUTSIZE];
+        root_digest.copy_from_slice(self.root.finalize()?.as_ref());
+
+        let output_size = if self.xof_length == BLAKE2XB_UNKNOWN_OUTSIZE {
+            BLAKE2XB_MAX_UNKNOWN_OUTSIZE
+        } else {
+            u64::from(self.xof_length)
+        };
+
+        Ok(XofReader {
+            root_digest,
+            block: [0u8; BLAKE2B_OUTSIZE],
+            xof_length: self.xof_length,
+            position: 0,
+            output_size,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate BLAKE2Xb of some `data` with an optional key, filling `dst_out`.
+    pub fn digest(
+        secret_key: Option<&SecretKey>,
+        data: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(secret_key, Some(dst_out.len()))?;
+        state.update(data)?;
+        state.finalize()?.squeeze(dst_out)
+    }
+}
+
+impl Xof for Blake2xb {
+    type Reader = XofReader;
+
+    fn new() -> Self {
+        Blake2xb::new(None, None).unwrap()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset(None)
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+/// A reader for the output of BLAKE2Xb.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `dest` is empty.
+/// - More output is read in total than the output length of BLAKE2Xb.
+///
+/// # Security:
+/// - Output read with several calls to [`squeeze()`] is the same as output read
+///   with a single call. Reading 32 bytes and then 64 bytes thus gives the same
+///   first 32 bytes as reading 96 bytes at once.
+///
+/// [`squeeze()`]: struct.XofReader.html#method.squeeze
+pub struct XofReader {
+    root_digest: [u8; BLAKE2B_OUTSIZE],
+    block: [u8; BLAKE2B_OUTSIZE],
+    xof_length: u32,
+    position: u64,
+    output_size: u64,
+}
+
+impl Drop for XofReader {
+    fn drop(&mut self) {
+        self.root_digest.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl core::fmt::Debug for XofReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "XofReader {{ root_digest: [***OMITTED***], block: [***OMITTED***], xof_length: {:?}, \
+             position: {:?} }}",
+            self.xof_length, self.position
+        )
+    }
+}
+
+impl XofReader {
+    /// Compute the block of output at index `node_offset` into `self.block`.
+    fn compute_block(&mut self, node_offset: u64) -> Result<(), UnknownCryptoError> {
+        // All blocks are 64 bytes, except for the last one when the output
+        // length is known in advance.
+        let size = if self.xof_length == BLAKE2XB_UNKNOWN_OUTSIZE {
+            BLAKE2B_OUTSIZE
+        } else {
+            core::cmp::min(
+                BLAKE2B_OUTSIZE as u64,
+                self.output_size - node_offset * BLAKE2B_OUTSIZE as u64,
+            ) as usize
+        };
+
+        let params = Params {
+            size,
+            fanout: 0,
+            max_depth: 0,
+            leaf_length: BLAKE2B_OUTSIZE as u32,
+            node_offset: (u64::from(self.xof_length) << 32) | node_offset,
+            node_depth: 0,
+            inner_length: BLAKE2B_OUTSIZE,
+            ..Params::new(size)?
+        };
+
+        let mut node = Blake2b::new_node(None, 0, &params, size)?;
+        node.update(&self.root_digest)?;
+        self.block[..size].copy_from_slice(node.finalize()?.as_ref());
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dest` with the next `dest.len()` bytes of output.
+    pub fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dest.is_empty() || (dest.len() as u64) > self.output_size - self.position {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut written = 0;
+        while written < dest.len() {
+            let offset = (self.position % BLAKE2B_OUTSIZE as u64) as usize;
+            if offset == 0 {
+                self.compute_block(self.position / BLAKE2B_OUTSIZE as u64)?;
+            }
+
+            let take = core::cmp::min(BLAKE2B_OUTSIZE - offset, dest.len() - written);
+            dest[written..written + take].copy_from_slice(&self.block[offset..offset + take]);
+            written += take;
+            self.position += take as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl XofRead for XofReader {
+    fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.squeeze(dest)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::blake2b::{compare_blake2b_states, BLAKE2B_BLOCKSIZE};
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let mut initial_state = Blake2xb::new(None, Some(128)).unwrap();
+        let debug = format!("{:?}", initial_state);
+        let expected = format!(
+            "Blake2xb {{ root: {:?}, xof_length: 128 }}",
+            initial_state.root
+        );
+        assert_eq!(debug, expected);
+
+        let reader = initial_state.finalize().unwrap();
+        let debug = format!("{:?}", reader);
+        let expected = "XofReader { root_digest: [***OMITTED***], block: [***OMITTED***], xof_length: 128, position: 0 }";
+        assert_eq!(debug, expected);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        /// The output of a `Blake2xb` in these tests.
+        type Output = [u8; 32];
+
+        impl TestableStreamingContext<Output> for Blake2xb {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset(None)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                self.finalize()?.squeeze(&mut output)?;
+                Ok(output)
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                Blake2xb::digest(None, input, &mut output)?;
+                Ok(output)
+            }
+
+            fn verify_result(expected: &Output, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Output = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Blake2xb, state_2: &Blake2xb) {
+                compare_blake2b_states(&state_1.root, &state_2.root);
+                assert_eq!(state_1.xof_length, state_2.xof_length);
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state = Blake2xb::new(None, Some(32)).unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Output, Blake2xb>::new(
+                initial_state,
+                BLAKE2B_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state = Blake2xb::new(None, Some(32)).unwrap();
+
+                    let test_runner = StreamingContextConsistencyTester::<Output, Blake2xb>::new(
+                        initial_state,
+                        BLAKE2B_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        fn test_output_size() {
+            assert!(Blake2xb::new(None, Some(0)).is_err());
+            assert!(Blake2xb::new(None, Some(1)).is_ok());
+            assert!(Blake2xb::new(None, Some(BLAKE2XB_MAX_OUTSIZE)).is_ok());
+            assert!(Blake2xb::new(None, None).is_ok());
+        }
+
+        #[test]
+        #[cfg(target_pointer_width = "64")]
+        fn test_output_size_too_large() {
+            assert!(Blake2xb::new(None, Some(BLAKE2XB_MAX_OUTSIZE + 1)).is_err());
+        }
+
+        #[test]
+        fn test_digest_empty_dst_out_err() {
+            assert!(Blake2xb::digest(None, b"", &mut [0u8; 0]).is_err());
+            assert!(Blake2xb::digest(None, b"", &mut [0u8; 1]).is_ok());
+        }
+    }
+
+    mod test_reset {
+        use super::*;
+
+        #[test]
+        fn test_switching_keyed_modes_fails() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+
+            let mut state = Blake2xb::new(Some(&secret_key), Some(64)).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(state.reset(None).is_err());
+            assert!(state.reset(Some(&secret_key)).is_ok());
+
+            let mut state_second = Blake2xb::new(None, Some(64)).unwrap();
+            state_second.update(b"Tests").unwrap();
+            let _ = state_second.finalize().unwrap();
+            assert!(state_second.reset(Some(&secret_key)).is_err());
+            assert!(state_second.reset(None).is_ok());
+        }
+    }
+
+    mod test_squeeze {
+        use super::*;
+
+        #[test]
+        fn test_squeeze_empty_err() {
+            let mut reader = Blake2xb::new(None, None).unwrap().finalize().unwrap();
+            assert!(reader.squeeze(&mut [0u8; 0]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 1]).is_ok());
+        }
+
+        #[test]
+        fn test_squeeze_past_output_size_err() {
+            let mut reader = Blake2xb::new(None, Some(100)).unwrap().finalize().unwrap();
+            assert!(reader.squeeze(&mut [0u8; 101]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 60]).is_ok());
+            assert!(reader.squeeze(&mut [0u8; 41]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 40]).is_ok());
+            assert!(reader.squeeze(&mut [0u8; 1]).is_err());
+        }
+
+        #[test]
+        fn test_squeeze_past_unknown_output_size_err() {
+            let mut reader = Blake2xb::new(None, None).unwrap().finalize().unwrap();
+            // Skip to the last block, instead of computing all blocks before it.
+            reader.position = BLAKE2XB_MAX_UNKNOWN_OUTSIZE - 64;
+            assert!(reader.squeeze(&mut [0u8; 65]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 64]).is_ok());
+            assert!(reader.squeeze(&mut [0u8; 1]).is_err());
+        }
+
+        #[test]
+        fn test_squeeze_incremental() {
+            for output_size in [Some(300), None].iter() {
+                let mut one_shot = [0u8; 300];
+                Blake2xb::new(None, *output_size)
+                    .unwrap()
+                    .finalize()
+                    .unwrap()
+                    .squeeze(&mut one_shot)
+                    .unwrap();
+
+                // Read across the boundaries of the blocks in differently sized pieces.
+                for step in [1, 7, 63, 64, 65, 128].iter() {
+                    let mut reader = Blake2xb::new(None, *output_size)
+                        .unwrap()
+                        .finalize()
+                        .unwrap();
+                    let mut incremental = [0u8; 300];
+                    for chunk in incremental.chunks_mut(*step) {
+                        reader.squeeze(chunk).unwrap();
+                    }
+                    assert_eq!(one_shot[..], incremental[..]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_output_size_separates_output() {
+            let mut short = [0u8; 64];
+            Blake2xb::digest(None, b"Tests", &mut short).unwrap();
+            let mut long = [0u8; 65];
+            Blake2xb::digest(None, b"Tests", &mut long).unwrap();
+            assert_ne!(short[..], long[..64]);
+
+            // Without a known output length, shorter output is a prefix.
+            let mut unknown_short = [0u8; 64];
+            let mut unknown_long = [0u8; 65];
+            Blake2xb::new(None, None)
+                .unwrap()
+                .finalize()
+                .unwrap()
+                .squeeze(&mut unknown_short)
+                .unwrap();
+            Blake2xb::new(None, None)
+                .unwrap()
+                .finalize()
+                .unwrap()
+                .squeeze(&mut unknown_long)
+                .unwrap();
+            assert_eq!(unknown_short[..], unknown_long[..64]);
+            assert_ne!(unknown_short[..], short[..]);
+        }
+    }
+}
@@ -25,10 +25,17 @@
 //! - `size`: The desired output length for the digest.
 //! - `data`: The data to be hashed.
 //! - `expected`: The expected digest when verifying.
+//! - `params`: The parameter block, which sets the salt, personalization and tree
+//!   hashing parameters besides `size`.
+//! - `dst_out`: Destination buffer for the exported state.
+//! - `exported`: A state exported with [`export_state()`].
 //!
 //! # Errors:
 //! An error will be returned if:
 //! - `size` is 0 or greater than 64.
+//! - The salt or personalization given to [`Params`] is greater than 16 bytes.
+//! - The maximal depth given to [`Params`] is 0.
+//! - The inner hash length given to [`Params`] is greater than 64.
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
@@ -36,6 +43,11 @@
 //!   initialized with `None`.
 //! - [`reset()`] is called with `None` as `secret_key` but the struct was
 //!   initialized with `Some(secret_key)`.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` or `exported` is not [`BLAKE2B_EXPORTSIZE`] bytes.
+//! - `exported` was not exported by a `Blake2b` with the same version of the
+//!   encoding, or is otherwise invalid.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -51,10 +63,23 @@
 //!   to compare such MACs and use instead [`verify()`], which will compare
 //!   the MAC in constant time.
 //! - The recommended minimum output size is 32.
+//! - The salt and personalization in [`Params`] are not secret. They separate
+//!   otherwise identical uses of BLAKE2b, but do not replace a secret key.
+//! - An exported state contains the data that has been buffered since the last
+//!   block was processed. If the struct was initialized with a secret key and no
+//!   data has been hashed since, this is the secret key itself. An exported state
+//!   should therefore be protected like the secret key.
+//!
+//! # Recommendation:
+//! - BLAKE2bp, which hashes large inputs faster on several cores, is provided by
+//!   the [`blake2bp`] submodule. General tree hashing is provided by the [`tree`]
+//!   submodule. Neither produces the same digests as BLAKE2b.
+//! - BLAKE2Xb, which produces output of arbitrary length, is provided by the
+//!   [`blake2xb`] submodule.
 //!
 //! # Example:
 //! ```rust
-//! use orion::hazardous::hash::blake2b::{Blake2b, Hasher, SecretKey};
+//! use orion::hazardous::hash::blake2b::{Blake2b, Hasher, Params, SecretKey};
 //!
 //! // Using the streaming interface without a key.
 //! let mut state = Blake2b::new(None, 64)?;
@@ -68,6 +93,26 @@
 //! let mac = state_keyed.finalize()?;
 //! assert!(Blake2b::verify(&mac, &secret_key, 64, b"Some data").is_ok());
 //!
+//! // Using the streaming interface with a salt and personalization.
+//! let params = Params::new(32)?
+//!     .with_salt(b"Unique salt")?
+//!     .with_personal(b"MyApp v1")?;
+//! let mut state_params = Blake2b::with_params(Some(&secret_key), &params)?;
+//! state_params.update(b"Some data")?;
+//! let mac = state_params.finalize()?;
+//!
+//! // Exporting the state to continue hashing later.
+//! use orion::hazardous::hash::blake2b::BLAKE2B_EXPORTSIZE;
+//!
+//! let mut state = Blake2b::new(Some(&secret_key), 64)?;
+//! state.update(b"Some ")?;
+//! let mut exported = [0u8; BLAKE2B_EXPORTSIZE];
+//! state.export_state(&mut exported)?;
+//!
+//! let mut imported = Blake2b::import_state(&exported)?;
+//! imported.update(b"data")?;
+//! assert!(Blake2b::verify(&imported.finalize()?, &secret_key, 64, b"Some data").is_ok());
+//!
 //! // Using the `Hasher` for convenience functions.
 //! let digest = Hasher::Blake2b512.digest(b"Some data")?;
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
@@ -77,15 +122,46 @@
 //! [`finalize()`]: struct.Blake2b.html
 //! [`SecretKey::generate()`]: struct.SecretKey.html
 //! [`verify()`]: struct.Blake2b.html
+//! [`export_state()`]: struct.Blake2b.html
+//! [`BLAKE2B_EXPORTSIZE`]: constant.BLAKE2B_EXPORTSIZE.html
 //! [`as_ref()`]: struct.Digest.html
-use crate::{errors::UnknownCryptoError, util::endianness::load_u64_into_le, util::u64x4::U64x4};
+//! [`Params`]: struct.Params.html
+//! [`blake2bp`]: blake2bp/index.html
+//! [`tree`]: tree/index.html
+//! [`blake2xb`]: blake2xb/index.html
+use crate::hazardous::traits::IncrementalHash;
+use crate::{
+    errors::UnknownCryptoError,
+    util::endianness::load_u64_into_le,
+    util::state_export::{StateReader, StateWriter, STATE_EXPORT_HEADERSIZE, STATE_ID_BLAKE2B},
+    util::u64x4::U64x4,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+/// BLAKE2bp as specified in the [BLAKE2 specification](https://www.blake2.net/blake2.pdf).
+pub mod blake2bp;
+
+/// BLAKE2Xb as specified in the [BLAKE2X paper](https://www.blake2.net/blake2x.pdf).
+pub mod blake2xb;
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+/// BLAKE2b tree hashing as specified in the [BLAKE2 specification](https://www.blake2.net/blake2.pdf).
+pub mod tree;
 
 /// The blocksize for the hash function BLAKE2b.
-const BLAKE2B_BLOCKSIZE: usize = 128;
+pub(crate) const BLAKE2B_BLOCKSIZE: usize = 128;
 /// The maximum key size for the hash function BLAKE2b when used in keyed mode.
 pub(crate) const BLAKE2B_KEYSIZE: usize = 64;
 /// The maximum output size for the hash function BLAKE2b.
 pub(crate) const BLAKE2B_OUTSIZE: usize = 64;
+/// The size of the salt and personalization in the BLAKE2b parameter block.
+pub(crate) const BLAKE2B_SALTSIZE: usize = 16;
+/// The size of the fields of a `Blake2b` in an exported state, which are the
+/// initial and internal state, buffer, leftover, offset, whether it is keyed
+/// and the last node, and its output size.
+const BLAKE2B_FIELDSIZE: usize = 64 + 64 + BLAKE2B_BLOCKSIZE + 8 + 16 + 1 + 1 + 1;
+/// The size of an exported `Blake2b` state.
+pub const BLAKE2B_EXPORTSIZE: usize = STATE_EXPORT_HEADERSIZE + BLAKE2B_FIELDSIZE;
 
 construct_secret_key! {
     /// A type to represent the secret key that BLAKE2b uses for keyed mode.
@@ -111,6 +187,170 @@ construct_public! {
     (Digest, test_digest, 1, BLAKE2B_OUTSIZE)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The BLAKE2b parameter block.
+///
+/// By default, the parameter block is that of sequential hashing, with an empty
+/// salt and personalization, which is what [`Blake2b::new()`] uses. The
+/// remaining fields are only meaningful when BLAKE2b is used in tree hashing
+/// mode, as described in section 2.10 of the [BLAKE2 specification].
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `size` is 0 or greater than 64.
+/// - `salt` or `personal` is greater than 16 bytes.
+/// - `max_depth` is 0.
+/// - `inner_length` is greater than 64.
+///
+/// # Note:
+/// A `salt` or `personal` shorter than 16 bytes is padded with zeroes, the same
+/// as libsodium and Python's `hashlib` do.
+///
+/// [`Blake2b::new()`]: struct.Blake2b.html
+/// [BLAKE2 specification]: https://www.blake2.net/blake2.pdf
+pub struct Params {
+    size: usize,
+    fanout: u8,
+    max_depth: u8,
+    leaf_length: u32,
+    node_offset: u64,
+    node_depth: u8,
+    inner_length: usize,
+    salt: [u8; BLAKE2B_SALTSIZE],
+    personal: [u8; BLAKE2B_SALTSIZE],
+    last_node: bool,
+}
+
+impl Params {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a parameter block for sequential hashing with an output length of `size`.
+    pub fn new(size: usize) -> Result<Self, UnknownCryptoError> {
+        if !(1..=BLAKE2B_OUTSIZE).contains(&size) {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            size,
+            fanout: 1,
+            max_depth: 1,
+            leaf_length: 0,
+            node_offset: 0,
+            node_depth: 0,
+            inner_length: 0,
+            salt: [0u8; BLAKE2B_SALTSIZE],
+            personal: [0u8; BLAKE2B_SALTSIZE],
+            last_node: false,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the salt.
+    pub fn with_salt(self, salt: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if salt.len() > BLAKE2B_SALTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut padded = [0u8; BLAKE2B_SALTSIZE];
+        padded[..salt.len()].copy_from_slice(salt);
+
+        Ok(Self {
+            salt: padded,
+            ..self
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the personalization.
+    pub fn with_personal(self, personal: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if personal.len() > BLAKE2B_SALTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut padded = [0u8; BLAKE2B_SALTSIZE];
+        padded[..personal.len()].copy_from_slice(personal);
+
+        Ok(Self {
+            personal: padded,
+            ..self
+        })
+    }
+
+    /// Set the fanout of the tree. A `fanout` of 0 means unlimited.
+    pub fn with_fanout(self, fanout: u8) -> Self {
+        Self { fanout, ..self }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the maximal depth of the tree. A `max_depth` of 255 means unlimited.
+    pub fn with_max_depth(self, max_depth: u8) -> Result<Self, UnknownCryptoError> {
+        if max_depth < 1 {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self { max_depth, ..self })
+    }
+
+    /// Set the maximal byte length of the leaves. A `leaf_length` of 0 means unlimited.
+    pub fn with_leaf_length(self, leaf_length: u32) -> Self {
+        Self {
+            leaf_length,
+            ..self
+        }
+    }
+
+    /// Set the offset of the node within its level of the tree.
+    pub fn with_node_offset(self, node_offset: u64) -> Self {
+        Self {
+            node_offset,
+            ..self
+        }
+    }
+
+    /// Set the depth of the node, with leaves being at depth 0.
+    pub fn with_node_depth(self, node_depth: u8) -> Self {
+        Self { node_depth, ..self }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the output length of the inner nodes of the tree.
+    pub fn with_inner_length(self, inner_length: usize) -> Result<Self, UnknownCryptoError> {
+        if inner_length > BLAKE2B_OUTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            inner_length,
+            ..self
+        })
+    }
+
+    /// Set whether this is the last node at its depth of the tree.
+    pub fn with_last_node(self, last_node: bool) -> Self {
+        Self { last_node, ..self }
+    }
+
+    /// The initial chaining value for this parameter block and a key of `key_length` bytes.
+    fn initial_state(&self, key_length: usize) -> [U64x4; 2] {
+        debug_assert!(key_length <= BLAKE2B_KEYSIZE);
+        let mut salt = [0u64; 2];
+        let mut personal = [0u64; 2];
+        load_u64_into_le(&self.salt, &mut salt);
+        load_u64_into_le(&self.personal, &mut personal);
+
+        let word_0 = (self.size as u64)
+            | ((key_length as u64) << 8)
+            | (u64::from(self.fanout) << 16)
+            | (u64::from(self.max_depth) << 24)
+            | (u64::from(self.leaf_length) << 32);
+        let word_2 = u64::from(self.node_depth) | ((self.inner_length as u64) << 8);
+
+        [
+            IV[0] ^ U64x4(word_0, self.node_offset, word_2, 0),
+            IV[1] ^ U64x4(salt[0], salt[1], personal[0], personal[1]),
+        ]
+    }
+}
+
 #[allow(clippy::unreadable_literal)]
 /// The BLAKE2b initialization vector as defined in the RFC 7693.
 const IV: [U64x4; 2] = [
@@ -237,12 +477,13 @@ pub struct Blake2b {
     f: [u64; 2],
     is_finalized: bool,
     is_keyed: bool,
+    is_last_node: bool,
     size: usize,
+    key_block: Option<Zeroizing<[u8; BLAKE2B_BLOCKSIZE]>>,
 }
 
 impl Drop for Blake2b {
     fn drop(&mut self) {
-        use zeroize::Zeroize;
         self.init_state.iter_mut().zeroize();
         self.internal_state.iter_mut().zeroize();
         self.buffer.zeroize();
@@ -255,12 +496,27 @@ impl core::fmt::Debug for Blake2b {
             f,
             "Blake2b {{ init_state: [***OMITTED***], internal_state: [***OMITTED***], buffer: \
              [***OMITTED***], leftover: {:?}, t: {:?}, f: {:?}, is_finalized: {:?}, is_keyed: \
-             {:?}, size: {:?} }}",
-            self.leftover, self.t, self.f, self.is_finalized, self.is_keyed, self.size
+             {:?}, is_last_node: {:?}, size: {:?} }}",
+            self.leftover,
+            self.t,
+            self.f,
+            self.is_finalized,
+            self.is_keyed,
+            self.is_last_node,
+            self.size
         )
     }
 }
 
+impl Default for Blake2b {
+    /// Initialize a `Blake2b` struct for BLAKE2b-512 without a key.
+    fn default() -> Self {
+        Self::new(None, BLAKE2B_OUTSIZE).unwrap()
+    }
+}
+
+impl_io_write_trait!(Blake2b);
+
 impl Blake2b {
     /// Increment the internal states offset value `t`.
     fn increment_offset(&mut self, value: u64) {
@@ -311,41 +567,56 @@ impl Blake2b {
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    #[allow(clippy::unreadable_literal)]
     /// Initialize a `Blake2b` struct with a given size and an optional key.
     pub fn new(secret_key: Option<&SecretKey>, size: usize) -> Result<Self, UnknownCryptoError> {
-        if size < 1 || size > BLAKE2B_OUTSIZE {
-            return Err(UnknownCryptoError);
-        }
+        Self::with_params(secret_key, &Params::new(size)?)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2b` struct with a given parameter block and an optional key.
+    pub fn with_params(
+        secret_key: Option<&SecretKey>,
+        params: &Params,
+    ) -> Result<Self, UnknownCryptoError> {
+        let klen = match secret_key {
+            Some(sk) => sk.len(),
+            None => 0,
+        };
+
+        Self::new_node(secret_key, klen, params, params.size)
+    }
+
+    /// Initialize a `Blake2b` struct for a node of BLAKE2bp or tree hashing. The
+    /// parameter block of every node records a key of `key_length` bytes and the
+    /// digest length of the root, but only the leaves process `secret_key` and
+    /// every node outputs `output_size` bytes.
+    pub(crate) fn new_node(
+        secret_key: Option<&SecretKey>,
+        key_length: usize,
+        params: &Params,
+        output_size: usize,
+    ) -> Result<Self, UnknownCryptoError> {
+        debug_assert!((1..=BLAKE2B_OUTSIZE).contains(&output_size));
+        let init_state = params.initial_state(key_length);
 
         let mut context = Self {
-            init_state: [U64x4::default(); 2],
-            internal_state: IV,
+            init_state,
+            internal_state: init_state,
             buffer: [0u8; BLAKE2B_BLOCKSIZE],
             leftover: 0,
             t: [0u64; 2],
             f: [0u64; 2],
             is_finalized: false,
-            is_keyed: false,
-            size,
+            is_keyed: secret_key.is_some(),
+            is_last_node: params.last_node,
+            size: output_size,
+            key_block: None,
         };
 
-        match secret_key {
-            Some(sk) => {
-                context.is_keyed = true;
-                let klen = sk.len();
-                context.internal_state[0].0 ^= 0x01010000 ^ ((klen as u64) << 8) ^ (size as u64);
-                context.init_state.copy_from_slice(&context.internal_state);
-                context.update(sk.unprotected_as_bytes())?;
-                // The state needs updating with the secret key padded to blocksize length
-                let pad = [0u8; BLAKE2B_BLOCKSIZE];
-                let rem = BLAKE2B_BLOCKSIZE - klen;
-                context.update(pad[..rem].as_ref())?;
-            }
-            None => {
-                context.internal_state[0].0 ^= 0x01010000 ^ (size as u64);
-                context.init_state.copy_from_slice(&context.internal_state);
-            }
+        if let Some(sk) = secret_key {
+            debug_assert_eq!(sk.len(), key_length);
+            context.key_block = Some(Self::pad_key(sk));
+            context.reset_to_key();
         }
 
         Ok(context)
@@ -362,6 +633,24 @@ impl Blake2b {
             return Err(UnknownCryptoError);
         }
 
+        if let Some(sk) = secret_key {
+            self.key_block = Some(Self::pad_key(sk));
+        }
+        self.reset_to_key();
+
+        Ok(())
+    }
+
+    /// The secret key padded to blocksize length, which keyed states process first.
+    fn pad_key(secret_key: &SecretKey) -> Zeroizing<[u8; BLAKE2B_BLOCKSIZE]> {
+        let mut key_block = Zeroizing::new([0u8; BLAKE2B_BLOCKSIZE]);
+        key_block[..secret_key.len()].copy_from_slice(secret_key.unprotected_as_bytes());
+
+        key_block
+    }
+
+    /// Reset to the state after processing the key block, if any.
+    fn reset_to_key(&mut self) {
         self.internal_state.copy_from_slice(&self.init_state);
         self.buffer = [0u8; BLAKE2B_BLOCKSIZE];
         self.leftover = 0;
@@ -369,15 +658,11 @@ impl Blake2b {
         self.f = [0u64; 2];
         self.is_finalized = false;
 
-        match secret_key {
-            Some(sk) => {
-                self.update(sk.unprotected_as_bytes())?;
-                // The state needs updating with the secret key padded to blocksize length
-                let pad = [0u8; BLAKE2B_BLOCKSIZE];
-                let rem = BLAKE2B_BLOCKSIZE - sk.len();
-                self.update(pad[..rem].as_ref())
-            }
-            None => Ok(()),
+        if let Some(key_block) = &self.key_block {
+            // The same as updating with the key block, since update() keeps
+            // a full buffer until more data arrives.
+            self.buffer.copy_from_slice(&key_block[..]);
+            self.leftover = BLAKE2B_BLOCKSIZE;
         }
     }
 
@@ -439,6 +724,9 @@ impl Blake2b {
         self.increment_offset(in_buffer_len as u64);
         // Mark that it is the last block of data to be processed
         self.f[0] = !0;
+        if self.is_last_node {
+            self.f[1] = !0;
+        }
 
         for leftover_block in self.buffer.iter_mut().skip(in_buffer_len) {
             *leftover_block = 0;
@@ -469,6 +757,131 @@ impl Blake2b {
             Err(UnknownCryptoError)
         }
     }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state to `dst_out`, from which it can be restored with
+    /// [`import_state()`].
+    ///
+    /// [`import_state()`]: struct.Blake2b.html#method.import_state
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut writer = StateWriter::new(dst_out, STATE_ID_BLAKE2B, BLAKE2B_EXPORTSIZE)?;
+        for words in self.init_state.iter().chain(self.internal_state.iter()) {
+            writer.put_u64(words.0);
+            writer.put_u64(words.1);
+            writer.put_u64(words.2);
+            writer.put_u64(words.3);
+        }
+        // Only the buffered data is exported, and not what was left in the
+        // buffer by previous blocks.
+        let mut buffer = [0u8; BLAKE2B_BLOCKSIZE];
+        buffer[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+        writer.put_bytes(&buffer);
+        buffer.zeroize();
+        writer.put_u64(self.leftover as u64);
+        writer.put_u64(self.t[0]);
+        writer.put_u64(self.t[1]);
+        writer.put_bool(self.is_keyed);
+        writer.put_bool(self.is_last_node);
+        writer.put_u8(self.size as u8);
+        writer.finish();
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Restore a state exported with [`export_state()`].
+    ///
+    /// # Note:
+    /// The secret key of a keyed state is not exported, so a keyed state can
+    /// only be reset by passing the key to [`reset()`] after importing it.
+    ///
+    /// [`reset()`]: struct.Blake2b.html#method.reset
+    /// [`export_state()`]: struct.Blake2b.html#method.export_state
+    pub fn import_state(exported: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut reader = StateReader::new(exported, STATE_ID_BLAKE2B, BLAKE2B_EXPORTSIZE)?;
+        let mut state = Self::new(None, BLAKE2B_OUTSIZE)?;
+        for words in state
+            .init_state
+            .iter_mut()
+            .chain(state.internal_state.iter_mut())
+        {
+            *words = U64x4(
+                reader.get_u64(),
+                reader.get_u64(),
+                reader.get_u64(),
+                reader.get_u64(),
+            );
+        }
+        reader.get_bytes(&mut state.buffer);
+        let leftover = reader.get_u64();
+        state.t[0] = reader.get_u64();
+        state.t[1] = reader.get_u64();
+        state.is_keyed = reader.get_bool()?;
+        state.is_last_node = reader.get_bool()?;
+        let size = reader.get_u8() as usize;
+        reader.finish();
+
+        // update() keeps a full buffer until more data arrives.
+        if leftover > BLAKE2B_BLOCKSIZE as u64 {
+            return Err(UnknownCryptoError);
+        }
+        if size == 0 || size > BLAKE2B_OUTSIZE {
+            return Err(UnknownCryptoError);
+        }
+        state.leftover = leftover as usize;
+        state.size = size;
+
+        Ok(state)
+    }
+}
+
+impl IncrementalHash for Blake2b {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        Blake2b::default()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        // An imported keyed state does not have its key.
+        if self.is_keyed && self.key_block.is_none() {
+            return Err(UnknownCryptoError);
+        }
+        self.reset_to_key();
+
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+#[cfg(test)]
+/// Compare two `Blake2b` states, for testing.
+pub(crate) fn compare_blake2b_states(state_1: &Blake2b, state_2: &Blake2b) {
+    assert!(state_1.init_state == state_2.init_state);
+    assert!(state_1.internal_state == state_2.internal_state);
+    assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+    assert_eq!(state_1.leftover, state_2.leftover);
+    assert_eq!(state_1.t, state_2.t);
+    assert_eq!(state_1.f, state_2.f);
+    assert_eq!(state_1.is_finalized, state_2.is_finalized);
+    assert_eq!(state_1.is_keyed, state_2.is_keyed);
+    assert_eq!(state_1.is_last_node, state_2.is_last_node);
+    assert_eq!(state_1.size, state_2.size);
+    assert_eq!(
+        state_1.key_block.as_ref().map(|key_block| &key_block[..]),
+        state_2.key_block.as_ref().map(|key_block| &key_block[..])
+    );
 }
 
 // Testing public functions in the module.
@@ -481,22 +894,10 @@ mod public {
     fn test_debug_impl() {
         let initial_state = Blake2b::new(None, BLAKE2B_OUTSIZE).unwrap();
         let debug = format!("{:?}", initial_state);
-        let expected = "Blake2b { init_state: [***OMITTED***], internal_state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, t: [0, 0], f: [0, 0], is_finalized: false, is_keyed: false, size: 64 }";
+        let expected = "Blake2b { init_state: [***OMITTED***], internal_state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, t: [0, 0], f: [0, 0], is_finalized: false, is_keyed: false, is_last_node: false, size: 64 }";
         assert_eq!(debug, expected);
     }
 
-    fn compare_blake2b_states(state_1: &Blake2b, state_2: &Blake2b) {
-        assert!(state_1.init_state == state_2.init_state);
-        assert!(state_1.internal_state == state_2.internal_state);
-        assert_eq!(state_1.buffer[..], state_2.buffer[..]);
-        assert_eq!(state_1.leftover, state_2.leftover);
-        assert_eq!(state_1.t, state_2.t);
-        assert_eq!(state_1.f, state_2.f);
-        assert_eq!(state_1.is_finalized, state_2.is_finalized);
-        assert_eq!(state_1.is_keyed, state_2.is_keyed);
-        assert_eq!(state_1.size, state_2.size);
-    }
-
     mod test_streaming_interface_no_key {
         use super::*;
         use crate::test_framework::incremental_interface::*;
@@ -613,6 +1014,83 @@ mod public {
         }
     }
 
+    mod test_params {
+        use super::*;
+
+        #[test]
+        fn test_params_errors() {
+            assert!(Params::new(0).is_err());
+            assert!(Params::new(65).is_err());
+
+            let params = Params::new(64).unwrap();
+            assert!(params.with_salt(&[0u8; 16]).is_ok());
+            assert!(params.with_salt(&[0u8; 17]).is_err());
+            assert!(params.with_personal(&[0u8; 16]).is_ok());
+            assert!(params.with_personal(&[0u8; 17]).is_err());
+            assert!(params.with_max_depth(1).is_ok());
+            assert!(params.with_max_depth(0).is_err());
+            assert!(params.with_inner_length(64).is_ok());
+            assert!(params.with_inner_length(65).is_err());
+        }
+
+        #[test]
+        fn test_default_params_same_as_new() {
+            let sk = SecretKey::from_slice(b"Testing").unwrap();
+            let params = Params::new(32).unwrap();
+
+            compare_blake2b_states(
+                &Blake2b::new(None, 32).unwrap(),
+                &Blake2b::with_params(None, &params).unwrap(),
+            );
+            compare_blake2b_states(
+                &Blake2b::new(Some(&sk), 32).unwrap(),
+                &Blake2b::with_params(Some(&sk), &params).unwrap(),
+            );
+        }
+
+        #[test]
+        fn test_salt_zero_padded() {
+            let params_short = Params::new(64).unwrap().with_salt(b"salt").unwrap();
+            let mut padded = [0u8; 16];
+            padded[..4].copy_from_slice(b"salt");
+            let params_padded = Params::new(64).unwrap().with_salt(&padded).unwrap();
+            assert_eq!(params_short, params_padded);
+        }
+
+        #[test]
+        fn test_params_change_digest() {
+            let digest = |params: &Params| {
+                let mut state = Blake2b::with_params(None, params).unwrap();
+                state.update(b"Some data").unwrap();
+                state.finalize().unwrap()
+            };
+
+            let params = Params::new(64).unwrap();
+            let default = digest(&params);
+            assert_eq!(default, Hasher::Blake2b512.digest(b"Some data").unwrap());
+            assert_ne!(default, digest(&params.with_salt(b"salt").unwrap()));
+            assert_ne!(default, digest(&params.with_personal(b"personal").unwrap()));
+            assert_ne!(default, digest(&params.with_fanout(2)));
+            assert_ne!(default, digest(&params.with_max_depth(2).unwrap()));
+            assert_ne!(default, digest(&params.with_leaf_length(1)));
+            assert_ne!(default, digest(&params.with_node_offset(1)));
+            assert_ne!(default, digest(&params.with_node_depth(1)));
+            assert_ne!(default, digest(&params.with_inner_length(1).unwrap()));
+            assert_ne!(default, digest(&params.with_last_node(true)));
+        }
+
+        #[test]
+        fn test_reset_keeps_last_node() {
+            let params = Params::new(64).unwrap().with_last_node(true);
+            let mut state = Blake2b::with_params(None, &params).unwrap();
+            state.update(b"Some data").unwrap();
+            let first = state.finalize().unwrap();
+            state.reset(None).unwrap();
+            state.update(b"Some data").unwrap();
+            assert_eq!(first, state.finalize().unwrap());
+        }
+    }
+
     #[cfg(feature = "safe_api")]
     mod test_verify {
         use super::*;
@@ -769,6 +1247,31 @@ mod public {
             assert!(state_second.reset(Some(&secret_key)).is_err());
             assert!(state_second.reset(None).is_ok());
         }
+
+        #[test]
+        fn test_incremental_hash_reset_keeps_key() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+            let other_key = SecretKey::from_slice(b"Tasting").unwrap();
+            let expected = |sk: &SecretKey| {
+                let mut state = Blake2b::new(Some(sk), 64).unwrap();
+                state.update(b"Tests").unwrap();
+                state.finalize().unwrap()
+            };
+
+            let mut state = Blake2b::new(Some(&secret_key), 64).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(IncrementalHash::reset(&mut state).is_ok());
+            state.update(b"Tests").unwrap();
+            assert_eq!(state.finalize().unwrap(), expected(&secret_key));
+
+            // The key given to the last reset is kept.
+            state.reset(Some(&other_key)).unwrap();
+            state.update(b"More tests").unwrap();
+            assert!(IncrementalHash::reset(&mut state).is_ok());
+            state.update(b"Tests").unwrap();
+            assert_eq!(state.finalize().unwrap(), expected(&other_key));
+        }
     }
 
     mod test_streaming_interface {
@@ -937,6 +1440,163 @@ mod public {
             }
         }
     }
+
+    mod test_export_state {
+        use super::*;
+
+        /// Hash `data` with the state exported and imported after `split` bytes.
+        fn digest_with_export(
+            sk: Option<&SecretKey>,
+            params: &Params,
+            data: &[u8],
+            split: usize,
+        ) -> Digest {
+            let mut state = Blake2b::with_params(sk, params).unwrap();
+            state.update(&data[..split]).unwrap();
+            let mut exported = [0u8; BLAKE2B_EXPORTSIZE];
+            state.export_state(&mut exported).unwrap();
+
+            let mut imported = Blake2b::import_state(&exported).unwrap();
+            let mut exported_again = [0u8; BLAKE2B_EXPORTSIZE];
+            imported.export_state(&mut exported_again).unwrap();
+            assert_eq!(exported[..], exported_again[..]);
+
+            imported.update(&data[split..]).unwrap();
+            imported.finalize().unwrap()
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let data = [1u8; 3 * BLAKE2B_BLOCKSIZE + 3];
+            let params = Params::new(32)
+                .unwrap()
+                .with_salt(b"Salt")
+                .unwrap()
+                .with_last_node(true);
+
+            for sk in [None, Some(&sk)].iter() {
+                for params in [Params::new(64).unwrap(), params].iter() {
+                    let mut state = Blake2b::with_params(*sk, params).unwrap();
+                    state.update(&data).unwrap();
+                    let expected = state.finalize().unwrap();
+
+                    for split in [0, 1, 127, 128, 129, 300, data.len()].iter() {
+                        assert_eq!(digest_with_export(*sk, params, &data, *split), expected);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_imported_keeps_keyed_mode() {
+            let sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let mut exported = [0u8; BLAKE2B_EXPORTSIZE];
+            Blake2b::new(Some(&sk), 64)
+                .unwrap()
+                .export_state(&mut exported)
+                .unwrap();
+
+            let mut imported = Blake2b::import_state(&exported).unwrap();
+            assert!(imported.reset(None).is_err());
+            // The key is not exported, so it must be given to reset.
+            assert!(IncrementalHash::reset(&mut imported).is_err());
+            assert!(imported.reset(Some(&sk)).is_ok());
+            assert!(IncrementalHash::reset(&mut imported).is_ok());
+        }
+
+        #[test]
+        fn test_length_err() {
+            let state = Blake2b::new(None, 64).unwrap();
+            let mut exported = [0u8; BLAKE2B_EXPORTSIZE + 1];
+            assert!(state
+                .export_state(&mut exported[..BLAKE2B_EXPORTSIZE - 1])
+                .is_err());
+            assert!(state.export_state(&mut exported).is_err());
+            assert!(state
+                .export_state(&mut exported[..BLAKE2B_EXPORTSIZE])
+                .is_ok());
+
+            assert!(Blake2b::import_state(&exported[..BLAKE2B_EXPORTSIZE - 1]).is_err());
+            assert!(Blake2b::import_state(&exported).is_err());
+            assert!(Blake2b::import_state(&exported[..BLAKE2B_EXPORTSIZE]).is_ok());
+        }
+
+        #[test]
+        fn test_finalized_err() {
+            let mut state = Blake2b::new(None, 64).unwrap();
+            let mut exported = [0u8; BLAKE2B_EXPORTSIZE];
+            let _ = state.finalize().unwrap();
+            assert!(state.export_state(&mut exported).is_err());
+            state.reset(None).unwrap();
+            assert!(state.export_state(&mut exported).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_state_err() {
+            let mut exported = [0u8; BLAKE2B_EXPORTSIZE];
+            Blake2b::new(None, 64)
+                .unwrap()
+                .export_state(&mut exported)
+                .unwrap();
+            assert!(Blake2b::import_state(&exported).is_ok());
+
+            // The version of the encoding.
+            let mut invalid = exported;
+            invalid[0] += 1;
+            assert!(Blake2b::import_state(&invalid).is_err());
+
+            // The type that exported the state.
+            let mut invalid = exported;
+            invalid[1] += 1;
+            assert!(Blake2b::import_state(&invalid).is_err());
+
+            // The leftover, which follows the initial and internal state and buffer.
+            let leftover = STATE_EXPORT_HEADERSIZE + 128 + BLAKE2B_BLOCKSIZE;
+            let mut invalid = exported;
+            invalid[leftover] = BLAKE2B_BLOCKSIZE as u8;
+            assert!(Blake2b::import_state(&invalid).is_ok());
+            invalid[leftover] = (BLAKE2B_BLOCKSIZE + 1) as u8;
+            assert!(Blake2b::import_state(&invalid).is_err());
+
+            // Whether it is keyed and the last node, which must be 0 or 1.
+            for flag in [BLAKE2B_EXPORTSIZE - 3, BLAKE2B_EXPORTSIZE - 2].iter() {
+                let mut invalid = exported;
+                invalid[*flag] = 1;
+                assert!(Blake2b::import_state(&invalid).is_ok());
+                invalid[*flag] = 2;
+                assert!(Blake2b::import_state(&invalid).is_err());
+            }
+
+            // The output size.
+            let mut invalid = exported;
+            invalid[BLAKE2B_EXPORTSIZE - 1] = 1;
+            assert!(Blake2b::import_state(&invalid).is_ok());
+            invalid[BLAKE2B_EXPORTSIZE - 1] = 0;
+            assert!(Blake2b::import_state(&invalid).is_err());
+            invalid[BLAKE2B_EXPORTSIZE - 1] = (BLAKE2B_OUTSIZE + 1) as u8;
+            assert!(Blake2b::import_state(&invalid).is_err());
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Exporting and importing the state at any point should not
+                /// change the digest.
+                fn prop_export_import_same_digest(data: Vec<u8>, split: usize) -> bool {
+                    let split = if data.is_empty() { 0 } else { split % data.len() };
+                    let params = Params::new(64).unwrap();
+                    let mut state = Blake2b::new(None, 64).unwrap();
+                    state.update(&data).unwrap();
+
+                    digest_with_export(None, &params, &data, split) == state.finalize().unwrap()
+                }
+            }
+        }
+    }
 }
 
 // Testing private functions in the module.
@@ -958,7 +1618,9 @@ mod private {
                 f: [0u64; 2],
                 is_finalized: false,
                 is_keyed: false,
+                is_last_node: false,
                 size: 1,
+                key_block: None,
             };
 
             context.increment_offset(1);
@@ -984,7 +1646,9 @@ mod private {
                 f: [0u64; 2],
                 is_finalized: false,
                 is_keyed: false,
+                is_last_node: false,
                 size: 1,
+                key_block: None,
             };
 
             context.increment_offset(u64::max_value());
@@ -0,0 +1,619 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: An optional secret key.
+//! - `params`: The parameter block, which sets the shape of the tree and the
+//!   output length for the digest.
+//! - `data`: The data to be hashed.
+//! - `expected`: The expected digest when verifying.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The maximal depth of `params` is less than 2.
+//! - The inner hash length of `params` is 0.
+//! - The tree needed for `data` is deeper than the maximal depth of `params`.
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - [`reset()`] is called with `Some(secret_key)` but the struct was
+//!   initialized with `None`.
+//! - [`reset()`] is called with `None` as `secret_key` but the struct was
+//!   initialized with `Some(secret_key)`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 2^64-1 leaves are hashed.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - The minimum recommended size for a secret key is 32 bytes.
+//! - When using tree hashing with a secret key, then the output can be used as a
+//!   MAC. If this is the intention, __**avoid using**__ [`as_ref()`]
+//!   to compare such MACs and use instead [`verify()`], which will compare
+//!   the MAC in constant time.
+//! - The recommended minimum output size and inner hash length is 32.
+//!
+//! # Note:
+//! The data is split into leaves of the leaf length of `params`, where only the
+//! last leaf may be shorter. Each level above the leaves hashes the concatenated
+//! digests of at most the fanout of `params` consecutive nodes of the level below,
+//! until a level consists of a single node. This node is the root, which is never
+//! a leaf. A leaf length or fanout of 0 is unlimited.
+//!
+//! Every node is a BLAKE2b node with the parameter block `params`, in which the
+//! node offset, node depth and last node flag are set for that node. As with
+//! BLAKE2bp, the parameter block of every node holds the digest length and key
+//! length of `params`, but only the leaves process the secret key and only the
+//! root outputs the digest length. The other nodes output the inner hash length
+//! of `params`.
+//!
+//! The digests of at most the fanout of `params` nodes are kept in memory for each
+//! level of the tree.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::blake2b::{tree::Blake2bTree, Params};
+//!
+//! let params = Params::new(32)?
+//!     .with_fanout(2)
+//!     .with_max_depth(255)?
+//!     .with_leaf_length(4096)
+//!     .with_inner_length(64)?;
+//!
+//! let mut state = Blake2bTree::new(None, &params)?;
+//! state.update(&[0u8; 10000])?;
+//! let digest = state.finalize()?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Blake2bTree.html
+//! [`reset()`]: struct.Blake2bTree.html
+//! [`finalize()`]: struct.Blake2bTree.html
+//! [`SecretKey::generate()`]: ../struct.SecretKey.html
+//! [`verify()`]: struct.Blake2bTree.html
+//! [`as_ref()`]: ../struct.Digest.html
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::blake2b::{Blake2b, Digest, Params, SecretKey, BLAKE2B_KEYSIZE},
+};
+#[cfg(not(feature = "safe_api"))]
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+#[derive(Clone)]
+/// The node of a level above the leaves that is currently being filled.
+struct Level {
+    /// The concatenated digests of the children of the current node.
+    children: Vec<u8>,
+    /// The amount of children of the current node.
+    count: usize,
+    /// The node offset of the current node.
+    node_offset: u64,
+}
+
+#[derive(Clone)]
+/// BLAKE2b tree hashing streaming state.
+pub struct Blake2bTree {
+    params: Params,
+    key: [u8; BLAKE2B_KEYSIZE],
+    key_length: usize,
+    leaf: Blake2b,
+    leaf_offset: u64,
+    leaf_filled: usize,
+    levels: Vec<Level>,
+    is_finalized: bool,
+}
+
+impl Drop for Blake2bTree {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        for level in self.levels.iter_mut() {
+            level.children.zeroize();
+        }
+    }
+}
+
+impl core::fmt::Debug for Blake2bTree {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Blake2bTree {{ params: {:?}, key: [***OMITTED***], leaf: [***OMITTED***], \
+             leaf_offset: {:?}, levels: [***OMITTED***], is_finalized: {:?}, is_keyed: {:?} }}",
+            self.params,
+            self.leaf_offset,
+            self.is_finalized,
+            self.key_length != 0
+        )
+    }
+}
+
+impl_io_write_trait!(Blake2bTree);
+
+impl Blake2bTree {
+    /// Return a `Blake2b` struct for the leaf at `node_offset`.
+    fn new_leaf(&self, node_offset: u64) -> Result<Blake2b, UnknownCryptoError> {
+        let params = self
+            .params
+            .with_node_offset(node_offset)
+            .with_node_depth(0)
+            .with_last_node(false);
+
+        if self.key_length != 0 {
+            let sk = SecretKey::from_slice(&self.key[..self.key_length])?;
+            Blake2b::new_node(
+                Some(&sk),
+                self.key_length,
+                &params,
+                self.params.inner_length,
+            )
+        } else {
+            Blake2b::new_node(None, 0, &params, self.params.inner_length)
+        }
+    }
+
+    /// Hash the current node of the level at index `idx`, which has a node depth
+    /// of `idx + 1`, and start the next node of that level.
+    fn hash_node(
+        &mut self,
+        idx: usize,
+        last_node: bool,
+        output_size: usize,
+    ) -> Result<Digest, UnknownCryptoError> {
+        let level = &mut self.levels[idx];
+        let params = self
+            .params
+            .with_node_offset(level.node_offset)
+            .with_node_depth((idx + 1) as u8)
+            .with_last_node(last_node);
+
+        let mut node = Blake2b::new_node(None, self.key_length, &params, output_size)?;
+        node.update(&level.children)?;
+        level.children.zeroize();
+        level.children.clear();
+        level.count = 0;
+        level.node_offset += 1;
+
+        node.finalize()
+    }
+
+    /// Add `child` to the current node of the level at index `idx`.
+    fn push_child(&mut self, idx: usize, child: &Digest) -> Result<(), UnknownCryptoError> {
+        let mut idx = idx;
+        let mut child = *child;
+
+        loop {
+            if idx == self.levels.len() {
+                // The level has a node depth of idx + 1, which must be less than the
+                // maximal depth.
+                if idx + 2 > usize::from(self.params.max_depth) {
+                    return Err(UnknownCryptoError);
+                }
+                self.levels.push(Level {
+                    children: Vec::new(),
+                    count: 0,
+                    node_offset: 0,
+                });
+            }
+
+            let fanout = usize::from(self.params.fanout);
+            if fanout == 0 || self.levels[idx].count < fanout {
+                let level = &mut self.levels[idx];
+                level.children.extend_from_slice(child.as_ref());
+                level.count += 1;
+                return Ok(());
+            }
+
+            // The current node is full and another node follows it, so it is not the
+            // last node of its level.
+            let full_node = self.hash_node(idx, false, self.params.inner_length)?;
+            let level = &mut self.levels[idx];
+            level.children.extend_from_slice(child.as_ref());
+            level.count += 1;
+
+            child = full_node;
+            idx += 1;
+        }
+    }
+
+    /// Hash the current leaf and start the next one.
+    fn finish_leaf(&mut self, last_node: bool) -> Result<(), UnknownCryptoError> {
+        self.leaf.is_last_node = last_node;
+        let leaf_digest = self.leaf.finalize()?;
+        self.push_child(0, &leaf_digest)?;
+
+        if !last_node {
+            // If this panics, the amount of leaves is at its limit.
+            self.leaf_offset = self.leaf_offset.checked_add(1).unwrap();
+            self.leaf = self.new_leaf(self.leaf_offset)?;
+            self.leaf_filled = 0;
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2bTree` struct with a given parameter block and an optional key.
+    pub fn new(
+        secret_key: Option<&SecretKey>,
+        params: &Params,
+    ) -> Result<Self, UnknownCryptoError> {
+        if params.max_depth < 2 || params.inner_length == 0 {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut key = [0u8; BLAKE2B_KEYSIZE];
+        let key_length = match secret_key {
+            Some(sk) => {
+                key[..sk.len()].copy_from_slice(sk.unprotected_as_bytes());
+                sk.len()
+            }
+            None => 0,
+        };
+
+        let leaf = Blake2b::new_node(
+            secret_key,
+            key_length,
+            &params
+                .with_node_offset(0)
+                .with_node_depth(0)
+                .with_last_node(false),
+            params.inner_length,
+        )?;
+
+        Ok(Self {
+            params: *params,
+            key,
+            key_length,
+            leaf,
+            leaf_offset: 0,
+            leaf_filled: 0,
+            levels: Vec::new(),
+            is_finalized: false,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Reset to `new()` state.
+    pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
+        if secret_key.is_some() != (self.key_length != 0) {
+            return Err(UnknownCryptoError);
+        }
+
+        if let Some(sk) = secret_key {
+            self.key.zeroize();
+            self.key[..sk.len()].copy_from_slice(sk.unprotected_as_bytes());
+            self.key_length = sk.len();
+        }
+
+        self.leaf = self.new_leaf(0)?;
+        self.leaf_offset = 0;
+        self.leaf_filled = 0;
+        for level in self.levels.iter_mut() {
+            level.children.zeroize();
+        }
+        self.levels.clear();
+        self.is_finalized = false;
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        let leaf_length = self.params.leaf_length as usize;
+        if leaf_length == 0 {
+            return self.leaf.update(data);
+        }
+
+        let mut bytes = data;
+        while !bytes.is_empty() {
+            if self.leaf_filled == leaf_length {
+                self.finish_leaf(false)?;
+            }
+
+            let take = core::cmp::min(leaf_length - self.leaf_filled, bytes.len());
+            self.leaf.update(&bytes[..take])?;
+            self.leaf_filled += take;
+            bytes = &bytes[take..];
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a BLAKE2b tree hashing digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+        self.finish_leaf(true)?;
+
+        let mut idx = 0;
+        loop {
+            if idx == self.levels.len() - 1 {
+                // The highest level only has one node, which is the root.
+                debug_assert_eq!(self.levels[idx].node_offset, 0);
+                return self.hash_node(idx, true, self.params.size);
+            }
+
+            let last_node = self.hash_node(idx, true, self.params.inner_length)?;
+            self.push_child(idx + 1, &last_node)?;
+            idx += 1;
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a Blake2bTree Digest in constant time.
+    pub fn verify(
+        expected: &Digest,
+        secret_key: &SecretKey,
+        params: &Params,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(Some(secret_key), params)?;
+        state.update(data)?;
+
+        if expected == &state.finalize()? {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::blake2b::compare_blake2b_states;
+
+    /// The parameters used for testing, which give a new level of the tree for
+    /// every doubling of the amount of leaves.
+    fn test_params() -> Params {
+        Params::new(64)
+            .unwrap()
+            .with_fanout(2)
+            .with_max_depth(255)
+            .unwrap()
+            .with_leaf_length(64)
+            .with_inner_length(32)
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Blake2bTree::new(
+            None,
+            &Params::new(32)
+                .unwrap()
+                .with_max_depth(2)
+                .unwrap()
+                .with_inner_length(32)
+                .unwrap(),
+        )
+        .unwrap();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Blake2bTree { params: Params { size: 32, fanout: 1, max_depth: 2, leaf_length: 0, node_offset: 0, node_depth: 0, inner_length: 32, salt: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], personal: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], last_node: false }, key: [***OMITTED***], leaf: [***OMITTED***], leaf_offset: 0, levels: [***OMITTED***], is_finalized: false, is_keyed: false }";
+        assert_eq!(debug, expected);
+    }
+
+    fn compare_tree_states(state_1: &Blake2bTree, state_2: &Blake2bTree) {
+        assert_eq!(state_1.params, state_2.params);
+        assert_eq!(state_1.key[..], state_2.key[..]);
+        assert_eq!(state_1.key_length, state_2.key_length);
+        compare_blake2b_states(&state_1.leaf, &state_2.leaf);
+        assert_eq!(state_1.leaf_offset, state_2.leaf_offset);
+        assert_eq!(state_1.leaf_filled, state_2.leaf_filled);
+        assert_eq!(state_1.levels.len(), state_2.levels.len());
+        for (level_1, level_2) in state_1.levels.iter().zip(state_2.levels.iter()) {
+            assert_eq!(level_1.children, level_2.children);
+            assert_eq!(level_1.count, level_2.count);
+            assert_eq!(level_1.node_offset, level_2.node_offset);
+        }
+        assert_eq!(state_1.is_finalized, state_2.is_finalized);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Blake2bTree {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset(None)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                let mut state = Blake2bTree::new(None, &test_params())?;
+                state.update(input)?;
+                state.finalize()
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Blake2bTree, state_2: &Blake2bTree) {
+                compare_tree_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state = Blake2bTree::new(None, &test_params()).unwrap();
+
+            let test_runner =
+                StreamingContextConsistencyTester::<Digest, Blake2bTree>::new(initial_state, 64);
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state = Blake2bTree::new(None, &test_params()).unwrap();
+
+                    let test_runner = StreamingContextConsistencyTester::<Digest, Blake2bTree>::new(
+                        initial_state,
+                        64,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        fn test_params_err() {
+            assert!(Blake2bTree::new(None, &test_params()).is_ok());
+            assert!(Blake2bTree::new(None, &test_params().with_max_depth(1).unwrap()).is_err());
+            assert!(Blake2bTree::new(None, &test_params().with_inner_length(0).unwrap()).is_err());
+        }
+
+        #[test]
+        fn test_node_fields_of_params_ignored() {
+            let params = test_params()
+                .with_node_offset(3)
+                .with_node_depth(2)
+                .with_last_node(true);
+
+            let mut state = Blake2bTree::new(None, &test_params()).unwrap();
+            state.update(&[0u8; 300]).unwrap();
+            let mut state_node_fields = Blake2bTree::new(None, &params).unwrap();
+            state_node_fields.update(&[0u8; 300]).unwrap();
+
+            assert_eq!(
+                state.finalize().unwrap(),
+                state_node_fields.finalize().unwrap()
+            );
+        }
+    }
+
+    mod test_update {
+        use super::*;
+
+        #[test]
+        fn test_leaves_only_finished_when_followed() {
+            let mut state = Blake2bTree::new(None, &test_params()).unwrap();
+            state.update(&[0u8; 64]).unwrap();
+            assert_eq!(state.leaf_offset, 0);
+            assert!(state.levels.is_empty());
+
+            state.update(&[0u8; 1]).unwrap();
+            assert_eq!(state.leaf_offset, 1);
+            assert_eq!(state.levels.len(), 1);
+        }
+    }
+
+    mod test_reset {
+        use super::*;
+
+        #[test]
+        fn test_switching_keyed_modes_fails() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+
+            let mut state = Blake2bTree::new(Some(&secret_key), &test_params()).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(state.reset(None).is_err());
+            assert!(state.reset(Some(&secret_key)).is_ok());
+
+            let mut state_second = Blake2bTree::new(None, &test_params()).unwrap();
+            state_second.update(b"Tests").unwrap();
+            let _ = state_second.finalize().unwrap();
+            assert!(state_second.reset(Some(&secret_key)).is_err());
+            assert!(state_second.reset(None).is_ok());
+        }
+
+        #[test]
+        fn test_reset_with_other_key() {
+            let sk_1 = SecretKey::from_slice(b"Testing").unwrap();
+            let sk_2 = SecretKey::from_slice(&[1u8; 64]).unwrap();
+
+            let mut state = Blake2bTree::new(Some(&sk_1), &test_params()).unwrap();
+            state.update(&[0u8; 1000]).unwrap();
+            let _ = state.finalize().unwrap();
+            state.reset(Some(&sk_2)).unwrap();
+
+            compare_tree_states(
+                &state,
+                &Blake2bTree::new(Some(&sk_2), &test_params()).unwrap(),
+            );
+        }
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut state = Blake2bTree::new(Some(&sk), &test_params()).unwrap();
+                    state.update(&data[..]).unwrap();
+                    let tag = state.finalize().unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Blake2bTree::verify(&tag, &bad_sk, &test_params(), &data[..]).is_err()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1338 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: An optional secret key.
+//! - `size`: The desired output length for the digest.
+//! - `data`: The data to be hashed.
+//! - `expected`: The expected digest when verifying.
+//! - `params`: The parameter block, which sets the salt, personalization and tree
+//!   hashing parameters besides `size`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `size` is 0 or greater than 32.
+//! - The salt or personalization given to [`Params`] is greater than 8 bytes.
+//! - The maximal depth given to [`Params`] is 0.
+//! - The node offset given to [`Params`] is greater than 2^48-1.
+//! - The inner hash length given to [`Params`] is greater than 32.
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - [`reset()`] is called with `Some(secret_key)` but the struct was
+//!   initialized with `None`.
+//! - [`reset()`] is called with `None` as `secret_key` but the struct was
+//!   initialized with `Some(secret_key)`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 2^64-1 bytes of data are hashed.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - The minimum recommended size for a secret key is 16 bytes.
+//! - When using Blake2s with a secret key, then the output can be used as a
+//!   MAC. If this is the intention, __**avoid using**__ [`as_ref()`]
+//!   to compare such MACs and use instead [`verify()`], which will compare
+//!   the MAC in constant time.
+//! - The recommended minimum output size is 16.
+//!
+//! # Recommendation:
+//! - BLAKE2s is intended for 8- to 32-bit platforms. On 64-bit platforms,
+//!   [BLAKE2b] is usually faster.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::blake2s::{Blake2s, Hasher, Params, SecretKey};
+//!
+//! // Using the streaming interface without a key.
+//! let mut state = Blake2s::new(None, 32)?;
+//! state.update(b"Some data")?;
+//! let digest = state.finalize()?;
+//!
+//! // Using the streaming interface with a key.
+//! let secret_key = SecretKey::generate();
+//! let mut state_keyed = Blake2s::new(Some(&secret_key), 32)?;
+//! state_keyed.update(b"Some data")?;
+//! let mac = state_keyed.finalize()?;
+//! assert!(Blake2s::verify(&mac, &secret_key, 32, b"Some data").is_ok());
+//!
+//! // Using the streaming interface with a salt and personalization.
+//! let params = Params::new(32)?
+//!     .with_salt(b"Salt")?
+//!     .with_personal(b"MyApp v1")?;
+//! let mut state_params = Blake2s::with_params(Some(&secret_key), &params)?;
+//! state_params.update(b"Some data")?;
+//! let mac = state_params.finalize()?;
+//!
+//! // Using the `Hasher` for convenience functions.
+//! let digest = Hasher::Blake2s256.digest(b"Some data")?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Blake2s.html
+//! [`reset()`]: struct.Blake2s.html
+//! [`finalize()`]: struct.Blake2s.html
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+//! [`verify()`]: struct.Blake2s.html
+//! [`as_ref()`]: struct.Digest.html
+//! [`Params`]: struct.Params.html
+//! [BLAKE2b]: ../blake2b/index.html
+use crate::hazardous::traits::IncrementalHash;
+use crate::{errors::UnknownCryptoError, util::endianness::load_u32_into_le, util::u32x4::U32x4};
+use zeroize::Zeroizing;
+
+/// The blocksize for the hash function BLAKE2s.
+const BLAKE2S_BLOCKSIZE: usize = 64;
+/// The maximum key size for the hash function BLAKE2s when used in keyed mode.
+pub(crate) const BLAKE2S_KEYSIZE: usize = 32;
+/// The maximum output size for the hash function BLAKE2s.
+pub(crate) const BLAKE2S_OUTSIZE: usize = 32;
+/// The size of the salt and personalization in the BLAKE2s parameter block.
+pub(crate) const BLAKE2S_SALTSIZE: usize = 8;
+/// The maximum node offset in the BLAKE2s parameter block, which is 48 bits.
+const BLAKE2S_MAX_NODE_OFFSET: u64 = 0xFFFF_FFFF_FFFF;
+
+construct_secret_key! {
+    /// A type to represent the secret key that BLAKE2s uses for keyed mode.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is empty.
+    /// - `slice` is greater than 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, 1, BLAKE2S_KEYSIZE, 32)
+}
+
+construct_public! {
+    /// A type to represent the `Digest` that BLAKE2s returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is empty.
+    /// - `slice` is greater than 32 bytes.
+    (Digest, test_digest, 1, BLAKE2S_OUTSIZE)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The BLAKE2s parameter block.
+///
+/// By default, the parameter block is that of sequential hashing, with an empty
+/// salt and personalization, which is what [`Blake2s::new()`] uses. The
+/// remaining fields are only meaningful when BLAKE2s is used in tree hashing
+/// mode, as described in section 2.10 of the [BLAKE2 specification].
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `size` is 0 or greater than 32.
+/// - `salt` or `personal` is greater than 8 bytes.
+/// - `max_depth` is 0.
+/// - `node_offset` is greater than 2^48-1.
+/// - `inner_length` is greater than 32.
+///
+/// # Note:
+/// A `salt` or `personal` shorter than 8 bytes is padded with zeroes, the same
+/// as Python's `hashlib` does.
+///
+/// [`Blake2s::new()`]: struct.Blake2s.html
+/// [BLAKE2 specification]: https://www.blake2.net/blake2.pdf
+pub struct Params {
+    size: usize,
+    fanout: u8,
+    max_depth: u8,
+    leaf_length: u32,
+    node_offset: u64,
+    node_depth: u8,
+    inner_length: usize,
+    salt: [u8; BLAKE2S_SALTSIZE],
+    personal: [u8; BLAKE2S_SALTSIZE],
+    last_node: bool,
+}
+
+impl Params {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a parameter block for sequential hashing with an output length of `size`.
+    pub fn new(size: usize) -> Result<Self, UnknownCryptoError> {
+        if !(1..=BLAKE2S_OUTSIZE).contains(&size) {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            size,
+            fanout: 1,
+            max_depth: 1,
+            leaf_length: 0,
+            node_offset: 0,
+            node_depth: 0,
+            inner_length: 0,
+            salt: [0u8; BLAKE2S_SALTSIZE],
+            personal: [0u8; BLAKE2S_SALTSIZE],
+            last_node: false,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the salt.
+    pub fn with_salt(self, salt: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if salt.len() > BLAKE2S_SALTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut padded = [0u8; BLAKE2S_SALTSIZE];
+        padded[..salt.len()].copy_from_slice(salt);
+
+        Ok(Self {
+            salt: padded,
+            ..self
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the personalization.
+    pub fn with_personal(self, personal: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if personal.len() > BLAKE2S_SALTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut padded = [0u8; BLAKE2S_SALTSIZE];
+        padded[..personal.len()].copy_from_slice(personal);
+
+        Ok(Self {
+            personal: padded,
+            ..self
+        })
+    }
+
+    /// Set the fanout of the tree. A `fanout` of 0 means unlimited.
+    pub fn with_fanout(self, fanout: u8) -> Self {
+        Self { fanout, ..self }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the maximal depth of the tree. A `max_depth` of 255 means unlimited.
+    pub fn with_max_depth(self, max_depth: u8) -> Result<Self, UnknownCryptoError> {
+        if max_depth < 1 {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self { max_depth, ..self })
+    }
+
+    /// Set the maximal byte length of the leaves. A `leaf_length` of 0 means unlimited.
+    pub fn with_leaf_length(self, leaf_length: u32) -> Self {
+        Self {
+            leaf_length,
+            ..self
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the offset of the node within its level of the tree.
+    pub fn with_node_offset(self, node_offset: u64) -> Result<Self, UnknownCryptoError> {
+        if node_offset > BLAKE2S_MAX_NODE_OFFSET {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            node_offset,
+            ..self
+        })
+    }
+
+    /// Set the depth of the node, with leaves being at depth 0.
+    pub fn with_node_depth(self, node_depth: u8) -> Self {
+        Self { node_depth, ..self }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the output length of the inner nodes of the tree.
+    pub fn with_inner_length(self, inner_length: usize) -> Result<Self, UnknownCryptoError> {
+        if inner_length > BLAKE2S_OUTSIZE {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            inner_length,
+            ..self
+        })
+    }
+
+    /// Set whether this is the last node at its depth of the tree.
+    pub fn with_last_node(self, last_node: bool) -> Self {
+        Self { last_node, ..self }
+    }
+
+    /// The initial chaining value for this parameter block and a key of `key_length` bytes.
+    fn initial_state(&self, key_length: usize) -> [U32x4; 2] {
+        debug_assert!(key_length <= BLAKE2S_KEYSIZE);
+        let mut salt = [0u32; 2];
+        let mut personal = [0u32; 2];
+        load_u32_into_le(&self.salt, &mut salt);
+        load_u32_into_le(&self.personal, &mut personal);
+
+        let word_0 = (self.size as u32)
+            | ((key_length as u32) << 8)
+            | (u32::from(self.fanout) << 16)
+            | (u32::from(self.max_depth) << 24);
+        let word_3 = ((self.node_offset >> 32) as u32)
+            | (u32::from(self.node_depth) << 16)
+            | ((self.inner_length as u32) << 24);
+
+        [
+            IV[0] ^ U32x4(word_0, self.leaf_length, self.node_offset as u32, word_3),
+            IV[1] ^ U32x4(salt[0], salt[1], personal[0], personal[1]),
+        ]
+    }
+}
+
+#[allow(clippy::unreadable_literal)]
+/// The BLAKE2s initialization vector as defined in the RFC 7693.
+const IV: [U32x4; 2] = [
+    U32x4(0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a),
+    U32x4(0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19),
+];
+
+/// BLAKE2s SIGMA as defined in the RFC 7693.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Quarter round on the BLAKE2s internal matrix.
+macro_rules! QROUND {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr, $s_idx:expr, $rconst1:expr, $rconst2:expr) => {
+        $v0 = $v0.wrapping_add($v1).wrapping_add($s_idx);
+        $v3 = ($v3 ^ $v0).rotate_right($rconst1);
+        $v2 = $v2.wrapping_add($v3);
+        $v1 = ($v1 ^ $v2).rotate_right($rconst2);
+    };
+}
+
+/// Perform a single round based on a message schedule selection.
+macro_rules! ROUND {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr, $s_idx:expr, $m:expr) => {
+        let s_indexed = U32x4($m[$s_idx[0]], $m[$s_idx[2]], $m[$s_idx[4]], $m[$s_idx[6]]);
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 16, 12);
+        let s_indexed = U32x4($m[$s_idx[1]], $m[$s_idx[3]], $m[$s_idx[5]], $m[$s_idx[7]]);
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 8, 7);
+
+        // Shuffle
+        $v1 = $v1.shl_1();
+        $v2 = $v2.shl_2();
+        $v3 = $v3.shl_3();
+
+        let s_indexed = U32x4(
+            $m[$s_idx[8]],
+            $m[$s_idx[10]],
+            $m[$s_idx[12]],
+            $m[$s_idx[14]],
+        );
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 16, 12);
+        let s_indexed = U32x4(
+            $m[$s_idx[9]],
+            $m[$s_idx[11]],
+            $m[$s_idx[13]],
+            $m[$s_idx[15]],
+        );
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 8, 7);
+
+        // Unshuffle
+        $v1 = $v1.shl_3();
+        $v2 = $v2.shl_2();
+        $v3 = $v3.shl_1();
+    };
+}
+
+/// Convenience functions for common BLAKE2s operations.
+pub enum Hasher {
+    /// Blake2s with `16` as `size`.
+    Blake2s128,
+    /// Blake2s with `28` as `size`.
+    Blake2s224,
+    /// Blake2s with `32` as `size`.
+    Blake2s256,
+}
+
+impl Hasher {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a digest selected by the given Blake2s variant.
+    pub fn digest(&self, data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+        let size: usize = match *self {
+            Hasher::Blake2s128 => 16,
+            Hasher::Blake2s224 => 28,
+            Hasher::Blake2s256 => 32,
+        };
+
+        let mut state = Blake2s::new(None, size)?;
+        state.update(data)?;
+
+        state.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a `Blake2s` state selected by the given Blake2s variant.
+    pub fn init(&self) -> Result<Blake2s, UnknownCryptoError> {
+        match *self {
+            Hasher::Blake2s128 => Blake2s::new(None, 16),
+            Hasher::Blake2s224 => Blake2s::new(None, 28),
+            Hasher::Blake2s256 => Blake2s::new(None, 32),
+        }
+    }
+}
+
+#[derive(Clone)]
+/// BLAKE2s streaming state.
+pub struct Blake2s {
+    init_state: [U32x4; 2],
+    internal_state: [U32x4; 2],
+    buffer: [u8; BLAKE2S_BLOCKSIZE],
+    leftover: usize,
+    t: [u32; 2],
+    f: [u32; 2],
+    is_finalized: bool,
+    is_keyed: bool,
+    is_last_node: bool,
+    size: usize,
+    key_block: Option<Zeroizing<[u8; BLAKE2S_BLOCKSIZE]>>,
+}
+
+impl Drop for Blake2s {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.init_state.iter_mut().zeroize();
+        self.internal_state.iter_mut().zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for Blake2s {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Blake2s {{ init_state: [***OMITTED***], internal_state: [***OMITTED***], buffer: \
+             [***OMITTED***], leftover: {:?}, t: {:?}, f: {:?}, is_finalized: {:?}, is_keyed: \
+             {:?}, is_last_node: {:?}, size: {:?} }}",
+            self.leftover,
+            self.t,
+            self.f,
+            self.is_finalized,
+            self.is_keyed,
+            self.is_last_node,
+            self.size
+        )
+    }
+}
+
+impl Default for Blake2s {
+    /// Initialize a `Blake2s` struct for BLAKE2s-256 without a key.
+    fn default() -> Self {
+        Self::new(None, BLAKE2S_OUTSIZE).unwrap()
+    }
+}
+
+impl_io_write_trait!(Blake2s);
+
+impl Blake2s {
+    /// Increment the internal states offset value `t`.
+    fn increment_offset(&mut self, value: u32) {
+        let (res, was_overflow) = self.t[0].overflowing_add(value);
+        self.t[0] = res;
+        if was_overflow {
+            // If this panics size limit is reached.
+            self.t[1] = self.t[1].checked_add(1).unwrap();
+        }
+    }
+
+    /// The compression function f.
+    fn compress_f(&mut self, data: Option<&[u8]>) {
+        let mut m_vec = [0u32; 16];
+        match data {
+            Some(bytes) => {
+                debug_assert!(bytes.len() == BLAKE2S_BLOCKSIZE);
+                load_u32_into_le(bytes, &mut m_vec);
+            }
+            None => load_u32_into_le(&self.buffer, &mut m_vec),
+        }
+
+        let mut v0 = self.internal_state[0];
+        let mut v1 = self.internal_state[1];
+        let mut v2 = IV[0];
+        let mut v3 = U32x4(
+            self.t[0] ^ IV[1].0,
+            self.t[1] ^ IV[1].1,
+            self.f[0] ^ IV[1].2,
+            self.f[1] ^ IV[1].3,
+        );
+
+        ROUND!(v0, v1, v2, v3, SIGMA[0], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[1], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[2], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[3], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[4], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[5], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[6], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[7], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[8], m_vec);
+        ROUND!(v0, v1, v2, v3, SIGMA[9], m_vec);
+
+        self.internal_state[0] ^= v0 ^ v2;
+        self.internal_state[1] ^= v1 ^ v3;
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2s` struct with a given size and an optional key.
+    pub fn new(secret_key: Option<&SecretKey>, size: usize) -> Result<Self, UnknownCryptoError> {
+        Self::with_params(secret_key, &Params::new(size)?)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2s` struct with a given parameter block and an optional key.
+    pub fn with_params(
+        secret_key: Option<&SecretKey>,
+        params: &Params,
+    ) -> Result<Self, UnknownCryptoError> {
+        let klen = match secret_key {
+            Some(sk) => sk.len(),
+            None => 0,
+        };
+
+        let mut context = Self {
+            init_state: params.initial_state(klen),
+            internal_state: [U32x4::default(); 2],
+            buffer: [0u8; BLAKE2S_BLOCKSIZE],
+            leftover: 0,
+            t: [0u32; 2],
+            f: [0u32; 2],
+            is_finalized: false,
+            is_keyed: secret_key.is_some(),
+            is_last_node: params.last_node,
+            size: params.size,
+            key_block: secret_key.map(Self::pad_key),
+        };
+        context.reset_to_key();
+
+        Ok(context)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Reset to `new()` state.
+    pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
+        if secret_key.is_some() && (!self.is_keyed) {
+            return Err(UnknownCryptoError);
+        }
+
+        if secret_key.is_none() && self.is_keyed {
+            return Err(UnknownCryptoError);
+        }
+
+        if let Some(sk) = secret_key {
+            self.key_block = Some(Self::pad_key(sk));
+        }
+        self.reset_to_key();
+
+        Ok(())
+    }
+
+    /// The secret key padded to blocksize length, which keyed states process first.
+    fn pad_key(secret_key: &SecretKey) -> Zeroizing<[u8; BLAKE2S_BLOCKSIZE]> {
+        let mut key_block = Zeroizing::new([0u8; BLAKE2S_BLOCKSIZE]);
+        key_block[..secret_key.len()].copy_from_slice(secret_key.unprotected_as_bytes());
+
+        key_block
+    }
+
+    /// Reset to the state after processing the key block, if any.
+    fn reset_to_key(&mut self) {
+        self.internal_state.copy_from_slice(&self.init_state);
+        self.buffer = [0u8; BLAKE2S_BLOCKSIZE];
+        self.leftover = 0;
+        self.t = [0u32; 2];
+        self.f = [0u32; 2];
+        self.is_finalized = false;
+
+        if let Some(key_block) = &self.key_block {
+            // The same as updating with the key block, since update() keeps
+            // a full buffer until more data arrives.
+            self.buffer.copy_from_slice(&key_block[..]);
+            self.leftover = BLAKE2S_BLOCKSIZE;
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = data;
+
+        if self.leftover != 0 {
+            debug_assert!(self.leftover <= BLAKE2S_BLOCKSIZE);
+
+            let fill = BLAKE2S_BLOCKSIZE - self.leftover;
+
+            if bytes.len() <= fill {
+                self.buffer[self.leftover..(self.leftover + bytes.len())].copy_from_slice(bytes);
+                self.leftover += bytes.len();
+                return Ok(());
+            }
+
+            self.buffer[self.leftover..(self.leftover + fill)].copy_from_slice(&bytes[..fill]);
+            self.increment_offset(BLAKE2S_BLOCKSIZE as u32);
+            self.compress_f(None);
+            self.leftover = 0;
+            bytes = &bytes[fill..];
+        }
+
+        while bytes.len() > BLAKE2S_BLOCKSIZE {
+            self.increment_offset(BLAKE2S_BLOCKSIZE as u32);
+            self.compress_f(Some(bytes[..BLAKE2S_BLOCKSIZE].as_ref()));
+            bytes = &bytes[BLAKE2S_BLOCKSIZE..];
+        }
+
+        if !bytes.is_empty() {
+            debug_assert!(self.leftover == 0);
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.leftover += bytes.len();
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a BLAKE2s digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        let in_buffer_len = self.leftover;
+        self.increment_offset(in_buffer_len as u32);
+        // Mark that it is the last block of data to be processed
+        self.f[0] = !0;
+        if self.is_last_node {
+            self.f[1] = !0;
+        }
+
+        for leftover_block in self.buffer.iter_mut().skip(in_buffer_len) {
+            *leftover_block = 0;
+        }
+        self.compress_f(None);
+
+        let mut digest = [0u8; 32];
+        self.internal_state[0].store_into_le(&mut digest[..16]);
+        self.internal_state[1].store_into_le(&mut digest[16..]);
+
+        Digest::from_slice(&digest[..self.size])
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a Blake2s Digest in constant time.
+    pub fn verify(
+        expected: &Digest,
+        secret_key: &SecretKey,
+        size: usize,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(Some(secret_key), size)?;
+        state.update(data)?;
+
+        if expected == &state.finalize()? {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+}
+
+impl IncrementalHash for Blake2s {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        Blake2s::default()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset_to_key();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Blake2s::new(None, BLAKE2S_OUTSIZE).unwrap();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Blake2s { init_state: [***OMITTED***], internal_state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, t: [0, 0], f: [0, 0], is_finalized: false, is_keyed: false, is_last_node: false, size: 32 }";
+        assert_eq!(debug, expected);
+    }
+
+    fn compare_blake2s_states(state_1: &Blake2s, state_2: &Blake2s) {
+        assert!(state_1.init_state == state_2.init_state);
+        assert!(state_1.internal_state == state_2.internal_state);
+        assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+        assert_eq!(state_1.leftover, state_2.leftover);
+        assert_eq!(state_1.t, state_2.t);
+        assert_eq!(state_1.f, state_2.f);
+        assert_eq!(state_1.is_finalized, state_2.is_finalized);
+        assert_eq!(state_1.is_keyed, state_2.is_keyed);
+        assert_eq!(state_1.is_last_node, state_2.is_last_node);
+        assert_eq!(state_1.size, state_2.size);
+        assert_eq!(
+            state_1.key_block.as_ref().map(|key_block| &key_block[..]),
+            state_2.key_block.as_ref().map(|key_block| &key_block[..])
+        );
+    }
+
+    mod test_streaming_interface_no_key {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Blake2s {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset(None)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                // Blake2s256 is used since this is the same as BLAKE2S_OUTSIZE.
+                Hasher::Blake2s256.digest(input)
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Blake2s, state_2: &Blake2s) {
+                compare_blake2s_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Blake2s = Blake2s::new(None, BLAKE2S_OUTSIZE).unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake2s>::new(
+                initial_state,
+                BLAKE2S_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Blake2s = Blake2s::new(None, BLAKE2S_OUTSIZE).unwrap();
+
+                    let test_runner = StreamingContextConsistencyTester::<Digest, Blake2s>::new(
+                        initial_state,
+                        BLAKE2S_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        /// Convenience testing function to avoid repetition when testing
+        /// new sizes with and without a secret key.
+        fn new_tester(sk: Option<&SecretKey>, size: usize) -> bool {
+            if (1..=BLAKE2S_OUTSIZE).contains(&size) {
+                Blake2s::new(sk, size).is_ok()
+            } else {
+                Blake2s::new(sk, size).is_err()
+            }
+        }
+
+        #[test]
+        fn test_init_size() {
+            assert!(new_tester(None, 0));
+            assert!(new_tester(None, 33));
+            assert!(new_tester(None, 32));
+            assert!(new_tester(None, 1));
+
+            let sk = SecretKey::from_slice(&[0u8; 32]).unwrap();
+            assert!(new_tester(Some(&sk), 0));
+            assert!(new_tester(Some(&sk), 33));
+            assert!(new_tester(Some(&sk), 32));
+            assert!(new_tester(Some(&sk), 1));
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Given a valid size parameter, new should always pass. If size
+                /// is invalid, then new should always fail.
+                fn prop_new_size(size: usize) -> bool {
+                    let no_key = new_tester(None, size);
+                    let sk = SecretKey::generate();
+                    let key = new_tester(Some(&sk), size);
+
+                    no_key && key
+                }
+            }
+        }
+    }
+
+    mod test_params {
+        use super::*;
+
+        #[test]
+        fn test_params_errors() {
+            assert!(Params::new(0).is_err());
+            assert!(Params::new(33).is_err());
+
+            let params = Params::new(32).unwrap();
+            assert!(params.with_salt(&[0u8; 8]).is_ok());
+            assert!(params.with_salt(&[0u8; 9]).is_err());
+            assert!(params.with_personal(&[0u8; 8]).is_ok());
+            assert!(params.with_personal(&[0u8; 9]).is_err());
+            assert!(params.with_max_depth(1).is_ok());
+            assert!(params.with_max_depth(0).is_err());
+            assert!(params.with_inner_length(32).is_ok());
+            assert!(params.with_inner_length(33).is_err());
+            assert!(params.with_node_offset(0xFFFF_FFFF_FFFF).is_ok());
+            assert!(params.with_node_offset(0x1_0000_0000_0000).is_err());
+        }
+
+        #[test]
+        fn test_default_params_same_as_new() {
+            let sk = SecretKey::from_slice(b"Testing").unwrap();
+            let params = Params::new(32).unwrap();
+
+            compare_blake2s_states(
+                &Blake2s::new(None, 32).unwrap(),
+                &Blake2s::with_params(None, &params).unwrap(),
+            );
+            compare_blake2s_states(
+                &Blake2s::new(Some(&sk), 32).unwrap(),
+                &Blake2s::with_params(Some(&sk), &params).unwrap(),
+            );
+        }
+
+        #[test]
+        fn test_salt_zero_padded() {
+            let params_short = Params::new(32).unwrap().with_salt(b"salt").unwrap();
+            let mut padded = [0u8; 8];
+            padded[..4].copy_from_slice(b"salt");
+            let params_padded = Params::new(32).unwrap().with_salt(&padded).unwrap();
+            assert_eq!(params_short, params_padded);
+        }
+
+        #[test]
+        fn test_params_change_digest() {
+            let digest = |params: &Params| {
+                let mut state = Blake2s::with_params(None, params).unwrap();
+                state.update(b"Some data").unwrap();
+                state.finalize().unwrap()
+            };
+
+            let params = Params::new(32).unwrap();
+            let default = digest(&params);
+            assert_eq!(default, Hasher::Blake2s256.digest(b"Some data").unwrap());
+            assert_ne!(default, digest(&params.with_salt(b"salt").unwrap()));
+            assert_ne!(default, digest(&params.with_personal(b"personal").unwrap()));
+            assert_ne!(default, digest(&params.with_fanout(2)));
+            assert_ne!(default, digest(&params.with_max_depth(2).unwrap()));
+            assert_ne!(default, digest(&params.with_leaf_length(1)));
+            assert_ne!(default, digest(&params.with_node_offset(1).unwrap()));
+            assert_ne!(default, digest(&params.with_node_depth(1)));
+            assert_ne!(default, digest(&params.with_inner_length(1).unwrap()));
+            assert_ne!(default, digest(&params.with_last_node(true)));
+        }
+
+        #[test]
+        fn test_reset_keeps_last_node() {
+            let params = Params::new(32).unwrap().with_last_node(true);
+            let mut state = Blake2s::with_params(None, &params).unwrap();
+            state.update(b"Some data").unwrap();
+            let first = state.finalize().unwrap();
+            state.reset(None).unwrap();
+            state.update(b"Some data").unwrap();
+            assert_eq!(first, state.finalize().unwrap());
+        }
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut state = Blake2s::new(Some(&sk), 32).unwrap();
+                    state.update(&data[..]).unwrap();
+                    let tag = state.finalize().unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Blake2s::verify(&tag, &bad_sk, 32, &data[..]).is_err()
+                }
+            }
+        }
+    }
+
+    mod test_hasher {
+        use super::*;
+
+        #[test]
+        fn test_hasher_interface_no_panic_and_same_result() {
+            let digest_128 = Hasher::Blake2s128.digest(b"Test").unwrap();
+            let digest_224 = Hasher::Blake2s224.digest(b"Test").unwrap();
+            let digest_256 = Hasher::Blake2s256.digest(b"Test").unwrap();
+
+            assert_eq!(digest_128, Hasher::Blake2s128.digest(b"Test").unwrap());
+            assert_eq!(digest_224, Hasher::Blake2s224.digest(b"Test").unwrap());
+            assert_eq!(digest_256, Hasher::Blake2s256.digest(b"Test").unwrap());
+
+            assert_ne!(digest_128, Hasher::Blake2s128.digest(b"Wrong").unwrap());
+            assert_ne!(digest_224, Hasher::Blake2s224.digest(b"Wrong").unwrap());
+            assert_ne!(digest_256, Hasher::Blake2s256.digest(b"Wrong").unwrap());
+
+            let _state_128 = Hasher::Blake2s128.init().unwrap();
+            let _state_224 = Hasher::Blake2s224.init().unwrap();
+            let _state_256 = Hasher::Blake2s256.init().unwrap();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Given some data, digest() should never fail in practice and should
+                /// produce the same output on a second call.
+                /// Only panics if data is unreasonably large.
+                fn prop_hasher_digest_no_panic_and_same_result(data: Vec<u8>) -> bool {
+                    let d128 = Hasher::Blake2s128.digest(&data[..]).unwrap();
+                    let d224 = Hasher::Blake2s224.digest(&data[..]).unwrap();
+                    let d256 = Hasher::Blake2s256.digest(&data[..]).unwrap();
+
+                    let d128_re = Hasher::Blake2s128.digest(&data[..]).unwrap();
+                    let d224_re = Hasher::Blake2s224.digest(&data[..]).unwrap();
+                    let d256_re = Hasher::Blake2s256.digest(&data[..]).unwrap();
+
+                    (d128 == d128_re) && (d224 == d224_re) && (d256 == d256_re)
+                }
+            }
+
+            quickcheck! {
+                /// Given some data, .digest() should produce the same output as when
+                /// calling with streaming state.
+                fn prop_hasher_digest_128_same_as_streaming(data: Vec<u8>) -> bool {
+                    let d128 = Hasher::Blake2s128.digest(&data[..]).unwrap();
+
+                    let mut state = Blake2s::new(None, 16).unwrap();
+                    state.update(&data[..]).unwrap();
+
+                    d128 == state.finalize().unwrap()
+                }
+            }
+
+            quickcheck! {
+                /// Given some data, .digest() should produce the same output as when
+                /// calling with streaming state.
+                fn prop_hasher_digest_224_same_as_streaming(data: Vec<u8>) -> bool {
+                    let d224 = Hasher::Blake2s224.digest(&data[..]).unwrap();
+
+                    let mut state = Blake2s::new(None, 28).unwrap();
+                    state.update(&data[..]).unwrap();
+
+                    d224 == state.finalize().unwrap()
+                }
+            }
+
+            quickcheck! {
+                /// Given some data, .digest() should produce the same output as when
+                /// calling with streaming state.
+                fn prop_hasher_digest_256_same_as_streaming(data: Vec<u8>) -> bool {
+                    let d256 = Hasher::Blake2s256.digest(&data[..]).unwrap();
+
+                    let mut state = Blake2s::new(None, 32).unwrap();
+                    state.update(&data[..]).unwrap();
+
+                    d256 == state.finalize().unwrap()
+                }
+            }
+
+            quickcheck! {
+                /// Given two different data, .digest() should never produce the
+                /// same output.
+                fn prop_hasher_digest_diff_input_diff_result(data: Vec<u8>) -> bool {
+                    let d128 = Hasher::Blake2s128.digest(&data[..]).unwrap();
+                    let d224 = Hasher::Blake2s224.digest(&data[..]).unwrap();
+                    let d256 = Hasher::Blake2s256.digest(&data[..]).unwrap();
+
+                    let d128_re = Hasher::Blake2s128.digest(b"Wrong data").unwrap();
+                    let d224_re = Hasher::Blake2s224.digest(b"Wrong data").unwrap();
+                    let d256_re = Hasher::Blake2s256.digest(b"Wrong data").unwrap();
+
+                    (d128 != d128_re) && (d224 != d224_re) && (d256 != d256_re)
+                }
+            }
+
+            quickcheck! {
+                /// .init() should never fail.
+                fn prop_hasher_init_no_panic() -> bool {
+                    let _d128 = Hasher::Blake2s128.init().unwrap();
+                    let _d224 = Hasher::Blake2s224.init().unwrap();
+                    let _d256 = Hasher::Blake2s256.init().unwrap();
+
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_reset {
+        use super::*;
+
+        #[test]
+        fn test_switching_keyed_modes_fails() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+
+            let mut state = Blake2s::new(Some(&secret_key), 32).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(state.reset(None).is_err());
+            assert!(state.reset(Some(&secret_key)).is_ok());
+
+            let mut state_second = Blake2s::new(None, 32).unwrap();
+            state_second.update(b"Tests").unwrap();
+            let _ = state_second.finalize().unwrap();
+            assert!(state_second.reset(Some(&secret_key)).is_err());
+            assert!(state_second.reset(None).is_ok());
+        }
+
+        #[test]
+        fn test_incremental_hash_reset_keeps_key() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+            let other_key = SecretKey::from_slice(b"Tasting").unwrap();
+            let expected = |sk: &SecretKey| {
+                let mut state = Blake2s::new(Some(sk), 32).unwrap();
+                state.update(b"Tests").unwrap();
+                state.finalize().unwrap()
+            };
+
+            let mut state = Blake2s::new(Some(&secret_key), 32).unwrap();
+            state.update(b"Tests").unwrap();
+            let _ = state.finalize().unwrap();
+            assert!(IncrementalHash::reset(&mut state).is_ok());
+            state.update(b"Tests").unwrap();
+            assert_eq!(state.finalize().unwrap(), expected(&secret_key));
+
+            // The key given to the last reset is kept.
+            state.reset(Some(&other_key)).unwrap();
+            state.update(b"More tests").unwrap();
+            assert!(IncrementalHash::reset(&mut state).is_ok());
+            state.update(b"Tests").unwrap();
+            assert_eq!(state.finalize().unwrap(), expected(&other_key));
+        }
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+
+        /// Related bug: https://github.com/brycx/orion/issues/46
+        /// Testing different usage combinations of new(), update(),
+        /// finalize() and reset() produce the same Digest/Tag.
+        fn produces_same_hash(sk: Option<&SecretKey>, size: usize, data: &[u8]) {
+            // new(), update(), finalize()
+            let mut state_1 = Blake2s::new(sk, size).unwrap();
+            state_1.update(data).unwrap();
+            let res_1 = state_1.finalize().unwrap();
+
+            // new(), reset(), update(), finalize()
+            let mut state_2 = Blake2s::new(sk, size).unwrap();
+            state_2.reset(sk).unwrap();
+            state_2.update(data).unwrap();
+            let res_2 = state_2.finalize().unwrap();
+
+            // new(), update(), reset(), update(), finalize()
+            let mut state_3 = Blake2s::new(sk, size).unwrap();
+            state_3.update(data).unwrap();
+            state_3.reset(sk).unwrap();
+            state_3.update(data).unwrap();
+            let res_3 = state_3.finalize().unwrap();
+
+            // new(), update(), finalize(), reset(), update(), finalize()
+            let mut state_4 = Blake2s::new(sk, size).unwrap();
+            state_4.update(data).unwrap();
+            let _ = state_4.finalize().unwrap();
+            state_4.reset(sk).unwrap();
+            state_4.update(data).unwrap();
+            let res_4 = state_4.finalize().unwrap();
+
+            assert_eq!(res_1, res_2);
+            assert_eq!(res_2, res_3);
+            assert_eq!(res_3, res_4);
+
+            // Tests for the assumption that returning Ok() on empty update() calls
+            // with streaming APIs, gives the correct result. This is done by testing
+            // the reasoning that if update() is empty, returns Ok(), it is the same as
+            // calling new() -> finalize(). i.e not calling update() at all.
+            if data.is_empty() {
+                // new(), finalize()
+                let mut state_5 = Blake2s::new(sk, size).unwrap();
+                let res_5 = state_5.finalize().unwrap();
+
+                // new(), reset(), finalize()
+                let mut state_6 = Blake2s::new(sk, size).unwrap();
+                state_6.reset(sk).unwrap();
+                let res_6 = state_6.finalize().unwrap();
+
+                // new(), update(), reset(), finalize()
+                let mut state_7 = Blake2s::new(sk, size).unwrap();
+                state_7.update(b"Wrong data").unwrap();
+                state_7.reset(sk).unwrap();
+                let res_7 = state_7.finalize().unwrap();
+
+                assert_eq!(res_4, res_5);
+                assert_eq!(res_5, res_6);
+                assert_eq!(res_6, res_7);
+            }
+        }
+
+        /// Related bug: https://github.com/brycx/orion/issues/46
+        /// Testing different usage combinations of new(), update(),
+        /// finalize() and reset() produce the same Digest/Tag.
+        fn produces_same_state(sk: Option<&SecretKey>, size: usize, data: &[u8]) {
+            // new()
+            let state_1 = Blake2s::new(sk, size).unwrap();
+
+            // new(), reset()
+            let mut state_2 = Blake2s::new(sk, size).unwrap();
+            state_2.reset(sk).unwrap();
+
+            // new(), update(), reset()
+            let mut state_3 = Blake2s::new(sk, size).unwrap();
+            state_3.update(data).unwrap();
+            state_3.reset(sk).unwrap();
+
+            // new(), update(), finalize(), reset()
+            let mut state_4 = Blake2s::new(sk, size).unwrap();
+            state_4.update(data).unwrap();
+            let _ = state_4.finalize().unwrap();
+            state_4.reset(sk).unwrap();
+
+            compare_blake2s_states(&state_1, &state_2);
+            compare_blake2s_states(&state_2, &state_3);
+            compare_blake2s_states(&state_3, &state_4);
+        }
+
+        #[test]
+        /// Related bug: https://github.com/brycx/orion/issues/46
+        fn test_produce_same_state() {
+            produces_same_state(None, 1, b"Tests");
+            produces_same_state(None, 16, b"Tests");
+            produces_same_state(None, 32, b"Tests");
+            produces_same_state(None, 20, b"Tests");
+
+            let sk = SecretKey::from_slice(b"Testing").unwrap();
+            produces_same_state(Some(&sk), 1, b"Tests");
+            produces_same_state(Some(&sk), 16, b"Tests");
+            produces_same_state(Some(&sk), 32, b"Tests");
+            produces_same_state(Some(&sk), 20, b"Tests");
+        }
+
+        #[test]
+        /// Related bug: https://github.com/brycx/orion/issues/46
+        fn test_produce_same_hash() {
+            produces_same_hash(None, 1, b"Tests");
+            produces_same_hash(None, 16, b"Tests");
+            produces_same_hash(None, 32, b"Tests");
+            produces_same_hash(None, 20, b"Tests");
+
+            produces_same_hash(None, 1, b"");
+            produces_same_hash(None, 16, b"");
+            produces_same_hash(None, 32, b"");
+            produces_same_hash(None, 20, b"");
+
+            let sk = SecretKey::from_slice(b"Testing").unwrap();
+            produces_same_hash(Some(&sk), 1, b"Tests");
+            produces_same_hash(Some(&sk), 16, b"Tests");
+            produces_same_hash(Some(&sk), 32, b"Tests");
+            produces_same_hash(Some(&sk), 20, b"Tests");
+
+            produces_same_hash(Some(&sk), 1, b"");
+            produces_same_hash(Some(&sk), 16, b"");
+            produces_same_hash(Some(&sk), 32, b"");
+            produces_same_hash(Some(&sk), 20, b"");
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_same_hash_different_usage(data: Vec<u8>, size: usize) -> bool {
+                    if (1..=BLAKE2S_OUTSIZE).contains(&size) {
+                        // Will panic on incorrect results.
+                        produces_same_hash(None, size, &data[..]);
+                        let sk = SecretKey::generate();
+                        produces_same_hash(Some(&sk), size, &data[..]);
+                    }
+
+                    true
+                }
+            }
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_same_state_different_usage(data: Vec<u8>, size: usize) -> bool {
+                    if (1..=BLAKE2S_OUTSIZE).contains(&size) {
+                        // Will panic on incorrect results.
+                        produces_same_state(None, size, &data[..]);
+                        let sk = SecretKey::generate();
+                        produces_same_state(Some(&sk), size, &data[..]);
+                    }
+
+                    true
+                }
+            }
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    mod test_increment_offset {
+        use super::*;
+
+        #[test]
+        fn test_offset_increase_values() {
+            let mut context = Blake2s {
+                init_state: [U32x4::default(); 2],
+                internal_state: IV,
+                buffer: [0u8; BLAKE2S_BLOCKSIZE],
+                leftover: 0,
+                t: [0u32; 2],
+                f: [0u32; 2],
+                is_finalized: false,
+                is_keyed: false,
+                is_last_node: false,
+                size: 1,
+                key_block: None,
+            };
+
+            context.increment_offset(1);
+            assert!(context.t == [1u32, 0u32]);
+            context.increment_offset(17);
+            assert!(context.t == [18u32, 0u32]);
+            context.increment_offset(12);
+            assert!(context.t == [30u32, 0u32]);
+            // Overflow
+            context.increment_offset(u32::max_value());
+            assert!(context.t == [29u32, 1u32]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_panic_on_second_overflow() {
+            let mut context = Blake2s {
+                init_state: [U32x4::default(); 2],
+                internal_state: IV,
+                buffer: [0u8; BLAKE2S_BLOCKSIZE],
+                leftover: 0,
+                t: [1u32, u32::max_value()],
+                f: [0u32; 2],
+                is_finalized: false,
+                is_keyed: false,
+                is_last_node: false,
+                size: 1,
+                key_block: None,
+            };
+
+            context.increment_offset(u32::max_value());
+        }
+    }
+}
@@ -0,0 +1,1213 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! # Parameters:
+//! - `secret_key`: The secret key for the keyed hash mode.
+//! - `context`: The context string for the key derivation mode.
+//! - `data`: The data to be hashed.
+//! - `key_material`: The key material to derive a key from.
+//! - `expected`: The expected digest when verifying.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is determined by the length of `dst_out`.
+//! - `offset`: The position in the input at which a subtree starts.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`], [`finalize_xof()`] or [`finalize_non_root()`] is called
+//!   twice without a [`reset()`] in between.
+//! - [`update()`] is called after finalizing without a [`reset()`] in between.
+//! - `dst_out` or the destination buffer given to [`squeeze()`] is empty.
+//! - More than 2^64-1 bytes of output are read with [`squeeze()`].
+//! - [`set_input_offset()`] is called after [`update()`], or `offset` is not a
+//!   multiple of [`BLAKE3_CHUNKSIZE`].
+//! - [`update()`] is given more data than fits in the subtree that starts at
+//!   `offset`.
+//! - [`finalize()`] or [`finalize_xof()`] is called when `offset` is not 0.
+//! - [`finalize_non_root()`] is called without any data when `offset` is not 0.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this.
+//! - When using the keyed hash mode, then the output can be used as a MAC. If
+//!   this is the intention, __**avoid using**__ [`as_ref()`] to compare such
+//!   MACs and use instead [`verify()`], which will compare the MAC in constant
+//!   time.
+//! - The context string of the key derivation mode should be hardcoded,
+//!   globally unique and application-specific. It must not contain secret or
+//!   variable data, which belongs in `key_material` instead.
+//! - BLAKE3 provides at most 128 bits of security against collisions and 256
+//!   bits against preimages, no matter the length of the output.
+//!
+//! # Note:
+//! BLAKE3 splits the data into chunks of 1024 bytes, which form the leaves of
+//! a binary tree. The chaining value of any complete subtree can be computed on
+//! its own with [`set_input_offset()`] and [`finalize_non_root()`], and two
+//! chaining values can be combined with [`merge_subtrees()`] and
+//! [`merge_subtrees_root()`]. The left subtree must always hold a power of two
+//! amount of chunks, and at least as many as the right subtree. This allows the
+//! subtrees of large inputs to be hashed independently, for example on
+//! different machines, and their chaining values to be stored and verified.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::blake3::{Blake3, SecretKey, BLAKE3_CHUNKSIZE};
+//!
+//! // Using the streaming interface.
+//! let mut state = Blake3::new();
+//! state.update(b"Some data")?;
+//! let digest = state.finalize()?;
+//! assert_eq!(digest, Blake3::digest(b"Some data")?);
+//!
+//! // Using the keyed hash mode.
+//! let secret_key = SecretKey::generate();
+//! let mac = Blake3::keyed_hash(&secret_key, b"Some data")?;
+//! assert!(Blake3::verify(&mac, &secret_key, b"Some data").is_ok());
+//!
+//! // Using the key derivation mode.
+//! let mut derived_key = [0u8; 64];
+//! Blake3::derive_key(
+//!     "MyApp 2020-08-01 12:00:00 session keys",
+//!     b"Some key material",
+//!     &mut derived_key,
+//! )?;
+//!
+//! // Reading output of arbitrary length.
+//! let mut state = Blake3::new();
+//! state.update(b"Some data")?;
+//! let mut reader = state.finalize_xof()?;
+//! let mut output = [0u8; 100];
+//! reader.squeeze(&mut output)?;
+//! assert_eq!(output[..32], digest.as_ref()[..]);
+//!
+//! // Hashing two subtrees of chunks independently.
+//! let data = [0u8; 3 * BLAKE3_CHUNKSIZE];
+//! let mut left = Blake3::new();
+//! left.update(&data[..2 * BLAKE3_CHUNKSIZE])?;
+//! let mut right = Blake3::new();
+//! right.set_input_offset(2 * BLAKE3_CHUNKSIZE as u64)?;
+//! right.update(&data[2 * BLAKE3_CHUNKSIZE..])?;
+//!
+//! let root = Blake3::new().merge_subtrees_root(
+//!     &left.finalize_non_root()?,
+//!     &right.finalize_non_root()?,
+//! );
+//! assert_eq!(root, Blake3::digest(&data)?);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Blake3.html
+//! [`reset()`]: struct.Blake3.html
+//! [`finalize()`]: struct.Blake3.html
+//! [`finalize_xof()`]: struct.Blake3.html
+//! [`finalize_non_root()`]: struct.Blake3.html
+//! [`set_input_offset()`]: struct.Blake3.html
+//! [`merge_subtrees()`]: struct.Blake3.html
+//! [`merge_subtrees_root()`]: struct.Blake3.html
+//! [`squeeze()`]: struct.XofReader.html#method.squeeze
+//! [`BLAKE3_CHUNKSIZE`]: constant.BLAKE3_CHUNKSIZE.html
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+//! [`verify()`]: struct.Blake3.html
+//! [`as_ref()`]: struct.Digest.html
+use crate::hazardous::traits::{IncrementalHash, Xof, XofRead};
+use crate::{errors::UnknownCryptoError, util::endianness::load_u32_into_le, util::u32x4::U32x4};
+use zeroize::Zeroize;
+
+/// The output size for the hash function BLAKE3.
+pub const BLAKE3_OUTSIZE: usize = 32;
+/// The key size for the hash function BLAKE3 when used in keyed hash mode.
+pub const BLAKE3_KEYSIZE: usize = 32;
+/// The size of the chunks that form the leaves of the BLAKE3 tree.
+pub const BLAKE3_CHUNKSIZE: usize = 1024;
+/// The blocksize for the hash function BLAKE3.
+const BLAKE3_BLOCKSIZE: usize = 64;
+/// The maximum amount of chaining values kept while hashing, which is enough
+/// for 2^64 bytes of data.
+const BLAKE3_MAX_DEPTH: usize = 54;
+
+// The domain separation flags of BLAKE3.
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+construct_secret_key! {
+    /// A type to represent the secret key that BLAKE3 uses for keyed hash mode.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, BLAKE3_KEYSIZE, BLAKE3_KEYSIZE, BLAKE3_KEYSIZE)
+}
+
+construct_public! {
+    /// A type to represent the `Digest` that BLAKE3 returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (Digest, test_digest, BLAKE3_OUTSIZE, BLAKE3_OUTSIZE)
+}
+
+impl_from_trait!(Digest, BLAKE3_OUTSIZE);
+
+construct_public! {
+    /// A type to represent the chaining value of a BLAKE3 subtree.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (ChainingValue, test_chaining_value, BLAKE3_OUTSIZE, BLAKE3_OUTSIZE)
+}
+
+impl_from_trait!(ChainingValue, BLAKE3_OUTSIZE);
+
+#[allow(clippy::unreadable_literal)]
+const IV: [U32x4; 2] = [
+    U32x4(0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a),
+    U32x4(0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19),
+];
+
+/// The message words used in each round, which is the message permutation of
+/// BLAKE3 applied once more for every round.
+const MSG_SCHEDULE: [[usize; 16]; 7] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+macro_rules! QROUND {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr, $s_idx:expr, $rconst1:expr, $rconst2:expr) => {
+        $v0 = $v0.wrapping_add($v1).wrapping_add($s_idx);
+        $v3 = ($v3 ^ $v0).rotate_right($rconst1);
+        $v2 = $v2.wrapping_add($v3);
+        $v1 = ($v1 ^ $v2).rotate_right($rconst2);
+    };
+}
+
+macro_rules! ROUND {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr, $s_idx:expr, $m:expr) => {
+        let s_indexed = U32x4($m[$s_idx[0]], $m[$s_idx[2]], $m[$s_idx[4]], $m[$s_idx[6]]);
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 16, 12);
+        let s_indexed = U32x4($m[$s_idx[1]], $m[$s_idx[3]], $m[$s_idx[5]], $m[$s_idx[7]]);
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 8, 7);
+
+        // Shuffle
+        $v1 = $v1.shl_1();
+        $v2 = $v2.shl_2();
+        $v3 = $v3.shl_3();
+
+        let s_indexed = U32x4(
+            $m[$s_idx[8]],
+            $m[$s_idx[10]],
+            $m[$s_idx[12]],
+            $m[$s_idx[14]],
+        );
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 16, 12);
+        let s_indexed = U32x4(
+            $m[$s_idx[9]],
+            $m[$s_idx[11]],
+            $m[$s_idx[13]],
+            $m[$s_idx[15]],
+        );
+        QROUND!($v0, $v1, $v2, $v3, s_indexed, 8, 7);
+
+        // Unshuffle
+        $v1 = $v1.shl_3();
+        $v2 = $v2.shl_2();
+        $v3 = $v3.shl_1();
+    };
+}
+
+/// The BLAKE3 compression function, returning the full 64-byte state.
+fn compress(
+    cv: &[U32x4; 2],
+    block: &[u8; BLAKE3_BLOCKSIZE],
+    block_len: usize,
+    counter: u64,
+    flags: u32,
+) -> [U32x4; 4] {
+    debug_assert!(block_len <= BLAKE3_BLOCKSIZE);
+    let mut m_vec = [0u32; 16];
+    load_u32_into_le(block, &mut m_vec);
+
+    let mut v0 = cv[0];
+    let mut v1 = cv[1];
+    let mut v2 = IV[0];
+    let mut v3 = U32x4(
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len as u32,
+        flags,
+    );
+
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[0], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[1], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[2], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[3], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[4], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[5], m_vec);
+    ROUND!(v0, v1, v2, v3, MSG_SCHEDULE[6], m_vec);
+
+    m_vec.zeroize();
+
+    [v0 ^ v2, v1 ^ v3, v2 ^ cv[0], v3 ^ cv[1]]
+}
+
+/// Load a chaining value or key from `bytes`.
+fn load_cv(bytes: &[u8]) -> [U32x4; 2] {
+    debug_assert_eq!(bytes.len(), BLAKE3_OUTSIZE);
+    let mut words = [0u32; 8];
+    load_u32_into_le(bytes, &mut words);
+    let cv = [
+        U32x4(words[0], words[1], words[2], words[3]),
+        U32x4(words[4], words[5], words[6], words[7]),
+    ];
+    words.zeroize();
+
+    cv
+}
+
+/// Store the chaining value `cv` into `bytes`.
+fn store_cv(cv: &[U32x4; 2], bytes: &mut [u8]) {
+    debug_assert_eq!(bytes.len(), BLAKE3_OUTSIZE);
+    cv[0].store_into_le(&mut bytes[..16]);
+    cv[1].store_into_le(&mut bytes[16..]);
+}
+
+#[derive(Clone)]
+/// The input to the last compression of a chunk or parent node, from which
+/// either its chaining value or, for the root, any amount of output is computed.
+struct Output {
+    input_cv: [U32x4; 2],
+    block: [u8; BLAKE3_BLOCKSIZE],
+    block_len: usize,
+    counter: u64,
+    flags: u32,
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        self.input_cv.iter_mut().zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl Output {
+    /// The chaining value of a node that is not the root.
+    fn chaining_value(&self) -> [U32x4; 2] {
+        let state = compress(
+            &self.input_cv,
+            &self.block,
+            self.block_len,
+            self.counter,
+            self.flags,
+        );
+
+        [state[0], state[1]]
+    }
+
+    /// The 64-byte block of output of the root at `output_block_counter`.
+    fn root_block(&self, output_block_counter: u64) -> [u8; BLAKE3_BLOCKSIZE] {
+        let state = compress(
+            &self.input_cv,
+            &self.block,
+            self.block_len,
+            output_block_counter,
+            self.flags | ROOT,
+        );
+
+        let mut out = [0u8; BLAKE3_BLOCKSIZE];
+        for (word, dst) in state.iter().zip(out.chunks_exact_mut(16)) {
+            word.store_into_le(dst);
+        }
+
+        out
+    }
+}
+
+/// The output of a parent node with the children `left` and `right`.
+fn parent_output(left: &[U32x4; 2], right: &[U32x4; 2], key: &[U32x4; 2], flags: u32) -> Output {
+    let mut block = [0u8; BLAKE3_BLOCKSIZE];
+    store_cv(left, &mut block[..BLAKE3_OUTSIZE]);
+    store_cv(right, &mut block[BLAKE3_OUTSIZE..]);
+
+    Output {
+        input_cv: *key,
+        block,
+        block_len: BLAKE3_BLOCKSIZE,
+        counter: 0,
+        flags: PARENT | flags,
+    }
+}
+
+#[derive(Clone)]
+/// The state of the chunk that is currently being hashed.
+struct ChunkState {
+    cv: [U32x4; 2],
+    chunk_counter: u64,
+    buffer: [u8; BLAKE3_BLOCKSIZE],
+    leftover: usize,
+    blocks_compressed: usize,
+    flags: u32,
+}
+
+impl Drop for ChunkState {
+    fn drop(&mut self) {
+        self.cv.iter_mut().zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl ChunkState {
+    fn new(key: &[U32x4; 2], chunk_counter: u64, flags: u32) -> Self {
+        Self {
+            cv: *key,
+            chunk_counter,
+            buffer: [0u8; BLAKE3_BLOCKSIZE],
+            leftover: 0,
+            blocks_compressed: 0,
+            flags,
+        }
+    }
+
+    /// The amount of data hashed in this chunk so far.
+    fn len(&self) -> usize {
+        BLAKE3_BLOCKSIZE * self.blocks_compressed + self.leftover
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    /// Update the chunk with `data`, which must fit in the rest of the chunk.
+    fn update(&mut self, data: &[u8]) {
+        debug_assert!(data.len() <= BLAKE3_CHUNKSIZE - self.len());
+        let mut bytes = data;
+
+        while !bytes.is_empty() {
+            // The last block is only compressed when finalizing, so a full
+            // buffer is compressed only once more data arrives.
+            if self.leftover == BLAKE3_BLOCKSIZE {
+                let state = compress(
+                    &self.cv,
+                    &self.buffer,
+                    BLAKE3_BLOCKSIZE,
+                    self.chunk_counter,
+                    self.flags | self.start_flag(),
+                );
+                self.cv = [state[0], state[1]];
+                self.blocks_compressed += 1;
+                self.leftover = 0;
+            }
+
+            let take = core::cmp::min(BLAKE3_BLOCKSIZE - self.leftover, bytes.len());
+            self.buffer[self.leftover..self.leftover + take].copy_from_slice(&bytes[..take]);
+            self.leftover += take;
+            bytes = &bytes[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        let mut block = [0u8; BLAKE3_BLOCKSIZE];
+        block[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+
+        Output {
+            input_cv: self.cv,
+            block,
+            block_len: self.leftover,
+            counter: self.chunk_counter,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+#[derive(Clone)]
+/// BLAKE3 streaming state.
+pub struct Blake3 {
+    key: [U32x4; 2],
+    flags: u32,
+    chunk: ChunkState,
+    cv_stack: [[U32x4; 2]; BLAKE3_MAX_DEPTH],
+    cv_stack_len: usize,
+    input_offset: u64,
+    is_finalized: bool,
+}
+
+impl Drop for Blake3 {
+    fn drop(&mut self) {
+        self.key.iter_mut().zeroize();
+        for cv in self.cv_stack.iter_mut() {
+            cv.iter_mut().zeroize();
+        }
+    }
+}
+
+impl core::fmt::Debug for Blake3 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Blake3 {{ key: [***OMITTED***], flags: {:?}, chunk: [***OMITTED***], \
+             cv_stack: [***OMITTED***], input_offset: {:?}, is_finalized: {:?} }}",
+            self.flags, self.input_offset, self.is_finalized
+        )
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_io_write_trait!(Blake3);
+
+impl Blake3 {
+    fn with_key_and_flags(key: [U32x4; 2], flags: u32) -> Self {
+        Self {
+            key,
+            flags,
+            chunk: ChunkState::new(&key, 0, flags),
+            cv_stack: [[U32x4::default(); 2]; BLAKE3_MAX_DEPTH],
+            cv_stack_len: 0,
+            input_offset: 0,
+            is_finalized: false,
+        }
+    }
+
+    /// Initialize a `Blake3` struct for the hash mode.
+    pub fn new() -> Self {
+        Self::with_key_and_flags(IV, 0)
+    }
+
+    /// Initialize a `Blake3` struct for the keyed hash mode.
+    pub fn new_keyed(secret_key: &SecretKey) -> Self {
+        Self::with_key_and_flags(load_cv(secret_key.unprotected_as_bytes()), KEYED_HASH)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake3` struct for the key derivation mode with `context`.
+    pub fn new_derive_key(context: &str) -> Result<Self, UnknownCryptoError> {
+        let mut context_state = Self::with_key_and_flags(IV, DERIVE_KEY_CONTEXT);
+        context_state.update(context.as_bytes())?;
+        let context_key = context_state.finalize()?;
+
+        Ok(Self::with_key_and_flags(
+            load_cv(context_key.as_ref()),
+            DERIVE_KEY_MATERIAL,
+        ))
+    }
+
+    /// Reset to the state after initialization, including an input offset of 0.
+    pub fn reset(&mut self) {
+        self.chunk = ChunkState::new(&self.key, 0, self.flags);
+        for cv in self.cv_stack.iter_mut() {
+            cv.iter_mut().zeroize();
+        }
+        self.cv_stack_len = 0;
+        self.input_offset = 0;
+        self.is_finalized = false;
+    }
+
+    /// The amount of data hashed so far.
+    fn count(&self) -> u64 {
+        (self.chunk.chunk_counter - self.input_offset) * BLAKE3_CHUNKSIZE as u64
+            + self.chunk.len() as u64
+    }
+
+    /// The most data that can be hashed, which is limited when hashing a subtree
+    /// that does not start at offset 0.
+    fn max_count(&self) -> u64 {
+        if self.input_offset == 0 {
+            u64::max_value()
+        } else {
+            (1u64 << self.input_offset.trailing_zeros()) * BLAKE3_CHUNKSIZE as u64
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the position in the input at which the data of this state starts, to
+    /// compute the chaining value of a subtree with [`finalize_non_root()`].
+    ///
+    /// [`finalize_non_root()`]: struct.Blake3.html#method.finalize_non_root
+    pub fn set_input_offset(&mut self, offset: u64) -> Result<(), UnknownCryptoError> {
+        // The offset must be a whole amount of chunks.
+        let chunk_offset = offset / BLAKE3_CHUNKSIZE as u64;
+        if self.is_finalized
+            || self.count() != 0
+            || chunk_offset * BLAKE3_CHUNKSIZE as u64 != offset
+        {
+            return Err(UnknownCryptoError);
+        }
+
+        self.input_offset = chunk_offset;
+        self.chunk = ChunkState::new(&self.key, self.input_offset, self.flags);
+
+        Ok(())
+    }
+
+    /// Add the chaining value of the chunk that was just completed, merging it
+    /// with the completed subtrees to its left.
+    fn add_chunk_chaining_value(&mut self, chunk_cv: [U32x4; 2], total_chunks: u64) {
+        let mut new_cv = chunk_cv;
+        let mut total_chunks = total_chunks;
+
+        while total_chunks & 1 == 0 {
+            self.cv_stack_len -= 1;
+            new_cv = parent_output(
+                &self.cv_stack[self.cv_stack_len],
+                &new_cv,
+                &self.key,
+                self.flags,
+            )
+            .chaining_value();
+            total_chunks >>= 1;
+        }
+
+        self.cv_stack[self.cv_stack_len] = new_cv;
+        self.cv_stack_len += 1;
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized || data.len() as u64 > self.max_count() - self.count() {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut bytes = data;
+
+        while !bytes.is_empty() {
+            // A full chunk is only added to the tree once more data arrives,
+            // because the last chunk must be finalized differently.
+            if self.chunk.len() == BLAKE3_CHUNKSIZE {
+                let chunk_cv = self.chunk.output().chaining_value();
+                let chunk_counter = self.chunk.chunk_counter;
+                self.add_chunk_chaining_value(chunk_cv, chunk_counter - self.input_offset + 1);
+                self.chunk = ChunkState::new(&self.key, chunk_counter + 1, self.flags);
+            }
+
+            let take = core::cmp::min(BLAKE3_CHUNKSIZE - self.chunk.len(), bytes.len());
+            self.chunk.update(&bytes[..take]);
+            bytes = &bytes[take..];
+        }
+
+        Ok(())
+    }
+
+    /// The output of the node at the top of the tree of the data hashed so far.
+    fn top_output(&self) -> Output {
+        let mut output = self.chunk.output();
+        for cv in self.cv_stack[..self.cv_stack_len].iter().rev() {
+            output = parent_output(cv, &output.chaining_value(), &self.key, self.flags);
+        }
+
+        output
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a BLAKE3 digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        let mut out = self.finalize_xof()?.output.root_block(0);
+        let digest = Digest::from_slice(&out[..BLAKE3_OUTSIZE]);
+        out.zeroize();
+
+        digest
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for output of arbitrary length.
+    pub fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        if self.is_finalized || self.input_offset != 0 {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        Ok(XofReader::new(self.top_output()))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return the chaining value of the subtree of the data hashed, which is
+    /// not the root of the tree.
+    pub fn finalize_non_root(&mut self) -> Result<ChainingValue, UnknownCryptoError> {
+        if self.is_finalized || (self.input_offset != 0 && self.count() == 0) {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        let mut cv = [0u8; BLAKE3_OUTSIZE];
+        store_cv(&self.top_output().chaining_value(), &mut cv);
+
+        Ok(ChainingValue::from(cv))
+    }
+
+    /// Merge the chaining values of two adjacent subtrees into the chaining
+    /// value of their parent, using the mode of this state.
+    pub fn merge_subtrees(&self, left: &ChainingValue, right: &ChainingValue) -> ChainingValue {
+        let parent = parent_output(
+            &load_cv(left.as_ref()),
+            &load_cv(right.as_ref()),
+            &self.key,
+            self.flags,
+        );
+
+        let mut cv = [0u8; BLAKE3_OUTSIZE];
+        store_cv(&parent.chaining_value(), &mut cv);
+
+        ChainingValue::from(cv)
+    }
+
+    /// Merge the chaining values of the two subtrees of the root into the
+    /// digest, using the mode of this state.
+    pub fn merge_subtrees_root(&self, left: &ChainingValue, right: &ChainingValue) -> Digest {
+        let mut out = self
+            .merge_subtrees_root_xof(left, right)
+            .output
+            .root_block(0);
+        let mut digest = [0u8; BLAKE3_OUTSIZE];
+        digest.copy_from_slice(&out[..BLAKE3_OUTSIZE]);
+        out.zeroize();
+
+        Digest::from(digest)
+    }
+
+    /// Merge the chaining values of the two subtrees of the root into a
+    /// reader for output of arbitrary length, using the mode of this state.
+    pub fn merge_subtrees_root_xof(
+        &self,
+        left: &ChainingValue,
+        right: &ChainingValue,
+    ) -> XofReader {
+        XofReader::new(parent_output(
+            &load_cv(left.as_ref()),
+            &load_cv(right.as_ref()),
+            &self.key,
+            self.flags,
+        ))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate a BLAKE3 digest of some `data`.
+    pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate a BLAKE3 digest of some `data` in keyed hash mode.
+    pub fn keyed_hash(secret_key: &SecretKey, data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+        let mut state = Self::new_keyed(secret_key);
+        state.update(data)?;
+        state.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a BLAKE3 Digest of the keyed hash mode in constant time.
+    pub fn verify(
+        expected: &Digest,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if expected == &Self::keyed_hash(secret_key, data)? {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a key from `key_material` with `context`, filling `dst_out`.
+    pub fn derive_key(
+        context: &str,
+        key_material: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new_derive_key(context)?;
+        state.update(key_material)?;
+        state.finalize_xof()?.squeeze(dst_out)
+    }
+}
+
+impl IncrementalHash for Blake3 {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        Blake3::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+impl Xof for Blake3 {
+    type Reader = XofReader;
+
+    fn new() -> Self {
+        Blake3::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.finalize_xof()
+    }
+}
+
+/// A reader for the output of BLAKE3.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `dest` is empty.
+/// - More than 2^64-1 bytes of output are read in total.
+///
+/// # Security:
+/// - Output read with several calls to [`squeeze()`] is the same as output read
+///   with a single call. Reading 32 bytes and then 64 bytes thus gives the same
+///   first 32 bytes as reading 96 bytes at once.
+/// - The first 32 bytes of output are the BLAKE3 [`Digest`].
+///
+/// [`squeeze()`]: struct.XofReader.html#method.squeeze
+/// [`Digest`]: struct.Digest.html
+pub struct XofReader {
+    output: Output,
+    block: [u8; BLAKE3_BLOCKSIZE],
+    position: u64,
+}
+
+impl Drop for XofReader {
+    fn drop(&mut self) {
+        self.block.zeroize();
+    }
+}
+
+impl core::fmt::Debug for XofReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "XofReader {{ output: [***OMITTED***], block: [***OMITTED***], position: {:?} }}",
+            self.position
+        )
+    }
+}
+
+impl XofReader {
+    fn new(output: Output) -> Self {
+        Self {
+            output,
+            block: [0u8; BLAKE3_BLOCKSIZE],
+            position: 0,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dest` with the next `dest.len()` bytes of output.
+    pub fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dest.is_empty() || self.position.checked_add(dest.len() as u64).is_none() {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut written = 0;
+        while written < dest.len() {
+            let offset = (self.position % BLAKE3_BLOCKSIZE as u64) as usize;
+            if offset == 0 {
+                self.block = self
+                    .output
+                    .root_block(self.position / BLAKE3_BLOCKSIZE as u64);
+            }
+
+            let take = core::cmp::min(BLAKE3_BLOCKSIZE - offset, dest.len() - written);
+            dest[written..written + take].copy_from_slice(&self.block[offset..offset + take]);
+            written += take;
+            self.position += take as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl XofRead for XofReader {
+    fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.squeeze(dest)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn compare_blake3_states(state_1: &Blake3, state_2: &Blake3) {
+        assert!(state_1.key == state_2.key);
+        assert_eq!(state_1.flags, state_2.flags);
+        assert!(state_1.chunk.cv == state_2.chunk.cv);
+        assert_eq!(state_1.chunk.chunk_counter, state_2.chunk.chunk_counter);
+        assert_eq!(state_1.chunk.buffer[..], state_2.chunk.buffer[..]);
+        assert_eq!(state_1.chunk.leftover, state_2.chunk.leftover);
+        assert_eq!(
+            state_1.chunk.blocks_compressed,
+            state_2.chunk.blocks_compressed
+        );
+        assert_eq!(state_1.cv_stack_len, state_2.cv_stack_len);
+        assert!(
+            state_1.cv_stack[..state_1.cv_stack_len] == state_2.cv_stack[..state_2.cv_stack_len]
+        );
+        assert_eq!(state_1.input_offset, state_2.input_offset);
+        assert_eq!(state_1.is_finalized, state_2.is_finalized);
+    }
+
+    #[test]
+    fn test_default_equals_new() {
+        compare_blake3_states(&Blake3::new(), &Blake3::default());
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let mut initial_state = Blake3::new();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Blake3 { key: [***OMITTED***], flags: 0, chunk: [***OMITTED***], cv_stack: [***OMITTED***], input_offset: 0, is_finalized: false }";
+        assert_eq!(debug, expected);
+
+        let reader = initial_state.finalize_xof().unwrap();
+        let debug = format!("{:?}", reader);
+        let expected = "XofReader { output: [***OMITTED***], block: [***OMITTED***], position: 0 }";
+        assert_eq!(debug, expected);
+    }
+
+    #[test]
+    fn test_modes_differ() {
+        let secret_key = SecretKey::from_slice(&[0u8; 32]).unwrap();
+        let digest = Blake3::digest(b"Tests").unwrap();
+        assert_ne!(digest, Blake3::keyed_hash(&secret_key, b"Tests").unwrap());
+
+        let mut derived_key = [0u8; 32];
+        Blake3::derive_key("Context", b"Tests", &mut derived_key).unwrap();
+        assert_ne!(digest.as_ref(), &derived_key[..]);
+
+        let mut derived_key_other_context = [0u8; 32];
+        Blake3::derive_key("Other context", b"Tests", &mut derived_key_other_context).unwrap();
+        assert_ne!(derived_key, derived_key_other_context);
+    }
+
+    #[test]
+    fn test_derive_key_empty_dst_out_err() {
+        assert!(Blake3::derive_key("Context", b"", &mut [0u8; 0]).is_err());
+        assert!(Blake3::derive_key("Context", b"", &mut [0u8; 1]).is_ok());
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Blake3 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset();
+                Ok(())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                Blake3::digest(input)
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Blake3, state_2: &Blake3) {
+                compare_blake3_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state = Blake3::new();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake3>::new(
+                initial_state,
+                BLAKE3_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+
+            // The same with the size of a chunk, which is where the tree grows.
+            let initial_state = Blake3::new();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake3>::new(
+                initial_state,
+                BLAKE3_CHUNKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state = Blake3::new();
+
+                    let test_runner = StreamingContextConsistencyTester::<Digest, Blake3>::new(
+                        initial_state,
+                        BLAKE3_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_subtrees {
+        use super::*;
+
+        fn subtree(data: &[u8], offset: usize) -> ChainingValue {
+            let mut state = Blake3::new();
+            state.set_input_offset(offset as u64).unwrap();
+            state.update(data).unwrap();
+            state.finalize_non_root().unwrap()
+        }
+
+        #[test]
+        fn test_set_input_offset_err() {
+            let mut state = Blake3::new();
+            assert!(state.set_input_offset(1).is_err());
+            assert!(state.set_input_offset(BLAKE3_CHUNKSIZE as u64 + 1).is_err());
+            assert!(state.set_input_offset(BLAKE3_CHUNKSIZE as u64).is_ok());
+            // Updating with no data is allowed before setting the offset.
+            state.update(b"").unwrap();
+            assert!(state.set_input_offset(0).is_ok());
+
+            state.update(b"Tests").unwrap();
+            assert!(state.set_input_offset(0).is_err());
+
+            state.reset();
+            let _ = state.finalize().unwrap();
+            assert!(state.set_input_offset(0).is_err());
+        }
+
+        #[test]
+        fn test_update_past_subtree_err() {
+            // A subtree starting at chunk 6 holds at most 2 chunks.
+            let mut state = Blake3::new();
+            state.set_input_offset(6 * BLAKE3_CHUNKSIZE as u64).unwrap();
+            assert!(state.update(&[0u8; 2 * BLAKE3_CHUNKSIZE + 1]).is_err());
+            assert!(state.update(&[0u8; 2 * BLAKE3_CHUNKSIZE]).is_ok());
+            assert!(state.update(&[0u8; 1]).is_err());
+            assert!(state.finalize_non_root().is_ok());
+
+            // A subtree starting at offset 0 is not limited.
+            let mut state = Blake3::new();
+            assert!(state.update(&[0u8; 3 * BLAKE3_CHUNKSIZE + 1]).is_ok());
+        }
+
+        #[test]
+        fn test_finalize_with_offset_err() {
+            let mut state = Blake3::new();
+            state.set_input_offset(BLAKE3_CHUNKSIZE as u64).unwrap();
+            state.update(b"Tests").unwrap();
+            assert!(state.finalize().is_err());
+            assert!(state.finalize_xof().is_err());
+            assert!(state.finalize_non_root().is_ok());
+            assert!(state.finalize_non_root().is_err());
+        }
+
+        #[test]
+        fn test_finalize_non_root_empty_with_offset_err() {
+            let mut state = Blake3::new();
+            assert!(state.finalize_non_root().is_ok());
+
+            let mut state = Blake3::new();
+            state.set_input_offset(BLAKE3_CHUNKSIZE as u64).unwrap();
+            assert!(state.finalize_non_root().is_err());
+        }
+
+        #[test]
+        fn test_reset_clears_offset() {
+            let mut state = Blake3::new();
+            state.set_input_offset(BLAKE3_CHUNKSIZE as u64).unwrap();
+            state.update(b"Tests").unwrap();
+            state.reset();
+            compare_blake3_states(&state, &Blake3::new());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_merge_four_chunks() {
+            let data: Vec<u8> = (0..(4 * BLAKE3_CHUNKSIZE)).map(|i| i as u8).collect();
+            let chunks: Vec<ChainingValue> = data
+                .chunks(BLAKE3_CHUNKSIZE)
+                .enumerate()
+                .map(|(idx, chunk)| subtree(chunk, idx * BLAKE3_CHUNKSIZE))
+                .collect();
+
+            let state = Blake3::new();
+            let left = state.merge_subtrees(&chunks[0], &chunks[1]);
+            let right = state.merge_subtrees(&chunks[2], &chunks[3]);
+            assert_eq!(left, subtree(&data[..2 * BLAKE3_CHUNKSIZE], 0));
+            assert_eq!(
+                right,
+                subtree(&data[2 * BLAKE3_CHUNKSIZE..], 2 * BLAKE3_CHUNKSIZE)
+            );
+            assert_eq!(
+                state.merge_subtrees_root(&left, &right),
+                Blake3::digest(&data).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_merge_uses_mode() {
+            let data = [0u8; 2 * BLAKE3_CHUNKSIZE];
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+            let mut left = Blake3::new_keyed(&secret_key);
+            left.update(&data[..BLAKE3_CHUNKSIZE]).unwrap();
+            let mut right = Blake3::new_keyed(&secret_key);
+            right.set_input_offset(BLAKE3_CHUNKSIZE as u64).unwrap();
+            right.update(&data[BLAKE3_CHUNKSIZE..]).unwrap();
+            let left_cv = left.finalize_non_root().unwrap();
+            let right_cv = right.finalize_non_root().unwrap();
+
+            let expected = Blake3::keyed_hash(&secret_key, &data).unwrap();
+            assert_eq!(
+                Blake3::new_keyed(&secret_key).merge_subtrees_root(&left_cv, &right_cv),
+                expected
+            );
+            assert_ne!(
+                Blake3::new().merge_subtrees_root(&left_cv, &right_cv),
+                expected
+            );
+        }
+    }
+
+    mod test_squeeze {
+        use super::*;
+
+        #[test]
+        fn test_squeeze_empty_err() {
+            let mut reader = Blake3::new().finalize_xof().unwrap();
+            assert!(reader.squeeze(&mut [0u8; 0]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 1]).is_ok());
+        }
+
+        #[test]
+        fn test_squeeze_past_max_output_err() {
+            let mut reader = Blake3::new().finalize_xof().unwrap();
+            // Skip to the end of the output, instead of computing all blocks before it.
+            reader.position = u64::max_value() - 64;
+            assert!(reader.squeeze(&mut [0u8; 65]).is_err());
+            assert!(reader.squeeze(&mut [0u8; 64]).is_ok());
+            assert!(reader.squeeze(&mut [0u8; 1]).is_err());
+        }
+
+        #[test]
+        fn test_squeeze_incremental() {
+            let mut one_shot = [0u8; 300];
+            Blake3::new()
+                .finalize_xof()
+                .unwrap()
+                .squeeze(&mut one_shot)
+                .unwrap();
+
+            // Read across the boundaries of the blocks in differently sized pieces.
+            for step in [1, 7, 31, 32, 64, 65, 128].iter() {
+                let mut reader = Blake3::new().finalize_xof().unwrap();
+                let mut incremental = [0u8; 300];
+                for chunk in incremental.chunks_mut(*step) {
+                    reader.squeeze(chunk).unwrap();
+                }
+                assert_eq!(one_shot[..], incremental[..]);
+            }
+        }
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let tag = Blake3::keyed_hash(&sk, &data[..]).unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Blake3::verify(&tag, &bad_sk, &data[..]).is_err()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! SHA1 is only provided for use as the PRF in [PBKDF2-HMAC-SHA1], which is
+//! needed to interoperate with legacy formats. It is not exposed as a hash
+//! function, since SHA1 is not collision resistant.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than (2^64-1) __bits__ of data are hashed.
+//!
+//! [PBKDF2-HMAC-SHA1]: ../../kdf/pbkdf2/sha1/index.html
+
+use crate::{
+    errors::UnknownCryptoError,
+    util::endianness::{load_u32_into_be, store_u32_into_be},
+};
+
+/// The blocksize for the hash function SHA1.
+pub(crate) const SHA1_BLOCKSIZE: usize = 64;
+/// The output size for the hash function SHA1.
+pub(crate) const SHA1_OUTSIZE: usize = 20;
+
+#[rustfmt::skip]
+#[allow(clippy::unreadable_literal)]
+/// The SHA1 initial hash value H(0) as defined in FIPS 180-4.
+const H0: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+#[derive(Clone)]
+/// SHA1 streaming state.
+pub(crate) struct Sha1 {
+    working_state: [u32; 5],
+    buffer: [u8; SHA1_BLOCKSIZE],
+    leftover: usize,
+    message_len: u64,
+    is_finalized: bool,
+}
+
+impl Drop for Sha1 {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.working_state.zeroize();
+        self.buffer.zeroize();
+        self.message_len.zeroize();
+    }
+}
+
+impl_io_write_trait!(Sha1);
+
+impl Sha1 {
+    #[allow(clippy::many_single_char_names)]
+    #[allow(clippy::unreadable_literal)]
+    /// Process data in `self.buffer` or optionally `data`.
+    fn process(&mut self, data: Option<&[u8]>) {
+        let mut w = [0u32; 80];
+        match data {
+            Some(bytes) => {
+                debug_assert!(bytes.len() == SHA1_BLOCKSIZE);
+                load_u32_into_be(bytes, &mut w[..16]);
+            }
+            None => load_u32_into_be(&self.buffer, &mut w[..16]),
+        }
+
+        for t in 16..80 {
+            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+        }
+
+        let mut a = self.working_state[0];
+        let mut b = self.working_state[1];
+        let mut c = self.working_state[2];
+        let mut d = self.working_state[3];
+        let mut e = self.working_state[4];
+
+        // The f functions and constants as specified in FIPS 180-4 sections
+        // 4.1.1 and 4.2.1.
+        for (t, wt) in w.iter().enumerate() {
+            let (f, k) = match t {
+                0..=19 => (d ^ (b & (c ^ d)), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (d & (b | c)), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*wt);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        self.working_state[0] = self.working_state[0].wrapping_add(a);
+        self.working_state[1] = self.working_state[1].wrapping_add(b);
+        self.working_state[2] = self.working_state[2].wrapping_add(c);
+        self.working_state[3] = self.working_state[3].wrapping_add(d);
+        self.working_state[4] = self.working_state[4].wrapping_add(e);
+    }
+
+    /// Increment the message length during processing of data.
+    fn increment_mlen(&mut self, length: u64) {
+        // The checked shift checks that the right-hand side is a legal shift.
+        // The result can still overflow if length > u64::max_value() / 8.
+        // Should be impossible for a user to trigger, because update() processes
+        // in SHA1_BLOCKSIZE chunks.
+        debug_assert!(length <= u64::max_value() / 8);
+
+        // left-shift to get bit-sized representation of length
+        // using .unwrap() because it should not panic in practice
+        let len = length.checked_shl(3).unwrap();
+        // If this panics size limit is reached.
+        self.message_len = self.message_len.checked_add(len).unwrap();
+    }
+
+    /// Initialize a `Sha1` struct.
+    pub(crate) fn new() -> Self {
+        Self {
+            working_state: H0,
+            buffer: [0u8; SHA1_BLOCKSIZE],
+            leftover: 0,
+            message_len: 0,
+            is_finalized: false,
+        }
+    }
+
+    /// Update state with `data`. This can be called multiple times.
+    pub(crate) fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = data;
+
+        if self.leftover != 0 {
+            debug_assert!(self.leftover <= SHA1_BLOCKSIZE);
+
+            let mut want = SHA1_BLOCKSIZE - self.leftover;
+            if want > bytes.len() {
+                want = bytes.len();
+            }
+
+            for (idx, itm) in bytes.iter().enumerate().take(want) {
+                self.buffer[self.leftover + idx] = *itm;
+            }
+
+            bytes = &bytes[want..];
+            self.leftover += want;
+            self.increment_mlen(want as u64);
+
+            if self.leftover < SHA1_BLOCKSIZE {
+                return Ok(());
+            }
+
+            self.process(None);
+            self.leftover = 0;
+        }
+
+        while bytes.len() >= SHA1_BLOCKSIZE {
+            self.process(Some(bytes[..SHA1_BLOCKSIZE].as_ref()));
+            self.increment_mlen(SHA1_BLOCKSIZE as u64);
+            bytes = &bytes[SHA1_BLOCKSIZE..];
+        }
+
+        if !bytes.is_empty() {
+            debug_assert!(self.leftover == 0);
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.leftover = bytes.len();
+            self.increment_mlen(bytes.len() as u64);
+        }
+
+        Ok(())
+    }
+
+    /// Return a SHA1 digest.
+    pub(crate) fn finalize(&mut self) -> Result<[u8; SHA1_OUTSIZE], UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        // self.leftover should not be greater than SHA1_BLOCKSIZE
+        // as that would have been processed in the update call
+        debug_assert!(self.leftover < SHA1_BLOCKSIZE);
+        self.buffer[self.leftover] = 0x80;
+        self.leftover += 1;
+
+        for itm in self.buffer.iter_mut().skip(self.leftover) {
+            *itm = 0;
+        }
+
+        // Check for available space for length padding
+        if (SHA1_BLOCKSIZE - self.leftover) < 8 {
+            self.process(None);
+            for itm in self.buffer.iter_mut().take(self.leftover) {
+                *itm = 0;
+            }
+        }
+
+        self.buffer[SHA1_BLOCKSIZE - 8..SHA1_BLOCKSIZE]
+            .copy_from_slice(&self.message_len.to_be_bytes());
+
+        self.process(None);
+
+        let mut digest = [0u8; SHA1_OUTSIZE];
+        store_u32_into_be(&self.working_state, &mut digest);
+
+        Ok(digest)
+    }
+
+    /// Calculate a SHA1 digest of some `data`.
+    pub(crate) fn digest(data: &[u8]) -> Result<[u8; SHA1_OUTSIZE], UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()
+    }
+}
+
+#[cfg(test)]
+/// Compare two Sha1 state objects to check if their fields
+/// are the same.
+pub(crate) fn compare_sha1_states(state_1: &Sha1, state_2: &Sha1) {
+    assert_eq!(state_1.working_state, state_2.working_state);
+    assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+    assert_eq!(state_1.leftover, state_2.leftover);
+    assert_eq!(state_1.message_len, state_2.message_len);
+    assert_eq!(state_1.is_finalized, state_2.is_finalized);
+}
+
+// Testing functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    // Examples from FIPS 180-2, Appendix A.
+    #[test]
+    fn test_fips_180_examples() {
+        assert_eq!(
+            Sha1::digest(b"abc").unwrap()[..],
+            hex::decode("a9993e364706816aba3e25717850c26c9cd0d89d").unwrap()[..]
+        );
+        assert_eq!(
+            Sha1::digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").unwrap()[..],
+            hex::decode("84983e441c3bd26ebaae4aa1f95129e5e54670f1").unwrap()[..]
+        );
+
+        let mut state = Sha1::new();
+        for _ in 0..1000 {
+            state.update(&[b'a'; 1000]).unwrap();
+        }
+        assert_eq!(
+            state.finalize().unwrap()[..],
+            hex::decode("34aa973cd4c4daa4f61eeb2bdbad27316534016f").unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(
+            Sha1::digest(b"").unwrap()[..],
+            hex::decode("da39a3ee5e6b4b0d3255bfef95601890afd80709").unwrap()[..]
+        );
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<[u8; SHA1_OUTSIZE]> for Sha1 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                *self = Sha1::new();
+                Ok(())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<[u8; SHA1_OUTSIZE], UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<[u8; SHA1_OUTSIZE], UnknownCryptoError> {
+                Sha1::digest(input)
+            }
+
+            fn verify_result(
+                expected: &[u8; SHA1_OUTSIZE],
+                input: &[u8],
+            ) -> Result<(), UnknownCryptoError> {
+                let actual = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Sha1, state_2: &Sha1) {
+                compare_sha1_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Sha1 = Sha1::new();
+
+            let test_runner = StreamingContextConsistencyTester::<[u8; SHA1_OUTSIZE], Sha1>::new(
+                initial_state,
+                SHA1_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Sha1 = Sha1::new();
+
+                    let test_runner = StreamingContextConsistencyTester::<[u8; SHA1_OUTSIZE], Sha1>::new(
+                        initial_state,
+                        SHA1_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
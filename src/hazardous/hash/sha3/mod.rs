@@ -0,0 +1,494 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/// SHA3-256 as specified in the [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf).
+pub mod sha3_256;
+
+/// SHA3-512 as specified in the [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf).
+pub mod sha3_512;
+
+/// SHAKE128 as specified in the [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf),
+/// and cSHAKE128 as specified in the [NIST SP 800-185](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf).
+pub mod shake128;
+
+/// SHAKE256 as specified in the [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf),
+/// and cSHAKE256 as specified in the [NIST SP 800-185](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf).
+pub mod shake256;
+
+use crate::hazardous::traits::XofRead;
+use crate::{errors::UnknownCryptoError, util::endianness::load_u64_into_le};
+
+/// The largest rate of the Keccak-f\[1600\] based functions, which is that of SHAKE128.
+pub(crate) const KECCAK_MAX_RATE: usize = 168;
+
+/// The domain separation and first padding bits of SHA3, as defined in FIPS 202.
+pub(crate) const SHA3_PADDING: u8 = 0x06;
+/// The domain separation and first padding bits of SHAKE, as defined in FIPS 202.
+pub(crate) const SHAKE_PADDING: u8 = 0x1F;
+/// The domain separation and first padding bits of cSHAKE, as defined in NIST SP 800-185.
+pub(crate) const CSHAKE_PADDING: u8 = 0x04;
+
+#[rustfmt::skip]
+#[allow(clippy::unreadable_literal)]
+/// The round constants of Keccak-f[1600] as defined in FIPS 202.
+const RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, in the order that the pi step visits the lanes.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// The order in which the pi step visits the lanes, starting from lane 1.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f\[1600\] permutation as specified in FIPS 202 section 3.3.
+pub(crate) fn keccak_f1600(state: &mut [u64; 25]) {
+    for rc in RC.iter() {
+        // Theta
+        let mut columns = [0u64; 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            for lane in state.iter_mut().skip(x).step_by(5) {
+                *lane ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = state[1];
+        for (pi, rho) in PI.iter().zip(RHO.iter()) {
+            let current = state[*pi];
+            state[*pi] = last.rotate_left(*rho);
+            last = current;
+        }
+
+        // Chi
+        for plane in state.chunks_exact_mut(5) {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(plane);
+            for (x, lane) in plane.iter_mut().enumerate() {
+                *lane = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        state[0] ^= rc;
+    }
+}
+
+/// XOR a `block` of rate bytes into `state` and apply the permutation.
+fn absorb_block(state: &mut [u64; 25], block: &[u8]) {
+    debug_assert_eq!(block.len() % 8, 0);
+    debug_assert!(block.len() <= KECCAK_MAX_RATE);
+    let mut lanes = [0u64; KECCAK_MAX_RATE / 8];
+    let lanes = &mut lanes[..block.len() / 8];
+    load_u64_into_le(block, lanes);
+
+    for (lane, input) in state.iter_mut().zip(lanes.iter()) {
+        *lane ^= input;
+    }
+
+    keccak_f1600(state);
+}
+
+/// `left_encode()` as specified in NIST SP 800-185 section 2.3.1. The encoding
+/// of `value` is returned as a slice of `buf`.
+pub(crate) fn left_encode(value: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = core::cmp::max(1, 8 - (value.leading_zeros() as usize / 8));
+    buf[0] = n as u8;
+    buf[1..=n].copy_from_slice(&value.to_be_bytes()[8 - n..]);
+
+    &buf[..=n]
+}
+
+/// `right_encode()` as specified in NIST SP 800-185 section 2.3.1. The encoding
+/// of `value` is returned as a slice of `buf`.
+pub(crate) fn right_encode(value: u64, buf: &mut [u8; 9]) -> &[u8] {
+    let n = core::cmp::max(1, 8 - (value.leading_zeros() as usize / 8));
+    buf[..n].copy_from_slice(&value.to_be_bytes()[8 - n..]);
+    buf[n] = n as u8;
+
+    &buf[..=n]
+}
+
+/// The length in bits of `len` bytes.
+pub(crate) fn bit_length(len: usize) -> Result<u64, UnknownCryptoError> {
+    (len as u64).checked_mul(8).ok_or(UnknownCryptoError)
+}
+
+#[derive(Clone)]
+/// A Keccak-f\[1600\] sponge, absorbing `rate` bytes at a time.
+pub(crate) struct Sponge {
+    state: [u64; 25],
+    initial_state: [u64; 25],
+    buffer: [u8; KECCAK_MAX_RATE],
+    leftover: usize,
+    rate: usize,
+    padding: u8,
+    is_finalized: bool,
+}
+
+impl Drop for Sponge {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.state.zeroize();
+        self.initial_state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for Sponge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Sponge {{ state: [***OMITTED***], buffer: [***OMITTED***], leftover: {:?}, \
+             rate: {:?}, is_finalized: {:?} }}",
+            self.leftover, self.rate, self.is_finalized
+        )
+    }
+}
+
+impl Sponge {
+    /// Initialize a `Sponge` with a `rate` in bytes and the `padding` byte
+    /// that starts the padding of the last block.
+    pub(crate) fn new(rate: usize, padding: u8) -> Self {
+        debug_assert_eq!(rate % 8, 0);
+        debug_assert!(rate <= KECCAK_MAX_RATE);
+
+        Self {
+            state: [0u64; 25],
+            initial_state: [0u64; 25],
+            buffer: [0u8; KECCAK_MAX_RATE],
+            leftover: 0,
+            rate,
+            padding,
+            is_finalized: false,
+        }
+    }
+
+    /// Initialize a `Sponge` for cSHAKE with the `function_name` and `customization`
+    /// strings. If both are empty, this is a sponge for SHAKE.
+    pub(crate) fn new_cshake(
+        rate: usize,
+        function_name: &[u8],
+        customization: &[u8],
+    ) -> Result<Self, UnknownCryptoError> {
+        if function_name.is_empty() && customization.is_empty() {
+            return Ok(Self::new(rate, SHAKE_PADDING));
+        }
+
+        let mut sponge = Self::new(rate, CSHAKE_PADDING);
+        sponge.absorb_prefix(&[function_name, customization])?;
+
+        Ok(sponge)
+    }
+
+    /// Absorb `bytepad(encode_string(strings[0]) || ... , rate)` as specified in
+    /// NIST SP 800-185 section 2.3. The state after absorbing these is the one
+    /// that `reset()` returns to.
+    pub(crate) fn absorb_prefix(&mut self, strings: &[&[u8]]) -> Result<(), UnknownCryptoError> {
+        let mut buf = [0u8; 9];
+        self.update(left_encode(self.rate as u64, &mut buf))?;
+        for string in strings.iter() {
+            self.update(left_encode(bit_length(string.len())?, &mut buf))?;
+            self.update(string)?;
+        }
+        if self.leftover != 0 {
+            let zeroes = [0u8; KECCAK_MAX_RATE];
+            self.update(&zeroes[..self.rate - self.leftover])?;
+        }
+
+        debug_assert!(self.leftover == 0);
+        self.initial_state = self.state;
+        // The prefix may contain secret data, such as the key of KMAC.
+        self.buffer = [0u8; KECCAK_MAX_RATE];
+
+        Ok(())
+    }
+
+    /// Reset to the state after initialization.
+    pub(crate) fn reset(&mut self) {
+        self.state = self.initial_state;
+        self.buffer = [0u8; KECCAK_MAX_RATE];
+        self.leftover = 0;
+        self.is_finalized = false;
+    }
+
+    /// Absorb `data`. This can be called multiple times.
+    pub(crate) fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = data;
+
+        if self.leftover != 0 {
+            debug_assert!(self.leftover < self.rate);
+
+            let want = core::cmp::min(self.rate - self.leftover, bytes.len());
+            self.buffer[self.leftover..self.leftover + want].copy_from_slice(&bytes[..want]);
+            bytes = &bytes[want..];
+            self.leftover += want;
+
+            if self.leftover < self.rate {
+                return Ok(());
+            }
+
+            absorb_block(&mut self.state, &self.buffer[..self.rate]);
+            self.leftover = 0;
+        }
+
+        while bytes.len() >= self.rate {
+            absorb_block(&mut self.state, &bytes[..self.rate]);
+            bytes = &bytes[self.rate..];
+        }
+
+        if !bytes.is_empty() {
+            debug_assert!(self.leftover == 0);
+            self.buffer[..bytes.len()].copy_from_slice(bytes);
+            self.leftover = bytes.len();
+        }
+
+        Ok(())
+    }
+
+    /// Pad and absorb the last block, and return a reader for the output.
+    pub(crate) fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+
+        debug_assert!(self.leftover < self.rate);
+        for itm in self.buffer.iter_mut().skip(self.leftover) {
+            *itm = 0;
+        }
+        self.buffer[self.leftover] ^= self.padding;
+        self.buffer[self.rate - 1] ^= 0x80;
+        absorb_block(&mut self.state, &self.buffer[..self.rate]);
+
+        Ok(XofReader {
+            state: self.state,
+            rate: self.rate,
+            position: 0,
+        })
+    }
+
+    /// Pad and absorb the last block, and fill `dest` with the output.
+    pub(crate) fn finalize(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.finalize_xof()?.squeeze(dest)
+    }
+}
+
+#[cfg(test)]
+/// Compare two Sponge state objects to check if their fields
+/// are the same.
+pub(crate) fn compare_sponge_states(state_1: &Sponge, state_2: &Sponge) {
+    assert_eq!(state_1.state, state_2.state);
+    assert_eq!(state_1.initial_state, state_2.initial_state);
+    assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+    assert_eq!(state_1.leftover, state_2.leftover);
+    assert_eq!(state_1.rate, state_2.rate);
+    assert_eq!(state_1.padding, state_2.padding);
+    assert_eq!(state_1.is_finalized, state_2.is_finalized);
+}
+
+/// A reader for the output of an extendable-output function (XOF), such as SHAKE128.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `dest` is empty.
+///
+/// # Security:
+/// - Output read with several calls to [`squeeze()`] is the same as output read
+///   with a single call. Reading 32 bytes and then 64 bytes thus gives the same
+///   first 32 bytes as reading 96 bytes at once.
+///
+/// [`squeeze()`]: struct.XofReader.html#method.squeeze
+pub struct XofReader {
+    state: [u64; 25],
+    rate: usize,
+    position: usize,
+}
+
+impl Drop for XofReader {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.state.zeroize();
+    }
+}
+
+impl core::fmt::Debug for XofReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "XofReader {{ state: [***OMITTED***], rate: {:?}, position: {:?} }}",
+            self.rate, self.position
+        )
+    }
+}
+
+impl XofReader {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dest` with the next `dest.len()` bytes of output.
+    pub fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dest.is_empty() {
+            return Err(UnknownCryptoError);
+        }
+
+        for byte in dest.iter_mut() {
+            if self.position == self.rate {
+                keccak_f1600(&mut self.state);
+                self.position = 0;
+            }
+            *byte = (self.state[self.position / 8] >> (8 * (self.position % 8))) as u8;
+            self.position += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl XofRead for XofReader {
+    fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.squeeze(dest)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let reader = Sponge::new(KECCAK_MAX_RATE, SHAKE_PADDING)
+            .finalize_xof()
+            .unwrap();
+        let debug = format!("{:?}", reader);
+        let expected = "XofReader { state: [***OMITTED***], rate: 168, position: 0 }";
+        assert_eq!(debug, expected);
+    }
+
+    #[test]
+    fn test_squeeze_empty_err() {
+        let mut reader = Sponge::new(KECCAK_MAX_RATE, SHAKE_PADDING)
+            .finalize_xof()
+            .unwrap();
+        assert!(reader.squeeze(&mut [0u8; 0]).is_err());
+        assert!(reader.squeeze(&mut [0u8; 1]).is_ok());
+    }
+
+    #[test]
+    fn test_squeeze_incremental() {
+        let mut one_shot = [0u8; 3 * KECCAK_MAX_RATE];
+        Sponge::new(KECCAK_MAX_RATE, SHAKE_PADDING)
+            .finalize(&mut one_shot)
+            .unwrap();
+
+        // Read across the boundaries of the rate in differently sized pieces.
+        for step in [1, 7, 8, 100, KECCAK_MAX_RATE, KECCAK_MAX_RATE + 1].iter() {
+            let mut reader = Sponge::new(KECCAK_MAX_RATE, SHAKE_PADDING)
+                .finalize_xof()
+                .unwrap();
+            let mut incremental = [0u8; 3 * KECCAK_MAX_RATE];
+            for chunk in incremental.chunks_mut(*step) {
+                reader.squeeze(chunk).unwrap();
+            }
+            assert_eq!(one_shot[..], incremental[..]);
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    #[test]
+    fn test_keccak_f1600_zero_state() {
+        // The first lanes of Keccak-f[1600] applied to the all-zero state, from the
+        // intermediate values published by the Keccak team.
+        let mut state = [0u64; 25];
+        keccak_f1600(&mut state);
+        assert_eq!(state[0], 0xF1258F7940E1DDE7);
+        assert_eq!(state[1], 0x84D5CCF933C0478A);
+        assert_eq!(state[24], 0xEAF1FF7B5CECA249);
+    }
+
+    #[test]
+    fn test_left_encode() {
+        let mut buf = [0u8; 9];
+        assert_eq!(left_encode(0, &mut buf), &[1, 0][..]);
+        assert_eq!(left_encode(168, &mut buf), &[1, 168][..]);
+        assert_eq!(left_encode(255, &mut buf), &[1, 255][..]);
+        assert_eq!(left_encode(256, &mut buf), &[2, 1, 0][..]);
+        assert_eq!(
+            left_encode(0xFFFF_FFFF_FFFF_FFFF, &mut buf),
+            &[8, 255, 255, 255, 255, 255, 255, 255, 255][..]
+        );
+    }
+
+    #[test]
+    fn test_right_encode() {
+        let mut buf = [0u8; 9];
+        assert_eq!(right_encode(0, &mut buf), &[0, 1][..]);
+        assert_eq!(right_encode(256, &mut buf), &[1, 0, 2][..]);
+        assert_eq!(right_encode(512, &mut buf), &[2, 0, 2][..]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_bit_length() {
+        assert_eq!(bit_length(0).unwrap(), 0);
+        assert_eq!(bit_length(32).unwrap(), 256);
+        assert!(bit_length(!0usize).is_err());
+    }
+
+    #[test]
+    fn test_cshake_without_strings_is_shake() {
+        let shake = Sponge::new(KECCAK_MAX_RATE, SHAKE_PADDING);
+        let cshake = Sponge::new_cshake(KECCAK_MAX_RATE, b"", b"").unwrap();
+        compare_sponge_states(&shake, &cshake);
+
+        let cshake = Sponge::new_cshake(KECCAK_MAX_RATE, b"", b"S").unwrap();
+        assert_eq!(cshake.padding, CSHAKE_PADDING);
+        assert_eq!(cshake.leftover, 0);
+        assert_eq!(cshake.state, cshake.initial_state);
+    }
+}
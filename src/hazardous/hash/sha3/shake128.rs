@@ -0,0 +1,328 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `data`: The data to be hashed.
+//! - `function_name`: The function-name string of cSHAKE128.
+//! - `customization`: The customization string of cSHAKE128.
+//! - `dst_out`: Destination buffer for the output. The length of the output is
+//!   determined by the length of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` is empty.
+//!
+//! # Security:
+//! - SHAKE128 provides at most 128 bits of security, no matter the length of
+//!   the output. 32 bytes of output is enough to reach this.
+//! - Output of a shorter length is a prefix of output of a longer length. The
+//!   length of the output should therefore not be used to separate different uses.
+//!   Use different customization strings with cSHAKE128 for this instead.
+//! - The function-name string is reserved for functions defined by NIST, such as
+//!   KMAC, and should otherwise be left empty.
+//! - cSHAKE128 with both an empty function-name and customization string is SHAKE128.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::sha3::shake128::Shake128;
+//!
+//! // Using the streaming interface
+//! let mut state = Shake128::new();
+//! state.update(b"Hello world")?;
+//! let mut reader = state.finalize()?;
+//! let mut output = [0u8; 64];
+//! reader.squeeze(&mut output[..32])?;
+//! reader.squeeze(&mut output[32..])?;
+//!
+//! // Using the one-shot function
+//! let mut output_one_shot = [0u8; 64];
+//! Shake128::digest(b"Hello world", &mut output_one_shot)?;
+//!
+//! assert_eq!(output[..], output_one_shot[..]);
+//!
+//! // Using cSHAKE128 with a customization string
+//! let mut state = Shake128::new_cshake(b"", b"My application")?;
+//! state.update(b"Hello world")?;
+//! let mut customized = [0u8; 64];
+//! state.finalize()?.squeeze(&mut customized)?;
+//!
+//! assert_ne!(output[..], customized[..]);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Shake128.html
+//! [`reset()`]: struct.Shake128.html
+//! [`finalize()`]: struct.Shake128.html
+
+use super::{Sponge, XofReader, SHAKE_PADDING};
+use crate::errors::UnknownCryptoError;
+use crate::hazardous::traits::Xof;
+
+/// The rate, in bytes, of the sponge function SHAKE128.
+pub const SHAKE128_RATE: usize = 168;
+
+#[derive(Clone, Debug)]
+/// SHAKE128 and cSHAKE128 streaming state.
+pub struct Shake128 {
+    state: Sponge,
+}
+
+impl Default for Shake128 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_io_write_trait!(Shake128);
+
+impl Shake128 {
+    /// Initialize a `Shake128` struct.
+    pub fn new() -> Self {
+        Self {
+            state: Sponge::new(SHAKE128_RATE, SHAKE_PADDING),
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Shake128` struct computing cSHAKE128 with `function_name`
+    /// and `customization`.
+    pub fn new_cshake(
+        function_name: &[u8],
+        customization: &[u8],
+    ) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            state: Sponge::new_cshake(SHAKE128_RATE, function_name, customization)?,
+        })
+    }
+
+    /// Reset to the state after initialization.
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for the output.
+    pub fn finalize(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.state.finalize_xof()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate SHAKE128 of some `data`, filling `dst_out`.
+    pub fn digest(data: &[u8], dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()?.squeeze(dst_out)
+    }
+}
+
+impl Xof for Shake128 {
+    type Reader = XofReader;
+
+    fn new() -> Self {
+        Shake128::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::sha3::compare_sponge_states;
+
+    #[test]
+    fn test_default_equals_new() {
+        let new = Shake128::new();
+        let default = Shake128::default();
+        compare_sponge_states(&new.state, &default.state);
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Shake128::new();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Shake128 { state: Sponge { state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, rate: 168, is_finalized: false } }";
+        assert_eq!(debug, expected);
+    }
+
+    #[test]
+    fn test_digest_empty_dst_out_err() {
+        assert!(Shake128::digest(b"", &mut [0u8; 0]).is_err());
+        assert!(Shake128::digest(b"", &mut [0u8; 1]).is_ok());
+    }
+
+    #[test]
+    fn test_fips_202_example() {
+        // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+        let expected = [
+            0x7f, 0x9c, 0x2b, 0xa4, 0xe8, 0x8f, 0x82, 0x7d, 0x61, 0x60, 0x45, 0x50, 0x76, 0x05,
+            0x85, 0x3e, 0xd7, 0x3b, 0x80, 0x93, 0xf6, 0xef, 0xbc, 0x88, 0xeb, 0x1a, 0x6e, 0xac,
+            0xfa, 0x66, 0xef, 0x26,
+        ];
+        let mut actual = [0u8; 32];
+        Shake128::digest(b"", &mut actual).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sp_800_185_cshake_samples() {
+        // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+        let data: [u8; 200] = {
+            let mut data = [0u8; 200];
+            for (idx, itm) in data.iter_mut().enumerate() {
+                *itm = idx as u8;
+            }
+            data
+        };
+
+        let expected_1 = [
+            0xc1, 0xc3, 0x69, 0x25, 0xb6, 0x40, 0x9a, 0x04, 0xf1, 0xb5, 0x04, 0xfc, 0xbc, 0xa9,
+            0xd8, 0x2b, 0x40, 0x17, 0x27, 0x7c, 0xb5, 0xed, 0x2b, 0x20, 0x65, 0xfc, 0x1d, 0x38,
+            0x14, 0xd5, 0xaa, 0xf5,
+        ];
+        let mut state = Shake128::new_cshake(b"", b"Email Signature").unwrap();
+        state.update(&data[..4]).unwrap();
+        let mut actual = [0u8; 32];
+        state.finalize().unwrap().squeeze(&mut actual).unwrap();
+        assert_eq!(actual, expected_1);
+
+        let expected_2 = [
+            0xc5, 0x22, 0x1d, 0x50, 0xe4, 0xf8, 0x22, 0xd9, 0x6a, 0x2e, 0x88, 0x81, 0xa9, 0x61,
+            0x42, 0x0f, 0x29, 0x4b, 0x7b, 0x24, 0xfe, 0x3d, 0x20, 0x94, 0xba, 0xed, 0x2c, 0x65,
+            0x24, 0xcc, 0x16, 0x6b,
+        ];
+        state.reset();
+        state.update(&data).unwrap();
+        state.finalize().unwrap().squeeze(&mut actual).unwrap();
+        assert_eq!(actual, expected_2);
+    }
+
+    #[test]
+    fn test_cshake_reset_keeps_customization() {
+        let initial_state = Shake128::new_cshake(b"", b"Email Signature").unwrap();
+        let mut state = initial_state.clone();
+        state.update(b"Some data").unwrap();
+        let _ = state.finalize().unwrap();
+        state.reset();
+        compare_sponge_states(&initial_state.state, &state.state);
+
+        let shake = Shake128::new();
+        assert_ne!(shake.state.initial_state, state.state.initial_state);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        /// The output of a `Shake128` in these tests.
+        type Output = [u8; 32];
+
+        impl TestableStreamingContext<Output> for Shake128 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                self.finalize()?.squeeze(&mut output)?;
+                Ok(output)
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                Shake128::digest(input, &mut output)?;
+                Ok(output)
+            }
+
+            fn verify_result(expected: &Output, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Output = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Shake128, state_2: &Shake128) {
+                compare_sponge_states(&state_1.state, &state_2.state)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Shake128 = Shake128::new();
+
+            let test_runner = StreamingContextConsistencyTester::<Output, Shake128>::new(
+                initial_state,
+                SHAKE128_RATE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Shake128 = Shake128::new();
+
+                    let test_runner = StreamingContextConsistencyTester::<Output, Shake128>::new(
+                        initial_state,
+                        SHAKE128_RATE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `data`: The data to be hashed.
+//! - `function_name`: The function-name string of cSHAKE256.
+//! - `customization`: The customization string of cSHAKE256.
+//! - `dst_out`: Destination buffer for the output. The length of the output is
+//!   determined by the length of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` is empty.
+//!
+//! # Security:
+//! - SHAKE256 provides at most 256 bits of security, no matter the length of
+//!   the output. 64 bytes of output is enough to reach this.
+//! - Output of a shorter length is a prefix of output of a longer length. The
+//!   length of the output should therefore not be used to separate different uses.
+//!   Use different customization strings with cSHAKE256 for this instead.
+//! - The function-name string is reserved for functions defined by NIST, such as
+//!   KMAC, and should otherwise be left empty.
+//! - cSHAKE256 with both an empty function-name and customization string is SHAKE256.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::sha3::shake256::Shake256;
+//!
+//! // Using the streaming interface
+//! let mut state = Shake256::new();
+//! state.update(b"Hello world")?;
+//! let mut reader = state.finalize()?;
+//! let mut output = [0u8; 64];
+//! reader.squeeze(&mut output[..32])?;
+//! reader.squeeze(&mut output[32..])?;
+//!
+//! // Using the one-shot function
+//! let mut output_one_shot = [0u8; 64];
+//! Shake256::digest(b"Hello world", &mut output_one_shot)?;
+//!
+//! assert_eq!(output[..], output_one_shot[..]);
+//!
+//! // Using cSHAKE256 with a customization string
+//! let mut state = Shake256::new_cshake(b"", b"My application")?;
+//! state.update(b"Hello world")?;
+//! let mut customized = [0u8; 64];
+//! state.finalize()?.squeeze(&mut customized)?;
+//!
+//! assert_ne!(output[..], customized[..]);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Shake256.html
+//! [`reset()`]: struct.Shake256.html
+//! [`finalize()`]: struct.Shake256.html
+
+use super::{Sponge, XofReader, SHAKE_PADDING};
+use crate::errors::UnknownCryptoError;
+use crate::hazardous::traits::Xof;
+
+/// The rate, in bytes, of the sponge function SHAKE256.
+pub const SHAKE256_RATE: usize = 136;
+
+#[derive(Clone, Debug)]
+/// SHAKE256 and cSHAKE256 streaming state.
+pub struct Shake256 {
+    state: Sponge,
+}
+
+impl Default for Shake256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_io_write_trait!(Shake256);
+
+impl Shake256 {
+    /// Initialize a `Shake256` struct.
+    pub fn new() -> Self {
+        Self {
+            state: Sponge::new(SHAKE256_RATE, SHAKE_PADDING),
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Shake256` struct computing cSHAKE256 with `function_name`
+    /// and `customization`.
+    pub fn new_cshake(
+        function_name: &[u8],
+        customization: &[u8],
+    ) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            state: Sponge::new_cshake(SHAKE256_RATE, function_name, customization)?,
+        })
+    }
+
+    /// Reset to the state after initialization.
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for the output.
+    pub fn finalize(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.state.finalize_xof()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate SHAKE256 of some `data`, filling `dst_out`.
+    pub fn digest(data: &[u8], dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()?.squeeze(dst_out)
+    }
+}
+
+impl Xof for Shake256 {
+    type Reader = XofReader;
+
+    fn new() -> Self {
+        Shake256::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::sha3::compare_sponge_states;
+
+    #[test]
+    fn test_default_equals_new() {
+        let new = Shake256::new();
+        let default = Shake256::default();
+        compare_sponge_states(&new.state, &default.state);
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Shake256::new();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Shake256 { state: Sponge { state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, rate: 136, is_finalized: false } }";
+        assert_eq!(debug, expected);
+    }
+
+    #[test]
+    fn test_digest_empty_dst_out_err() {
+        assert!(Shake256::digest(b"", &mut [0u8; 0]).is_err());
+        assert!(Shake256::digest(b"", &mut [0u8; 1]).is_ok());
+    }
+
+    #[test]
+    fn test_fips_202_example() {
+        // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+        let expected = [
+            0x46, 0xb9, 0xdd, 0x2b, 0x0b, 0xa8, 0x8d, 0x13, 0x23, 0x3b, 0x3f, 0xeb, 0x74, 0x3e,
+            0xeb, 0x24, 0x3f, 0xcd, 0x52, 0xea, 0x62, 0xb8, 0x1b, 0x82, 0xb5, 0x0c, 0x27, 0x64,
+            0x6e, 0xd5, 0x76, 0x2f, 0xd7, 0x5d, 0xc4, 0xdd, 0xd8, 0xc0, 0xf2, 0x00, 0xcb, 0x05,
+            0x01, 0x9d, 0x67, 0xb5, 0x92, 0xf6, 0xfc, 0x82, 0x1c, 0x49, 0x47, 0x9a, 0xb4, 0x86,
+            0x40, 0x29, 0x2e, 0xac, 0xb3, 0xb7, 0xc4, 0xbe,
+        ];
+        let mut actual = [0u8; 64];
+        Shake256::digest(b"", &mut actual).unwrap();
+        assert_eq!(actual[..], expected[..]);
+    }
+
+    #[test]
+    fn test_sp_800_185_cshake_samples() {
+        // https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+        let data: [u8; 200] = {
+            let mut data = [0u8; 200];
+            for (idx, itm) in data.iter_mut().enumerate() {
+                *itm = idx as u8;
+            }
+            data
+        };
+
+        let expected_1 = [
+            0xd0, 0x08, 0x82, 0x8e, 0x2b, 0x80, 0xac, 0x9d, 0x22, 0x18, 0xff, 0xee, 0x1d, 0x07,
+            0x0c, 0x48, 0xb8, 0xe4, 0xc8, 0x7b, 0xff, 0x32, 0xc9, 0x69, 0x9d, 0x5b, 0x68, 0x96,
+            0xee, 0xe0, 0xed, 0xd1, 0x64, 0x02, 0x0e, 0x2b, 0xe0, 0x56, 0x08, 0x58, 0xd9, 0xc0,
+            0x0c, 0x03, 0x7e, 0x34, 0xa9, 0x69, 0x37, 0xc5, 0x61, 0xa7, 0x4c, 0x41, 0x2b, 0xb4,
+            0xc7, 0x46, 0x46, 0x95, 0x27, 0x28, 0x1c, 0x8c,
+        ];
+        let mut state = Shake256::new_cshake(b"", b"Email Signature").unwrap();
+        state.update(&data[..4]).unwrap();
+        let mut actual = [0u8; 64];
+        state.finalize().unwrap().squeeze(&mut actual).unwrap();
+        assert_eq!(actual[..], expected_1[..]);
+
+        let expected_2 = [
+            0x07, 0xdc, 0x27, 0xb1, 0x1e, 0x51, 0xfb, 0xac, 0x75, 0xbc, 0x7b, 0x3c, 0x1d, 0x98,
+            0x3e, 0x8b, 0x4b, 0x85, 0xfb, 0x1d, 0xef, 0xaf, 0x21, 0x89, 0x12, 0xac, 0x86, 0x43,
+            0x02, 0x73, 0x09, 0x17, 0x27, 0xf4, 0x2b, 0x17, 0xed, 0x1d, 0xf6, 0x3e, 0x8e, 0xc1,
+            0x18, 0xf0, 0x4b, 0x23, 0x63, 0x3c, 0x1d, 0xfb, 0x15, 0x74, 0xc8, 0xfb, 0x55, 0xcb,
+            0x45, 0xda, 0x8e, 0x25, 0xaf, 0xb0, 0x92, 0xbb,
+        ];
+        state.reset();
+        state.update(&data).unwrap();
+        state.finalize().unwrap().squeeze(&mut actual).unwrap();
+        assert_eq!(actual[..], expected_2[..]);
+    }
+
+    #[test]
+    fn test_cshake_reset_keeps_customization() {
+        let initial_state = Shake256::new_cshake(b"", b"Email Signature").unwrap();
+        let mut state = initial_state.clone();
+        state.update(b"Some data").unwrap();
+        let _ = state.finalize().unwrap();
+        state.reset();
+        compare_sponge_states(&initial_state.state, &state.state);
+
+        let shake = Shake256::new();
+        assert_ne!(shake.state.initial_state, state.state.initial_state);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        /// The output of a `Shake256` in these tests.
+        type Output = [u8; 32];
+
+        impl TestableStreamingContext<Output> for Shake256 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                self.finalize()?.squeeze(&mut output)?;
+                Ok(output)
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Output, UnknownCryptoError> {
+                let mut output = [0u8; 32];
+                Shake256::digest(input, &mut output)?;
+                Ok(output)
+            }
+
+            fn verify_result(expected: &Output, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Output = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Shake256, state_2: &Shake256) {
+                compare_sponge_states(&state_1.state, &state_2.state)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Shake256 = Shake256::new();
+
+            let test_runner = StreamingContextConsistencyTester::<Output, Shake256>::new(
+                initial_state,
+                SHAKE256_RATE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Shake256 = Shake256::new();
+
+                    let test_runner = StreamingContextConsistencyTester::<Output, Shake256>::new(
+                        initial_state,
+                        SHAKE256_RATE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
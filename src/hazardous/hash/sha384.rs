@@ -0,0 +1,257 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `data`: The data to be hashed.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 2*(2^64-1) __bits__ of data are hashed.
+//!
+//! # Security:
+//! - SHA384 uses the SHA512 compression function with a different initial hash
+//!   value. Because the output is truncated, it is not vulnerable to length
+//!   extension attacks in the way SHA256 and SHA512 are.
+//!
+//! # Recommendation:
+//! - It is recommended to use [BLAKE2b] when possible.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::sha384::Sha384;
+//!
+//! // Using the streaming interface
+//! let mut state = Sha384::new();
+//! state.update(b"Hello world")?;
+//! let hash = state.finalize()?;
+//!
+//! // Using the one-shot function
+//! let hash_one_shot = Sha384::digest(b"Hello world")?;
+//!
+//! assert_eq!(hash, hash_one_shot);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Sha384.html
+//! [`reset()`]: struct.Sha384.html
+//! [`finalize()`]: struct.Sha384.html
+//! [BLAKE2b]: ../blake2b/index.html
+
+use crate::hazardous::traits::IncrementalHash;
+use crate::{errors::UnknownCryptoError, hazardous::hash::sha512::Sha512};
+
+/// The blocksize for the hash function SHA384.
+pub const SHA384_BLOCKSIZE: usize = 128;
+/// The output size for the hash function SHA384.
+pub const SHA384_OUTSIZE: usize = 48;
+
+construct_public! {
+    /// A type to represent the `Digest` that SHA384 returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 48 bytes.
+    (Digest, test_digest, SHA384_OUTSIZE, SHA384_OUTSIZE)
+}
+
+impl_from_trait!(Digest, SHA384_OUTSIZE);
+
+#[rustfmt::skip]
+#[allow(clippy::unreadable_literal)]
+/// The SHA384 initial hash value H(0) as defined in FIPS 180-4.
+const H0: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+
+#[derive(Clone, Debug)]
+/// SHA384 streaming state.
+pub struct Sha384 {
+    state: Sha512,
+}
+
+impl Default for Sha384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_io_write_trait!(Sha384);
+
+impl Sha384 {
+    /// Initialize a `Sha384` struct.
+    pub fn new() -> Self {
+        Self {
+            state: Sha512::with_initial_hash(H0),
+        }
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state = Sha512::with_initial_hash(H0);
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a SHA384 digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        let full = self.state.finalize()?;
+        Digest::from_slice(&full.as_ref()[..SHA384_OUTSIZE])
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Calculate a SHA384 digest of some `data`.
+    pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()
+    }
+}
+
+impl IncrementalHash for Sha384 {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        Sha384::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
+}
+
+#[cfg(test)]
+/// Compare two Sha384 state objects to check if their fields
+/// are the same.
+pub fn compare_sha384_states(state_1: &Sha384, state_2: &Sha384) {
+    crate::hazardous::hash::sha512::compare_sha512_states(&state_1.state, &state_2.state);
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    fn test_default_equals_new() {
+        let new = Sha384::new();
+        let default = Sha384::default();
+        compare_sha384_states(&new, &default);
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let initial_state = Sha384::new();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Sha384 { state: Sha512 { working_state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, message_len: [0, 0], is_finalized: false } }";
+        assert_eq!(debug, expected);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Sha384 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                Sha384::digest(input)
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError)
+                }
+            }
+
+            fn compare_states(state_1: &Sha384, state_2: &Sha384) {
+                compare_sha384_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Sha384 = Sha384::new();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Sha384>::new(
+                initial_state,
+                SHA384_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Sha384 = Sha384::new();
+
+                    let test_runner = StreamingContextConsistencyTester::<Digest, Sha384>::new(
+                        initial_state,
+                        SHA384_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
@@ -22,12 +22,19 @@
 
 //! # Parameters:
 //! - `data`: The data to be hashed.
+//! - `dst_out`: Destination buffer for the exported state.
+//! - `exported`: A state exported with [`export_state()`].
 //!
 //! # Errors:
 //! An error will be returned if:
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` or `exported` is not [`SHA512_EXPORTSIZE`] bytes.
+//! - `exported` was not exported by a `Sha512` with the same version of the
+//!   encoding, or is otherwise invalid.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -35,6 +42,8 @@
 //!
 //! # Security:
 //! - SHA512 is vulnerable to length extension attacks.
+//! - An exported state contains the data that has been buffered since the last
+//!   block was processed. It should be protected like the data itself.
 //!
 //! # Recommendation:
 //! - It is recommended to use [BLAKE2b] when possible.
@@ -52,22 +61,44 @@
 //! let hash_one_shot = Sha512::digest(b"Hello world")?;
 //!
 //! assert_eq!(hash, hash_one_shot);
+//!
+//! // Exporting the state to continue hashing later
+//! use orion::hazardous::hash::sha512::SHA512_EXPORTSIZE;
+//!
+//! let mut state = Sha512::new();
+//! state.update(b"Hello ")?;
+//! let mut exported = [0u8; SHA512_EXPORTSIZE];
+//! state.export_state(&mut exported)?;
+//!
+//! let mut imported = Sha512::import_state(&exported)?;
+//! imported.update(b"world")?;
+//! assert_eq!(imported.finalize()?, hash);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`update()`]: struct.Sha512.html
 //! [`reset()`]: struct.Sha512.html
 //! [`finalize()`]: struct.Sha512.html
+//! [`export_state()`]: struct.Sha512.html
+//! [`SHA512_EXPORTSIZE`]: constant.SHA512_EXPORTSIZE.html
 //! [BLAKE2b]: ../blake2b/index.html
 
+use crate::hazardous::traits::IncrementalHash;
 use crate::{
     errors::UnknownCryptoError,
     util::endianness::{load_u64_into_be, store_u64_into_be},
+    util::state_export::{StateReader, StateWriter, STATE_EXPORT_HEADERSIZE, STATE_ID_SHA512},
 };
+use zeroize::Zeroize;
 
 /// The blocksize for the hash function SHA512.
 pub const SHA512_BLOCKSIZE: usize = 128;
 /// The output size for the hash function SHA512.
 pub const SHA512_OUTSIZE: usize = 64;
+/// The size of the fields of a `Sha512` in an exported state, which are the
+/// working state, buffer, leftover and message length.
+pub(crate) const SHA512_FIELDSIZE: usize = 64 + SHA512_BLOCKSIZE + 8 + 16;
+/// The size of an exported `Sha512` state.
+pub const SHA512_EXPORTSIZE: usize = STATE_EXPORT_HEADERSIZE + SHA512_FIELDSIZE;
 
 construct_public! {
     /// A type to represent the `Digest` that SHA512 returns.
@@ -126,7 +157,6 @@ pub struct Sha512 {
 
 impl Drop for Sha512 {
     fn drop(&mut self) {
-        use zeroize::Zeroize;
         self.working_state.zeroize();
         self.buffer.zeroize();
         self.message_len.zeroize();
@@ -150,6 +180,8 @@ impl Default for Sha512 {
     }
 }
 
+impl_io_write_trait!(Sha512);
+
 impl Sha512 {
     /// The Ch function as specified in FIPS 180-4 section 4.1.3.
     const fn ch(x: u64, y: u64, z: u64) -> u64 {
@@ -279,10 +311,11 @@ impl Sha512 {
         }
     }
 
-    /// Initialize a `Sha512` struct.
-    pub fn new() -> Self {
+    /// Initialize a `Sha512` struct with `h0` as the initial hash value.
+    /// This allows SHA384 to reuse the SHA512 compression function.
+    pub(crate) fn with_initial_hash(h0: [u64; 8]) -> Self {
         Self {
-            working_state: H0,
+            working_state: h0,
             buffer: [0u8; SHA512_BLOCKSIZE],
             leftover: 0,
             message_len: [0u64; 2],
@@ -290,6 +323,11 @@ impl Sha512 {
         }
     }
 
+    /// Initialize a `Sha512` struct.
+    pub fn new() -> Self {
+        Self::with_initial_hash(H0)
+    }
+
     /// Reset to `new()` state.
     pub fn reset(&mut self) {
         self.working_state = H0;
@@ -398,6 +436,98 @@ impl Sha512 {
         state.update(data)?;
         state.finalize()
     }
+
+    /// Write the fields of the state, which must not be finalized.
+    pub(crate) fn write_fields(&self, writer: &mut StateWriter<'_>) {
+        debug_assert!(!self.is_finalized);
+        for word in self.working_state.iter() {
+            writer.put_u64(*word);
+        }
+        // Only the buffered data is exported, and not what was left in the
+        // buffer by previous blocks.
+        let mut buffer = [0u8; SHA512_BLOCKSIZE];
+        buffer[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+        writer.put_bytes(&buffer);
+        buffer.zeroize();
+        writer.put_u64(self.leftover as u64);
+        writer.put_u64(self.message_len[0]);
+        writer.put_u64(self.message_len[1]);
+    }
+
+    /// Read the fields of a state written by `write_fields()`.
+    pub(crate) fn read_fields(reader: &mut StateReader<'_>) -> Result<Self, UnknownCryptoError> {
+        let mut state = Self::new();
+        for word in state.working_state.iter_mut() {
+            *word = reader.get_u64();
+        }
+        reader.get_bytes(&mut state.buffer);
+        let leftover = reader.get_u64();
+        state.message_len[0] = reader.get_u64();
+        state.message_len[1] = reader.get_u64();
+
+        // update() processes the buffer as soon as it is full.
+        if leftover >= SHA512_BLOCKSIZE as u64 {
+            return Err(UnknownCryptoError);
+        }
+        // The message length is counted in bits, and the buffer holds the bytes
+        // that do not fill a whole block.
+        if state.message_len[1] % (SHA512_BLOCKSIZE as u64 * 8) != leftover * 8 {
+            return Err(UnknownCryptoError);
+        }
+        state.leftover = leftover as usize;
+
+        Ok(state)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state to `dst_out`, from which it can be restored with
+    /// [`import_state()`].
+    ///
+    /// [`import_state()`]: struct.Sha512.html#method.import_state
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut writer = StateWriter::new(dst_out, STATE_ID_SHA512, SHA512_EXPORTSIZE)?;
+        self.write_fields(&mut writer);
+        writer.finish();
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Restore a state exported with [`export_state()`].
+    ///
+    /// [`export_state()`]: struct.Sha512.html#method.export_state
+    pub fn import_state(exported: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut reader = StateReader::new(exported, STATE_ID_SHA512, SHA512_EXPORTSIZE)?;
+        let state = Self::read_fields(&mut reader)?;
+        reader.finish();
+
+        Ok(state)
+    }
+}
+
+impl IncrementalHash for Sha512 {
+    type Digest = Digest;
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        self.finalize()
+    }
 }
 
 #[cfg(test)]
@@ -500,6 +630,132 @@ mod public {
             }
         }
     }
+
+    mod test_export_state {
+        use super::*;
+
+        /// Hash `data` with the state exported and imported after `split` bytes.
+        fn digest_with_export(data: &[u8], split: usize) -> Digest {
+            let mut state = Sha512::new();
+            state.update(&data[..split]).unwrap();
+            let mut exported = [0u8; SHA512_EXPORTSIZE];
+            state.export_state(&mut exported).unwrap();
+
+            let mut imported = Sha512::import_state(&exported).unwrap();
+            let mut exported_again = [0u8; SHA512_EXPORTSIZE];
+            imported.export_state(&mut exported_again).unwrap();
+            assert_eq!(exported[..], exported_again[..]);
+
+            imported.update(&data[split..]).unwrap();
+            imported.finalize().unwrap()
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let data = [1u8; 3 * SHA512_BLOCKSIZE + 3];
+            let expected = Sha512::digest(&data).unwrap();
+            for split in [0, 1, 127, 128, 129, 300, data.len()].iter() {
+                assert_eq!(digest_with_export(&data, *split), expected);
+            }
+        }
+
+        #[test]
+        fn test_length_err() {
+            let state = Sha512::new();
+            let mut exported = [0u8; SHA512_EXPORTSIZE + 1];
+            assert!(state
+                .export_state(&mut exported[..SHA512_EXPORTSIZE - 1])
+                .is_err());
+            assert!(state.export_state(&mut exported).is_err());
+            assert!(state
+                .export_state(&mut exported[..SHA512_EXPORTSIZE])
+                .is_ok());
+
+            assert!(Sha512::import_state(&exported[..SHA512_EXPORTSIZE - 1]).is_err());
+            assert!(Sha512::import_state(&exported).is_err());
+            assert!(Sha512::import_state(&exported[..SHA512_EXPORTSIZE]).is_ok());
+        }
+
+        #[test]
+        fn test_finalized_err() {
+            let mut state = Sha512::new();
+            let mut exported = [0u8; SHA512_EXPORTSIZE];
+            let _ = state.finalize().unwrap();
+            assert!(state.export_state(&mut exported).is_err());
+            state.reset();
+            assert!(state.export_state(&mut exported).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_state_err() {
+            let mut exported = [0u8; SHA512_EXPORTSIZE];
+            Sha512::new().export_state(&mut exported).unwrap();
+            assert!(Sha512::import_state(&exported).is_ok());
+
+            // The version of the encoding.
+            let mut invalid = exported;
+            invalid[0] += 1;
+            assert!(Sha512::import_state(&invalid).is_err());
+
+            // The type that exported the state.
+            let mut invalid = exported;
+            invalid[1] += 1;
+            assert!(Sha512::import_state(&invalid).is_err());
+
+            // The leftover, which follows the working state and buffer.
+            let leftover = STATE_EXPORT_HEADERSIZE + 64 + SHA512_BLOCKSIZE;
+            let mut invalid = exported;
+            invalid[leftover] = SHA512_BLOCKSIZE as u8;
+            assert!(Sha512::import_state(&invalid).is_err());
+            invalid[leftover] = 1;
+            assert!(Sha512::import_state(&invalid).is_err());
+        }
+
+        #[test]
+        fn test_leftover_mismatch_message_len_err() {
+            let mut exported = [0u8; SHA512_EXPORTSIZE];
+            let mut state = Sha512::new();
+            state.update(&[0u8; SHA512_BLOCKSIZE + 3]).unwrap();
+            state.export_state(&mut exported).unwrap();
+            assert!(Sha512::import_state(&exported).is_ok());
+
+            // The leftover, followed by the upper and lower words of the message
+            // length in bits.
+            let leftover = STATE_EXPORT_HEADERSIZE + 64 + SHA512_BLOCKSIZE;
+            let message_len = leftover + 16;
+            assert_eq!(exported[leftover], 3);
+
+            let mut crafted = exported;
+            crafted[leftover] = 4;
+            assert!(Sha512::import_state(&crafted).is_err());
+            crafted[leftover] = 0;
+            assert!(Sha512::import_state(&crafted).is_err());
+
+            // A whole block more in the message length leaves the same leftover.
+            let mut crafted = exported;
+            crafted[message_len..message_len + 8]
+                .copy_from_slice(&((2 * SHA512_BLOCKSIZE as u64 + 3) * 8).to_le_bytes());
+            assert!(Sha512::import_state(&crafted).is_ok());
+            // The message length must be a whole number of bytes.
+            crafted[message_len] += 1;
+            assert!(Sha512::import_state(&crafted).is_err());
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Exporting and importing the state at any point should not
+                /// change the digest.
+                fn prop_export_import_same_digest(data: Vec<u8>, split: usize) -> bool {
+                    let split = if data.is_empty() { 0 } else { split % data.len() };
+                    digest_with_export(&data, split) == Sha512::digest(&data).unwrap()
+                }
+            }
+        }
+    }
 }
 
 // Testing private functions in the module.
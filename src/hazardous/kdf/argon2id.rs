@@ -0,0 +1,571 @@
+// MIT License
+
+// Copyright (c) 2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! # About:
+//! Argon2id version 1.3. [`derive_key()`] and [`verify()`] use a single lane, while
+//! [`derive_key_with_lanes()`] and [`verify_with_lanes()`] use `lanes` lanes. These allocate
+//! the memory they need, and are available with features `safe_api` and `alloc`.
+//! [`derive_key_with_memory()`] and [`verify_with_memory()`] use memory provided by the caller
+//! instead, and are always available.
+//!
+//! Argon2id is a hybrid of Argon2i and Argon2d. It uses data-independent memory access
+//! for the first half of the first pass and data-dependent memory access for the rest.
+//! It is the recommended variant in [RFC 9106](https://tools.ietf.org/html/rfc9106).
+//!
+//! # Note:
+//! The lanes are filled one after another on the calling thread. If the `parallel`
+//! feature is enabled, the lanes are instead split between at most as many threads as
+//! `std::thread::available_parallelism()` reports, which requires Rust 1.63.
+//! The derived key is the same in both cases.
+//!
+//! When using [`derive_key_with_memory()`] or [`verify_with_memory()`], each block of `blocks`
+//! holds 1 KiB of memory. `memory` is rounded down to the nearest multiple of `4 * lanes`
+//! and only that many blocks of `blocks` are used, so a buffer of `memory` blocks is always
+//! large enough. The used blocks are zeroized before returning.
+//!
+//! # Parameters:
+//! - `expected`: The expected derived key.
+//! - `password`: Password.
+//! - `salt`: Salt value.
+//! - `iterations`: Iteration count.
+//! - `memory`: Memory size in kibibytes (KiB).
+//! - `lanes`: Degree of parallelism (p).
+//! - `secret`: Optional secret value used for hashing.
+//! - `ad`: Optional associated data used for hashing.
+//! - `blocks`: Memory used for hashing, when not allocated internally.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is implied by the length of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of the `password` is greater than `u32::max_value()`.
+//! - The length of the `salt` is greater than `u32::max_value()` or less than `8`.
+//! - The length of the `secret` is greater than `u32::max_value()`.
+//! - The length of the `ad` is greater than `u32::max_value()`.
+//! - The length of `dst_out` is greater than `u32::max_value()` or less than `4`.
+//! - `iterations` is less than `1`.
+//! - `lanes` is less than `1` or greater than `2^24 - 1`.
+//! - `memory` is less than `8 * lanes`.
+//! - `blocks` has fewer blocks than `memory`, rounded down to the nearest multiple of `4 * lanes`.
+//! - The hashed password does not match the expected when verifying.
+//!
+//! # Security:
+//! - The minimum recommended iteration count is `1`, using at least `2` GiB of memory, or
+//!   `3` iterations using at least `64` MiB, as per RFC 9106.
+//! - Salts should always be generated using a CSPRNG.
+//!   [`util::secure_rand_bytes()`] can be used for this.
+//! - The minimum recommended length for a salt is `16` bytes.
+//! - The minimum recommended length for a hashed password is `16` bytes.
+//! - Password hashes should always be compared in constant-time.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{hazardous::kdf::argon2id, util};
+//!
+//! let mut salt = [0u8; 16];
+//! util::secure_rand_bytes(&mut salt)?;
+//! let password = b"Secret password";
+//! let mut dst_out = [0u8; 64];
+//!
+//! argon2id::derive_key(password, &salt, 3, 1<<16, None, None, &mut dst_out)?;
+//!
+//! let expected_dk = dst_out;
+//!
+//! assert!(argon2id::verify(
+//!     &expected_dk,
+//!     password,
+//!     &salt,
+//!     3,
+//!     1<<16,
+//!     None,
+//!     None,
+//!     &mut dst_out
+//! )
+//! .is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Using memory provided by the caller, e.g. when no allocator is available:
+//! ```rust
+//! use orion::hazardous::kdf::argon2id;
+//!
+//! const MEMORY: u32 = 32;
+//! let mut blocks = [[0u64; 128]; MEMORY as usize];
+//! let salt = [0u8; 16]; // Use a random salt in practice.
+//! let password = b"Secret password";
+//! let mut dst_out = [0u8; 32];
+//!
+//! argon2id::derive_key_with_memory(
+//!     password,
+//!     &salt,
+//!     3,
+//!     MEMORY,
+//!     1,
+//!     None,
+//!     None,
+//!     &mut blocks,
+//!     &mut dst_out,
+//! )?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`util::secure_rand_bytes()`]: ../../../util/fn.secure_rand_bytes.html
+//! [`derive_key()`]: fn.derive_key.html
+//! [`verify()`]: fn.verify.html
+//! [`derive_key_with_lanes()`]: fn.derive_key_with_lanes.html
+//! [`verify_with_lanes()`]: fn.verify_with_lanes.html
+//! [`derive_key_with_memory()`]: fn.derive_key_with_memory.html
+//! [`verify_with_memory()`]: fn.verify_with_memory.html
+
+use super::argon2;
+use crate::errors::UnknownCryptoError;
+use crate::util;
+
+pub use super::argon2::ARGON2_VERSION;
+
+/// The Argon2 variant (id).
+pub const ARGON2_VARIANT: u32 = argon2::ARGON2ID;
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Argon2id password hashing function as described in the [RFC 9106](https://tools.ietf.org/html/rfc9106).
+pub fn derive_key(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key_with_lanes(password, salt, iterations, memory, 1, secret, ad, dst_out)
+}
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify Argon2id derived key in constant time.
+pub fn verify(
+    expected: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    verify_with_lanes(
+        expected, password, salt, iterations, memory, 1, secret, ad, dst_out,
+    )
+}
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Argon2id password hashing function, using `lanes` lanes.
+pub fn derive_key_with_lanes(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    lanes: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    argon2::derive_key(
+        ARGON2_VARIANT,
+        password,
+        salt,
+        iterations,
+        memory,
+        lanes,
+        secret,
+        ad,
+        dst_out,
+    )
+}
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify Argon2id derived key in constant time, using `lanes` lanes.
+pub fn verify_with_lanes(
+    expected: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    lanes: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key_with_lanes(
+        password, salt, iterations, memory, lanes, secret, ad, dst_out,
+    )?;
+    util::secure_cmp(dst_out, expected)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Argon2id password hashing function, using `blocks` as memory.
+pub fn derive_key_with_memory(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    lanes: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    blocks: &mut [[u64; 128]],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    argon2::derive_key_with_memory(
+        ARGON2_VARIANT,
+        password,
+        salt,
+        iterations,
+        memory,
+        lanes,
+        secret,
+        ad,
+        blocks,
+        dst_out,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify Argon2id derived key in constant time, using `blocks` as memory.
+pub fn verify_with_memory(
+    expected: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    lanes: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    blocks: &mut [[u64; 128]],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key_with_memory(
+        password, salt, iterations, memory, lanes, secret, ad, blocks, dst_out,
+    )?;
+    util::secure_cmp(dst_out, expected)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_test_same_input_verify_true(hlen: u32, kib: u32, p: Vec<u8>, s: Vec<u8>, k: Vec<u8>, x: Vec<u8>) -> bool {
+
+                    let passes = 1;
+                    let mem = if !(8..=4096).contains(&kib) {
+                        1024
+                    } else {
+                        kib
+                    };
+                    let salt = if s.len() < 8 {
+                        vec![37u8; 8]
+                    } else {
+                        s
+                    };
+
+                    let mut dst_out = if !(4..=512).contains(&hlen) {
+                        vec![0u8; 32]
+                    } else {
+                        vec![0u8; hlen as usize]
+                    };
+
+                    let mut dst_out_verify = dst_out.clone();
+                    derive_key(&p, &salt, passes, mem, Some(&k), Some(&x), &mut dst_out).unwrap();
+
+                    verify(&dst_out, &p, &salt, passes, mem, Some(&k), Some(&x), &mut dst_out_verify).is_ok()
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "safe_api", feature = "alloc"))]
+    mod test_derive_key {
+        use super::*;
+
+        #[test]
+        fn test_invalid_mem() {
+            let mut dst_out = [0u8; 32];
+            assert!(derive_key(&[], &[0u8; 8], 1, 9, None, None, &mut dst_out).is_ok());
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out).is_ok());
+            assert!(derive_key(&[], &[0u8; 8], 1, 7, None, None, &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_invalid_passes() {
+            let mut dst_out = [0u8; 32];
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out).is_ok());
+            assert!(derive_key(&[], &[0u8; 8], 0, 8, None, None, &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_invalid_lanes() {
+            let mut dst_out = [0u8; 32];
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out).is_ok());
+            assert!(
+                derive_key_with_lanes(&[], &[0u8; 8], 1, 32, 4, None, None, &mut dst_out).is_ok()
+            );
+            assert!(
+                derive_key_with_lanes(&[], &[0u8; 8], 1, 31, 4, None, None, &mut dst_out).is_err()
+            );
+            assert!(
+                derive_key_with_lanes(&[], &[0u8; 8], 1, 8, 0, None, None, &mut dst_out).is_err()
+            );
+            assert!(derive_key_with_lanes(
+                &[],
+                &[0u8; 8],
+                1,
+                8,
+                0x0100_0000,
+                None,
+                None,
+                &mut dst_out
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn test_dst_out() {
+            let mut dst_out_less = [0u8; 3];
+            let mut dst_out_exact = [0u8; 4];
+            let mut dst_out_above = [0u8; 5];
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out_less).is_err());
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out_exact).is_ok());
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out_above).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_salt() {
+            let mut dst_out = [0u8; 32];
+            assert!(derive_key(&[], &[0u8; 8], 1, 8, None, None, &mut dst_out).is_ok());
+            assert!(derive_key(&[], &[0u8; 7], 1, 8, None, None, &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_differs_from_argon2i() {
+            let mut dst_one = [0u8; 32];
+            let mut dst_two = [0u8; 32];
+
+            derive_key(&[255u8; 16], &[1u8; 16], 3, 64, None, None, &mut dst_one).unwrap();
+            crate::hazardous::kdf::argon2i::derive_key(
+                &[255u8; 16],
+                &[1u8; 16],
+                3,
+                64,
+                None,
+                None,
+                &mut dst_two,
+            )
+            .unwrap();
+
+            assert_ne!(dst_one, dst_two);
+        }
+
+        // The following test vectors were generated with the Argon2 implementation
+        // in OpenSSL 3.5, using a single lane.
+
+        #[test]
+        fn test_hash_1() {
+            let expected =
+                hex::decode("09316115d5cf24ed5a15a31a3ba326e5cf32edc24702987c02b6566f61913cf7")
+                    .unwrap();
+            let mut actual = vec![0u8; expected.len()];
+            derive_key(b"password", b"somesalt", 2, 65536, None, None, &mut actual).unwrap();
+
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_hash_2() {
+            let expected =
+                hex::decode("9dfeb910e80bad0311fee20f9c0e2b12c17987b4cac90c2ef54d5b3021c68bfe")
+                    .unwrap();
+            let mut actual = vec![0u8; expected.len()];
+            derive_key(b"password", b"somesalt", 2, 256, None, None, &mut actual).unwrap();
+
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_hash_3() {
+            let expected =
+                hex::decode("0b84d652cf6b0c4beaef0dfe278ba6a80df6696281d7e0d2891b817d8c458fde")
+                    .unwrap();
+            let mut actual = vec![0u8; expected.len()];
+            derive_key(
+                b"differentpassword",
+                b"somesalt",
+                2,
+                65536,
+                None,
+                None,
+                &mut actual,
+            )
+            .unwrap();
+
+            assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn test_hash_4() {
+            let expected =
+                hex::decode("b163f1d802ff4ed74c9094ce4196c5715b9ac30d9cdb25559051599da0f7f766e24bc10cb03da440b1bbf584694927597d299d2d05b8d30f6c4478b6afb2f857")
+                    .unwrap();
+            let mut actual = vec![0u8; expected.len()];
+            derive_key(b"password", b"diffsalt", 4, 4096, None, None, &mut actual).unwrap();
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    mod test_derive_key_with_memory {
+        use super::*;
+
+        #[test]
+        fn test_blocks_too_small() {
+            let mut blocks = [[0u64; 128]; 32];
+            let mut dst_out = [0u8; 32];
+            assert!(derive_key_with_memory(
+                &[],
+                &[0u8; 8],
+                1,
+                32,
+                4,
+                None,
+                None,
+                &mut blocks,
+                &mut dst_out
+            )
+            .is_ok());
+            assert!(derive_key_with_memory(
+                &[],
+                &[0u8; 8],
+                1,
+                32,
+                4,
+                None,
+                None,
+                &mut blocks[..31],
+                &mut dst_out
+            )
+            .is_err());
+            // 35 is rounded down to 32, so only 32 blocks are needed.
+            assert!(derive_key_with_memory(
+                &[],
+                &[0u8; 8],
+                1,
+                35,
+                4,
+                None,
+                None,
+                &mut blocks,
+                &mut dst_out
+            )
+            .is_ok());
+            assert!(derive_key_with_memory(
+                &[],
+                &[0u8; 8],
+                1,
+                8,
+                1,
+                None,
+                None,
+                &mut [],
+                &mut dst_out
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn test_blocks_zeroized() {
+            let mut blocks = [[1u64; 128]; 40];
+            let mut dst_out = [0u8; 32];
+            derive_key_with_memory(
+                b"password",
+                &[0u8; 8],
+                1,
+                35,
+                4,
+                None,
+                None,
+                &mut blocks,
+                &mut dst_out,
+            )
+            .unwrap();
+
+            assert!(blocks[..32].iter().all(|block| block == &[0u64; 128]));
+            assert!(blocks[32..].iter().all(|block| block == &[1u64; 128]));
+        }
+
+        #[test]
+        #[cfg(any(feature = "safe_api", feature = "alloc"))]
+        fn test_same_as_derive_key() {
+            let mut blocks = [[0u64; 128]; 64];
+            let mut dst_out = [0u8; 32];
+            let mut dst_out_alloc = [0u8; 32];
+
+            derive_key_with_lanes(
+                b"password",
+                &[0u8; 8],
+                2,
+                64,
+                2,
+                Some(b"secret"),
+                Some(b"ad"),
+                &mut dst_out_alloc,
+            )
+            .unwrap();
+            assert!(verify_with_memory(
+                &dst_out_alloc,
+                b"password",
+                &[0u8; 8],
+                2,
+                64,
+                2,
+                Some(b"secret"),
+                Some(b"ad"),
+                &mut blocks,
+                &mut dst_out
+            )
+            .is_ok());
+        }
+    }
+}
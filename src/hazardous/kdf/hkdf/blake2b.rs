@@ -0,0 +1,346 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! This is HKDF with keyed BLAKE2b-512 used as the PRF, in place of HMAC.
+//! The extract step computes `PRK = BLAKE2b(key = salt, ikm)` and the expand
+//! step computes `T(i) = BLAKE2b(key = PRK, T(i-1) || info || i)`, as
+//! described in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
+//!
+//! This construction is not standardized, so it does not interoperate with
+//! HKDF-HMAC-BLAKE2b or other HKDF variants. Only use it when both parties use
+//! this exact construction.
+//!
+//! # Parameters:
+//! - `salt`: Salt value. If empty, 64 zero bytes are used.
+//! - `ikm`: Input keying material.
+//! - `info`: Optional context and application-specific information.  If `None`
+//!   then it's an empty string.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is implied by the length of `okm_out`.
+//! - `expected`: The expected derived key.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `salt` is greater than 64.
+//! - The length of `dst_out` is less than 1.
+//! - The length of `dst_out` is greater than 255 * 64.
+//! - The derived key does not match the expected when verifying.
+//!
+//! # Security:
+//! - Salts should always be generated using a CSPRNG.
+//!   [`util::secure_rand_bytes()`] can be used for this.
+//! - The recommended length for a salt is 64 bytes.
+//! - Even though a salt value is optional, it is strongly recommended to use
+//!   one.
+//! - HKDF is not suitable for password storage.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{hazardous::kdf::hkdf::blake2b, util};
+//!
+//! let mut salt = [0u8; 64];
+//! util::secure_rand_bytes(&mut salt)?;
+//! let mut okm_out = [0u8; 32];
+//!
+//! blake2b::derive_key(&salt, "IKM".as_bytes(), None, &mut okm_out)?;
+//!
+//! let exp_okm = okm_out;
+//!
+//! assert!(blake2b::verify(&exp_okm, &salt, "IKM".as_bytes(), None, &mut okm_out).is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`util::secure_rand_bytes()`]: ../../../../util/fn.secure_rand_bytes.html
+
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::blake2b::{Blake2b, SecretKey, BLAKE2B_KEYSIZE, BLAKE2B_OUTSIZE},
+    util,
+};
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF extract step. The returned pseudorandom key is a BLAKE2b key.
+pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<SecretKey, UnknownCryptoError> {
+    let salt_key = if salt.is_empty() {
+        SecretKey::from_slice(&[0u8; BLAKE2B_KEYSIZE])?
+    } else {
+        SecretKey::from_slice(salt)?
+    };
+
+    let mut prk = Blake2b::new(Some(&salt_key), BLAKE2B_OUTSIZE)?;
+    prk.update(ikm)?;
+    SecretKey::from_slice(prk.finalize()?.as_ref())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF expand step.
+pub fn expand(
+    prk: &SecretKey,
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if dst_out.len() > 255 * BLAKE2B_OUTSIZE {
+        return Err(UnknownCryptoError);
+    }
+    if dst_out.is_empty() {
+        return Err(UnknownCryptoError);
+    }
+
+    let optional_info = match info {
+        Some(n_val) => n_val,
+        None => &[0u8; 0],
+    };
+
+    let mut prf = Blake2b::new(Some(prk), BLAKE2B_OUTSIZE)?;
+    let okm_len = dst_out.len();
+
+    for (idx, hlen_block) in dst_out.chunks_mut(BLAKE2B_OUTSIZE).enumerate() {
+        let block_len = hlen_block.len();
+
+        prf.update(optional_info)?;
+        prf.update(&[idx as u8 + 1_u8])?;
+        hlen_block.copy_from_slice(&prf.finalize()?.as_ref()[..block_len]);
+
+        // Check if it's the last iteration, if yes don't process anything
+        if block_len < BLAKE2B_OUTSIZE || (block_len * (idx + 1) == okm_len) {
+            break;
+        } else {
+            prf.reset(Some(prk))?;
+            prf.update(hlen_block)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Combine `extract` and `expand` to return a derived key.
+pub fn derive_key(
+    salt: &[u8],
+    ikm: &[u8],
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    expand(&extract(salt, ikm)?, info, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a derived key in constant time.
+pub fn verify(
+    expected: &[u8],
+    salt: &[u8],
+    ikm: &[u8],
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key(salt, ikm, info, dst_out)?;
+    util::secure_cmp(dst_out, expected)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    mod test_extract {
+        use super::*;
+
+        #[test]
+        fn salt_length() {
+            assert!(extract(&[0u8; 0], b"").is_ok());
+            assert!(extract(&[0u8; 1], b"").is_ok());
+            assert!(extract(&[0u8; BLAKE2B_KEYSIZE], b"").is_ok());
+            assert!(extract(&[0u8; BLAKE2B_KEYSIZE + 1], b"").is_err());
+        }
+
+        #[test]
+        fn empty_salt_is_zero_key() {
+            assert_eq!(
+                extract(&[0u8; 0], b"IKM").unwrap(),
+                extract(&[0u8; BLAKE2B_KEYSIZE], b"IKM")
+                    .unwrap()
+                    .unprotected_as_bytes()
+            );
+        }
+    }
+
+    mod test_expand {
+        use super::*;
+
+        #[test]
+        fn hkdf_above_maximum_length_err() {
+            // Max allowed length here is 16320
+            let mut okm_out = [0u8; 16321];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn hkdf_exact_maximum_length_ok() {
+            // Max allowed length here is 16320
+            let mut okm_out = [0u8; 16320];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_ok());
+        }
+
+        #[test]
+        fn hkdf_zero_length_err() {
+            let mut okm_out = [0u8; 0];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn hkdf_info_param() {
+            let mut okm_out = [0u8; 32];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_ok());
+            assert!(expand(&prk, None, &mut okm_out).is_ok());
+        }
+    }
+
+    // These known-answer tests have been generated with Python's hashlib.blake2b.
+    mod test_derive_key {
+        use super::*;
+
+        fn known_answer(salt: &[u8], ikm: &[u8], info: &[u8], prk: &str, okm: &str) {
+            let expected_prk = hex::decode(prk).unwrap();
+            let expected_okm = hex::decode(okm).unwrap();
+            let mut okm_out = [0u8; 255 * BLAKE2B_OUTSIZE];
+            let okm_out = &mut okm_out[..expected_okm.len()];
+
+            assert_eq!(extract(salt, ikm).unwrap(), &expected_prk[..]);
+            derive_key(salt, ikm, Some(info), okm_out).unwrap();
+            assert_eq!(okm_out[..], expected_okm[..]);
+        }
+
+        #[test]
+        fn test_case_1() {
+            let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+            let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+
+            known_answer(
+                &salt,
+                &[0x0bu8; 22],
+                &info,
+                "cfdde0bc0d49c85bed1fd6c8696d1524c7ad7fa1fd3531a242ef8244298b7cf2\
+                 df7142168b43fac141948e0b7a6f498cff7d805d5d8c819b7c06eca8e60f122e",
+                "18eca4e83bda55e1a926e5dfd8869bb9f02f5de7772796f7e38350921f6cff0b\
+                 7d160f6812cb1fde8a64",
+            );
+        }
+
+        #[test]
+        fn test_case_2() {
+            let salt = hex::decode(
+                "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f\
+                 808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            )
+            .unwrap();
+            let ikm = hex::decode(
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+                 202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f\
+                 404142434445464748494a4b4c4d4e4f",
+            )
+            .unwrap();
+            let info = hex::decode(
+                "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf\
+                 d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef\
+                 f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+            )
+            .unwrap();
+
+            known_answer(
+                &salt,
+                &ikm,
+                &info,
+                "4c601a70088b9bdc87a2b8b0a44e41b5d512d06cd782688263d9aa219ee48f7e\
+                 6bcf796ba242b9c168ef3920d0246ba236720489b3f29526f924ab80d02fc5c8",
+                "ee497dd4b79ca8d9fc66e8a8c51dfad6cc397bc9a8f35bf146b8b79a45cd9dfc\
+                 ea870b206e0f5191f8f262f0d96d8ce457ea9294dc62c7400063d4d50699687c\
+                 4aa0d37fe8c432a7dc19b9079e6d2d5d561ef5edc667aac11988fb549741efe9\
+                 0858521aafca61fdd15b3c3ad0072fa02fb42bd7299144b08888224e3615e836\
+                 7d24",
+            );
+        }
+
+        #[test]
+        fn test_case_3() {
+            known_answer(
+                b"",
+                &[0x0bu8; 22],
+                b"",
+                "312852be4511209c77dfe98dcc3773d5b2de9cc8020ab3da65600c2b93cf3182\
+                 da95f35a7941a8f8ec6500f81ba66c07249e68af9e4e1aebf31815a67a9e30d3",
+                "0aad709babc52bc04596ee1d80741a999a0828c95c38faeda79c984961d6b5ec\
+                 50805e48bc7065d24f2f",
+            );
+        }
+    }
+
+    mod test_verify {
+        use super::*;
+
+        #[test]
+        fn hkdf_verify_true() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 42];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, salt, ikm, Some(info), &mut okm_out_verify).is_ok());
+        }
+
+        #[test]
+        fn hkdf_verify_wrong_salt() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 42];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, b"", ikm, Some(info), &mut okm_out_verify).is_err());
+        }
+
+        #[test]
+        fn verify_diff_length() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 43];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, salt, ikm, Some(info), &mut okm_out_verify).is_err());
+        }
+    }
+}
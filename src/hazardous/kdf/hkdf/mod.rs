@@ -43,6 +43,11 @@
 //!   one.
 //! - HKDF is not suitable for password storage.
 //!
+//! # Note:
+//! HKDF-HMAC-SHA256 and HKDF-HMAC-SHA384 are available in the [`sha256`]
+//! and [`sha384`] submodules. HKDF with keyed BLAKE2b as the PRF is available
+//! in the [`blake2b`] submodule.
+//!
 //! # Example:
 //! ```rust
 //! use orion::{hazardous::kdf::hkdf, util};
@@ -60,32 +65,37 @@
 //! ```
 //! [`util::secure_rand_bytes()`]: ../../../util/fn.secure_rand_bytes.html
 //! [`SHA512_OUTSIZE`]: ../../hash/sha512/constant.SHA512_OUTSIZE.html
+//! [`blake2b`]: blake2b/index.html
+//! [`sha256`]: sha256/index.html
+//! [`sha384`]: sha384/index.html
 
 use crate::{
     errors::UnknownCryptoError,
     hazardous::{
-        hash::sha512::SHA512_OUTSIZE,
-        mac::hmac::{self, SecretKey},
+        hash::sha512::{Sha512, SHA512_OUTSIZE},
+        mac::hmac::{self, HmacGeneric, HmacHashFunction, SecretKey},
     },
     util,
 };
+use zeroize::Zeroize;
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// The HKDF extract step.
-pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<hmac::Tag, UnknownCryptoError> {
-    let mut prk = hmac::Hmac::new(&SecretKey::from_slice(salt)?);
-    prk.update(ikm)?;
-    prk.finalize()
-}
+/// HKDF using keyed BLAKE2b as the PRF, in place of HMAC.
+pub mod blake2b;
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// The HKDF expand step.
-pub fn expand(
-    prk: &hmac::Tag,
+/// HKDF-HMAC-SHA256 (HMAC-based Extract-and-Expand Key Derivation Function) as specified in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
+pub mod sha256;
+
+/// HKDF-HMAC-SHA384 (HMAC-based Extract-and-Expand Key Derivation Function) as specified in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
+pub mod sha384;
+
+/// The HKDF expand step using HMAC with the hash function `H`. `padded_prk`
+/// must already be padded to the blocksize of `H`.
+pub(crate) fn expand_generic<H: HmacHashFunction>(
+    padded_prk: &[u8],
     info: Option<&[u8]>,
     dst_out: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
-    if dst_out.len() > 255 * SHA512_OUTSIZE {
+    if dst_out.len() > 255 * H::OUTSIZE {
         return Err(UnknownCryptoError);
     }
     if dst_out.is_empty() {
@@ -97,28 +107,55 @@ pub fn expand(
         None => &[0u8; 0],
     };
 
-    let mut hmac = hmac::Hmac::new(&hmac::SecretKey::from_slice(&prk.unprotected_as_bytes())?);
+    let mut hmac = HmacGeneric::<H>::new(padded_prk);
+    let mut tag = [0u8; SHA512_OUTSIZE];
+    let tag = &mut tag[..H::OUTSIZE];
     let okm_len = dst_out.len();
 
-    for (idx, hlen_block) in dst_out.chunks_mut(SHA512_OUTSIZE).enumerate() {
+    for (idx, hlen_block) in dst_out.chunks_mut(H::OUTSIZE).enumerate() {
         let block_len = hlen_block.len();
 
         hmac.update(optional_info)?;
         hmac.update(&[idx as u8 + 1_u8])?;
-        hlen_block.copy_from_slice(&hmac.finalize()?.unprotected_as_bytes()[..block_len]);
+        hmac.finalize_into(tag)?;
+        hlen_block.copy_from_slice(&tag[..block_len]);
 
         // Check if it's the last iteration, if yes don't process anything
-        if block_len < SHA512_OUTSIZE || (block_len * (idx + 1) == okm_len) {
+        if block_len < H::OUTSIZE || (block_len * (idx + 1) == okm_len) {
             break;
         } else {
             hmac.reset();
-            hmac.update(&hlen_block)?;
+            hmac.update(hlen_block)?;
         }
     }
 
+    tag.zeroize();
+
     Ok(())
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF extract step.
+pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<hmac::Tag, UnknownCryptoError> {
+    let mut prk = hmac::Hmac::new(&SecretKey::from_slice(salt)?);
+    prk.update(ikm)?;
+    prk.finalize()
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF expand step.
+pub fn expand(
+    prk: &hmac::Tag,
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    expand_generic::<Sha512>(
+        SecretKey::from_slice(prk.unprotected_as_bytes())?.unprotected_as_bytes(),
+        info,
+        dst_out,
+    )
+}
+
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Combine `extract` and `expand` to return a derived key.
 pub fn derive_key(
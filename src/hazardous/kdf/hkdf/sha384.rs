@@ -0,0 +1,221 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `salt`: Salt value.
+//! - `ikm`: Input keying material.
+//! - `info`: Optional context and application-specific information.  If `None`
+//!   then it's an empty string.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is implied by the length of `okm_out`.
+//! - `expected`: The expected derived key.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than 1.
+//! - The length of `dst_out` is greater than 255 * [`SHA384_OUTSIZE`].
+//! - The derived key does not match the expected when verifying.
+//!
+//! # Security:
+//! - Salts should always be generated using a CSPRNG.
+//!   [`util::secure_rand_bytes()`] can be used for this.
+//! - The recommended length for a salt is 48 bytes.
+//! - Even though a salt value is optional, it is strongly recommended to use
+//!   one.
+//! - HKDF is not suitable for password storage.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{hazardous::kdf::hkdf::sha384, util};
+//!
+//! let mut salt = [0u8; 48];
+//! util::secure_rand_bytes(&mut salt)?;
+//! let mut okm_out = [0u8; 32];
+//!
+//! sha384::derive_key(&salt, "IKM".as_bytes(), None, &mut okm_out)?;
+//!
+//! let exp_okm = okm_out;
+//!
+//! assert!(sha384::verify(&exp_okm, &salt, "IKM".as_bytes(), None, &mut okm_out).is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`util::secure_rand_bytes()`]: ../../../../util/fn.secure_rand_bytes.html
+//! [`SHA384_OUTSIZE`]: ../../../hash/sha384/constant.SHA384_OUTSIZE.html
+
+use super::expand_generic;
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::{
+        hash::sha384::Sha384,
+        mac::hmac::sha384::{HmacSha384, SecretKey, Tag},
+    },
+    util,
+};
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF extract step.
+pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<Tag, UnknownCryptoError> {
+    let mut prk = HmacSha384::new(&SecretKey::from_slice(salt)?);
+    prk.update(ikm)?;
+    prk.finalize()
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The HKDF expand step.
+pub fn expand(
+    prk: &Tag,
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    expand_generic::<Sha384>(
+        SecretKey::from_slice(prk.unprotected_as_bytes())?.unprotected_as_bytes(),
+        info,
+        dst_out,
+    )
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Combine `extract` and `expand` to return a derived key.
+pub fn derive_key(
+    salt: &[u8],
+    ikm: &[u8],
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    expand(&extract(salt, ikm)?, info, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a derived key in constant time.
+pub fn verify(
+    expected: &[u8],
+    salt: &[u8],
+    ikm: &[u8],
+    info: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key(salt, ikm, info, dst_out)?;
+    util::secure_cmp(dst_out, expected)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    mod test_expand {
+        use super::*;
+
+        #[test]
+        fn hkdf_above_maximum_length_err() {
+            // Max allowed length here is 12240
+            let mut okm_out = [0u8; 12241];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn hkdf_exact_maximum_length_ok() {
+            // Max allowed length here is 12240
+            let mut okm_out = [0u8; 12240];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_ok());
+        }
+
+        #[test]
+        fn hkdf_zero_length_err() {
+            let mut okm_out = [0u8; 0];
+            let prk = extract("".as_bytes(), "".as_bytes()).unwrap();
+
+            assert!(expand(&prk, Some(b""), &mut okm_out).is_err());
+        }
+    }
+
+    mod test_derive_key {
+        use super::*;
+
+        #[test]
+        fn known_answer() {
+            let ikm = [0x0bu8; 22];
+            let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+            let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+            let expected_prk = hex::decode(
+                "704b39990779ce1dc548052c7dc39f303570dd13fb39f7acc564680bef80e8dec70ee9a7e1f3e293ef68eceb072a5ade",
+            )
+            .unwrap();
+            let expected_okm = hex::decode(
+                "9b5097a86038b805309076a44b3a9f38063e25b516dcbf369f394cfab43685f748b6457763e4f0204fc5",
+            )
+            .unwrap();
+            let mut okm_out = [0u8; 42];
+
+            assert_eq!(extract(&salt, &ikm).unwrap(), &expected_prk[..]);
+            derive_key(&salt, &ikm, Some(&info), &mut okm_out).unwrap();
+            assert_eq!(okm_out[..], expected_okm[..]);
+        }
+    }
+
+    mod test_verify {
+        use super::*;
+
+        #[test]
+        fn hkdf_verify_true() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 42];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, salt, ikm, Some(info), &mut okm_out_verify).is_ok());
+        }
+
+        #[test]
+        fn hkdf_verify_wrong_ikm() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 42];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, salt, b"", Some(info), &mut okm_out_verify).is_err());
+        }
+
+        #[test]
+        fn verify_diff_length() {
+            let ikm = b"0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+            let salt = b"000102030405060708090a0b0c";
+            let info = b"f0f1f2f3f4f5f6f7f8f9";
+            let mut okm_out = [0u8; 42];
+            let mut okm_out_verify = [0u8; 43];
+
+            derive_key(salt, ikm, Some(info), &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, salt, ikm, Some(info), &mut okm_out_verify).is_err());
+        }
+    }
+}
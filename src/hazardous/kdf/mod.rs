@@ -20,12 +20,26 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-/// HKDF-HMAC-SHA512 (HMAC-based Extract-and-Expand Key Derivation Function) as specified in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
+/// HKDF-HMAC-SHA512, HKDF-HMAC-SHA384, HKDF-HMAC-SHA256 and HKDF-BLAKE2b (HMAC-based Extract-and-Expand Key Derivation Function) as specified in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
 pub mod hkdf;
 
-/// PBKDF2-HMAC-SHA512 (Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
+/// PBKDF2-HMAC-SHA512, PBKDF2-HMAC-SHA384, PBKDF2-HMAC-SHA256 and PBKDF2-HMAC-SHA1 (Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
 pub mod pbkdf2;
 
-#[cfg(any(feature = "safe_api", feature = "alloc"))]
+pub(crate) mod argon2;
+
 /// Argon2i password hashing function as described in the [P-H-C specification](https://github.com/P-H-C/phc-winner-argon2/blob/master/argon2-specs.pdf).
 pub mod argon2i;
+
+/// Argon2id password hashing function as described in the [RFC 9106](https://tools.ietf.org/html/rfc9106).
+pub mod argon2id;
+
+/// Argon2d password hashing function as described in the [RFC 9106](https://tools.ietf.org/html/rfc9106).
+pub mod argon2d;
+
+/// bcrypt password hashing function as described in [A Future-Adaptable Password Scheme](https://www.usenix.org/legacy/events/usenix99/provos/provos.pdf).
+pub mod bcrypt;
+
+#[cfg(any(feature = "safe_api", feature = "alloc"))]
+/// scrypt password-based key derivation function as specified in the [RFC 7914](https://tools.ietf.org/html/rfc7914).
+pub mod scrypt;
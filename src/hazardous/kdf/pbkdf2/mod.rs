@@ -46,6 +46,11 @@
 //! - The iteration count should be set as high as feasible. The recommended
 //!   minimum is 100000.
 //!
+//! # Note:
+//! PBKDF2-HMAC-SHA1, PBKDF2-HMAC-SHA256 and PBKDF2-HMAC-SHA384 are available
+//! in the [`sha1`], [`sha256`] and [`sha384`] submodules, for interoperability
+//! with existing systems.
+//!
 //! # Example:
 //! ```rust
 //! use orion::{hazardous::kdf::pbkdf2, util};
@@ -63,16 +68,29 @@
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`Password::generate()`]: struct.Password.html#method.generate
+//! [`sha1`]: sha1/index.html
+//! [`sha256`]: sha256/index.html
+//! [`sha384`]: sha384/index.html
 //! [`util::secure_rand_bytes()`]: ../../../util/fn.secure_rand_bytes.html
 
 use crate::{
     errors::UnknownCryptoError,
     hazardous::{
-        hash::sha512::{SHA512_BLOCKSIZE, SHA512_OUTSIZE},
-        mac::hmac,
+        hash::sha512::{Sha512, SHA512_BLOCKSIZE, SHA512_OUTSIZE},
+        mac::hmac::{HmacGeneric, HmacHashFunction},
     },
     util,
 };
+use zeroize::Zeroize;
+
+/// PBKDF2-HMAC-SHA1 (Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
+pub mod sha1;
+
+/// PBKDF2-HMAC-SHA256 (Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
+pub mod sha256;
+
+/// PBKDF2-HMAC-SHA384 (Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
+pub mod sha384;
 
 construct_hmac_key! {
     /// A type to represent the `Password` that PBKDF2 hashes.
@@ -88,41 +106,43 @@ construct_hmac_key! {
     /// # Panics:
     /// A panic will occur if:
     /// - Failure to generate random bytes securely.
-    (Password, test_pbkdf2_password, SHA512_BLOCKSIZE)
+    (Password, test_pbkdf2_password, Sha512, SHA512_BLOCKSIZE)
 }
 
 /// The F function as described in the RFC.
-fn function_f(
+fn function_f<H: HmacHashFunction>(
     salt: &[u8],
     iterations: usize,
     index: u32,
     dk_block: &mut [u8],
-    block_len: usize,
-    hmac: &mut hmac::Hmac,
+    hmac: &mut HmacGeneric<H>,
 ) -> Result<(), UnknownCryptoError> {
+    let mut u_step = [0u8; SHA512_OUTSIZE];
+    let u_step = &mut u_step[..H::OUTSIZE];
+
     hmac.update(salt)?;
     hmac.update(&index.to_be_bytes())?;
-
-    let mut u_step = hmac.finalize()?;
-    dk_block.copy_from_slice(&u_step.unprotected_as_bytes()[..block_len]);
+    hmac.finalize_into(u_step)?;
+    dk_block.copy_from_slice(&u_step[..dk_block.len()]);
 
     if iterations > 1 {
         for _ in 1..iterations {
             hmac.reset();
-            hmac.update(u_step.unprotected_as_bytes())?;
-            u_step = hmac.finalize()?;
-            xor_slices!(u_step.unprotected_as_bytes(), dk_block);
+            hmac.update(u_step)?;
+            hmac.finalize_into(u_step)?;
+            xor_slices!(u_step, dk_block);
         }
     }
 
+    u_step.zeroize();
+
     Ok(())
 }
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// PBKDF2-SHA512 (Password-Based Key Derivation Function 2) as specified in the
-/// [RFC 8018](https://tools.ietf.org/html/rfc8018).
-pub fn derive_key(
-    password: &Password,
+/// PBKDF2 using HMAC with the hash function `H`. `padded_password` must
+/// already be padded to the blocksize of `H`.
+pub(crate) fn derive_key_generic<H: HmacHashFunction>(
+    padded_password: &[u8],
     salt: &[u8],
     iterations: usize,
     dst_out: &mut [u8],
@@ -134,28 +154,31 @@ pub fn derive_key(
         return Err(UnknownCryptoError);
     }
 
-    let mut hmac = hmac::Hmac::new(&hmac::SecretKey::from_slice(
-        &password.unprotected_as_bytes(),
-    )?);
+    let mut hmac = HmacGeneric::<H>::new(padded_password);
 
-    for (idx, dk_block) in dst_out.chunks_mut(SHA512_OUTSIZE).enumerate() {
+    for (idx, dk_block) in dst_out.chunks_mut(H::OUTSIZE).enumerate() {
         // If this panics, then the size limit for PBKDF2 is reached.
         let block_idx = (1u32).checked_add(idx as u32).unwrap();
 
-        function_f(
-            salt,
-            iterations,
-            block_idx,
-            dk_block,
-            dk_block.len(),
-            &mut hmac,
-        )?;
+        function_f(salt, iterations, block_idx, dk_block, &mut hmac)?;
         hmac.reset();
     }
 
     Ok(())
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// PBKDF2-SHA512 (Password-Based Key Derivation Function 2) as specified in the
+/// [RFC 8018](https://tools.ietf.org/html/rfc8018).
+pub fn derive_key(
+    password: &Password,
+    salt: &[u8],
+    iterations: usize,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key_generic::<Sha512>(password.unprotected_as_bytes(), salt, iterations, dst_out)
+}
+
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Verify PBKDF2-HMAC-SHA512 derived key in constant time.
 pub fn verify(
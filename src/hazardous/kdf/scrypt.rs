@@ -0,0 +1,426 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! # Parameters:
+//! - `password`: Password.
+//! - `salt`: Salt value.
+//! - `log_n`: Base-2 logarithm of the CPU/memory cost parameter (N).
+//! - `r`: Block size parameter.
+//! - `p`: Parallelization parameter.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is implied by the length of `dst_out`.
+//! - `expected`: The expected derived key.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than 1.
+//! - `r` or `p` is less than 1.
+//! - `r * p` is greater than or equal to `2^30`.
+//! - `log_n` is less than 1 or greater than or equal to `16 * r`.
+//! - The memory needed, `128 * r * 2^log_n` bytes, cannot be addressed on the
+//!   platform.
+//! - The hashed password does not match the expected when verifying.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - The length of `dst_out` is greater than (2^32 - 1) * 32.
+//!
+//! # Security:
+//! - Salts should always be generated using a CSPRNG.
+//!   [`util::secure_rand_bytes()`] can be used for this.
+//! - The minimum recommended length for a salt is `16` bytes.
+//! - The memory used for hashing is `128 * r * 2^log_n` bytes. The parameters
+//!   should be set as high as feasible. The recommended minimum is
+//!   `log_n = 15`, `r = 8` and `p = 1`, which uses 32 MiB.
+//! - Password hashes should always be compared in constant-time.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{hazardous::kdf::scrypt, util};
+//!
+//! let mut salt = [0u8; 16];
+//! util::secure_rand_bytes(&mut salt)?;
+//! let password = b"Secret password";
+//! let mut dst_out = [0u8; 64];
+//!
+//! scrypt::derive_key(password, &salt, 15, 8, 1, &mut dst_out)?;
+//!
+//! let expected_dk = dst_out;
+//!
+//! assert!(scrypt::verify(&expected_dk, password, &salt, 15, 8, 1, &mut dst_out).is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`util::secure_rand_bytes()`]: ../../../util/fn.secure_rand_bytes.html
+
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::{kdf::pbkdf2, stream::salsa20::salsa20_rounds},
+    util::{
+        self,
+        endianness::{load_u32_into_le, store_u32_into_le},
+    },
+};
+use zeroize::Zeroize;
+
+/// The number of 32-bit words in a Salsa20 block.
+const SALSA_WORDS: usize = 16;
+
+/// PBKDF2-HMAC-SHA256 with a single iteration, as used by scrypt.
+fn pbkdf2_sha256(
+    password: &[u8],
+    salt: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let password = pbkdf2::sha256::Password::from_slice(password)?;
+    pbkdf2::sha256::derive_key(&password, salt, 1, dst_out)
+}
+
+/// The Salsa20/8 core function as described in the RFC.
+fn salsa20_8(block: &mut [u32; SALSA_WORDS]) {
+    let mut x = *block;
+    salsa20_rounds(&mut x, 8);
+
+    for (out, word) in block.iter_mut().zip(x.iter()) {
+        *out = out.wrapping_add(*word);
+    }
+
+    x.zeroize();
+}
+
+/// The scryptBlockMix function as described in the RFC. `src` and `dst`
+/// both hold `2 * r` Salsa20 blocks.
+fn block_mix(src: &[u32], dst: &mut [u32]) {
+    debug_assert_eq!(src.len(), dst.len());
+    debug_assert_eq!(src.len() % (2 * SALSA_WORDS), 0);
+    let r = src.len() / (2 * SALSA_WORDS);
+
+    let mut x = [0u32; SALSA_WORDS];
+    x.copy_from_slice(&src[src.len() - SALSA_WORDS..]);
+
+    for (idx, block) in src.chunks_exact(SALSA_WORDS).enumerate() {
+        xor_slices!(block, x);
+        salsa20_8(&mut x);
+        // Even blocks go to the first half of the output, odd blocks to the second.
+        let pos = ((idx / 2) + (idx % 2) * r) * SALSA_WORDS;
+        dst[pos..pos + SALSA_WORDS].copy_from_slice(&x);
+    }
+
+    x.zeroize();
+}
+
+/// The Integerify function as described in the RFC, reduced modulo `n`.
+fn integerify(x: &[u32], n: usize) -> usize {
+    let last = x.len() - SALSA_WORDS;
+    let j = u64::from(x[last]) | (u64::from(x[last + 1]) << 32);
+    // n is a power of two and fits in a usize, so this does not truncate.
+    (j & (n as u64 - 1)) as usize
+}
+
+/// The scryptROMix function as described in the RFC. `x` holds one block of
+/// `128 * r` bytes, `v` holds `n` such blocks and `tmp` holds one.
+fn ro_mix(x: &mut [u32], v: &mut [u32], tmp: &mut [u32], n: usize) {
+    let block_words = x.len();
+    debug_assert_eq!(v.len(), block_words * n);
+
+    for v_i in v.chunks_exact_mut(block_words) {
+        v_i.copy_from_slice(x);
+        block_mix(v_i, x);
+    }
+
+    for _ in 0..n {
+        let j = integerify(x, n);
+        xor_slices!(v[j * block_words..(j + 1) * block_words], x);
+        block_mix(x, tmp);
+        x.copy_from_slice(tmp);
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// The scrypt password-based key derivation function as specified in the
+/// [RFC 7914](https://tools.ietf.org/html/rfc7914).
+pub fn derive_key(
+    password: &[u8],
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if dst_out.is_empty() {
+        return Err(UnknownCryptoError);
+    }
+    if r < 1 || p < 1 || u64::from(r) * u64::from(p) >= 1 << 30 {
+        return Err(UnknownCryptoError);
+    }
+    if log_n < 1 || u64::from(log_n) >= 16 * u64::from(r) {
+        return Err(UnknownCryptoError);
+    }
+
+    let n = 1usize
+        .checked_shl(u32::from(log_n))
+        .ok_or(UnknownCryptoError)?;
+    let block_words = (r as usize)
+        .checked_mul(2 * SALSA_WORDS)
+        .ok_or(UnknownCryptoError)?;
+    let v_words = block_words.checked_mul(n).ok_or(UnknownCryptoError)?;
+    let b_len = block_words
+        .checked_mul(4 * p as usize)
+        .ok_or(UnknownCryptoError)?;
+    // The memory must be addressable in bytes.
+    v_words.checked_mul(4).ok_or(UnknownCryptoError)?;
+
+    let mut b = vec![0u8; b_len];
+    pbkdf2_sha256(password, salt, &mut b)?;
+
+    let mut x = vec![0u32; block_words];
+    let mut tmp = vec![0u32; block_words];
+    let mut v = vec![0u32; v_words];
+
+    for b_i in b.chunks_exact_mut(block_words * 4) {
+        load_u32_into_le(b_i, &mut x);
+        ro_mix(&mut x, &mut v, &mut tmp, n);
+        store_u32_into_le(&x, b_i);
+    }
+
+    pbkdf2_sha256(password, &b, dst_out)?;
+
+    b.zeroize();
+    x.zeroize();
+    tmp.zeroize();
+    v.zeroize();
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify scrypt derived key in constant time.
+pub fn verify(
+    expected: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key(password, salt, log_n, r, p, dst_out)?;
+    util::secure_cmp(dst_out, expected)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    mod test_verify {
+        use super::*;
+
+        #[test]
+        fn verify_true() {
+            let password = b"pass\0word";
+            let salt = b"sa\0lt";
+            let mut okm_out = [0u8; 16];
+            let mut okm_out_verify = [0u8; 16];
+
+            derive_key(password, salt, 4, 1, 1, &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, password, salt, 4, 1, 1, &mut okm_out_verify).is_ok());
+        }
+
+        #[test]
+        fn verify_false_wrong_salt() {
+            let password = b"pass\0word";
+            let salt = b"sa\0lt";
+            let mut okm_out = [0u8; 16];
+            let mut okm_out_verify = [0u8; 16];
+
+            derive_key(password, salt, 4, 1, 1, &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, password, b"", 4, 1, 1, &mut okm_out_verify).is_err());
+        }
+
+        #[test]
+        fn verify_false_wrong_password() {
+            let password = b"pass\0word";
+            let salt = b"sa\0lt";
+            let mut okm_out = [0u8; 16];
+            let mut okm_out_verify = [0u8; 16];
+
+            derive_key(password, salt, 4, 1, 1, &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, b"", salt, 4, 1, 1, &mut okm_out_verify).is_err());
+        }
+
+        #[test]
+        fn verify_diff_dklen_error() {
+            let password = b"pass\0word";
+            let salt = b"sa\0lt";
+            let mut okm_out = [0u8; 16];
+            let mut okm_out_verify = [0u8; 32];
+
+            derive_key(password, salt, 4, 1, 1, &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, password, salt, 4, 1, 1, &mut okm_out_verify).is_err());
+        }
+
+        #[test]
+        fn verify_diff_params_error() {
+            let password = b"pass\0word";
+            let salt = b"sa\0lt";
+            let mut okm_out = [0u8; 16];
+            let mut okm_out_verify = [0u8; 16];
+
+            derive_key(password, salt, 4, 1, 1, &mut okm_out).unwrap();
+
+            assert!(verify(&okm_out, password, salt, 5, 1, 1, &mut okm_out_verify).is_err());
+            assert!(verify(&okm_out, password, salt, 4, 2, 1, &mut okm_out_verify).is_err());
+            assert!(verify(&okm_out, password, salt, 4, 1, 2, &mut okm_out_verify).is_err());
+        }
+    }
+
+    mod test_derive_key {
+        use super::*;
+        use crate::hazardous::hash::sha256::{Sha256, SHA256_BLOCKSIZE};
+
+        #[test]
+        fn zero_dklen_err() {
+            let mut okm_out = [0u8; 0];
+
+            assert!(derive_key(b"password", b"salt", 4, 1, 1, &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn zero_r_or_p_err() {
+            let mut okm_out = [0u8; 32];
+
+            assert!(derive_key(b"password", b"salt", 4, 0, 1, &mut okm_out).is_err());
+            assert!(derive_key(b"password", b"salt", 4, 1, 0, &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn r_times_p_too_large_err() {
+            let mut okm_out = [0u8; 32];
+
+            assert!(derive_key(b"password", b"salt", 1, 1 << 15, 1 << 15, &mut okm_out).is_err());
+            assert!(derive_key(b"password", b"salt", 1, 1, 1 << 30, &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn log_n_bounds() {
+            let mut okm_out = [0u8; 32];
+
+            assert!(derive_key(b"password", b"salt", 0, 1, 1, &mut okm_out).is_err());
+            assert!(derive_key(b"password", b"salt", 1, 1, 1, &mut okm_out).is_ok());
+            // log_n must be less than 16 * r.
+            assert!(derive_key(b"password", b"salt", 16, 1, 1, &mut okm_out).is_err());
+            assert!(derive_key(b"password", b"salt", 64, 4, 1, &mut okm_out).is_err());
+        }
+
+        #[test]
+        fn long_password_is_hashed() {
+            // Passwords longer than the SHA256 blocksize are hashed before
+            // being used as the HMAC key.
+            let password = [1u8; SHA256_BLOCKSIZE + 1];
+            let hashed = Sha256::digest(&password).unwrap();
+            let mut okm_out = [0u8; 32];
+            let mut okm_out_hashed = [0u8; 32];
+
+            derive_key(&password, b"salt", 4, 1, 1, &mut okm_out).unwrap();
+            derive_key(hashed.as_ref(), b"salt", 4, 1, 1, &mut okm_out_hashed).unwrap();
+
+            assert_eq!(okm_out, okm_out_hashed);
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    fn decode_words(input: &str, dst: &mut [u32]) {
+        load_u32_into_le(&hex::decode(input).unwrap(), dst);
+    }
+
+    // Test vectors from RFC 7914, sections 8, 9 and 11.
+    const SALSA_IN: &str = "7e879a214f3ec9867ca940e641718f26baee555b8c61c1b50df846116dcd3b1d\
+                            ee24f319df9b3d8514121e4b5ac5aa3276021d2909c74829edebc68db8b8c25e";
+    const SALSA_OUT: &str = "a41f859c6608cc993b81cacb020cef05044b2181a2fd337dfd7b1c6396682f29\
+                             b4393168e3c9e6bcfe6bc5b7a06d96bae424cc102c91745c24ad673dc7618f81";
+
+    #[test]
+    fn test_salsa20_8() {
+        let mut block = [0u32; SALSA_WORDS];
+        let mut expected = [0u32; SALSA_WORDS];
+        decode_words(SALSA_IN, &mut block);
+        decode_words(SALSA_OUT, &mut expected);
+        salsa20_8(&mut block);
+
+        assert_eq!(block, expected);
+    }
+
+    #[test]
+    fn test_block_mix() {
+        let mut input = [0u32; 2 * SALSA_WORDS];
+        let mut expected = [0u32; 2 * SALSA_WORDS];
+        decode_words(
+            "f7ce0b653d2d72a4108cf5abe912ffdd777616dbbb27a70e8204f3ae2d0f6fad\
+             89f68f4811d1e87bcc3bd7400a9ffd29094f0184639574f39ae5a1315217bcd7\
+             894991447213bb226c25b54da86370fbcd984380374666bb8ffcb5bf40c254b0\
+             67d27c51ce4ad5fed829c90b505a571b7f4d1cad6a523cda770e67bceaaf7e89",
+            &mut input,
+        );
+        decode_words(
+            "a41f859c6608cc993b81cacb020cef05044b2181a2fd337dfd7b1c6396682f29\
+             b4393168e3c9e6bcfe6bc5b7a06d96bae424cc102c91745c24ad673dc7618f81\
+             20edc975323881a80540f64c162dcd3c21077cfe5f8d5fe2b1a4168f953678b7\
+             7d3b3d803b60e4ab920996e59b4d53b65d2a225877d5edf5842cb9f14eefe425",
+            &mut expected,
+        );
+        let mut actual = [0u32; 2 * SALSA_WORDS];
+        block_mix(&input, &mut actual);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pbkdf2_sha256() {
+        let expected = hex::decode(
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783",
+        )
+        .unwrap();
+        let mut actual = [0u8; 64];
+        pbkdf2_sha256(b"passwd", b"salt", &mut actual).unwrap();
+
+        assert_eq!(actual[..], expected[..]);
+    }
+
+    #[test]
+    fn test_integerify() {
+        let mut block = [0u32; 2 * SALSA_WORDS];
+        block[SALSA_WORDS] = 0x0000_0013;
+        block[SALSA_WORDS + 1] = 0x0000_0001;
+
+        assert_eq!(integerify(&block, 16), 3);
+        assert_eq!(integerify(&block, 1 << 5), 19);
+    }
+}
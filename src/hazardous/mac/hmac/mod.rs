@@ -0,0 +1,669 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`:  The authentication key.
+//! - `data`: Data to be authenticated.
+//! - `expected`: The expected authentication tag.
+//! - `dst_out`: Destination buffer for the exported state.
+//! - `exported`: A state exported with [`export_state()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - The HMAC does not match the expected when verifying.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` or `exported` is not [`HMAC_EXPORTSIZE`] bytes.
+//! - `exported` was not exported by an `Hmac` with the same version of the
+//!   encoding, or is otherwise invalid.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 128 bytes.
+//! - The minimum recommended size for a secret key is 64 bytes.
+//! - An exported state contains the hash states keyed with the secret key, from
+//!   which tags can be computed for any data. It should be protected like the
+//!   secret key.
+//!
+//! # Recommendation:
+//! - If you are unsure of whether to use HMAC or Poly1305, it is most often
+//!   easier to just use HMAC. See also [Cryptographic Right Answers].
+//! - HMAC-SHA512 is provided by this module directly. HMAC-SHA256 and
+//!   HMAC-SHA384 are provided by the [`sha256`] and [`sha384`] submodules,
+//!   which should be used when a protocol mandates them.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::mac::hmac::{Hmac, SecretKey};
+//!
+//! let key = SecretKey::generate();
+//!
+//! let mut state = Hmac::new(&key);
+//! state.update(b"Some message.")?;
+//! let tag = state.finalize()?;
+//!
+//! assert!(Hmac::verify(&tag, &key, b"Some message.").is_ok());
+//!
+//! // Exporting the state to continue authenticating later.
+//! use orion::hazardous::mac::hmac::HMAC_EXPORTSIZE;
+//!
+//! let mut state = Hmac::new(&key);
+//! state.update(b"Some ")?;
+//! let mut exported = [0u8; HMAC_EXPORTSIZE];
+//! state.export_state(&mut exported)?;
+//!
+//! let mut imported = Hmac::import_state(&exported)?;
+//! imported.update(b"message.")?;
+//! assert_eq!(imported.finalize()?, tag);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Hmac.html
+//! [`reset()`]: struct.Hmac.html
+//! [`finalize()`]: struct.Hmac.html
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+//! [`export_state()`]: struct.Hmac.html
+//! [`HMAC_EXPORTSIZE`]: constant.HMAC_EXPORTSIZE.html
+//! [`sha256`]: sha256/index.html
+//! [`sha384`]: sha384/index.html
+//! [Cryptographic Right Answers]: https://latacora.micro.blog/2018/04/03/cryptographic-right-answers.html
+
+use crate::hazardous::traits::Mac;
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::{
+        sha1::{Sha1, SHA1_BLOCKSIZE, SHA1_OUTSIZE},
+        sha256::{Sha256, SHA256_BLOCKSIZE, SHA256_OUTSIZE},
+        sha384::{Sha384, SHA384_BLOCKSIZE, SHA384_OUTSIZE},
+        sha512::{Sha512, SHA512_BLOCKSIZE, SHA512_FIELDSIZE, SHA512_OUTSIZE},
+    },
+    util::state_export::{StateReader, StateWriter, STATE_EXPORT_HEADERSIZE, STATE_ID_HMAC_SHA512},
+};
+use zeroize::Zeroize;
+
+/// HMAC-SHA256 (Hash-based Message Authentication Code) as specified in the [RFC 2104](https://tools.ietf.org/html/rfc2104).
+pub mod sha256;
+
+/// HMAC-SHA384 (Hash-based Message Authentication Code) as specified in the [RFC 2104](https://tools.ietf.org/html/rfc2104).
+pub mod sha384;
+
+/// The size of an exported `Hmac` state, which holds three SHA512 states.
+pub const HMAC_EXPORTSIZE: usize = STATE_EXPORT_HEADERSIZE + 3 * SHA512_FIELDSIZE;
+
+construct_hmac_key! {
+    /// A type to represent the `SecretKey` that HMAC uses for authentication.
+    ///
+    /// # Note:
+    /// `SecretKey` pads the secret key for use with HMAC to a length of 128, when initialized.
+    ///
+    /// Using `unprotected_as_bytes()` will return the secret key with padding.
+    ///
+    /// `len()` will return the length with padding (always 128).
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_hmac_key, Sha512, SHA512_BLOCKSIZE)
+}
+
+construct_tag! {
+    /// A type to represent the `Tag` that HMAC returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 64 bytes.
+    (Tag, test_tag, SHA512_OUTSIZE, SHA512_OUTSIZE)
+}
+
+impl_from_trait!(Tag, SHA512_OUTSIZE);
+
+/// A hash function that can be used as the underlying hash function of HMAC.
+pub(crate) trait HmacHashFunction: Clone {
+    /// The blocksize of the hash function.
+    const BLOCKSIZE: usize;
+    /// The output size of the hash function.
+    const OUTSIZE: usize;
+
+    /// Initialize a new hash state.
+    fn new() -> Self;
+
+    /// Update the hash state with `data`.
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError>;
+
+    /// Write the digest to `dest`, which must be `OUTSIZE` bytes.
+    fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError>;
+}
+
+impl HmacHashFunction for Sha1 {
+    const BLOCKSIZE: usize = SHA1_BLOCKSIZE;
+    const OUTSIZE: usize = SHA1_OUTSIZE;
+
+    fn new() -> Self {
+        Sha1::new()
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        Sha1::update(self, data)
+    }
+
+    fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        dest.copy_from_slice(&self.finalize()?);
+        Ok(())
+    }
+}
+
+impl HmacHashFunction for Sha256 {
+    const BLOCKSIZE: usize = SHA256_BLOCKSIZE;
+    const OUTSIZE: usize = SHA256_OUTSIZE;
+
+    fn new() -> Self {
+        Sha256::new()
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        Sha256::update(self, data)
+    }
+
+    fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        dest.copy_from_slice(self.finalize()?.as_ref());
+        Ok(())
+    }
+}
+
+impl HmacHashFunction for Sha384 {
+    const BLOCKSIZE: usize = SHA384_BLOCKSIZE;
+    const OUTSIZE: usize = SHA384_OUTSIZE;
+
+    fn new() -> Self {
+        Sha384::new()
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        Sha384::update(self, data)
+    }
+
+    fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        dest.copy_from_slice(self.finalize()?.as_ref());
+        Ok(())
+    }
+}
+
+impl HmacHashFunction for Sha512 {
+    const BLOCKSIZE: usize = SHA512_BLOCKSIZE;
+    const OUTSIZE: usize = SHA512_OUTSIZE;
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        Sha512::update(self, data)
+    }
+
+    fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        dest.copy_from_slice(self.finalize()?.as_ref());
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+/// HMAC streaming state, generic over the underlying hash function.
+pub(crate) struct HmacGeneric<H: HmacHashFunction> {
+    working_hasher: H,
+    opad_hasher: H,
+    ipad_hasher: H,
+    is_finalized: bool,
+}
+
+impl<H: HmacHashFunction> HmacGeneric<H> {
+    /// Initialize `HmacGeneric` struct with a key that has already been padded
+    /// to the blocksize of `H`.
+    pub(crate) fn new(padded_key: &[u8]) -> Self {
+        debug_assert!(padded_key.len() == H::BLOCKSIZE);
+        debug_assert!(H::BLOCKSIZE <= SHA512_BLOCKSIZE);
+
+        let mut ipad = [0x36; SHA512_BLOCKSIZE];
+        let mut opad = [0x5C; SHA512_BLOCKSIZE];
+        for (idx, itm) in padded_key.iter().enumerate() {
+            opad[idx] ^= itm;
+            ipad[idx] ^= itm;
+        }
+
+        let mut ipad_hasher = H::new();
+        let mut opad_hasher = H::new();
+        ipad_hasher.update(&ipad[..H::BLOCKSIZE]).unwrap();
+        opad_hasher.update(&opad[..H::BLOCKSIZE]).unwrap();
+        ipad.zeroize();
+        opad.zeroize();
+
+        Self {
+            working_hasher: ipad_hasher.clone(),
+            opad_hasher,
+            ipad_hasher,
+            is_finalized: false,
+        }
+    }
+
+    /// Reset to `new()` state.
+    pub(crate) fn reset(&mut self) {
+        self.working_hasher = self.ipad_hasher.clone();
+        self.is_finalized = false;
+    }
+
+    /// Update state with `data`. This can be called multiple times.
+    pub(crate) fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            Err(UnknownCryptoError)
+        } else {
+            self.working_hasher.update(data)
+        }
+    }
+
+    /// Write the HMAC tag to `dest`, which must be `H::OUTSIZE` bytes.
+    pub(crate) fn finalize_into(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        debug_assert!(dest.len() == H::OUTSIZE);
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        self.is_finalized = true;
+        let mut outer_hasher = self.opad_hasher.clone();
+        let mut inner_digest = [0u8; SHA512_OUTSIZE];
+        self.working_hasher
+            .finalize_into(&mut inner_digest[..H::OUTSIZE])?;
+        outer_hasher.update(&inner_digest[..H::OUTSIZE])?;
+        inner_digest.zeroize();
+
+        outer_hasher.finalize_into(dest)
+    }
+}
+
+#[derive(Clone)]
+/// HMAC-SHA512 streaming state.
+pub struct Hmac {
+    state: HmacGeneric<Sha512>,
+}
+
+impl core::fmt::Debug for Hmac {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Hmac {{ working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: {:?} }}",
+            self.state.is_finalized
+        )
+    }
+}
+
+impl_io_write_trait!(Hmac);
+
+impl Hmac {
+    /// Initialize `Hmac` struct with a given key.
+    pub fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            state: HmacGeneric::new(secret_key.unprotected_as_bytes()),
+        }
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state.reset()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a HMAC-SHA512 tag.
+    pub fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        let mut dest = [0u8; SHA512_OUTSIZE];
+        self.state.finalize_into(&mut dest)?;
+
+        Ok(Tag::from(dest))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating an HMAC-SHA512 tag of `data`.
+    pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+        let mut state = Self::new(secret_key);
+        state.update(data)?;
+        state.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a HMAC-SHA512 tag in constant time.
+    pub fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if &Self::hmac(secret_key, data)? == expected {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state to `dst_out`, from which it can be restored with
+    /// [`import_state()`].
+    ///
+    /// [`import_state()`]: struct.Hmac.html#method.import_state
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if self.state.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut writer = StateWriter::new(dst_out, STATE_ID_HMAC_SHA512, HMAC_EXPORTSIZE)?;
+        self.state.working_hasher.write_fields(&mut writer);
+        self.state.opad_hasher.write_fields(&mut writer);
+        self.state.ipad_hasher.write_fields(&mut writer);
+        writer.finish();
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Restore a state exported with [`export_state()`].
+    ///
+    /// [`export_state()`]: struct.Hmac.html#method.export_state
+    pub fn import_state(exported: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut reader = StateReader::new(exported, STATE_ID_HMAC_SHA512, HMAC_EXPORTSIZE)?;
+        let working_hasher = Sha512::read_fields(&mut reader)?;
+        let opad_hasher = Sha512::read_fields(&mut reader)?;
+        let ipad_hasher = Sha512::read_fields(&mut reader)?;
+        reader.finish();
+
+        Ok(Self {
+            state: HmacGeneric {
+                working_hasher,
+                opad_hasher,
+                ipad_hasher,
+                is_finalized: false,
+            },
+        })
+    }
+}
+
+impl Mac for Hmac {
+    type SecretKey = SecretKey;
+    type Tag = Tag;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Hmac::new(secret_key)
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        self.finalize()
+    }
+
+    fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        Hmac::verify(expected, secret_key, data)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let secret_key = SecretKey::generate();
+        let initial_state = Hmac::new(&secret_key);
+        let debug = format!("{:?}", initial_state);
+        let expected = "Hmac { working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: false }";
+        assert_eq!(debug, expected);
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut state = Hmac::new(&sk);
+                    state.update(&data[..]).unwrap();
+                    let tag = state.finalize().unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Hmac::verify(&tag, &bad_sk, &data[..]).is_err()
+                }
+            }
+        }
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::hazardous::hash::sha512::compare_sha512_states;
+        use crate::test_framework::incremental_interface::*;
+
+        const KEY: [u8; 32] = [0u8; 32];
+
+        impl TestableStreamingContext<Tag> for Hmac {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Tag, UnknownCryptoError> {
+                Hmac::hmac(&SecretKey::from_slice(&KEY).unwrap(), input)
+            }
+
+            fn verify_result(expected: &Tag, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                // This will only run verification tests on differing input. They do not
+                // include tests for different secret keys.
+                Hmac::verify(expected, &SecretKey::from_slice(&KEY).unwrap(), input)
+            }
+
+            fn compare_states(state_1: &Hmac, state_2: &Hmac) {
+                compare_sha512_states(&state_1.state.opad_hasher, &state_2.state.opad_hasher);
+                compare_sha512_states(&state_1.state.ipad_hasher, &state_2.state.ipad_hasher);
+                compare_sha512_states(&state_1.state.working_hasher, &state_2.state.working_hasher);
+                assert_eq!(state_1.state.is_finalized, state_2.state.is_finalized);
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Hmac = Hmac::new(&SecretKey::from_slice(&KEY).unwrap());
+
+            let test_runner = StreamingContextConsistencyTester::<Tag, Hmac>::new(
+                initial_state,
+                SHA512_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Hmac = Hmac::new(&SecretKey::from_slice(&KEY).unwrap());
+
+                    let test_runner = StreamingContextConsistencyTester::<Tag, Hmac>::new(
+                        initial_state,
+                        SHA512_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+
+    mod test_export_state {
+        use super::*;
+
+        /// Authenticate `data` with the state exported and imported after
+        /// `split` bytes.
+        fn hmac_with_export(secret_key: &SecretKey, data: &[u8], split: usize) -> Tag {
+            let mut state = Hmac::new(secret_key);
+            state.update(&data[..split]).unwrap();
+            let mut exported = [0u8; HMAC_EXPORTSIZE];
+            state.export_state(&mut exported).unwrap();
+
+            let mut imported = Hmac::import_state(&exported).unwrap();
+            let mut exported_again = [0u8; HMAC_EXPORTSIZE];
+            imported.export_state(&mut exported_again).unwrap();
+            assert_eq!(exported[..], exported_again[..]);
+
+            imported.update(&data[split..]).unwrap();
+            imported.finalize().unwrap()
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let data = [1u8; 3 * SHA512_BLOCKSIZE + 3];
+            let expected = Hmac::hmac(&sk, &data).unwrap();
+            for split in [0, 1, 127, 128, 129, 300, data.len()].iter() {
+                assert_eq!(hmac_with_export(&sk, &data, *split), expected);
+            }
+        }
+
+        #[test]
+        fn test_imported_reset() {
+            let sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let mut state = Hmac::new(&sk);
+            state.update(b"Some data").unwrap();
+            let mut exported = [0u8; HMAC_EXPORTSIZE];
+            state.export_state(&mut exported).unwrap();
+
+            let mut imported = Hmac::import_state(&exported).unwrap();
+            imported.reset();
+            imported.update(b"Other data").unwrap();
+            assert_eq!(
+                imported.finalize().unwrap(),
+                Hmac::hmac(&sk, b"Other data").unwrap()
+            );
+        }
+
+        #[test]
+        fn test_length_err() {
+            let state = Hmac::new(&SecretKey::from_slice(&[0u8; 32]).unwrap());
+            let mut exported = [0u8; HMAC_EXPORTSIZE + 1];
+            assert!(state
+                .export_state(&mut exported[..HMAC_EXPORTSIZE - 1])
+                .is_err());
+            assert!(state.export_state(&mut exported).is_err());
+            assert!(state.export_state(&mut exported[..HMAC_EXPORTSIZE]).is_ok());
+
+            assert!(Hmac::import_state(&exported[..HMAC_EXPORTSIZE - 1]).is_err());
+            assert!(Hmac::import_state(&exported).is_err());
+            assert!(Hmac::import_state(&exported[..HMAC_EXPORTSIZE]).is_ok());
+        }
+
+        #[test]
+        fn test_finalized_err() {
+            let mut state = Hmac::new(&SecretKey::from_slice(&[0u8; 32]).unwrap());
+            let mut exported = [0u8; HMAC_EXPORTSIZE];
+            let _ = state.finalize().unwrap();
+            assert!(state.export_state(&mut exported).is_err());
+            state.reset();
+            assert!(state.export_state(&mut exported).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_state_err() {
+            let mut exported = [0u8; HMAC_EXPORTSIZE];
+            Hmac::new(&SecretKey::from_slice(&[0u8; 32]).unwrap())
+                .export_state(&mut exported)
+                .unwrap();
+            assert!(Hmac::import_state(&exported).is_ok());
+
+            // The version of the encoding.
+            let mut invalid = exported;
+            invalid[0] += 1;
+            assert!(Hmac::import_state(&invalid).is_err());
+
+            // The type that exported the state.
+            let mut invalid = exported;
+            invalid[1] += 1;
+            assert!(Hmac::import_state(&invalid).is_err());
+
+            // The leftover of each SHA512 state.
+            for hasher in 0..3 {
+                let leftover =
+                    STATE_EXPORT_HEADERSIZE + hasher * SHA512_FIELDSIZE + 64 + SHA512_BLOCKSIZE;
+                let mut invalid = exported;
+                invalid[leftover] = SHA512_BLOCKSIZE as u8;
+                assert!(Hmac::import_state(&invalid).is_err());
+            }
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Exporting and importing the state at any point should not
+                /// change the tag.
+                fn prop_export_import_same_tag(data: Vec<u8>, split: usize) -> bool {
+                    let sk = SecretKey::generate();
+                    let split = if data.is_empty() { 0 } else { split % data.len() };
+                    hmac_with_export(&sk, &data, split) == Hmac::hmac(&sk, &data).unwrap()
+                }
+            }
+        }
+    }
+}
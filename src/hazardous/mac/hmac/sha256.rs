@@ -0,0 +1,318 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`:  The authentication key.
+//! - `data`: Data to be authenticated.
+//! - `expected`: The expected authentication tag.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - The HMAC does not match the expected when verifying.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 64 bytes.
+//! - The minimum recommended size for a secret key is 32 bytes.
+//!
+//! # Recommendation:
+//! - Prefer HMAC-SHA512 from the [`hmac`] module, unless a protocol requires
+//!   HMAC-SHA256.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::mac::hmac::sha256::{HmacSha256, SecretKey};
+//!
+//! let key = SecretKey::generate();
+//!
+//! let mut state = HmacSha256::new(&key);
+//! state.update(b"Some message.")?;
+//! let tag = state.finalize()?;
+//!
+//! assert!(HmacSha256::verify(&tag, &key, b"Some message.").is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.HmacSha256.html
+//! [`reset()`]: struct.HmacSha256.html
+//! [`finalize()`]: struct.HmacSha256.html
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+//! [`hmac`]: ../index.html
+
+use super::HmacGeneric;
+use crate::hazardous::traits::Mac;
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::sha256::{Sha256, SHA256_BLOCKSIZE, SHA256_OUTSIZE},
+};
+
+construct_hmac_key! {
+    /// A type to represent the `SecretKey` that HMAC-SHA256 uses for authentication.
+    ///
+    /// # Note:
+    /// `SecretKey` pads the secret key for use with HMAC to a length of 64, when initialized.
+    ///
+    /// Using `unprotected_as_bytes()` will return the secret key with padding.
+    ///
+    /// `len()` will return the length with padding (always 64).
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_hmac_key, Sha256, SHA256_BLOCKSIZE)
+}
+
+construct_tag! {
+    /// A type to represent the `Tag` that HMAC-SHA256 returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (Tag, test_tag, SHA256_OUTSIZE, SHA256_OUTSIZE)
+}
+
+impl_from_trait!(Tag, SHA256_OUTSIZE);
+
+#[derive(Clone)]
+/// HMAC-SHA256 streaming state.
+pub struct HmacSha256 {
+    state: HmacGeneric<Sha256>,
+}
+
+impl core::fmt::Debug for HmacSha256 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "HmacSha256 {{ working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: {:?} }}",
+            self.state.is_finalized
+        )
+    }
+}
+
+impl_io_write_trait!(HmacSha256);
+
+impl HmacSha256 {
+    /// Initialize `HmacSha256` struct with a given key.
+    pub fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            state: HmacGeneric::new(secret_key.unprotected_as_bytes()),
+        }
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state.reset()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a HMAC-SHA256 tag.
+    pub fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        let mut dest = [0u8; SHA256_OUTSIZE];
+        self.state.finalize_into(&mut dest)?;
+
+        Ok(Tag::from(dest))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating an HMAC-SHA256 tag of `data`.
+    pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+        let mut state = Self::new(secret_key);
+        state.update(data)?;
+        state.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a HMAC-SHA256 tag in constant time.
+    pub fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if &Self::hmac(secret_key, data)? == expected {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError)
+        }
+    }
+}
+
+impl Mac for HmacSha256 {
+    type SecretKey = SecretKey;
+    type Tag = Tag;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        HmacSha256::new(secret_key)
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        self.finalize()
+    }
+
+    fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        HmacSha256::verify(expected, secret_key, data)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let secret_key = SecretKey::generate();
+        let initial_state = HmacSha256::new(&secret_key);
+        let debug = format!("{:?}", initial_state);
+        let expected = "HmacSha256 { working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: false }";
+        assert_eq!(debug, expected);
+    }
+
+    #[test]
+    fn test_long_key_is_hashed() {
+        let long_key = [0xaau8; SHA256_BLOCKSIZE + 1];
+        let hashed_key = Sha256::digest(&long_key).unwrap();
+
+        assert_eq!(
+            HmacSha256::hmac(&SecretKey::from_slice(&long_key).unwrap(), b"").unwrap(),
+            HmacSha256::hmac(&SecretKey::from_slice(hashed_key.as_ref()).unwrap(), b"").unwrap()
+        );
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut state = HmacSha256::new(&sk);
+                    state.update(&data[..]).unwrap();
+                    let tag = state.finalize().unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    HmacSha256::verify(&tag, &bad_sk, &data[..]).is_err()
+                }
+            }
+        }
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::hazardous::hash::sha256::compare_sha256_states;
+        use crate::test_framework::incremental_interface::*;
+
+        const KEY: [u8; 32] = [0u8; 32];
+
+        impl TestableStreamingContext<Tag> for HmacSha256 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Tag, UnknownCryptoError> {
+                HmacSha256::hmac(&SecretKey::from_slice(&KEY).unwrap(), input)
+            }
+
+            fn verify_result(expected: &Tag, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                // This will only run verification tests on differing input. They do not
+                // include tests for different secret keys.
+                HmacSha256::verify(expected, &SecretKey::from_slice(&KEY).unwrap(), input)
+            }
+
+            fn compare_states(state_1: &HmacSha256, state_2: &HmacSha256) {
+                compare_sha256_states(&state_1.state.opad_hasher, &state_2.state.opad_hasher);
+                compare_sha256_states(&state_1.state.ipad_hasher, &state_2.state.ipad_hasher);
+                compare_sha256_states(&state_1.state.working_hasher, &state_2.state.working_hasher);
+                assert_eq!(state_1.state.is_finalized, state_2.state.is_finalized);
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: HmacSha256 = HmacSha256::new(&SecretKey::from_slice(&KEY).unwrap());
+
+            let test_runner = StreamingContextConsistencyTester::<Tag, HmacSha256>::new(
+                initial_state,
+                SHA256_BLOCKSIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: HmacSha256 = HmacSha256::new(&SecretKey::from_slice(&KEY).unwrap());
+
+                    let test_runner = StreamingContextConsistencyTester::<Tag, HmacSha256>::new(
+                        initial_state,
+                        SHA256_BLOCKSIZE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
@@ -36,39 +36,40 @@
 //! - The secret key should always be generated using a CSPRNG.
 //!   [`SecretKey::generate()`] can be used for this. It generates
 //!   a secret key of 128 bytes.
-//! - The minimum recommended size for a secret key is 64 bytes.
+//! - The minimum recommended size for a secret key is 48 bytes.
 //!
 //! # Recommendation:
-//! - If you are unsure of whether to use HMAC or Poly1305, it is most often
-//!   easier to just use HMAC. See also [Cryptographic Right Answers].
+//! - Prefer HMAC-SHA512 from the [`hmac`] module, unless a protocol requires
+//!   HMAC-SHA384.
 //!
 //! # Example:
 //! ```rust
-//! use orion::hazardous::mac::hmac::{Hmac, SecretKey};
+//! use orion::hazardous::mac::hmac::sha384::{HmacSha384, SecretKey};
 //!
 //! let key = SecretKey::generate();
 //!
-//! let mut state = Hmac::new(&key);
+//! let mut state = HmacSha384::new(&key);
 //! state.update(b"Some message.")?;
 //! let tag = state.finalize()?;
 //!
-//! assert!(Hmac::verify(&tag, &key, b"Some message.").is_ok());
+//! assert!(HmacSha384::verify(&tag, &key, b"Some message.").is_ok());
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
-//! [`update()`]: struct.Hmac.html
-//! [`reset()`]: struct.Hmac.html
-//! [`finalize()`]: struct.Hmac.html
+//! [`update()`]: struct.HmacSha384.html
+//! [`reset()`]: struct.HmacSha384.html
+//! [`finalize()`]: struct.HmacSha384.html
 //! [`SecretKey::generate()`]: struct.SecretKey.html
-//! [Cryptographic Right Answers]: https://latacora.micro.blog/2018/04/03/cryptographic-right-answers.html
+//! [`hmac`]: ../index.html
 
+use super::HmacGeneric;
+use crate::hazardous::traits::Mac;
 use crate::{
     errors::UnknownCryptoError,
-    hazardous::hash::sha512::{self, SHA512_BLOCKSIZE, SHA512_OUTSIZE},
+    hazardous::hash::sha384::{Sha384, SHA384_BLOCKSIZE, SHA384_OUTSIZE},
 };
-use zeroize::Zeroize;
 
 construct_hmac_key! {
-    /// A type to represent the `SecretKey` that HMAC uses for authentication.
+    /// A type to represent the `SecretKey` that HMAC-SHA384 uses for authentication.
     ///
     /// # Note:
     /// `SecretKey` pads the secret key for use with HMAC to a length of 128, when initialized.
@@ -80,101 +81,68 @@ construct_hmac_key! {
     /// # Panics:
     /// A panic will occur if:
     /// - Failure to generate random bytes securely.
-    (SecretKey, test_hmac_key, SHA512_BLOCKSIZE)
+    (SecretKey, test_hmac_key, Sha384, SHA384_BLOCKSIZE)
 }
 
 construct_tag! {
-    /// A type to represent the `Tag` that HMAC returns.
+    /// A type to represent the `Tag` that HMAC-SHA384 returns.
     ///
     /// # Errors:
     /// An error will be returned if:
-    /// - `slice` is not 64 bytes.
-    (Tag, test_tag, SHA512_OUTSIZE, SHA512_OUTSIZE)
+    /// - `slice` is not 48 bytes.
+    (Tag, test_tag, SHA384_OUTSIZE, SHA384_OUTSIZE)
 }
 
-impl_from_trait!(Tag, SHA512_OUTSIZE);
+impl_from_trait!(Tag, SHA384_OUTSIZE);
 
 #[derive(Clone)]
-/// HMAC-SHA512 streaming state.
-pub struct Hmac {
-    working_hasher: sha512::Sha512,
-    opad_hasher: sha512::Sha512,
-    ipad_hasher: sha512::Sha512,
-    is_finalized: bool,
+/// HMAC-SHA384 streaming state.
+pub struct HmacSha384 {
+    state: HmacGeneric<Sha384>,
 }
 
-impl core::fmt::Debug for Hmac {
+impl core::fmt::Debug for HmacSha384 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
-            "Hmac {{ working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: {:?} }}",
-            self.is_finalized
+            "HmacSha384 {{ working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: {:?} }}",
+            self.state.is_finalized
         )
     }
 }
 
-impl Hmac {
-    /// Pad `key` with `ipad` and `opad`.
-    fn pad_key_io(&mut self, key: &SecretKey) {
-        let mut ipad = [0x36; SHA512_BLOCKSIZE];
-        let mut opad = [0x5C; SHA512_BLOCKSIZE];
-        // The key is padded in SecretKey::from_slice
-        for (idx, itm) in key.unprotected_as_bytes().iter().enumerate() {
-            opad[idx] ^= itm;
-            ipad[idx] ^= itm;
-        }
-
-        self.ipad_hasher.update(ipad.as_ref()).unwrap();
-        self.opad_hasher.update(opad.as_ref()).unwrap();
-        self.working_hasher = self.ipad_hasher.clone();
-        ipad.zeroize();
-        opad.zeroize();
-    }
+impl_io_write_trait!(HmacSha384);
 
-    /// Initialize `Hmac` struct with a given key.
+impl HmacSha384 {
+    /// Initialize `HmacSha384` struct with a given key.
     pub fn new(secret_key: &SecretKey) -> Self {
-        let mut state = Self {
-            working_hasher: sha512::Sha512::new(),
-            opad_hasher: sha512::Sha512::new(),
-            ipad_hasher: sha512::Sha512::new(),
-            is_finalized: false,
-        };
-
-        state.pad_key_io(secret_key);
-        state
+        Self {
+            state: HmacGeneric::new(secret_key.unprotected_as_bytes()),
+        }
     }
 
     /// Reset to `new()` state.
     pub fn reset(&mut self) {
-        self.working_hasher = self.ipad_hasher.clone();
-        self.is_finalized = false;
+        self.state.reset()
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Update state with `data`. This can be called multiple times.
     pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
-        if self.is_finalized {
-            Err(UnknownCryptoError)
-        } else {
-            self.working_hasher.update(data)
-        }
+        self.state.update(data)
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    /// Return a HMAC-SHA512 tag.
+    /// Return a HMAC-SHA384 tag.
     pub fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
-        if self.is_finalized {
-            return Err(UnknownCryptoError);
-        }
+        let mut dest = [0u8; SHA384_OUTSIZE];
+        self.state.finalize_into(&mut dest)?;
 
-        self.is_finalized = true;
-        let mut outer_hasher = self.opad_hasher.clone();
-        outer_hasher.update(self.working_hasher.finalize()?.as_ref())?;
-        Tag::from_slice(outer_hasher.finalize()?.as_ref())
+        Ok(Tag::from(dest))
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    /// One-shot function for generating an HMAC-SHA512 tag of `data`.
+    /// One-shot function for generating an HMAC-SHA384 tag of `data`.
     pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
         let mut state = Self::new(secret_key);
         state.update(data)?;
@@ -182,7 +150,7 @@ impl Hmac {
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    /// Verify a HMAC-SHA512 tag in constant time.
+    /// Verify a HMAC-SHA384 tag in constant time.
     pub fn verify(
         expected: &Tag,
         secret_key: &SecretKey,
@@ -196,6 +164,36 @@ impl Hmac {
     }
 }
 
+impl Mac for HmacSha384 {
+    type SecretKey = SecretKey;
+    type Tag = Tag;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        HmacSha384::new(secret_key)
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        self.finalize()
+    }
+
+    fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        HmacSha384::verify(expected, secret_key, data)
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 mod public {
@@ -205,12 +203,23 @@ mod public {
     #[cfg(feature = "safe_api")]
     fn test_debug_impl() {
         let secret_key = SecretKey::generate();
-        let initial_state = Hmac::new(&secret_key);
+        let initial_state = HmacSha384::new(&secret_key);
         let debug = format!("{:?}", initial_state);
-        let expected = "Hmac { working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: false }";
+        let expected = "HmacSha384 { working_hasher: [***OMITTED***], opad_hasher: [***OMITTED***], ipad_hasher: [***OMITTED***], is_finalized: false }";
         assert_eq!(debug, expected);
     }
 
+    #[test]
+    fn test_long_key_is_hashed() {
+        let long_key = [0xaau8; SHA384_BLOCKSIZE + 1];
+        let hashed_key = Sha384::digest(&long_key).unwrap();
+
+        assert_eq!(
+            HmacSha384::hmac(&SecretKey::from_slice(&long_key).unwrap(), b"").unwrap(),
+            HmacSha384::hmac(&SecretKey::from_slice(hashed_key.as_ref()).unwrap(), b"").unwrap()
+        );
+    }
+
     #[cfg(feature = "safe_api")]
     mod test_verify {
         use super::*;
@@ -225,12 +234,12 @@ mod public {
                 /// NOTE: Using different and same input data is tested with TestableStreamingContext.
                 fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
                     let sk = SecretKey::generate();
-                    let mut state = Hmac::new(&sk);
+                    let mut state = HmacSha384::new(&sk);
                     state.update(&data[..]).unwrap();
                     let tag = state.finalize().unwrap();
                     let bad_sk = SecretKey::generate();
 
-                    Hmac::verify(&tag, &bad_sk, &data[..]).is_err()
+                    HmacSha384::verify(&tag, &bad_sk, &data[..]).is_err()
                 }
             }
         }
@@ -238,12 +247,12 @@ mod public {
 
     mod test_streaming_interface {
         use super::*;
-        use crate::hazardous::hash::sha512::compare_sha512_states;
+        use crate::hazardous::hash::sha384::compare_sha384_states;
         use crate::test_framework::incremental_interface::*;
 
         const KEY: [u8; 32] = [0u8; 32];
 
-        impl TestableStreamingContext<Tag> for Hmac {
+        impl TestableStreamingContext<Tag> for HmacSha384 {
             fn reset(&mut self) -> Result<(), UnknownCryptoError> {
                 Ok(self.reset())
             }
@@ -257,30 +266,30 @@ mod public {
             }
 
             fn one_shot(input: &[u8]) -> Result<Tag, UnknownCryptoError> {
-                Hmac::hmac(&SecretKey::from_slice(&KEY).unwrap(), input)
+                HmacSha384::hmac(&SecretKey::from_slice(&KEY).unwrap(), input)
             }
 
             fn verify_result(expected: &Tag, input: &[u8]) -> Result<(), UnknownCryptoError> {
                 // This will only run verification tests on differing input. They do not
                 // include tests for different secret keys.
-                Hmac::verify(expected, &SecretKey::from_slice(&KEY).unwrap(), input)
+                HmacSha384::verify(expected, &SecretKey::from_slice(&KEY).unwrap(), input)
             }
 
-            fn compare_states(state_1: &Hmac, state_2: &Hmac) {
-                compare_sha512_states(&state_1.opad_hasher, &state_2.opad_hasher);
-                compare_sha512_states(&state_1.ipad_hasher, &state_2.ipad_hasher);
-                compare_sha512_states(&state_1.working_hasher, &state_2.working_hasher);
-                assert_eq!(state_1.is_finalized, state_2.is_finalized);
+            fn compare_states(state_1: &HmacSha384, state_2: &HmacSha384) {
+                compare_sha384_states(&state_1.state.opad_hasher, &state_2.state.opad_hasher);
+                compare_sha384_states(&state_1.state.ipad_hasher, &state_2.state.ipad_hasher);
+                compare_sha384_states(&state_1.state.working_hasher, &state_2.state.working_hasher);
+                assert_eq!(state_1.state.is_finalized, state_2.state.is_finalized);
             }
         }
 
         #[test]
         fn default_consistency_tests() {
-            let initial_state: Hmac = Hmac::new(&SecretKey::from_slice(&KEY).unwrap());
+            let initial_state: HmacSha384 = HmacSha384::new(&SecretKey::from_slice(&KEY).unwrap());
 
-            let test_runner = StreamingContextConsistencyTester::<Tag, Hmac>::new(
+            let test_runner = StreamingContextConsistencyTester::<Tag, HmacSha384>::new(
                 initial_state,
-                SHA512_BLOCKSIZE,
+                SHA384_BLOCKSIZE,
             );
             test_runner.run_all_tests();
         }
@@ -294,11 +303,11 @@ mod public {
                 /// Related bug: https://github.com/brycx/orion/issues/46
                 /// Test different streaming state usage patterns.
                 fn prop_input_to_consistency(data: Vec<u8>) -> bool {
-                    let initial_state: Hmac = Hmac::new(&SecretKey::from_slice(&KEY).unwrap());
+                    let initial_state: HmacSha384 = HmacSha384::new(&SecretKey::from_slice(&KEY).unwrap());
 
-                    let test_runner = StreamingContextConsistencyTester::<Tag, Hmac>::new(
+                    let test_runner = StreamingContextConsistencyTester::<Tag, HmacSha384>::new(
                         initial_state,
-                        SHA512_BLOCKSIZE,
+                        SHA384_BLOCKSIZE,
                     );
                     test_runner.run_all_tests_property(&data);
                     true
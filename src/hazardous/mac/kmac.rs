@@ -0,0 +1,523 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: The authentication key.
+//! - `customization`: The customization string.
+//! - `data`: Data to be authenticated.
+//! - `dst_out`: Destination buffer for the authentication tag. The length of the
+//!   tag is determined by the length of `dst_out`.
+//! - `expected`: The expected authentication tag.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] or [`finalize_xof()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] or [`finalize_xof()`] without a [`reset()`] in
+//!   between.
+//! - The length of `dst_out` or `expected` is less than 4.
+//! - The KMAC does not match the expected when verifying.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - KMAC128 provides at most 128 bits of security and KMAC256 at most 256 bits. The secret key
+//!   should be at least 16 bytes for KMAC128 and 32 bytes for KMAC256 to reach this.
+//! - Secret keys must be between 16 and 64 bytes. This limit is not part of NIST SP 800-185,
+//!   which allows keys of any length.
+//! - The length of the tag is part of the input to KMAC, so tags of different lengths are
+//!   unrelated. This does not apply to KMACXOF, where output of a shorter length is a prefix of
+//!   output of a longer length.
+//! - Tags shorter than 4 bytes are not allowed, as NIST SP 800-185 requires. Tags of at
+//!   least 16 bytes are recommended.
+//!
+//! # Recommendation:
+//! - Use different customization strings to separate different uses of the same key.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::mac::kmac::{Kmac256, SecretKey};
+//!
+//! let key = SecretKey::generate();
+//!
+//! let mut state = Kmac256::new(&key, b"My Tagged Application")?;
+//! state.update(b"Some message.")?;
+//! let mut tag = [0u8; 32];
+//! state.finalize(&mut tag)?;
+//!
+//! assert!(Kmac256::verify(&tag, &key, b"My Tagged Application", b"Some message.").is_ok());
+//!
+//! // Using KMACXOF256
+//! state.reset();
+//! state.update(b"Some message.")?;
+//! let mut reader = state.finalize_xof()?;
+//! let mut output = [0u8; 64];
+//! reader.squeeze(&mut output)?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: struct.Kmac128.html
+//! [`reset()`]: struct.Kmac128.html
+//! [`finalize()`]: struct.Kmac128.html
+//! [`finalize_xof()`]: struct.Kmac128.html
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::hash::sha3::{
+        bit_length, right_encode, shake128::SHAKE128_RATE, shake256::SHAKE256_RATE, Sponge,
+        XofReader,
+    },
+};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// The minimum size of a secret key for KMAC.
+pub const KMAC_MIN_KEYSIZE: usize = 16;
+/// The maximum size of a secret key for KMAC.
+pub const KMAC_MAX_KEYSIZE: usize = 64;
+/// The minimum size of a KMAC tag.
+pub const KMAC_MIN_OUTSIZE: usize = 4;
+
+/// The function-name string of cSHAKE that KMAC uses.
+const KMAC_FUNCTION_NAME: &[u8] = b"KMAC";
+
+construct_secret_key! {
+    /// A type to represent the `SecretKey` that KMAC uses for authentication.
+    ///
+    /// # Note:
+    /// NIST SP 800-185 allows keys of any length. The limit of 16 to 64 bytes is
+    /// orion's own, so that the key fits in a fixed-size buffer without `alloc`.
+    /// KMAC tags produced with keys outside this range cannot be verified.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is less than 16 bytes or greater than 64 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, KMAC_MIN_KEYSIZE, KMAC_MAX_KEYSIZE, 32)
+}
+
+/// Initialize a sponge for KMAC with a `rate` in bytes, absorbing the key.
+fn init(
+    rate: usize,
+    secret_key: &SecretKey,
+    customization: &[u8],
+) -> Result<Sponge, UnknownCryptoError> {
+    let mut state = Sponge::new_cshake(rate, KMAC_FUNCTION_NAME, customization)?;
+    state.absorb_prefix(&[secret_key.unprotected_as_bytes()])?;
+
+    Ok(state)
+}
+
+/// Absorb the length of the tag, which must be at least [`KMAC_MIN_OUTSIZE`], and
+/// return a reader for the tag.
+///
+/// [`KMAC_MIN_OUTSIZE`]: constant.KMAC_MIN_OUTSIZE.html
+fn finalize_tag(state: &mut Sponge, tag_len: usize) -> Result<XofReader, UnknownCryptoError> {
+    if tag_len < KMAC_MIN_OUTSIZE {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut buf = [0u8; 9];
+    state.update(right_encode(bit_length(tag_len)?, &mut buf))?;
+    state.finalize_xof()
+}
+
+/// Fill `dst_out` with the tag.
+fn finalize(state: &mut Sponge, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+    finalize_tag(state, dst_out.len())?.squeeze(dst_out)
+}
+
+/// Compare the tag with `expected` in constant time. The tag is compared in
+/// blocks, so that `expected` can be of any length.
+fn verify(state: &mut Sponge, expected: &[u8]) -> Result<(), UnknownCryptoError> {
+    let mut reader = finalize_tag(state, expected.len())?;
+    let mut block = [0u8; 64];
+    let mut is_equal = Choice::from(1u8);
+
+    for expected_block in expected.chunks(block.len()) {
+        let actual_block = &mut block[..expected_block.len()];
+        reader.squeeze(actual_block)?;
+        is_equal &= actual_block.ct_eq(expected_block);
+    }
+    block.zeroize();
+
+    if is_equal.into() {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError)
+    }
+}
+
+/// Absorb the length of zero that marks KMACXOF, and return a reader for the output.
+fn finalize_xof(state: &mut Sponge) -> Result<XofReader, UnknownCryptoError> {
+    let mut buf = [0u8; 9];
+    state.update(right_encode(0, &mut buf))?;
+    state.finalize_xof()
+}
+
+#[derive(Clone, Debug)]
+/// KMAC128 streaming state.
+pub struct Kmac128 {
+    state: Sponge,
+}
+
+impl_io_write_trait!(Kmac128);
+
+impl Kmac128 {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize `Kmac128` struct with a given key and customization string.
+    pub fn new(secret_key: &SecretKey, customization: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            state: init(SHAKE128_RATE, secret_key, customization)?,
+        })
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dst_out` with a KMAC128 tag.
+    pub fn finalize(&mut self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        finalize(&mut self.state, dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for the output of KMACXOF128.
+    pub fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        finalize_xof(&mut self.state)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating a KMAC128 tag of `data`.
+    pub fn kmac(
+        secret_key: &SecretKey,
+        customization: &[u8],
+        data: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(secret_key, customization)?;
+        state.update(data)?;
+        state.finalize(dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a KMAC128 tag in constant time.
+    pub fn verify(
+        expected: &[u8],
+        secret_key: &SecretKey,
+        customization: &[u8],
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(secret_key, customization)?;
+        state.update(data)?;
+        verify(&mut state.state, expected)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// KMAC256 streaming state.
+pub struct Kmac256 {
+    state: Sponge,
+}
+
+impl_io_write_trait!(Kmac256);
+
+impl Kmac256 {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize `Kmac256` struct with a given key and customization string.
+    pub fn new(secret_key: &SecretKey, customization: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            state: init(SHAKE256_RATE, secret_key, customization)?,
+        })
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.state.update(data)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dst_out` with a KMAC256 tag.
+    pub fn finalize(&mut self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        finalize(&mut self.state, dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a reader for the output of KMACXOF256.
+    pub fn finalize_xof(&mut self) -> Result<XofReader, UnknownCryptoError> {
+        finalize_xof(&mut self.state)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating a KMAC256 tag of `data`.
+    pub fn kmac(
+        secret_key: &SecretKey,
+        customization: &[u8],
+        data: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(secret_key, customization)?;
+        state.update(data)?;
+        state.finalize(dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a KMAC256 tag in constant time.
+    pub fn verify(
+        expected: &[u8],
+        secret_key: &SecretKey,
+        customization: &[u8],
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(secret_key, customization)?;
+        state.update(data)?;
+        verify(&mut state.state, expected)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const KEY: [u8; 32] = [0u8; 32];
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let secret_key = SecretKey::generate();
+        let initial_state = Kmac128::new(&secret_key, b"").unwrap();
+        let debug = format!("{:?}", initial_state);
+        let expected = "Kmac128 { state: Sponge { state: [***OMITTED***], buffer: [***OMITTED***], leftover: 0, rate: 168, is_finalized: false } }";
+        assert_eq!(debug, expected);
+    }
+
+    mod test_finalize {
+        use super::*;
+
+        #[test]
+        fn test_tag_length() {
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+
+            let mut state = Kmac128::new(&sk, b"").unwrap();
+            assert!(state.finalize(&mut [0u8; 0]).is_err());
+            assert!(state.finalize(&mut [0u8; 3]).is_err());
+            assert!(state.finalize(&mut [0u8; 4]).is_ok());
+            assert!(state.finalize(&mut [0u8; 4]).is_err());
+
+            let mut state = Kmac256::new(&sk, b"").unwrap();
+            assert!(state.finalize(&mut [0u8; 3]).is_err());
+            assert!(state.finalize(&mut [0u8; 512]).is_ok());
+        }
+
+        #[test]
+        fn test_tag_length_is_input() {
+            // Unlike with KMACXOF, a shorter tag is not a prefix of a longer one.
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+            let mut short = [0u8; 16];
+            let mut long = [0u8; 32];
+            Kmac128::kmac(&sk, b"", b"Some data", &mut short).unwrap();
+            Kmac128::kmac(&sk, b"", b"Some data", &mut long).unwrap();
+            assert_ne!(short[..], long[..16]);
+
+            let mut state = Kmac128::new(&sk, b"").unwrap();
+            state.update(b"Some data").unwrap();
+            let mut reader = state.finalize_xof().unwrap();
+            let mut xof_short = [0u8; 16];
+            let mut xof_long = [0u8; 32];
+            reader.squeeze(&mut xof_long).unwrap();
+            state.reset();
+            state.update(b"Some data").unwrap();
+            state
+                .finalize_xof()
+                .unwrap()
+                .squeeze(&mut xof_short)
+                .unwrap();
+            assert_eq!(xof_short[..], xof_long[..16]);
+            assert_ne!(xof_long[..], long[..]);
+        }
+
+        #[test]
+        fn test_finalize_xof_twice_err() {
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+            let mut state = Kmac256::new(&sk, b"").unwrap();
+            assert!(state.finalize_xof().is_ok());
+            assert!(state.finalize_xof().is_err());
+            assert!(state.finalize(&mut [0u8; 32]).is_err());
+            assert!(state.update(b"").is_err());
+            state.reset();
+            assert!(state.finalize(&mut [0u8; 32]).is_ok());
+        }
+
+        #[test]
+        fn test_customization_separates() {
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+            let mut tag_1 = [0u8; 32];
+            let mut tag_2 = [0u8; 32];
+            Kmac256::kmac(&sk, b"", b"Some data", &mut tag_1).unwrap();
+            Kmac256::kmac(&sk, b"Other", b"Some data", &mut tag_2).unwrap();
+            assert_ne!(tag_1, tag_2);
+        }
+    }
+
+    mod test_verify {
+        use super::*;
+
+        #[test]
+        fn test_verify_tag_length() {
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+
+            let mut tag = [0u8; 3];
+            assert!(Kmac128::kmac(&sk, b"", b"Some data", &mut tag).is_err());
+            assert!(Kmac128::verify(&tag, &sk, b"", b"Some data").is_err());
+
+            // Tags longer than the internal block used when verifying.
+            let mut tag = [0u8; 200];
+            Kmac256::kmac(&sk, b"", b"Some data", &mut tag).unwrap();
+            assert!(Kmac256::verify(&tag, &sk, b"", b"Some data").is_ok());
+            assert!(Kmac256::verify(&tag[..199], &sk, b"", b"Some data").is_err());
+            tag[199] ^= 1;
+            assert!(Kmac256::verify(&tag, &sk, b"", b"Some data").is_err());
+        }
+
+        #[test]
+        fn test_verify_diff_customization_err() {
+            let sk = SecretKey::from_slice(&KEY).unwrap();
+            let mut tag = [0u8; 32];
+            Kmac128::kmac(&sk, b"A", b"Some data", &mut tag).unwrap();
+            assert!(Kmac128::verify(&tag, &sk, b"A", b"Some data").is_ok());
+            assert!(Kmac128::verify(&tag, &sk, b"B", b"Some data").is_err());
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// When using a different key, verify() should always yield an error.
+                /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+                fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+                    let sk = SecretKey::generate();
+                    let mut tag = [0u8; 32];
+                    Kmac128::kmac(&sk, b"", &data[..], &mut tag).unwrap();
+                    let bad_sk = SecretKey::generate();
+
+                    Kmac128::verify(&tag, &bad_sk, b"", &data[..]).is_err()
+                        && Kmac256::verify(&tag, &sk, b"", &data[..]).is_err()
+                }
+            }
+        }
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::hazardous::hash::sha3::{compare_sponge_states, shake128::SHAKE128_RATE};
+        use crate::test_framework::incremental_interface::*;
+
+        /// The tag of a `Kmac128` in these tests.
+        type Tag = [u8; 32];
+
+        impl TestableStreamingContext<Tag> for Kmac128 {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                Ok(self.reset())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+                let mut tag = [0u8; 32];
+                self.finalize(&mut tag)?;
+                Ok(tag)
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Tag, UnknownCryptoError> {
+                let mut tag = [0u8; 32];
+                Kmac128::kmac(&SecretKey::from_slice(&KEY).unwrap(), b"", input, &mut tag)?;
+                Ok(tag)
+            }
+
+            fn verify_result(expected: &Tag, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                // This will only run verification tests on differing input. They do not
+                // include tests for different secret keys.
+                Kmac128::verify(expected, &SecretKey::from_slice(&KEY).unwrap(), b"", input)
+            }
+
+            fn compare_states(state_1: &Kmac128, state_2: &Kmac128) {
+                compare_sponge_states(&state_1.state, &state_2.state)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Kmac128 =
+                Kmac128::new(&SecretKey::from_slice(&KEY).unwrap(), b"").unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Tag, Kmac128>::new(
+                initial_state,
+                SHAKE128_RATE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Related bug: https://github.com/brycx/orion/issues/46
+                /// Test different streaming state usage patterns.
+                fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+                    let initial_state: Kmac128 =
+                        Kmac128::new(&SecretKey::from_slice(&KEY).unwrap(), b"").unwrap();
+
+                    let test_runner = StreamingContextConsistencyTester::<Tag, Kmac128>::new(
+                        initial_state,
+                        SHAKE128_RATE,
+                    );
+                    test_runner.run_all_tests_property(&data);
+                    true
+                }
+            }
+        }
+    }
+}
@@ -29,6 +29,8 @@
 //! - `data`: Data to be authenticated.
 //! - `one_time_key`: One-time key used to authenticate.
 //! - `expected`: The expected tag that needs to be verified.
+//! - `dst_out`: Destination buffer for the exported state.
+//! - `exported`: A state exported with [`export_state()`].
 //!
 //! # Errors:
 //! An error will be returned if:
@@ -36,6 +38,11 @@
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
 //! - The calculated tag does not match the expected when verifying.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - `dst_out` or `exported` is not [`POLY1305_EXPORTSIZE`] bytes.
+//! - `exported` was not exported by a `Poly1305` with the same version of the
+//!   encoding, or is otherwise invalid.
 //!
 //! # Security:
 //! - A given key must never be used more than once. A unique [`OneTimeKey`],
@@ -43,6 +50,9 @@
 //!   it reveals enough information for an attacker to forge future authentications with the same key.
 //! - The one-time key should be generated using a CSPRNG.
 //!   [`OneTimeKey::generate()`] can be used for this.
+//! - An exported state contains the one-time key. It should be protected like
+//!   the key, and a state should never be imported more than once to
+//!   authenticate different messages.
 //!
 //! # Recommendation:
 //! - If you are unsure of whether to use HMAC or Poly1305, it is most often
@@ -60,6 +70,18 @@
 //! let tag = poly1305_state.finalize()?;
 //!
 //! assert!(Poly1305::verify(&tag, &one_time_key, msg.as_bytes()).is_ok());
+//!
+//! // Exporting the state to continue authenticating later.
+//! use orion::hazardous::mac::poly1305::POLY1305_EXPORTSIZE;
+//!
+//! let mut poly1305_state = Poly1305::new(&one_time_key);
+//! poly1305_state.update(b"Some ")?;
+//! let mut exported = [0u8; POLY1305_EXPORTSIZE];
+//! poly1305_state.export_state(&mut exported)?;
+//!
+//! let mut imported = Poly1305::import_state(&exported)?;
+//! imported.update(b"message.")?;
+//! assert_eq!(imported.finalize()?, tag);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`update()`]: struct.Poly1305.html
@@ -67,11 +89,16 @@
 //! [`finalize()`]: struct.Poly1305.html
 //! [`OneTimeKey::generate()`]: struct.OneTimeKey.html
 //! [`OneTimeKey`]: struct.OneTimeKey.html
+//! [`export_state()`]: struct.Poly1305.html
+//! [`POLY1305_EXPORTSIZE`]: constant.POLY1305_EXPORTSIZE.html
 
+use crate::hazardous::traits::Mac;
 use crate::{
     errors::UnknownCryptoError,
     util::endianness::{load_u32_le, store_u32_into_le},
+    util::state_export::{StateReader, StateWriter, STATE_EXPORT_HEADERSIZE, STATE_ID_POLY1305},
 };
+use zeroize::Zeroize;
 
 /// The blocksize which Poly1305 operates on.
 const POLY1305_BLOCKSIZE: usize = 16;
@@ -79,6 +106,12 @@ const POLY1305_BLOCKSIZE: usize = 16;
 pub const POLY1305_OUTSIZE: usize = 16;
 /// The key size for Poly1305.
 pub const POLY1305_KEYSIZE: usize = 32;
+/// The size of an exported `Poly1305` state, which holds the accumulator, the
+/// key, the buffer and leftover.
+pub const POLY1305_EXPORTSIZE: usize =
+    STATE_EXPORT_HEADERSIZE + 20 + 20 + 16 + POLY1305_BLOCKSIZE + 8;
+/// The bits of `r` that are cleared when a one-time key is loaded.
+const R_CLAMP: [u32; 5] = [0x3ffffff, 0x3ffff03, 0x3ffc0ff, 0x3f03fff, 0x00fffff];
 /// Type for a Poly1305 tag.
 type Poly1305Tag = [u8; POLY1305_OUTSIZE];
 
@@ -121,7 +154,6 @@ pub struct Poly1305 {
 
 impl Drop for Poly1305 {
     fn drop(&mut self) {
-        use zeroize::Zeroize;
         self.a.zeroize();
         self.r.zeroize();
         self.s.zeroize();
@@ -139,6 +171,8 @@ impl core::fmt::Debug for Poly1305 {
     }
 }
 
+impl_io_write_trait!(Poly1305);
+
 impl Poly1305 {
     #[rustfmt::skip]
     #[allow(clippy::cast_lossless)]
@@ -304,11 +338,11 @@ impl Poly1305 {
             is_finalized: false,
         };
 
-        state.r[0] = (load_u32_le(&one_time_key.unprotected_as_bytes()[0..4])) & 0x3ffffff;
-        state.r[1] = (load_u32_le(&one_time_key.unprotected_as_bytes()[3..7]) >> 2) & 0x3ffff03;
-        state.r[2] = (load_u32_le(&one_time_key.unprotected_as_bytes()[6..10]) >> 4) & 0x3ffc0ff;
-        state.r[3] = (load_u32_le(&one_time_key.unprotected_as_bytes()[9..13]) >> 6) & 0x3f03fff;
-        state.r[4] = (load_u32_le(&one_time_key.unprotected_as_bytes()[12..16]) >> 8) & 0x00fffff;
+        state.r[0] = (load_u32_le(&one_time_key.unprotected_as_bytes()[0..4])) & R_CLAMP[0];
+        state.r[1] = (load_u32_le(&one_time_key.unprotected_as_bytes()[3..7]) >> 2) & R_CLAMP[1];
+        state.r[2] = (load_u32_le(&one_time_key.unprotected_as_bytes()[6..10]) >> 4) & R_CLAMP[2];
+        state.r[3] = (load_u32_le(&one_time_key.unprotected_as_bytes()[9..13]) >> 6) & R_CLAMP[3];
+        state.r[4] = (load_u32_le(&one_time_key.unprotected_as_bytes()[12..16]) >> 8) & R_CLAMP[4];
 
         state.s[0] = load_u32_le(&one_time_key.unprotected_as_bytes()[16..20]);
         state.s[1] = load_u32_le(&one_time_key.unprotected_as_bytes()[20..24]);
@@ -452,6 +486,110 @@ impl Poly1305 {
             Err(UnknownCryptoError)
         }
     }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state to `dst_out`, from which it can be restored with
+    /// [`import_state()`].
+    ///
+    /// [`import_state()`]: struct.Poly1305.html#method.import_state
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut writer = StateWriter::new(dst_out, STATE_ID_POLY1305, POLY1305_EXPORTSIZE)?;
+        for word in self.a.iter().chain(self.r.iter()).chain(self.s.iter()) {
+            writer.put_u32(*word);
+        }
+        // Only the buffered data is exported, and not what was left in the
+        // buffer by previous blocks.
+        let mut buffer = [0u8; POLY1305_BLOCKSIZE];
+        buffer[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+        writer.put_bytes(&buffer);
+        buffer.zeroize();
+        writer.put_u64(self.leftover as u64);
+        writer.finish();
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Restore a state exported with [`export_state()`].
+    ///
+    /// [`export_state()`]: struct.Poly1305.html#method.export_state
+    pub fn import_state(exported: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut reader = StateReader::new(exported, STATE_ID_POLY1305, POLY1305_EXPORTSIZE)?;
+        let mut state = Self {
+            a: [0u32; 5],
+            r: [0u32; 5],
+            s: [0u32; 4],
+            leftover: 0,
+            buffer: [0u8; POLY1305_BLOCKSIZE],
+            is_finalized: false,
+        };
+        for word in state
+            .a
+            .iter_mut()
+            .chain(state.r.iter_mut())
+            .chain(state.s.iter_mut())
+        {
+            *word = reader.get_u32();
+        }
+        reader.get_bytes(&mut state.buffer);
+        let leftover = reader.get_u64();
+        reader.finish();
+
+        // update() processes the buffer as soon as it is full.
+        if leftover >= POLY1305_BLOCKSIZE as u64 {
+            return Err(UnknownCryptoError);
+        }
+        // The limbs of the accumulator are at most 27 bits between blocks, and
+        // `r` must be clamped, for process_block() not to overflow.
+        if state.a.iter().any(|limb| *limb >= 1 << 27) {
+            return Err(UnknownCryptoError);
+        }
+        if state
+            .r
+            .iter()
+            .zip(R_CLAMP.iter())
+            .any(|(r, clamp)| r & !clamp != 0)
+        {
+            return Err(UnknownCryptoError);
+        }
+        state.leftover = leftover as usize;
+
+        Ok(state)
+    }
+}
+
+impl Mac for Poly1305 {
+    type SecretKey = OneTimeKey;
+    type Tag = Tag;
+
+    fn new(secret_key: &OneTimeKey) -> Self {
+        Poly1305::new(secret_key)
+    }
+
+    fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+        self.reset();
+        Ok(())
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        self.update(data)
+    }
+
+    fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        self.finalize()
+    }
+
+    fn verify(
+        expected: &Tag,
+        secret_key: &OneTimeKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        Poly1305::verify(expected, secret_key, data)
+    }
 }
 
 // Testing public functions in the module.
@@ -566,6 +704,127 @@ mod public {
             }
         }
     }
+
+    mod test_export_state {
+        use super::*;
+
+        /// Authenticate `data` with the state exported and imported after
+        /// `split` bytes.
+        fn poly1305_with_export(key: &OneTimeKey, data: &[u8], split: usize) -> Tag {
+            let mut state = Poly1305::new(key);
+            state.update(&data[..split]).unwrap();
+            let mut exported = [0u8; POLY1305_EXPORTSIZE];
+            state.export_state(&mut exported).unwrap();
+
+            let mut imported = Poly1305::import_state(&exported).unwrap();
+            let mut exported_again = [0u8; POLY1305_EXPORTSIZE];
+            imported.export_state(&mut exported_again).unwrap();
+            assert_eq!(exported[..], exported_again[..]);
+
+            imported.update(&data[split..]).unwrap();
+            imported.finalize().unwrap()
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let key = OneTimeKey::from_slice(&[0xFFu8; POLY1305_KEYSIZE]).unwrap();
+            let data = [0xFFu8; 3 * POLY1305_BLOCKSIZE + 3];
+            let expected = Poly1305::poly1305(&key, &data).unwrap();
+            for split in [0, 1, 15, 16, 17, 40, data.len()].iter() {
+                assert_eq!(poly1305_with_export(&key, &data, *split), expected);
+            }
+        }
+
+        #[test]
+        fn test_length_err() {
+            let state = Poly1305::new(&OneTimeKey::from_slice(&[0u8; 32]).unwrap());
+            let mut exported = [0u8; POLY1305_EXPORTSIZE + 1];
+            assert!(state
+                .export_state(&mut exported[..POLY1305_EXPORTSIZE - 1])
+                .is_err());
+            assert!(state.export_state(&mut exported).is_err());
+            assert!(state
+                .export_state(&mut exported[..POLY1305_EXPORTSIZE])
+                .is_ok());
+
+            assert!(Poly1305::import_state(&exported[..POLY1305_EXPORTSIZE - 1]).is_err());
+            assert!(Poly1305::import_state(&exported).is_err());
+            assert!(Poly1305::import_state(&exported[..POLY1305_EXPORTSIZE]).is_ok());
+        }
+
+        #[test]
+        fn test_finalized_err() {
+            let mut state = Poly1305::new(&OneTimeKey::from_slice(&[0u8; 32]).unwrap());
+            let mut exported = [0u8; POLY1305_EXPORTSIZE];
+            let _ = state.finalize().unwrap();
+            assert!(state.export_state(&mut exported).is_err());
+            state.reset();
+            assert!(state.export_state(&mut exported).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_state_err() {
+            let mut exported = [0u8; POLY1305_EXPORTSIZE];
+            Poly1305::new(&OneTimeKey::from_slice(&[0u8; 32]).unwrap())
+                .export_state(&mut exported)
+                .unwrap();
+            assert!(Poly1305::import_state(&exported).is_ok());
+
+            // The version of the encoding.
+            let mut invalid = exported;
+            invalid[0] += 1;
+            assert!(Poly1305::import_state(&invalid).is_err());
+
+            // The type that exported the state.
+            let mut invalid = exported;
+            invalid[1] += 1;
+            assert!(Poly1305::import_state(&invalid).is_err());
+
+            // The most significant byte of each limb of the accumulator.
+            for limb in 0..5 {
+                let msb = STATE_EXPORT_HEADERSIZE + limb * 4 + 3;
+                let mut invalid = exported;
+                invalid[msb] = 0x07;
+                assert!(Poly1305::import_state(&invalid).is_ok());
+                invalid[msb] = 0x08;
+                assert!(Poly1305::import_state(&invalid).is_err());
+            }
+
+            // The limbs of `r` must be clamped.
+            for limb in 0..5 {
+                let lsb = STATE_EXPORT_HEADERSIZE + 20 + limb * 4;
+                let mut invalid = exported;
+                invalid[lsb..lsb + 4].copy_from_slice(&R_CLAMP[limb].to_le_bytes());
+                assert!(Poly1305::import_state(&invalid).is_ok());
+                invalid[lsb..lsb + 4].copy_from_slice(&(R_CLAMP[limb] + 1).to_le_bytes());
+                assert!(Poly1305::import_state(&invalid).is_err());
+            }
+
+            // The leftover, which follows the buffer.
+            let leftover = POLY1305_EXPORTSIZE - 8;
+            let mut invalid = exported;
+            invalid[leftover] = (POLY1305_BLOCKSIZE - 1) as u8;
+            assert!(Poly1305::import_state(&invalid).is_ok());
+            invalid[leftover] = POLY1305_BLOCKSIZE as u8;
+            assert!(Poly1305::import_state(&invalid).is_err());
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                /// Exporting and importing the state at any point should not
+                /// change the tag.
+                fn prop_export_import_same_tag(data: Vec<u8>, split: usize) -> bool {
+                    let key = OneTimeKey::generate();
+                    let split = if data.is_empty() { 0 } else { split % data.len() };
+                    poly1305_with_export(&key, &data, split) == Poly1305::poly1305(&key, &data).unwrap()
+                }
+            }
+        }
+    }
 }
 
 // Testing private functions in the module.
@@ -41,3 +41,10 @@ pub mod kdf;
 
 /// Stream ciphers.
 pub mod stream;
+
+/// Traits implemented by the primitives in `hazardous`.
+pub mod traits;
+
+#[cfg(any(feature = "digest", feature = "aead"))]
+/// Compatibility with the traits of the RustCrypto `digest` and `aead` crates.
+pub mod rustcrypto;
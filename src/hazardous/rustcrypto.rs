@@ -0,0 +1,392 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Implementations of the traits from the RustCrypto [`digest`] and [`aead`]
+//! crates, enabled by the features of the same names.
+//!
+//! The AEADs in `hazardous` implement the `aead` traits themselves. The hash
+//! functions are wrapped by the types of this module, which implement the
+//! `digest` traits with the default output size of the hash function. These
+//! types are never keyed, and are replaced by a new state whenever they are
+//! reset or finalized, so resetting and finalizing them cannot fail.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More data is hashed than the wrapped hash function supports, since the
+//!   `digest` traits cannot return an error.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "digest")] {
+//! use digest::Digest;
+//! use orion::hazardous::{hash::blake2b, rustcrypto::Blake2b512};
+//!
+//! let digest = Blake2b512::digest(b"Some data");
+//! assert_eq!(&digest[..], blake2b::Hasher::Blake2b512.digest(b"Some data")?.as_ref());
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`digest`]: https://docs.rs/digest/0.9.0/digest/
+//! [`aead`]: https://docs.rs/aead/0.3.2/aead/
+
+#![allow(deprecated)]
+
+#[cfg(feature = "digest")]
+mod digest_impls {
+    use crate::hazardous::hash::{blake2b, blake2s, blake3, sha256, sha3, sha384, sha512};
+    use crate::hazardous::traits::IncrementalHash;
+    use ::digest::generic_array::{
+        typenum::{U128, U136, U32, U48, U64, U72},
+        GenericArray,
+    };
+    use ::digest::{BlockInput, FixedOutputDirty, Reset, Update};
+
+    /// Define a type wrapping the state of a hash function that implements
+    /// `IncrementalHash`, and implement the `digest` traits for it.
+    macro_rules! impl_digest_type {
+        ($(#[$meta:meta])* $name:ident, $inner:ty, $outsize:ty, $blocksize:ty) => {
+            $(#[$meta])*
+            #[derive(Clone, Debug)]
+            pub struct $name($inner);
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self(<$inner as IncrementalHash>::new())
+                }
+            }
+
+            impl Update for $name {
+                fn update(&mut self, data: impl AsRef<[u8]>) {
+                    // The only error is hashing more data than the hash function supports.
+                    IncrementalHash::update(&mut self.0, data.as_ref())
+                        .expect("more data was hashed than the hash function supports");
+                }
+            }
+
+            impl BlockInput for $name {
+                type BlockSize = $blocksize;
+            }
+
+            impl FixedOutputDirty for $name {
+                type OutputSize = $outsize;
+
+                fn finalize_into_dirty(&mut self, out: &mut GenericArray<u8, Self::OutputSize>) {
+                    // Finalizing only fails for a state that was already finalized, and
+                    // the state is replaced by a new one below.
+                    let digest = IncrementalHash::finalize(&mut self.0)
+                        .expect("the state is never finalized twice");
+                    out.copy_from_slice(digest.as_ref());
+                    Reset::reset(self);
+                }
+            }
+
+            impl Reset for $name {
+                fn reset(&mut self) {
+                    // Replace the state instead of calling `IncrementalHash::reset()`, which can fail.
+                    *self = Self::default();
+                }
+            }
+        };
+    }
+
+    impl_digest_type!(
+        /// SHA256 implementing the traits of the `digest` crate.
+        Sha256,
+        sha256::Sha256,
+        U32,
+        U64
+    );
+    impl_digest_type!(
+        /// SHA384 implementing the traits of the `digest` crate.
+        Sha384,
+        sha384::Sha384,
+        U48,
+        U128
+    );
+    impl_digest_type!(
+        /// SHA512 implementing the traits of the `digest` crate.
+        Sha512,
+        sha512::Sha512,
+        U64,
+        U128
+    );
+    impl_digest_type!(
+        /// SHA3-256 implementing the traits of the `digest` crate.
+        Sha3_256,
+        sha3::sha3_256::Sha3_256,
+        U32,
+        U136
+    );
+    impl_digest_type!(
+        /// SHA3-512 implementing the traits of the `digest` crate.
+        Sha3_512,
+        sha3::sha3_512::Sha3_512,
+        U64,
+        U72
+    );
+    impl_digest_type!(
+        /// Unkeyed BLAKE2b-512 implementing the traits of the `digest` crate.
+        Blake2b512,
+        blake2b::Blake2b,
+        U64,
+        U128
+    );
+    impl_digest_type!(
+        /// Unkeyed BLAKE2bp-512 implementing the traits of the `digest` crate.
+        Blake2bp512,
+        blake2b::blake2bp::Blake2bp,
+        U64,
+        U128
+    );
+    impl_digest_type!(
+        /// Unkeyed BLAKE2s-256 implementing the traits of the `digest` crate.
+        Blake2s256,
+        blake2s::Blake2s,
+        U32,
+        U64
+    );
+    impl_digest_type!(
+        /// Unkeyed BLAKE3 implementing the traits of the `digest` crate.
+        Blake3,
+        blake3::Blake3,
+        U32,
+        U64
+    );
+}
+
+#[cfg(feature = "digest")]
+pub use digest_impls::*;
+
+#[cfg(feature = "aead")]
+mod aead_impls {
+    use crate::hazardous::aead::{
+        chacha20poly1305::{self, ChaCha20Poly1305},
+        xchacha20poly1305::{self, XChaCha20Poly1305},
+    };
+    use crate::hazardous::traits::Aead;
+    use ::aead::generic_array::{
+        typenum::{U0, U12, U16, U24, U32},
+        GenericArray,
+    };
+    use ::aead::{AeadInPlace, Error, Key, NewAead, Nonce, Tag};
+
+    /// Implement the `aead` traits for an AEAD that implements `Aead` and has
+    /// in-place detached functions in `$module`.
+    macro_rules! impl_aead_traits {
+        ($name:ident, $module:ident, $noncesize:ty) => {
+            impl NewAead for $name {
+                type KeySize = U32;
+
+                fn new(key: &Key<Self>) -> Self {
+                    let secret_key = $module::SecretKey::from_slice(key.as_slice()).unwrap();
+                    Aead::new(&secret_key)
+                }
+            }
+
+            impl AeadInPlace for $name {
+                type NonceSize = $noncesize;
+                type TagSize = U16;
+                type CiphertextOverhead = U0;
+
+                fn encrypt_in_place_detached(
+                    &self,
+                    nonce: &Nonce<Self::NonceSize>,
+                    associated_data: &[u8],
+                    buffer: &mut [u8],
+                ) -> Result<Tag<Self::TagSize>, Error> {
+                    let nonce = $module::Nonce::from_slice(nonce.as_slice()).unwrap();
+                    let tag = $module::seal_in_place_detached(
+                        &self.secret_key,
+                        &nonce,
+                        associated_data,
+                        buffer,
+                    )
+                    .map_err(|_| Error)?;
+
+                    Ok(GenericArray::clone_from_slice(tag.unprotected_as_bytes()))
+                }
+
+                fn decrypt_in_place_detached(
+                    &self,
+                    nonce: &Nonce<Self::NonceSize>,
+                    associated_data: &[u8],
+                    buffer: &mut [u8],
+                    tag: &Tag<Self::TagSize>,
+                ) -> Result<(), Error> {
+                    let nonce = $module::Nonce::from_slice(nonce.as_slice()).unwrap();
+                    $module::open_in_place_detached(
+                        &self.secret_key,
+                        &nonce,
+                        associated_data,
+                        buffer,
+                        tag.as_slice(),
+                    )
+                    .map_err(|_| Error)
+                }
+            }
+        };
+    }
+
+    impl_aead_traits!(ChaCha20Poly1305, chacha20poly1305, U12);
+    impl_aead_traits!(XChaCha20Poly1305, xchacha20poly1305, U24);
+}
+
+#[cfg(test)]
+mod public {
+    #[cfg(feature = "digest")]
+    mod test_digest {
+        use crate::hazardous::hash::{blake2b, blake2s, blake3, sha256, sha3, sha384, sha512};
+        use crate::hazardous::rustcrypto::*;
+        use crate::hazardous::traits::IncrementalHash;
+        use ::digest::{Digest, FixedOutputDirty, Update};
+
+        fn digest_matches<D: Digest, H: IncrementalHash>(data: &[u8])
+        where
+            H::Digest: AsRef<[u8]>,
+        {
+            let expected = <H as IncrementalHash>::digest(data).unwrap();
+
+            let mut state = D::new();
+            for chunk in data.chunks(13) {
+                Digest::update(&mut state, chunk);
+            }
+            assert_eq!(state.finalize_reset().as_slice(), expected.as_ref());
+            Digest::update(&mut state, data);
+            assert_eq!(state.finalize().as_slice(), expected.as_ref());
+            assert_eq!(D::digest(data).as_slice(), expected.as_ref());
+        }
+
+        #[test]
+        fn test_digest_matches_orion() {
+            let data = [0x61u8; 300];
+
+            digest_matches::<Sha256, sha256::Sha256>(&data);
+            digest_matches::<Sha384, sha384::Sha384>(&data);
+            digest_matches::<Sha512, sha512::Sha512>(&data);
+            digest_matches::<Sha3_256, sha3::sha3_256::Sha3_256>(&data);
+            digest_matches::<Sha3_512, sha3::sha3_512::Sha3_512>(&data);
+            digest_matches::<Blake2b512, blake2b::Blake2b>(&data);
+            digest_matches::<Blake2bp512, blake2b::blake2bp::Blake2bp>(&data);
+            digest_matches::<Blake2s256, blake2s::Blake2s>(&data);
+            digest_matches::<Blake3, blake3::Blake3>(&data);
+        }
+
+        #[test]
+        fn test_update_after_finalize_into_dirty() {
+            let expected = Blake2b512::digest(b"Tests");
+
+            let mut state = Blake2b512::default();
+            Update::update(&mut state, b"Other tests");
+            let mut out = Default::default();
+            state.finalize_into_dirty(&mut out);
+            // The state is reset when finalized, so it can be used again.
+            Update::update(&mut state, b"Tests");
+            state.finalize_into_dirty(&mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[cfg(feature = "aead")]
+    mod test_aead {
+        use crate::hazardous::aead::{chacha20poly1305, xchacha20poly1305};
+        use ::aead::generic_array::GenericArray;
+        use ::aead::{AeadInPlace, NewAead};
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_chacha20poly1305_matches_orion() {
+            let secret_key = chacha20poly1305::SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let nonce = chacha20poly1305::Nonce::from([2u8; 12]);
+            let cipher = <chacha20poly1305::ChaCha20Poly1305 as NewAead>::new(
+                GenericArray::from_slice(&[1u8; 32]),
+            );
+
+            for len in [0usize, 1, 64, 65, 300].iter() {
+                let plaintext = vec![3u8; *len];
+                let mut expected = vec![0u8; *len + 16];
+                chacha20poly1305::seal(&secret_key, &nonce, &plaintext, Some(b"ad"), &mut expected)
+                    .unwrap();
+
+                let mut buffer = plaintext.clone();
+                let tag = cipher
+                    .encrypt_in_place_detached(
+                        GenericArray::from_slice(&[2u8; 12]),
+                        b"ad",
+                        &mut buffer,
+                    )
+                    .unwrap();
+                assert_eq!(&buffer[..], &expected[..*len]);
+                assert_eq!(tag.as_slice(), &expected[*len..]);
+
+                assert!(cipher
+                    .decrypt_in_place_detached(
+                        GenericArray::from_slice(&[2u8; 12]),
+                        b"",
+                        &mut buffer,
+                        &tag
+                    )
+                    .is_err());
+                assert_eq!(&buffer[..], &expected[..*len]);
+
+                cipher
+                    .decrypt_in_place_detached(
+                        GenericArray::from_slice(&[2u8; 12]),
+                        b"ad",
+                        &mut buffer,
+                        &tag,
+                    )
+                    .unwrap();
+                assert_eq!(buffer, plaintext);
+            }
+        }
+
+        #[test]
+        fn test_xchacha20poly1305_matches_orion() {
+            let secret_key = xchacha20poly1305::SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let nonce = xchacha20poly1305::Nonce::from([2u8; 24]);
+            let cipher = <xchacha20poly1305::XChaCha20Poly1305 as NewAead>::new(
+                GenericArray::from_slice(&[1u8; 32]),
+            );
+
+            let plaintext = [3u8; 100];
+            let mut expected = [0u8; 100 + 16];
+            xchacha20poly1305::seal(&secret_key, &nonce, &plaintext, None, &mut expected).unwrap();
+
+            let mut buffer = plaintext;
+            let tag = cipher
+                .encrypt_in_place_detached(GenericArray::from_slice(&[2u8; 24]), b"", &mut buffer)
+                .unwrap();
+            assert_eq!(&buffer[..], &expected[..100]);
+            assert_eq!(tag.as_slice(), &expected[100..]);
+
+            cipher
+                .decrypt_in_place_detached(
+                    GenericArray::from_slice(&[2u8; 24]),
+                    b"",
+                    &mut buffer,
+                    &tag,
+                )
+                .unwrap();
+            assert_eq!(&buffer[..], &plaintext[..]);
+        }
+    }
+}
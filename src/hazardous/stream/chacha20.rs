@@ -91,6 +91,7 @@
 //! [`aead`]: ../../aead/index.html
 //! [`XChaCha20Poly1305`]: ../../aead/xchacha20poly1305/index.html
 use crate::errors::UnknownCryptoError;
+use crate::hazardous::traits::StreamCipher;
 use crate::util::endianness::load_u32_le;
 use crate::util::u32x4::U32x4;
 use zeroize::{Zeroize, Zeroizing};
@@ -166,19 +167,19 @@ macro_rules! DOUBLE_ROUND {
         $r3 = $r3.shl_1();
     };
 }
-pub(crate) struct ChaCha20 {
+pub(crate) struct ChaCha20State {
     state: [U32x4; 4],
     internal_counter: u32,
     is_ietf: bool,
 }
 
-impl Drop for ChaCha20 {
+impl Drop for ChaCha20State {
     fn drop(&mut self) {
         self.state.iter_mut().zeroize();
     }
 }
 
-impl ChaCha20 {
+impl ChaCha20State {
     #[allow(clippy::unreadable_literal)]
     /// Initialize either a ChaCha or HChaCha state with a `secret_key` and
     /// `nonce`.
@@ -287,7 +288,7 @@ impl ChaCha20 {
 
 /// XOR keystream into destination array using a temporary buffer for each keystream block.
 pub(crate) fn xor_keystream(
-    ctx: &mut ChaCha20,
+    ctx: &mut ChaCha20State,
     initial_counter: u32,
     tmp_block: &mut [u8],
     bytes: &mut [u8],
@@ -321,7 +322,7 @@ pub(crate) fn encrypt_in_place(
         return Err(UnknownCryptoError);
     }
 
-    let mut ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
+    let mut ctx = ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
     let mut keystream_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
     xor_keystream(&mut ctx, initial_counter, keystream_block.as_mut(), bytes)
 }
@@ -363,13 +364,224 @@ pub fn decrypt(
     encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
 }
 
+/// IETF ChaCha20 initialized with a secret key, which implements the [`StreamCipher`] trait.
+///
+/// [`StreamCipher`]: ../../traits/trait.StreamCipher.html
+#[derive(Debug)]
+pub struct ChaCha20 {
+    secret_key: SecretKey,
+}
+
+impl StreamCipher for ChaCha20 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn encrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        encrypt(&self.secret_key, nonce, initial_counter, plaintext, dst_out)
+    }
+
+    fn decrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        decrypt(
+            &self.secret_key,
+            nonce,
+            initial_counter,
+            ciphertext,
+            dst_out,
+        )
+    }
+}
+
+/// The amount of keystream bytes available for a single nonce, which is
+/// `2^32` blocks of `64` bytes each.
+const CHACHA_KEYSTREAM_MAX: u64 = (u32::max_value() as u64 + 1) * CHACHA_BLOCKSIZE as u64;
+
+/// IETF ChaCha20 keystream that can be applied incrementally and seeked to
+/// an arbitrary byte offset.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - [`seek()`] is called with a `position` greater than `2^32 * 64`.
+/// - Applying the keystream would process data past byte `2^32 * 64` of the
+///   keystream.
+/// - The length of `dst_out` is less than `plaintext` or `ciphertext`.
+///
+/// # Security:
+/// - The same rules for nonce reuse apply as for [`encrypt()`]. Seeking
+///   backwards and encrypting different data at an offset that has already been
+///   used, reuses the keystream for that data.
+/// - This does not provide any data integrity. See the [`aead`] module.
+///
+/// # Example:
+/// ```rust
+/// use orion::hazardous::stream::chacha20;
+///
+/// let secret_key = chacha20::SecretKey::generate();
+/// let nonce = chacha20::Nonce::from([0u8; 12]);
+/// let message = [1u8; 300];
+///
+/// let mut ciphertext = [0u8; 300];
+/// chacha20::encrypt(&secret_key, &nonce, 0, &message, &mut ciphertext)?;
+///
+/// // Decrypt only bytes 100..200, without processing the first 100 bytes.
+/// let mut keystream = chacha20::ChaCha20Keystream::new(&secret_key, &nonce);
+/// let mut part = [0u8; 100];
+/// keystream.seek(100)?;
+/// keystream.decrypt(&ciphertext[100..150], &mut part[..50])?;
+/// keystream.decrypt(&ciphertext[150..200], &mut part[50..])?;
+///
+/// assert_eq!(&part[..], &message[100..200]);
+/// assert_eq!(keystream.position(), 200);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`seek()`]: struct.ChaCha20Keystream.html#method.seek
+/// [`encrypt()`]: fn.encrypt.html
+/// [`aead`]: ../../aead/index.html
+pub struct ChaCha20Keystream {
+    ctx: ChaCha20State,
+    keystream_block: Zeroizing<[u8; CHACHA_BLOCKSIZE]>,
+    buffered_block: Option<u32>,
+    position: u64,
+}
+
+impl core::fmt::Debug for ChaCha20Keystream {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "ChaCha20Keystream {{ ctx: [***OMITTED***], keystream_block: [***OMITTED***], buffered_block: {:?}, position: {:?} }}",
+            self.buffered_block, self.position
+        )
+    }
+}
+
+impl ChaCha20Keystream {
+    /// Initialize a keystream positioned at byte `0`.
+    pub fn new(secret_key: &SecretKey, nonce: &Nonce) -> Self {
+        Self {
+            // .unwrap() should not be able to panic because the nonce is 12 bytes.
+            ctx: ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)
+                .unwrap(),
+            keystream_block: Zeroizing::new([0u8; CHACHA_BLOCKSIZE]),
+            buffered_block: None,
+            position: 0,
+        }
+    }
+
+    /// Return the byte offset into the keystream that will be used next.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Move to byte offset `position` of the keystream.
+    pub fn seek(&mut self, position: u64) -> Result<(), UnknownCryptoError> {
+        if position > CHACHA_KEYSTREAM_MAX {
+            return Err(UnknownCryptoError);
+        }
+
+        self.position = position;
+
+        Ok(())
+    }
+
+    /// Generate keystream block `counter` into the buffered block.
+    fn buffer_block(&mut self, counter: u32) {
+        // Each block only depends on its counter, and seek() can revisit any
+        // of them, so the number of blocks generated so far is not limited.
+        self.ctx.internal_counter = 0;
+        self.ctx
+            .keystream_block(counter, self.keystream_block.as_mut());
+        self.buffered_block = Some(counter);
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// XOR the keystream at the current position into `bytes` and advance the
+    /// position by the length of `bytes`.
+    pub fn apply_keystream(&mut self, bytes: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        match self.position.checked_add(bytes.len() as u64) {
+            Some(end) if end <= CHACHA_KEYSTREAM_MAX => (),
+            _ => return Err(UnknownCryptoError),
+        }
+
+        let mut offset = (self.position % CHACHA_BLOCKSIZE as u64) as usize;
+        for chunk in bytes.chunks_mut(CHACHA_BLOCKSIZE) {
+            // The position is less than the end checked above, so the
+            // block counter always fits in a u32.
+            let counter = (self.position / CHACHA_BLOCKSIZE as u64) as u32;
+            if self.buffered_block != Some(counter) {
+                self.buffer_block(counter);
+            }
+
+            // When starting in the middle of a block, each chunk is split
+            // over the remainder of the current block and the next one.
+            let split = core::cmp::min(CHACHA_BLOCKSIZE - offset, chunk.len());
+            xor_slices!(self.keystream_block[offset..], chunk[..split]);
+            self.position += split as u64;
+
+            if split < chunk.len() {
+                self.buffer_block(counter + 1);
+                xor_slices!(self.keystream_block, chunk[split..]);
+                self.position += (chunk.len() - split) as u64;
+            }
+
+            offset = (self.position % CHACHA_BLOCKSIZE as u64) as usize;
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt `plaintext` with the keystream at the current position and
+    /// advance the position by the length of `plaintext`.
+    pub fn encrypt(
+        &mut self,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if dst_out.len() < plaintext.len() {
+            return Err(UnknownCryptoError);
+        }
+
+        dst_out[..plaintext.len()].copy_from_slice(plaintext);
+        self.apply_keystream(&mut dst_out[..plaintext.len()])
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decrypt `ciphertext` with the keystream at the current position and
+    /// advance the position by the length of `ciphertext`.
+    pub fn decrypt(
+        &mut self,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        self.encrypt(ciphertext, dst_out)
+    }
+}
+
 #[doc(hidden)]
 /// HChaCha20 as specified in the [draft-RFC](https://github.com/bikeshedders/xchacha-rfc/blob/master).
 pub(super) fn hchacha20(
     secret_key: &SecretKey,
     nonce: &[u8],
 ) -> Result<[u8; HCHACHA_OUTSIZE], UnknownCryptoError> {
-    let mut chacha_state = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce, false)?;
+    let mut chacha_state = ChaCha20State::new(secret_key.unprotected_as_bytes(), nonce, false)?;
     let mut keystream_block = [0u8; HCHACHA_OUTSIZE];
     chacha_state.keystream_block(0, &mut keystream_block);
 
@@ -868,6 +1080,153 @@ mod public {
             hchacha_test_runner(key, nonce, expected_output);
         }
     }
+
+    mod test_keystream {
+        use super::*;
+
+        /// Apply the keystream starting at `position` in chunks of `chunk_size`
+        /// and compare against one-shot encryption from counter `0`.
+        fn keystream_matches_encrypt(position: usize, len: usize, chunk_size: usize) {
+            let secret_key = SecretKey::from_slice(&[7u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from_slice(&[3u8; IETF_CHACHA_NONCESIZE]).unwrap();
+
+            let mut expected = [0u8; 1024];
+            encrypt_in_place(&secret_key, &nonce, 0, &mut expected).unwrap();
+
+            let mut actual = [0u8; 1024];
+            let mut keystream = ChaCha20Keystream::new(&secret_key, &nonce);
+            keystream.seek(position as u64).unwrap();
+            for chunk in actual[position..position + len].chunks_mut(chunk_size) {
+                keystream.apply_keystream(chunk).unwrap();
+            }
+
+            assert_eq!(keystream.position(), (position + len) as u64);
+            assert_eq!(
+                &actual[position..position + len],
+                &expected[position..position + len]
+            );
+        }
+
+        #[test]
+        fn test_chunks_across_block_boundaries() {
+            for position in [0, 1, 31, 63, 64, 65, 127, 128, 200].iter() {
+                for chunk_size in [1, 15, 63, 64, 65, 127, 128, 300].iter() {
+                    keystream_matches_encrypt(*position, 600, *chunk_size);
+                }
+            }
+        }
+
+        #[test]
+        fn test_seek_backwards() {
+            let secret_key = SecretKey::from_slice(&[7u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from_slice(&[3u8; IETF_CHACHA_NONCESIZE]).unwrap();
+            let mut keystream = ChaCha20Keystream::new(&secret_key, &nonce);
+
+            let mut first = [0u8; 150];
+            keystream.apply_keystream(&mut first).unwrap();
+
+            let mut second = [0u8; 100];
+            keystream.seek(50).unwrap();
+            keystream.apply_keystream(&mut second).unwrap();
+
+            assert_eq!(&first[50..], &second[..]);
+            assert_eq!(keystream.position(), 150);
+        }
+
+        #[test]
+        fn test_encrypt_decrypt() {
+            let secret_key = SecretKey::from_slice(&[7u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from_slice(&[3u8; IETF_CHACHA_NONCESIZE]).unwrap();
+            let plaintext = [1u8; 100];
+            let mut ciphertext = [0u8; 100];
+            let mut dst_out_pt = [0u8; 100];
+
+            let mut keystream = ChaCha20Keystream::new(&secret_key, &nonce);
+            keystream.seek(10).unwrap();
+            keystream.encrypt(&plaintext, &mut ciphertext).unwrap();
+            assert!(keystream.encrypt(&plaintext, &mut [0u8; 99]).is_err());
+            assert_eq!(keystream.position(), 110);
+
+            keystream.seek(10).unwrap();
+            keystream.decrypt(&ciphertext, &mut dst_out_pt).unwrap();
+            assert_eq!(&dst_out_pt[..], &plaintext[..]);
+
+            // Empty input does nothing.
+            keystream.encrypt(&[], &mut []).unwrap();
+            assert_eq!(keystream.position(), 110);
+        }
+
+        #[test]
+        fn test_keystream_max() {
+            let secret_key = SecretKey::from_slice(&[7u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from_slice(&[3u8; IETF_CHACHA_NONCESIZE]).unwrap();
+            let mut keystream = ChaCha20Keystream::new(&secret_key, &nonce);
+
+            assert!(keystream.seek(CHACHA_KEYSTREAM_MAX + 1).is_err());
+            assert!(keystream.seek(u64::max_value()).is_err());
+            assert_eq!(keystream.position(), 0);
+
+            keystream.seek(CHACHA_KEYSTREAM_MAX).unwrap();
+            assert!(keystream.apply_keystream(&mut []).is_ok());
+            assert!(keystream.apply_keystream(&mut [0u8; 1]).is_err());
+            assert_eq!(keystream.position(), CHACHA_KEYSTREAM_MAX);
+
+            // The last block uses counter 2^32-1.
+            let mut expected = [0u8; CHACHA_BLOCKSIZE];
+            encrypt_in_place(&secret_key, &nonce, u32::max_value(), &mut expected).unwrap();
+
+            let mut actual = [0u8; CHACHA_BLOCKSIZE + 1];
+            keystream.seek(CHACHA_KEYSTREAM_MAX - 64).unwrap();
+            assert!(keystream.apply_keystream(&mut actual).is_err());
+            assert_eq!(keystream.position(), CHACHA_KEYSTREAM_MAX - 64);
+            keystream
+                .apply_keystream(&mut actual[..CHACHA_BLOCKSIZE])
+                .unwrap();
+            assert_eq!(&actual[..CHACHA_BLOCKSIZE], &expected[..]);
+        }
+
+        #[test]
+        fn test_keystream_blocks_generated_not_limited() {
+            let secret_key = SecretKey::from_slice(&[7u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from_slice(&[3u8; IETF_CHACHA_NONCESIZE]).unwrap();
+            let mut expected = [0u8; 2 * CHACHA_BLOCKSIZE];
+            encrypt_in_place(&secret_key, &nonce, u32::max_value() - 1, &mut expected).unwrap();
+
+            let mut keystream = ChaCha20Keystream::new(&secret_key, &nonce);
+            // As if 2^32-1 blocks had already been generated.
+            keystream.ctx.internal_counter = u32::max_value();
+            let mut actual = [0u8; 2 * CHACHA_BLOCKSIZE];
+            keystream.seek(CHACHA_KEYSTREAM_MAX - 64).unwrap();
+            keystream
+                .apply_keystream(&mut actual[CHACHA_BLOCKSIZE..])
+                .unwrap();
+            assert_eq!(&actual[CHACHA_BLOCKSIZE..], &expected[CHACHA_BLOCKSIZE..]);
+
+            // Crossing a block boundary generates two blocks in one chunk.
+            keystream.ctx.internal_counter = u32::max_value();
+            let mut actual = [0u8; 2 * CHACHA_BLOCKSIZE];
+            keystream.seek(CHACHA_KEYSTREAM_MAX - 100).unwrap();
+            keystream.apply_keystream(&mut actual[28..]).unwrap();
+            assert_eq!(&actual[28..], &expected[28..]);
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_keystream_matches_encrypt(position: u16, len: u16, chunk_size: u8) -> bool {
+                    let position = position as usize % 512;
+                    let len = len as usize % (1024 - position);
+                    let chunk_size = core::cmp::max(chunk_size as usize, 1);
+                    keystream_matches_encrypt(position, len, chunk_size);
+
+                    true
+                }
+            }
+        }
+    }
 }
 
 // Testing private functions in the module.
@@ -880,16 +1239,20 @@ mod private {
 
         #[test]
         fn test_nonce_length() {
-            assert!(ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 15], true).is_err());
-            assert!(ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 10], true).is_err());
-            assert!(
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).is_ok()
-            );
+            assert!(ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 15], true).is_err());
+            assert!(ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 10], true).is_err());
+            assert!(ChaCha20State::new(
+                &[0u8; CHACHA_KEYSIZE],
+                &[0u8; IETF_CHACHA_NONCESIZE],
+                true
+            )
+            .is_ok());
 
-            assert!(ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 15], false).is_err());
-            assert!(ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 17], false).is_err());
+            assert!(ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 15], false).is_err());
+            assert!(ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; 17], false).is_err());
             assert!(
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false).is_ok()
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false)
+                    .is_ok()
             );
         }
 
@@ -901,9 +1264,9 @@ mod private {
             quickcheck! {
                 fn prop_test_nonce_length_ietf(nonce: Vec<u8>) -> bool {
                     if nonce.len() == IETF_CHACHA_NONCESIZE {
-                        ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &nonce[..], true).is_ok()
+                        ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &nonce[..], true).is_ok()
                     } else {
-                        ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &nonce[..], true).is_err()
+                        ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &nonce[..], true).is_err()
                     }
                 }
             }
@@ -913,9 +1276,9 @@ mod private {
                 // the correct length. If it is correct length, never panic.
                 fn prop_test_nonce_length_hchacha(nonce: Vec<u8>) -> bool {
                     if nonce.len() == HCHACHA_NONCESIZE {
-                        ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &nonce, false).is_ok()
+                        ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &nonce, false).is_ok()
                     } else {
-                        ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &nonce, false).is_err()
+                        ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &nonce, false).is_err()
                     }
                 }
             }
@@ -930,7 +1293,8 @@ mod private {
         #[cfg(debug_assertions)]
         fn test_xor_keystream_err_bad_tmp() {
             let mut ctx =
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true)
+                    .unwrap();
             let mut tmp = [0u8; CHACHA_BLOCKSIZE - 1];
             let mut out = [0u8; CHACHA_BLOCKSIZE];
             xor_keystream(&mut ctx, 0, &mut tmp, &mut out).unwrap();
@@ -939,7 +1303,8 @@ mod private {
         #[test]
         fn test_xor_keystream_err_empty_input() {
             let mut ctx =
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true)
+                    .unwrap();
             let mut tmp = [0u8; CHACHA_BLOCKSIZE];
             let mut out = [0u8; 0];
             assert!(xor_keystream(&mut ctx, 0, &mut tmp, &mut out).is_err());
@@ -960,7 +1325,8 @@ mod private {
         #[test]
         fn test_xor_keystream_block_ignore_counter_when_hchacha() {
             let mut chacha_state_hchacha =
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false).unwrap();
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false)
+                    .unwrap();
 
             let mut hchacha_keystream_block_zero = [0u8; HCHACHA_OUTSIZE];
             let mut hchacha_keystream_block_max = [0u8; HCHACHA_OUTSIZE];
@@ -977,7 +1343,8 @@ mod private {
         #[should_panic]
         fn test_xor_keystream_block_invalid_blocksize_ietf() {
             let mut chacha_state_ietf =
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true)
+                    .unwrap();
 
             let mut ietf_keystream_block = [0u8; CHACHA_BLOCKSIZE];
             let mut hchacha_keystream_block = [0u8; HCHACHA_OUTSIZE];
@@ -991,7 +1358,8 @@ mod private {
         #[should_panic]
         fn test_xor_keystream_block_invalid_blocksize_hchacha() {
             let mut chacha_state_hchacha =
-                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false).unwrap();
+                ChaCha20State::new(&[0u8; CHACHA_KEYSIZE], &[0u8; HCHACHA_NONCESIZE], false)
+                    .unwrap();
 
             let mut ietf_keystream_block = [0u8; CHACHA_BLOCKSIZE];
             let mut hchacha_keystream_block = [0u8; HCHACHA_OUTSIZE];
@@ -1003,7 +1371,7 @@ mod private {
         #[test]
         #[should_panic]
         fn test_xor_keystream_panic_on_too_much_keystream_data_ietf() {
-            let mut chacha_state_ietf = ChaCha20 {
+            let mut chacha_state_ietf = ChaCha20State {
                 state: [
                     U32x4(0, 0, 0, 0),
                     U32x4(0, 0, 0, 0),
@@ -1024,7 +1392,7 @@ mod private {
         #[test]
         #[should_panic]
         fn test_xor_keystream_panic_on_too_much_keystream_data_hchacha() {
-            let mut chacha_state_ietf = ChaCha20 {
+            let mut chacha_state_ietf = ChaCha20State {
                 state: [
                     U32x4(0, 0, 0, 0),
                     U32x4(0, 0, 0, 0),
@@ -1049,17 +1417,9 @@ mod private {
 mod test_vectors {
     use super::*;
 
-    // NOTE: These PartialEq implementation should only be available in testing.
-    #[cfg(test)]
-    impl PartialEq for U32x4 {
-        fn eq(&self, other: &Self) -> bool {
-            self.0 == other.0 && self.1 == other.1 && self.2 == other.2 && self.3 == other.3
-        }
-    }
-
     // Convenience function for testing.
-    fn init(key: &[u8], nonce: &[u8]) -> Result<ChaCha20, UnknownCryptoError> {
-        Ok(ChaCha20::new(key, nonce, true)?)
+    fn init(key: &[u8], nonce: &[u8]) -> Result<ChaCha20State, UnknownCryptoError> {
+        Ok(ChaCha20State::new(key, nonce, true)?)
     }
     #[test]
     fn rfc8439_chacha20_block_results() {
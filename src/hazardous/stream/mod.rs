@@ -25,3 +25,9 @@ pub mod chacha20;
 
 /// XChaCha20 as specified in the [draft-irtf-cfrg-xchacha-03](https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03).
 pub mod xchacha20;
+
+/// Salsa20 as specified in the [Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf).
+pub mod salsa20;
+
+/// XSalsa20 as specified in [Extending the Salsa20 nonce](https://cr.yp.to/snuffle/xsalsa-20081128.pdf).
+pub mod xsalsa20;
@@ -0,0 +1,851 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `nonce`: The nonce value.
+//! - `initial_counter`: The initial counter value. In most cases, this is `0`.
+//! - `ciphertext`: The encrypted data.
+//! - `plaintext`: The data to be encrypted.
+//! - `dst_out`: Destination array that will hold the ciphertext/plaintext after
+//!   encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` or `ciphertext`.
+//! - `plaintext` or `ciphertext` is empty.
+//! - The `initial_counter` is high enough to cause a potential overflow.
+//!
+//! Even though `dst_out` is allowed to be of greater length than `plaintext`,
+//! the `ciphertext` produced by `salsa20`/`xsalsa20` will always be of the
+//! same length as the `plaintext`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than `2^32-1` keystream blocks are processed or more than `2^32-1 * 64`
+//!   bytes of data are processed.
+//!
+//! # Security:
+//! - It is critical for security that a given nonce is not re-used with a given
+//!   key. Should this happen, the security of all data that has been encrypted
+//!   with that given key is compromised.
+//! - Functions herein do not provide any data integrity. If you need
+//!   data integrity, which is nearly ***always the case***, you should use an
+//!   AEAD construction instead. See orions [`aead`] module for this.
+//! - Only a nonce for XSalsa20 is big enough to be randomly generated using a CSPRNG.
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//!
+//! # Recommendation:
+//! - Salsa20 is provided for compatibility with NaCl and libsodium. For
+//!   anything else, it is recommended to use [`XChaCha20Poly1305`].
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::stream::salsa20;
+//!
+//! let secret_key = salsa20::SecretKey::generate();
+//!
+//! // WARNING: This nonce is only meant for demonstration and should not
+//! // be repeated. Please read the security section.
+//! let nonce = salsa20::Nonce::from([0u8; 8]);
+//! let message = "Data to protect".as_bytes();
+//!
+//! // The length of this message is 15.
+//!
+//! let mut dst_out_pt = [0u8; 15];
+//! let mut dst_out_ct = [0u8; 15];
+//!
+//! salsa20::encrypt(&secret_key, &nonce, 0, message, &mut dst_out_ct)?;
+//!
+//! salsa20::decrypt(&secret_key, &nonce, 0, &dst_out_ct, &mut dst_out_pt)?;
+//!
+//! assert_eq!(dst_out_pt, message);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`SecretKey::generate()`]: struct.SecretKey.html
+//! [`aead`]: ../../aead/index.html
+//! [`XChaCha20Poly1305`]: ../../aead/xchacha20poly1305/index.html
+use crate::errors::UnknownCryptoError;
+use crate::hazardous::traits::StreamCipher;
+use crate::util::endianness::{load_u32_into_le, store_u32_into_le};
+use zeroize::{Zeroize, Zeroizing};
+
+/// The key size for Salsa20.
+pub const SALSA_KEYSIZE: usize = 32;
+/// The nonce size for Salsa20.
+pub const SALSA_NONCESIZE: usize = 8;
+/// The blocksize which Salsa20 operates on.
+pub(crate) const SALSA_BLOCKSIZE: usize = 64;
+/// The size of the subkey that HSalsa20 returns.
+const HSALSA_OUTSIZE: usize = 32;
+/// The nonce size for HSalsa20.
+pub(crate) const HSALSA_NONCESIZE: usize = 16;
+
+construct_secret_key! {
+    /// A type to represent the `SecretKey` that `salsa20`, `xsalsa20` and
+    /// `xsalsa20poly1305` use.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, SALSA_KEYSIZE, SALSA_KEYSIZE, SALSA_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, SALSA_KEYSIZE);
+
+construct_public! {
+    /// A type that represents a `Nonce` that Salsa20 uses.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 8 bytes.
+    (Nonce, test_nonce, SALSA_NONCESIZE, SALSA_NONCESIZE)
+}
+
+impl_from_trait!(Nonce, SALSA_NONCESIZE);
+
+macro_rules! QUARTER_ROUND {
+    ($s:expr, $a:expr, $b:expr, $c:expr, $d:expr) => {
+        $s[$b] ^= $s[$a].wrapping_add($s[$d]).rotate_left(7);
+        $s[$c] ^= $s[$b].wrapping_add($s[$a]).rotate_left(9);
+        $s[$d] ^= $s[$c].wrapping_add($s[$b]).rotate_left(13);
+        $s[$a] ^= $s[$d].wrapping_add($s[$c]).rotate_left(18);
+    };
+}
+
+macro_rules! DOUBLE_ROUND {
+    ($s:expr) => {
+        // Column round
+        QUARTER_ROUND!($s, 0, 4, 8, 12);
+        QUARTER_ROUND!($s, 5, 9, 13, 1);
+        QUARTER_ROUND!($s, 10, 14, 2, 6);
+        QUARTER_ROUND!($s, 15, 3, 7, 11);
+
+        // Row round
+        QUARTER_ROUND!($s, 0, 1, 2, 3);
+        QUARTER_ROUND!($s, 5, 6, 7, 4);
+        QUARTER_ROUND!($s, 10, 11, 8, 9);
+        QUARTER_ROUND!($s, 15, 12, 13, 14);
+    };
+}
+
+/// Apply `rounds` rounds of the Salsa20 permutation to `state`, without adding the
+/// input to the output. Salsa20 uses 20 rounds and scrypt uses 8.
+pub(crate) fn salsa20_rounds(state: &mut [u32; 16], rounds: usize) {
+    debug_assert_eq!(rounds % 2, 0);
+    for _ in 0..rounds / 2 {
+        DOUBLE_ROUND!(state);
+    }
+}
+
+pub(crate) struct Salsa20State {
+    state: [u32; 16],
+    internal_counter: u32,
+    is_hsalsa: bool,
+}
+
+impl Drop for Salsa20State {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+impl Salsa20State {
+    #[allow(clippy::unreadable_literal)]
+    /// Initialize either a Salsa20 or HSalsa20 state with a `secret_key` and
+    /// `nonce`.
+    pub(crate) fn new(sk: &[u8], n: &[u8], is_hsalsa: bool) -> Result<Self, UnknownCryptoError> {
+        debug_assert!(sk.len() == SALSA_KEYSIZE);
+        if (n.len() != SALSA_NONCESIZE) && !is_hsalsa {
+            return Err(UnknownCryptoError);
+        }
+        if (n.len() != HSALSA_NONCESIZE) && is_hsalsa {
+            return Err(UnknownCryptoError);
+        }
+
+        let mut state = [0u32; 16];
+        // Constants on the diagonal.
+        state[0] = 0x61707865;
+        state[5] = 0x3320646e;
+        state[10] = 0x79622d32;
+        state[15] = 0x6b206574;
+        // Secret key.
+        load_u32_into_le(&sk[..16], &mut state[1..5]);
+        load_u32_into_le(&sk[16..], &mut state[11..15]);
+        // Nonce and counter if Salsa20, but only nonce if HSalsa20.
+        // The counter is set when producing a keystream block.
+        load_u32_into_le(n, &mut state[6..(6 + n.len() / 4)]);
+
+        Ok(Self {
+            state,
+            internal_counter: 0,
+            is_hsalsa,
+        })
+    }
+
+    /// Process the next keystream and copy into destination array.
+    pub(crate) fn keystream_block(&mut self, block_counter: u32, inplace: &mut [u8]) {
+        debug_assert!(if self.is_hsalsa {
+            inplace.len() == HSALSA_OUTSIZE
+        } else {
+            inplace.len() == SALSA_BLOCKSIZE
+        });
+
+        if !self.is_hsalsa {
+            // The upper word of the 64-bit block counter is never used,
+            // since the counter cannot exceed a u32.
+            self.state[8] = block_counter;
+            self.state[9] = 0;
+        }
+
+        // If this panics, max amount of keystream blocks
+        // have been retrieved.
+        self.internal_counter = self.internal_counter.checked_add(1).unwrap();
+
+        let mut working_state = Zeroizing::new(self.state);
+        salsa20_rounds(&mut working_state, 20);
+
+        if self.is_hsalsa {
+            store_u32_into_le(
+                &[
+                    working_state[0],
+                    working_state[5],
+                    working_state[10],
+                    working_state[15],
+                    working_state[6],
+                    working_state[7],
+                    working_state[8],
+                    working_state[9],
+                ],
+                inplace,
+            );
+        } else {
+            for (word, initial) in working_state.iter_mut().zip(self.state.iter()) {
+                *word = word.wrapping_add(*initial);
+            }
+            store_u32_into_le(&working_state[..], inplace);
+        }
+    }
+}
+
+/// XOR keystream into destination array using a temporary buffer for each keystream block.
+pub(crate) fn xor_keystream(
+    ctx: &mut Salsa20State,
+    initial_counter: u32,
+    tmp_block: &mut [u8],
+    bytes: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    debug_assert!(tmp_block.len() == SALSA_BLOCKSIZE);
+    if bytes.is_empty() {
+        return Err(UnknownCryptoError);
+    }
+
+    for (ctr, out_block) in bytes.chunks_mut(SALSA_BLOCKSIZE).enumerate() {
+        match initial_counter.checked_add(ctr as u32) {
+            Some(counter) => {
+                ctx.keystream_block(counter, tmp_block);
+                xor_slices!(tmp_block, out_block);
+            }
+            None => return Err(UnknownCryptoError),
+        }
+    }
+
+    Ok(())
+}
+
+/// In-place Salsa20 encryption as specified in the [Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf).
+pub(crate) fn encrypt_in_place(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    bytes: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if bytes.is_empty() {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut ctx = Salsa20State::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), false)?;
+    let mut keystream_block = Zeroizing::new([0u8; SALSA_BLOCKSIZE]);
+    xor_keystream(&mut ctx, initial_counter, keystream_block.as_mut(), bytes)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Salsa20 encryption as specified in the [Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf).
+pub fn encrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if dst_out.len() < plaintext.len() {
+        return Err(UnknownCryptoError);
+    }
+    if plaintext.is_empty() {
+        return Err(UnknownCryptoError);
+    }
+
+    dst_out[..plaintext.len()].copy_from_slice(plaintext);
+    encrypt_in_place(
+        secret_key,
+        nonce,
+        initial_counter,
+        &mut dst_out[..plaintext.len()],
+    )
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Salsa20 decryption as specified in the [Salsa20 specification](https://cr.yp.to/snuffle/spec.pdf).
+pub fn decrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
+}
+
+/// Salsa20 initialized with a secret key, which implements the [`StreamCipher`] trait.
+///
+/// [`StreamCipher`]: ../../traits/trait.StreamCipher.html
+#[derive(Debug)]
+pub struct Salsa20 {
+    secret_key: SecretKey,
+}
+
+impl StreamCipher for Salsa20 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn encrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        encrypt(&self.secret_key, nonce, initial_counter, plaintext, dst_out)
+    }
+
+    fn decrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        decrypt(
+            &self.secret_key,
+            nonce,
+            initial_counter,
+            ciphertext,
+            dst_out,
+        )
+    }
+}
+
+#[doc(hidden)]
+/// HSalsa20 as specified in [Extending the Salsa20 nonce](https://cr.yp.to/snuffle/xsalsa-20081128.pdf).
+pub(super) fn hsalsa20(
+    secret_key: &SecretKey,
+    nonce: &[u8],
+) -> Result<[u8; HSALSA_OUTSIZE], UnknownCryptoError> {
+    let mut salsa_state = Salsa20State::new(secret_key.unprotected_as_bytes(), nonce, true)?;
+    let mut keystream_block = [0u8; HSALSA_OUTSIZE];
+    salsa_state.keystream_block(0, &mut keystream_block);
+
+    Ok(keystream_block)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[cfg(feature = "safe_api")]
+    mod test_encrypt_decrypt {
+        use super::*;
+        use crate::test_framework::streamcipher_interface::*;
+
+        impl TestingRandom for SecretKey {
+            fn gen() -> Self {
+                Self::generate()
+            }
+        }
+
+        impl TestingRandom for Nonce {
+            fn gen() -> Self {
+                let mut n = [0u8; SALSA_NONCESIZE];
+                crate::util::secure_rand_bytes(&mut n).unwrap();
+                Self::from_slice(&n).unwrap()
+            }
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_streamcipher_interface(input: Vec<u8>, counter: u32) -> bool {
+                    let secret_key = SecretKey::generate();
+                    let nonce = Nonce::from_slice(&[0u8; SALSA_NONCESIZE]).unwrap();
+                    StreamCipherTestRunner(encrypt, decrypt, secret_key, nonce, counter, &input, None);
+                    test_diff_params_diff_output(&encrypt, &decrypt);
+
+                    true
+                }
+            }
+        }
+    }
+
+    // hex crate uses Vec<u8>, so we need std.
+    mod test_hsalsa20 {
+        use super::*;
+
+        use hex::decode;
+
+        #[test]
+        fn test_nonce_length() {
+            assert!(hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 16]).is_ok());
+            assert!(hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 17]).is_err());
+            assert!(hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 15]).is_err());
+            assert!(hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 8]).is_err());
+            assert!(hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 0]).is_err());
+        }
+
+        #[test]
+        fn test_diff_keys_diff_output() {
+            let keystream1 =
+                hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 16]).unwrap();
+
+            let keystream2 =
+                hsalsa20(&SecretKey::from_slice(&[1u8; 32]).unwrap(), &[0u8; 16]).unwrap();
+
+            assert!(keystream1 != keystream2);
+        }
+
+        #[test]
+        fn test_diff_nonce_diff_output() {
+            let keystream1 =
+                hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[0u8; 16]).unwrap();
+
+            let keystream2 =
+                hsalsa20(&SecretKey::from_slice(&[0u8; 32]).unwrap(), &[1u8; 16]).unwrap();
+
+            assert!(keystream1 != keystream2);
+        }
+
+        pub fn hsalsa_test_runner(key: &str, nonce: &str, output_expected: &str) {
+            let actual: [u8; 32] = hsalsa20(
+                &SecretKey::from_slice(&decode(key).unwrap()).unwrap(),
+                &decode(nonce).unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(&actual, &decode(output_expected).unwrap()[..]);
+        }
+
+        // NaCl tests/core1.c: the first key used in the secretbox and stream
+        // tests, derived from a Curve25519 shared secret.
+        #[test]
+        fn test_nacl_core1() {
+            let key = "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742";
+            let nonce = "00000000000000000000000000000000";
+            let expected_output =
+                "1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+
+        // NaCl tests/core2.c: the subkey XSalsa20 derives from the first key
+        // and the first 16 bytes of the nonce used in the secretbox and stream tests.
+        #[test]
+        fn test_nacl_core2() {
+            let key = "1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389";
+            let nonce = "69696ee955b62b73cd62bda875fc73d6";
+            let expected_output =
+                "dc908dda0b9344a953629b733820778880f3ceb421bb61b91cbd4c3e66256ce4";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+
+        // Generated with libsodium's crypto_core_hsalsa20().
+
+        #[test]
+        fn test_case_0() {
+            let key = "e201dc062685b30e214d45b3bd90090d75948ea186e1078f577880bc13b969b9";
+            let nonce = "4b9785c39db7973784962ed105cd3bb1";
+            let expected_output =
+                "275d1241859b19deec8c8fc092e4e8da5068ed5aa37cc908a34eed3780240d51";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+        #[test]
+        fn test_case_1() {
+            let key = "c1c916461ce7a3a8db9923f9da3f8a20a4d3c6db278cb22523920ebe9494f7a8";
+            let nonce = "b00ec4d388adac60404592b3373f3c23";
+            let expected_output =
+                "f418aba55fed382f37d47c858e6c44e4de7c5b8833c92438403072972b998065";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+        #[test]
+        fn test_case_2() {
+            let key = "df60cedc98bf3388fecdd5f10a01a259a30de6d6d46c09b5e1247175659e5ef7";
+            let nonce = "a9561ac69edbf0f54d4f709a62b67fc3";
+            let expected_output =
+                "af9ebb60e4d7b101c8307e7433285232807e9f297df8f864a828107345de742b";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+        #[test]
+        fn test_case_3() {
+            let key = "22ee7eccf186b325fadf250b7747c823d364909a5b286e54ee7c22375b7278f9";
+            let nonce = "d3e64aee7eeee617db4fce5bf45a6d1f";
+            let expected_output =
+                "bb35085b2c0aa8c68985451b267a9a45cc62205d92af7837518b69e0e5a9aa03";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+        #[test]
+        fn test_case_4() {
+            let key = "14d76928002cd3d0bf0690486e388b102de9755b0d80772a14b148f43e0018ec";
+            let nonce = "d32ce8cde309f39a4cab3bf4f962a67c";
+            let expected_output =
+                "65940d7982b9e9fc75ce5c1c061db834810430d485db3e91576a8e47ed68c762";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+        #[test]
+        fn test_case_5() {
+            let key = "ce5a0e1504620db978588b0b4bec0c99bc02a5848ef371f41096de10e43df56d";
+            let nonce = "e93d4655b0c858b495d14fec93a988f3";
+            let expected_output =
+                "687d0f35d28f4ccc25abe9387eda21531657b9999ac95dc70e4950e66e78b04b";
+            hsalsa_test_runner(key, nonce, expected_output);
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    mod test_init_state {
+        use super::*;
+
+        #[test]
+        fn test_nonce_length() {
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 7], false).is_err());
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 9], false).is_err());
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 16], false).is_err());
+            assert!(
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; SALSA_NONCESIZE], false).is_ok()
+            );
+
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 15], true).is_err());
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 17], true).is_err());
+            assert!(Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; 8], true).is_err());
+            assert!(
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; HSALSA_NONCESIZE], true).is_ok()
+            );
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_test_nonce_length_salsa(nonce: Vec<u8>) -> bool {
+                    if nonce.len() == SALSA_NONCESIZE {
+                        Salsa20State::new(&[0u8; SALSA_KEYSIZE], &nonce[..], false).is_ok()
+                    } else {
+                        Salsa20State::new(&[0u8; SALSA_KEYSIZE], &nonce[..], false).is_err()
+                    }
+                }
+            }
+
+            quickcheck! {
+                // Always fail to initialize state while the nonce is not
+                // the correct length. If it is correct length, never panic.
+                fn prop_test_nonce_length_hsalsa(nonce: Vec<u8>) -> bool {
+                    if nonce.len() == HSALSA_NONCESIZE {
+                        Salsa20State::new(&[0u8; SALSA_KEYSIZE], &nonce, true).is_ok()
+                    } else {
+                        Salsa20State::new(&[0u8; SALSA_KEYSIZE], &nonce, true).is_err()
+                    }
+                }
+            }
+        }
+    }
+
+    mod test_encrypt_in_place {
+        use super::*;
+
+        #[test]
+        #[should_panic]
+        #[cfg(debug_assertions)]
+        fn test_xor_keystream_err_bad_tmp() {
+            let mut ctx =
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; SALSA_NONCESIZE], false).unwrap();
+            let mut tmp = [0u8; SALSA_BLOCKSIZE - 1];
+            let mut out = [0u8; SALSA_BLOCKSIZE];
+            xor_keystream(&mut ctx, 0, &mut tmp, &mut out).unwrap();
+        }
+
+        #[test]
+        fn test_xor_keystream_err_empty_input() {
+            let mut ctx =
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; SALSA_NONCESIZE], false).unwrap();
+            let mut tmp = [0u8; SALSA_BLOCKSIZE];
+            let mut out = [0u8; 0];
+            assert!(xor_keystream(&mut ctx, 0, &mut tmp, &mut out).is_err());
+        }
+
+        #[test]
+        fn test_enc_in_place_err_empty_input() {
+            let n = Nonce::from([0u8; SALSA_NONCESIZE]);
+            let sk = SecretKey::from([0u8; SALSA_KEYSIZE]);
+            let mut out = [0u8; 0];
+            assert!(encrypt_in_place(&sk, &n, 0, &mut out).is_err());
+        }
+    }
+
+    mod test_keystream_block {
+        use super::*;
+
+        #[test]
+        fn test_xor_keystream_block_ignore_counter_when_hsalsa() {
+            let mut salsa_state_hsalsa =
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; HSALSA_NONCESIZE], true).unwrap();
+
+            let mut hsalsa_keystream_block_zero = [0u8; HSALSA_OUTSIZE];
+            let mut hsalsa_keystream_block_max = [0u8; HSALSA_OUTSIZE];
+
+            salsa_state_hsalsa.keystream_block(0, &mut hsalsa_keystream_block_zero);
+            salsa_state_hsalsa.keystream_block(u32::max_value(), &mut hsalsa_keystream_block_max);
+
+            assert_eq!(hsalsa_keystream_block_zero, hsalsa_keystream_block_max);
+        }
+
+        #[cfg(debug_assertions)]
+        #[test]
+        #[should_panic]
+        fn test_xor_keystream_block_invalid_blocksize_salsa() {
+            let mut salsa_state =
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; SALSA_NONCESIZE], false).unwrap();
+
+            let mut salsa_keystream_block = [0u8; SALSA_BLOCKSIZE];
+            let mut hsalsa_keystream_block = [0u8; HSALSA_OUTSIZE];
+
+            salsa_state.keystream_block(0, &mut salsa_keystream_block);
+            salsa_state.keystream_block(0, &mut hsalsa_keystream_block);
+        }
+
+        #[cfg(debug_assertions)]
+        #[test]
+        #[should_panic]
+        fn test_xor_keystream_block_invalid_blocksize_hsalsa() {
+            let mut salsa_state_hsalsa =
+                Salsa20State::new(&[0u8; SALSA_KEYSIZE], &[0u8; HSALSA_NONCESIZE], true).unwrap();
+
+            let mut salsa_keystream_block = [0u8; SALSA_BLOCKSIZE];
+            let mut hsalsa_keystream_block = [0u8; HSALSA_OUTSIZE];
+
+            salsa_state_hsalsa.keystream_block(0, &mut hsalsa_keystream_block);
+            salsa_state_hsalsa.keystream_block(0, &mut salsa_keystream_block);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_xor_keystream_panic_on_too_much_keystream_data_salsa() {
+            let mut salsa_state = Salsa20State {
+                state: [0u32; 16],
+                internal_counter: (u32::max_value() - 128),
+                is_hsalsa: false,
+            };
+
+            let mut keystream_block = [0u8; SALSA_BLOCKSIZE];
+
+            for amount in 0..(128 + 1) {
+                salsa_state.keystream_block(amount, &mut keystream_block);
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_xor_keystream_panic_on_too_much_keystream_data_hsalsa() {
+            let mut salsa_state_hsalsa = Salsa20State {
+                state: [0u32; 16],
+                internal_counter: (u32::max_value() - 128),
+                is_hsalsa: true,
+            };
+
+            let mut keystream_block = [0u8; HSALSA_OUTSIZE];
+
+            for _ in 0..(128 + 1) {
+                salsa_state_hsalsa.keystream_block(0, &mut keystream_block);
+            }
+        }
+    }
+}
+
+// Testing any test vectors that aren't put into library's /tests folder.
+#[cfg(test)]
+mod test_vectors {
+    use super::*;
+
+    // Convenience function for testing.
+    fn keystream(key: &[u8], nonce: &[u8], initial_counter: u32, dst_out: &mut [u8]) {
+        let mut ctx = Salsa20State::new(key, nonce, false).unwrap();
+        let mut tmp = [0u8; SALSA_BLOCKSIZE];
+        xor_keystream(&mut ctx, initial_counter, &mut tmp, dst_out).unwrap();
+    }
+
+    #[test]
+    fn test_init_state() {
+        let key = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+            0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c,
+            0x1d, 0x1e, 0x1f, 0x20,
+        ];
+        let nonce = [0x03, 0x01, 0x04, 0x01, 0x05, 0x09, 0x02, 0x06];
+        let expected_init: [u32; 16] = [
+            0x61707865, 0x04030201, 0x08070605, 0x0c0b0a09, 0x100f0e0d, 0x3320646e, 0x01040103,
+            0x06020905, 0x00000000, 0x00000000, 0x79622d32, 0x14131211, 0x18171615, 0x1c1b1a19,
+            0x201f1e1d, 0x6b206574,
+        ];
+
+        let state = Salsa20State::new(&key, &nonce, false).unwrap();
+        assert_eq!(state.state, expected_init);
+    }
+
+    // eSTREAM Salsa20 256-bit key, Set 1, vector 0.
+    #[test]
+    fn estream_set_1_vector_0() {
+        let mut key = [0u8; 32];
+        key[0] = 0x80;
+        let expected = [
+            0xe3, 0xbe, 0x8f, 0xdd, 0x8b, 0xec, 0xa2, 0xe3, 0xea, 0x8e, 0xf9, 0x47, 0x5b, 0x29,
+            0xa6, 0xe7, 0x00, 0x39, 0x51, 0xe1, 0x09, 0x7a, 0x5c, 0x38, 0xd2, 0x3b, 0x7a, 0x5f,
+            0xad, 0x9f, 0x68, 0x44, 0xb2, 0x2c, 0x97, 0x55, 0x9e, 0x27, 0x23, 0xc7, 0xcb, 0xbd,
+            0x3f, 0xe4, 0xfc, 0x8d, 0x9a, 0x07, 0x44, 0x65, 0x2a, 0x83, 0xe7, 0x2a, 0x9c, 0x46,
+            0x18, 0x76, 0xaf, 0x4d, 0x7e, 0xf1, 0xa1, 0x17,
+        ];
+
+        let mut actual = [0u8; 64];
+        keystream(&key, &[0u8; 8], 0, &mut actual);
+        assert_eq!(actual[..], expected[..]);
+    }
+
+    // All-zero key with only the first or the last bit of the nonce set, to
+    // check the byte order of the nonce. Checked against libsodium's
+    // crypto_stream_salsa20().
+    #[test]
+    fn test_zero_key_nonce_first_bit() {
+        let expected = [
+            0x2a, 0xba, 0x3d, 0xc4, 0x5b, 0x49, 0x47, 0x00, 0x7b, 0x14, 0xc8, 0x51, 0xcd, 0x69,
+            0x44, 0x56, 0xb3, 0x03, 0xad, 0x59, 0xa4, 0x65, 0x66, 0x28, 0x03, 0x00, 0x67, 0x05,
+            0x67, 0x3d, 0x6c, 0x3e, 0x29, 0xf1, 0xd3, 0x51, 0x0d, 0xfc, 0x04, 0x05, 0x46, 0x3c,
+            0x03, 0x41, 0x4e, 0x0e, 0x07, 0xe3, 0x59, 0xf1, 0xf1, 0x81, 0x6c, 0x68, 0xb2, 0x43,
+            0x4a, 0x19, 0xd3, 0xee, 0xe0, 0x46, 0x48, 0x73,
+        ];
+
+        let mut actual = [0u8; 64];
+        keystream(&[0u8; 32], &[0x80, 0, 0, 0, 0, 0, 0, 0], 0, &mut actual);
+        assert_eq!(actual[..], expected[..]);
+    }
+
+    #[test]
+    fn test_zero_key_nonce_last_bit() {
+        let expected = [
+            0xb4, 0x7f, 0x96, 0xaa, 0x96, 0x78, 0x61, 0x35, 0x29, 0x7a, 0x3c, 0x4e, 0xc5, 0x6a,
+            0x61, 0x3d, 0x0b, 0x80, 0x09, 0x53, 0x24, 0xff, 0x43, 0x23, 0x9d, 0x68, 0x4c, 0x57,
+            0xff, 0xe4, 0x2e, 0x1c, 0x44, 0xf3, 0xcc, 0x01, 0x16, 0x13, 0xdb, 0x6c, 0xdc, 0x88,
+            0x09, 0x99, 0xa1, 0xe6, 0x5a, 0xed, 0x12, 0x87, 0xfc, 0xb1, 0x1c, 0x83, 0x9c, 0x37,
+            0x12, 0x07, 0x65, 0xaf, 0xa7, 0x3e, 0x50, 0x75,
+        ];
+
+        let mut actual = [0u8; 64];
+        keystream(&[0u8; 32], &[0, 0, 0, 0, 0, 0, 0, 0x01], 0, &mut actual);
+        assert_eq!(actual[..], expected[..]);
+    }
+
+    // Generated with libsodium's crypto_stream_salsa20_xor_ic(), first with
+    // an initial counter of 0 and then of 7.
+    #[test]
+    fn test_multiple_blocks_and_initial_counter() {
+        let key = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+            0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c,
+            0x1d, 0x1e, 0x1f, 0x20,
+        ];
+        let nonce = [0x03, 0x01, 0x04, 0x01, 0x05, 0x09, 0x02, 0x06];
+        let expected_ctr_0 = [
+            0x6e, 0xbc, 0xbd, 0xbf, 0x76, 0xfc, 0xcc, 0x64, 0xab, 0x05, 0x54, 0x2b, 0xee, 0x8a,
+            0x67, 0xcb, 0xc2, 0x8f, 0xa2, 0xe1, 0x41, 0xfb, 0xef, 0xbb, 0x3a, 0x2f, 0x9b, 0x22,
+            0x19, 0x09, 0xc8, 0xd7, 0xd4, 0x29, 0x52, 0x58, 0xcb, 0x53, 0x97, 0x70, 0xdd, 0x24,
+            0xd7, 0xac, 0x34, 0x43, 0x76, 0x9f, 0xfa, 0x27, 0xa5, 0x0e, 0x60, 0x64, 0x42, 0x64,
+            0xdc, 0x8b, 0x6b, 0x61, 0x26, 0x83, 0x37, 0x2e, 0x08, 0x5d, 0x0a, 0x12, 0xbf, 0x24,
+            0x0b, 0x18, 0x9c, 0xe2, 0xb7, 0x82, 0x89, 0x86, 0x2b, 0x56, 0xfd, 0xc9, 0xfc, 0xff,
+            0xc3, 0x3b, 0xef, 0x93, 0x25, 0xa2, 0xe8, 0x1b, 0x98, 0xfb, 0x3f, 0xb9, 0xaa, 0x04,
+            0xcf, 0x43, 0x46, 0x15, 0xce, 0xff, 0xeb, 0x98, 0x5c, 0x1c, 0xb0, 0x8d, 0x84, 0x40,
+            0xe9, 0x0b, 0x1d, 0x56, 0xdd, 0xea, 0xea, 0x16, 0xd9, 0xe1, 0x5a, 0xff, 0xff, 0x1f,
+            0x69, 0x8c, 0x48, 0x3c, 0x7a, 0x46, 0x6a, 0xf1, 0xfe, 0x06, 0x25, 0x74, 0xad, 0xfd,
+            0x2b, 0x06, 0xa6, 0x2b, 0x4d, 0x98, 0x44, 0x07, 0x19, 0xea, 0x77, 0x63, 0x85, 0xc4,
+            0x70, 0x34, 0x9a, 0x7e, 0xd6, 0x96, 0x95, 0x83, 0x46, 0x3e, 0xd5, 0xd2, 0x6b, 0x8f,
+            0xef, 0xcc, 0xb2, 0x05, 0xda, 0x0f, 0x5b, 0xfa, 0x98, 0xc7, 0x78, 0x12, 0xfe, 0x75,
+            0x6b, 0x09, 0xea, 0xcc, 0x28, 0x2a, 0xa4, 0x2f, 0x4b, 0xaf, 0xa7, 0x96, 0x33, 0x18,
+            0x90, 0x46, 0xe2, 0xb2, 0x0f, 0x35, 0xb3, 0xe0, 0xe5, 0x4a, 0xa3, 0xb9, 0x29, 0xe2,
+            0x3c, 0x0f, 0x47, 0xdc, 0x7b, 0xcd, 0x4f, 0x92, 0x8b, 0x2a, 0x97, 0x64, 0xbe, 0x7d,
+            0x4b, 0x8a, 0x50, 0xf9, 0x80, 0xa5, 0x0b, 0x35, 0xad, 0x80, 0x87, 0x37, 0x5e, 0x0c,
+            0x55, 0x6e, 0xcb, 0xe6, 0xa7, 0x16, 0x1e, 0x86, 0x53, 0xce, 0x93, 0x91, 0xe1, 0xe6,
+            0x71, 0x0e, 0xd4, 0xf1,
+        ];
+        let expected_ctr_7 = [
+            0xa3, 0x05, 0xa2, 0xb9, 0x50, 0xe1, 0x95, 0x06, 0x1a, 0x88, 0x94, 0xaa, 0x2c, 0xb1,
+            0xb7, 0xad, 0xd4, 0x42, 0x89, 0x79, 0x16, 0x70, 0x10, 0x26, 0xa4, 0xb1, 0xed, 0x64,
+            0x3f, 0x17, 0x27, 0x2d, 0xfa, 0xf1, 0xc7, 0xb1, 0xdc, 0x6e, 0x06, 0x62, 0x23, 0xfa,
+            0x35, 0xe0, 0x04, 0x6f, 0x49, 0xc4, 0xb3, 0xe6, 0x31, 0x21, 0x28, 0xde, 0x0b, 0x81,
+            0x07, 0xb4, 0x2c, 0xf6, 0x3d, 0xde, 0xde, 0x6b, 0x00, 0x7f, 0x25, 0xd7, 0x44, 0xad,
+            0x3b, 0x9a, 0x5d, 0x8c, 0x21, 0xfc, 0x42, 0x4f, 0xcc, 0xe6, 0x64, 0x56, 0x4c, 0xea,
+            0xd7, 0x25, 0x17, 0x30, 0x60, 0xe6, 0x05, 0xea, 0x50, 0x44, 0x0e, 0xd4, 0x8a, 0xd0,
+            0xcb, 0xa0, 0x5f, 0x2a, 0xec, 0x7a, 0xda, 0x49, 0x0f, 0x83, 0x7a, 0x60, 0x6d, 0xd4,
+            0x24, 0xe9, 0x5f, 0x3a, 0xae, 0x5b, 0xd6, 0x94, 0x7f, 0x6f, 0x5d, 0x21, 0x4a, 0xf8,
+            0x49, 0x2d,
+        ];
+
+        let mut actual = [0u8; 256];
+        keystream(&key, &nonce, 0, &mut actual);
+        assert_eq!(actual[..], expected_ctr_0[..]);
+
+        let mut actual = [0u8; 128];
+        keystream(&key, &nonce, 7, &mut actual);
+        assert_eq!(actual[..], expected_ctr_7[..]);
+    }
+}
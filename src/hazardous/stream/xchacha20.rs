@@ -84,7 +84,10 @@
 pub use crate::hazardous::stream::chacha20::SecretKey;
 use crate::{
     errors::UnknownCryptoError,
-    hazardous::stream::chacha20::{self, Nonce as IETFNonce, IETF_CHACHA_NONCESIZE},
+    hazardous::stream::chacha20::{
+        self, ChaCha20Keystream, Nonce as IETFNonce, IETF_CHACHA_NONCESIZE,
+    },
+    hazardous::traits::StreamCipher,
 };
 
 /// The nonce size for XChaCha20.
@@ -143,6 +146,126 @@ pub fn decrypt(
     encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
 }
 
+/// XChaCha20 initialized with a secret key, which implements the [`StreamCipher`] trait.
+///
+/// [`StreamCipher`]: ../../traits/trait.StreamCipher.html
+#[derive(Debug)]
+pub struct XChaCha20 {
+    secret_key: SecretKey,
+}
+
+impl StreamCipher for XChaCha20 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn encrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        encrypt(&self.secret_key, nonce, initial_counter, plaintext, dst_out)
+    }
+
+    fn decrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        decrypt(
+            &self.secret_key,
+            nonce,
+            initial_counter,
+            ciphertext,
+            dst_out,
+        )
+    }
+}
+
+/// XChaCha20 keystream that can be applied incrementally and seeked to an
+/// arbitrary byte offset. See [`ChaCha20Keystream`] for errors and security
+/// considerations.
+///
+/// # Example:
+/// ```rust
+/// use orion::hazardous::stream::xchacha20;
+///
+/// let secret_key = xchacha20::SecretKey::generate();
+/// let nonce = xchacha20::Nonce::generate();
+/// let mut sector = [0u8; 512];
+///
+/// let mut keystream = xchacha20::XChaCha20Keystream::new(&secret_key, &nonce);
+/// keystream.seek(512 * 7)?;
+/// keystream.apply_keystream(&mut sector)?;
+/// assert_eq!(keystream.position(), 512 * 8);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`ChaCha20Keystream`]: ../chacha20/struct.ChaCha20Keystream.html
+#[derive(Debug)]
+pub struct XChaCha20Keystream {
+    keystream: ChaCha20Keystream,
+}
+
+impl XChaCha20Keystream {
+    /// Initialize a keystream positioned at byte `0`.
+    pub fn new(secret_key: &SecretKey, nonce: &Nonce) -> Self {
+        let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+
+        Self {
+            keystream: ChaCha20Keystream::new(&subkey, &ietf_nonce),
+        }
+    }
+
+    /// Return the byte offset into the keystream that will be used next.
+    pub fn position(&self) -> u64 {
+        self.keystream.position()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Move to byte offset `position` of the keystream.
+    pub fn seek(&mut self, position: u64) -> Result<(), UnknownCryptoError> {
+        self.keystream.seek(position)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// XOR the keystream at the current position into `bytes` and advance the
+    /// position by the length of `bytes`.
+    pub fn apply_keystream(&mut self, bytes: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.keystream.apply_keystream(bytes)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt `plaintext` with the keystream at the current position and
+    /// advance the position by the length of `plaintext`.
+    pub fn encrypt(
+        &mut self,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        self.keystream.encrypt(plaintext, dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decrypt `ciphertext` with the keystream at the current position and
+    /// advance the position by the length of `ciphertext`.
+    pub fn decrypt(
+        &mut self,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        self.keystream.decrypt(ciphertext, dst_out)
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -173,6 +296,27 @@ mod public {
 
                     true
                 }
+
+                fn prop_keystream_matches_encrypt(input: Vec<u8>, counter: u8, offset: u8) -> bool {
+                    if input.is_empty() {
+                        return true;
+                    }
+
+                    let secret_key = SecretKey::generate();
+                    let nonce = Nonce::generate();
+                    let mut expected = vec![0u8; input.len()];
+                    encrypt(&secret_key, &nonce, counter as u32, &input, &mut expected).unwrap();
+
+                    // Start mid-block by first applying the keystream to a few bytes.
+                    let offset = offset as usize % input.len();
+                    let mut actual = input.clone();
+                    let mut keystream = XChaCha20Keystream::new(&secret_key, &nonce);
+                    keystream.seek(counter as u64 * 64).unwrap();
+                    keystream.apply_keystream(&mut actual[..offset]).unwrap();
+                    keystream.apply_keystream(&mut actual[offset..]).unwrap();
+
+                    actual == expected
+                }
             }
         }
     }
@@ -0,0 +1,227 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `nonce`: The nonce value.
+//! - `initial_counter`: The initial counter value. In most cases, this is `0`.
+//! - `ciphertext`: The encrypted data.
+//! - `plaintext`: The data to be encrypted.
+//! - `dst_out`: Destination array that will hold the ciphertext/plaintext after
+//!   encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` or `ciphertext`.
+//! - `plaintext` or `ciphertext` is empty.
+//! - The `initial_counter` is high enough to cause a potential overflow.
+//!
+//! Even though `dst_out` is allowed to be of greater length than `plaintext`,
+//! the `ciphertext` produced by `salsa20`/`xsalsa20` will always be of the
+//! same length as the `plaintext`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than `2^32-1 * 64` bytes of data are processed.
+//!
+//! # Security:
+//! - It is critical for security that a given nonce is not re-used with a given
+//!   key. Should this happen, the security of all data that has been encrypted
+//!   with that given key is compromised.
+//! - Functions herein do not provide any data integrity. If you need
+//!   data integrity, which is nearly ***always the case***, you should use an
+//!   AEAD construction instead. See the [`aead`] module for this.
+//! - Only a nonce for XSalsa20 is big enough to be randomly generated using a
+//!   CSPRNG. [`Nonce::generate()`] can be used for this.
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//!
+//! # Recommendation:
+//! - XSalsa20 is provided for compatibility with NaCl and libsodium. For
+//!   anything else, it is recommended to use [`XChaCha20Poly1305`].
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::stream::xsalsa20;
+//!
+//! let secret_key = xsalsa20::SecretKey::generate();
+//! let nonce = xsalsa20::Nonce::generate();
+//! let message = "Data to protect".as_bytes();
+//!
+//! // Length of this message is 15
+//!
+//! let mut dst_out_pt = [0u8; 15];
+//! let mut dst_out_ct = [0u8; 15];
+//!
+//! xsalsa20::encrypt(&secret_key, &nonce, 0, message, &mut dst_out_ct)?;
+//!
+//! xsalsa20::decrypt(&secret_key, &nonce, 0, &dst_out_ct, &mut dst_out_pt)?;
+//!
+//! assert_eq!(dst_out_pt, message);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`Nonce::generate()`]: struct.Nonce.html
+//! [`SecretKey::generate()`]: ../salsa20/struct.SecretKey.html
+//! [`XChaCha20Poly1305`]: ../../aead/xchacha20poly1305/index.html
+//! [`aead`]: ../../aead/index.html
+pub use crate::hazardous::stream::salsa20::SecretKey;
+use crate::{
+    errors::UnknownCryptoError,
+    hazardous::stream::salsa20::{self, Nonce as SalsaNonce, HSALSA_NONCESIZE},
+    hazardous::traits::StreamCipher,
+};
+
+/// The nonce size for XSalsa20.
+pub const XSALSA_NONCESIZE: usize = 24;
+
+construct_public! {
+    /// A type that represents a `Nonce` that XSalsa20 and XSalsa20Poly1305 use.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 24 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (Nonce, test_nonce, XSALSA_NONCESIZE, XSALSA_NONCESIZE, XSALSA_NONCESIZE)
+}
+
+impl_from_trait!(Nonce, XSALSA_NONCESIZE);
+
+/// Generate a subkey using HSalsa20 for XSalsa20 and corresponding nonce.
+pub(crate) fn subkey_and_nonce(secret_key: &SecretKey, nonce: &Nonce) -> (SecretKey, SalsaNonce) {
+    // .unwrap() should not be able to panic because we pass a 16-byte nonce.
+    let subkey: SecretKey = SecretKey::from(
+        salsa20::hsalsa20(secret_key, &nonce.as_ref()[..HSALSA_NONCESIZE]).unwrap(),
+    );
+
+    (
+        subkey,
+        SalsaNonce::from_slice(&nonce.as_ref()[HSALSA_NONCESIZE..]).unwrap(),
+    )
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// XSalsa20 encryption as specified in [Extending the Salsa20 nonce](https://cr.yp.to/snuffle/xsalsa-20081128.pdf).
+pub fn encrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let (subkey, salsa_nonce) = subkey_and_nonce(secret_key, nonce);
+
+    salsa20::encrypt(&subkey, &salsa_nonce, initial_counter, plaintext, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// XSalsa20 decryption as specified in [Extending the Salsa20 nonce](https://cr.yp.to/snuffle/xsalsa-20081128.pdf).
+pub fn decrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
+}
+
+/// XSalsa20 initialized with a secret key, which implements the [`StreamCipher`] trait.
+///
+/// [`StreamCipher`]: ../../traits/trait.StreamCipher.html
+#[derive(Debug)]
+pub struct XSalsa20 {
+    secret_key: SecretKey,
+}
+
+impl StreamCipher for XSalsa20 {
+    type SecretKey = SecretKey;
+    type Nonce = Nonce;
+
+    fn new(secret_key: &SecretKey) -> Self {
+        Self {
+            secret_key: SecretKey::from_slice(secret_key.unprotected_as_bytes()).unwrap(),
+        }
+    }
+
+    fn encrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        encrypt(&self.secret_key, nonce, initial_counter, plaintext, dst_out)
+    }
+
+    fn decrypt(
+        &self,
+        nonce: &Nonce,
+        initial_counter: u32,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        decrypt(
+            &self.secret_key,
+            nonce,
+            initial_counter,
+            ciphertext,
+            dst_out,
+        )
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+mod public {
+    use super::*;
+
+    mod test_encrypt_decrypt {
+        use super::*;
+        use crate::test_framework::streamcipher_interface::*;
+
+        impl TestingRandom for Nonce {
+            fn gen() -> Self {
+                Self::generate()
+            }
+        }
+
+        // Proptests. Only executed when NOT testing no_std.
+        #[cfg(feature = "safe_api")]
+        mod proptest {
+            use super::*;
+
+            quickcheck! {
+                fn prop_streamcipher_interface(input: Vec<u8>, counter: u32) -> bool {
+                    let secret_key = SecretKey::generate();
+                    let nonce = Nonce::generate();
+                    StreamCipherTestRunner(encrypt, decrypt, secret_key, nonce, counter, &input, None);
+                    test_diff_params_diff_output(&encrypt, &decrypt);
+
+                    true
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,506 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! These traits are implemented by the primitives in `hazardous`, so that code
+//! can be generic over which algorithm it uses. Every trait function behaves
+//! like the function of the same name on the implementing type, and returns the
+//! same errors. See the documentation of each type for these. `reset()` returns a
+//! `Result` in every trait, also for types whose own `reset()` cannot fail.
+//!
+//! | Trait | Implemented by |
+//! |---|---|
+//! | [`IncrementalHash`] | `Sha256`, `Sha384`, `Sha512`, `Sha3_256`, `Sha3_512`, `Blake2b`, `Blake2bp`, `Blake2s`, `Blake3` |
+//! | [`Mac`] | `Hmac`, `HmacSha256`, `HmacSha384`, `Poly1305` |
+//! | [`Xof`] | `Shake128`, `Shake256`, `Blake2xb`, `Blake3` |
+//! | [`Aead`] | `ChaCha20Poly1305`, `XChaCha20Poly1305` |
+//! | [`StreamCipher`] | `ChaCha20`, `XChaCha20`, `Salsa20`, `XSalsa20` |
+//!
+//! `Kmac128` and `Kmac256` do not implement [`Mac`], as they take a customization
+//! string and an output length in addition to the secret key. `xsalsa20poly1305` does
+//! not implement [`Aead`], as it takes no additional data and places the tag before the
+//! ciphertext.
+//!
+//! # Note:
+//! The functions of these traits share their names with the functions of the
+//! implementing types, which take precedence when called on a concrete type.
+//! Use the traits in generic code, or call them as `<Sha512 as
+//! IncrementalHash>::new()`.
+//!
+//! With the `digest` feature enabled, the hash functions implementing
+//! [`IncrementalHash`] are wrapped by types in [`rustcrypto`], which implement
+//! the traits of the RustCrypto `digest` crate for their default output size.
+//! With the `aead` feature enabled, the types implementing [`Aead`] also
+//! implement `NewAead` and `AeadInPlace` of the RustCrypto `aead` crate.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::{blake2b::Blake2b, sha512::Sha512};
+//! use orion::hazardous::traits::IncrementalHash;
+//!
+//! fn checksum<H: IncrementalHash>(parts: &[&[u8]]) -> Result<H::Digest, orion::errors::UnknownCryptoError> {
+//!     let mut state = H::new();
+//!     for part in parts {
+//!         state.update(part)?;
+//!     }
+//!     state.finalize()
+//! }
+//!
+//! let digest = checksum::<Sha512>(&[b"Some ", b"data"])?;
+//! assert_eq!(digest, Sha512::digest(b"Some data")?);
+//!
+//! let digest = checksum::<Blake2b>(&[b"Some ", b"data"])?;
+//! assert_eq!(digest, <Blake2b as IncrementalHash>::digest(b"Some data")?);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`IncrementalHash`]: trait.IncrementalHash.html
+//! [`Mac`]: trait.Mac.html
+//! [`Xof`]: trait.Xof.html
+//! [`Aead`]: trait.Aead.html
+//! [`StreamCipher`]: trait.StreamCipher.html
+//! [`rustcrypto`]: ../rustcrypto/index.html
+
+use crate::errors::UnknownCryptoError;
+
+/// A hash function with a streaming state.
+pub trait IncrementalHash: Sized {
+    /// The digest that the hash function returns.
+    type Digest;
+
+    /// Initialize a streaming state. Hash functions with a variable output size
+    /// use their largest, and keyed hash functions are not keyed.
+    fn new() -> Self;
+
+    /// Reset to the state after initialization. Keyed states keep their key.
+    fn reset(&mut self) -> Result<(), UnknownCryptoError>;
+
+    /// Update state with `data`. This can be called multiple times.
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError>;
+
+    /// Return the digest.
+    fn finalize(&mut self) -> Result<Self::Digest, UnknownCryptoError>;
+
+    /// Calculate the digest of `data`.
+    fn digest(data: &[u8]) -> Result<Self::Digest, UnknownCryptoError> {
+        let mut state = Self::new();
+        state.update(data)?;
+        state.finalize()
+    }
+}
+
+/// A MAC with a streaming state.
+pub trait Mac: Sized {
+    /// The secret key that the MAC uses.
+    type SecretKey;
+    /// The tag that the MAC returns.
+    type Tag;
+
+    /// Initialize a streaming state with `secret_key`.
+    fn new(secret_key: &Self::SecretKey) -> Self;
+
+    /// Reset to `new()` state.
+    fn reset(&mut self) -> Result<(), UnknownCryptoError>;
+
+    /// Update state with `data`. This can be called multiple times.
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError>;
+
+    /// Return the tag.
+    fn finalize(&mut self) -> Result<Self::Tag, UnknownCryptoError>;
+
+    /// Verify in constant time that `expected` is the tag of `data`.
+    fn verify(
+        expected: &Self::Tag,
+        secret_key: &Self::SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError>;
+}
+
+/// An extendable-output function with a streaming state.
+pub trait Xof: Sized {
+    /// The reader that the output is squeezed from.
+    type Reader: XofRead;
+
+    /// Initialize a streaming state. Keyed functions are not keyed, and
+    /// functions that can be given the output length are not.
+    fn new() -> Self;
+
+    /// Reset to `new()` state.
+    fn reset(&mut self) -> Result<(), UnknownCryptoError>;
+
+    /// Update state with `data`. This can be called multiple times.
+    fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError>;
+
+    /// Return a reader for the output.
+    fn finalize_xof(&mut self) -> Result<Self::Reader, UnknownCryptoError>;
+}
+
+/// A reader for the output of an extendable-output function.
+pub trait XofRead {
+    /// Fill `dest` with the next bytes of output.
+    fn squeeze(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError>;
+}
+
+/// An AEAD that has been initialized with a secret key.
+pub trait Aead: Sized {
+    /// The secret key that the AEAD uses.
+    type SecretKey;
+    /// The nonce that the AEAD uses.
+    type Nonce;
+    /// The size of the authentication tag that is appended to the ciphertext.
+    const TAGSIZE: usize;
+
+    /// Initialize the AEAD with `secret_key`.
+    fn new(secret_key: &Self::SecretKey) -> Self;
+
+    /// Encrypt and authenticate `plaintext` and the optional `ad`, writing the
+    /// ciphertext with the tag appended to `dst_out`.
+    fn seal(
+        &self,
+        nonce: &Self::Nonce,
+        plaintext: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError>;
+
+    /// Authenticate and decrypt `ciphertext_with_tag` and the optional `ad`,
+    /// writing the plaintext to `dst_out`.
+    fn open(
+        &self,
+        nonce: &Self::Nonce,
+        ciphertext_with_tag: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError>;
+}
+
+/// A stream cipher that has been initialized with a secret key.
+pub trait StreamCipher: Sized {
+    /// The secret key that the stream cipher uses.
+    type SecretKey;
+    /// The nonce that the stream cipher uses.
+    type Nonce;
+
+    /// Initialize the stream cipher with `secret_key`.
+    fn new(secret_key: &Self::SecretKey) -> Self;
+
+    /// Encrypt `plaintext` into `dst_out`, starting at the keystream block
+    /// `initial_counter`.
+    fn encrypt(
+        &self,
+        nonce: &Self::Nonce,
+        initial_counter: u32,
+        plaintext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError>;
+
+    /// Decrypt `ciphertext` into `dst_out`, starting at the keystream block
+    /// `initial_counter`.
+    fn decrypt(
+        &self,
+        nonce: &Self::Nonce,
+        initial_counter: u32,
+        ciphertext: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError>;
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::{aead, hash, mac, stream};
+
+    fn incremental_hash<H: IncrementalHash>(data: &[u8]) -> H::Digest
+    where
+        H::Digest: PartialEq + core::fmt::Debug,
+    {
+        let mut state = H::new();
+        for chunk in data.chunks(7) {
+            state.update(chunk).unwrap();
+        }
+        let digest = state.finalize().unwrap();
+        assert!(state.update(data).is_err());
+        assert!(state.finalize().is_err());
+
+        state.reset().unwrap();
+        state.update(data).unwrap();
+        assert_eq!(state.finalize().unwrap(), digest);
+        assert_eq!(H::digest(data).unwrap(), digest);
+
+        digest
+    }
+
+    fn mac<M: Mac>(secret_key: &M::SecretKey, data: &[u8]) -> M::Tag
+    where
+        M::Tag: PartialEq + core::fmt::Debug,
+    {
+        let mut state = M::new(secret_key);
+        state.update(data).unwrap();
+        let tag = state.finalize().unwrap();
+
+        state.reset().unwrap();
+        state.update(data).unwrap();
+        assert_eq!(state.finalize().unwrap(), tag);
+        assert!(M::verify(&tag, secret_key, data).is_ok());
+        assert!(M::verify(&tag, secret_key, b"Other data").is_err());
+
+        tag
+    }
+
+    fn xof<X: Xof>(data: &[u8]) -> [u8; 100] {
+        let mut state = X::new();
+        state.update(data).unwrap();
+        let mut reader = state.finalize_xof().unwrap();
+        let mut out = [0u8; 100];
+        reader.squeeze(&mut out[..33]).unwrap();
+        reader.squeeze(&mut out[33..]).unwrap();
+
+        state.reset().unwrap();
+        state.update(data).unwrap();
+        let mut other = [0u8; 100];
+        state.finalize_xof().unwrap().squeeze(&mut other).unwrap();
+        assert_eq!(out[..], other[..]);
+
+        out
+    }
+
+    fn aead<A: Aead>(secret_key: &A::SecretKey, nonce: &A::Nonce) {
+        let cipher = A::new(secret_key);
+        let plaintext = b"Some plaintext";
+        let mut ciphertext = [0u8; 14 + 16];
+        let mut decrypted = [0u8; 14];
+        assert_eq!(A::TAGSIZE, 16);
+
+        cipher
+            .seal(nonce, plaintext, Some(b"ad"), &mut ciphertext)
+            .unwrap();
+        cipher
+            .open(nonce, &ciphertext, Some(b"ad"), &mut decrypted)
+            .unwrap();
+        assert_eq!(&decrypted, plaintext);
+        assert!(cipher
+            .open(nonce, &ciphertext, None, &mut decrypted)
+            .is_err());
+    }
+
+    fn stream_cipher<S: StreamCipher>(secret_key: &S::SecretKey, nonce: &S::Nonce) {
+        let cipher = S::new(secret_key);
+        let plaintext = [1u8; 100];
+        let mut ciphertext = [0u8; 100];
+        let mut decrypted = [0u8; 100];
+
+        cipher
+            .encrypt(nonce, 1, &plaintext, &mut ciphertext)
+            .unwrap();
+        cipher
+            .decrypt(nonce, 1, &ciphertext, &mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted[..], plaintext[..]);
+        assert_ne!(ciphertext[..], plaintext[..]);
+    }
+
+    #[test]
+    fn test_incremental_hash_matches_inherent() {
+        let data = [0x61u8; 300];
+
+        assert_eq!(
+            incremental_hash::<hash::sha256::Sha256>(&data),
+            hash::sha256::Sha256::digest(&data).unwrap()
+        );
+        assert_eq!(
+            incremental_hash::<hash::sha384::Sha384>(&data),
+            hash::sha384::Sha384::digest(&data).unwrap()
+        );
+        assert_eq!(
+            incremental_hash::<hash::sha512::Sha512>(&data),
+            hash::sha512::Sha512::digest(&data).unwrap()
+        );
+        assert_eq!(
+            incremental_hash::<hash::sha3::sha3_256::Sha3_256>(&data),
+            hash::sha3::sha3_256::Sha3_256::digest(&data).unwrap()
+        );
+        assert_eq!(
+            incremental_hash::<hash::sha3::sha3_512::Sha3_512>(&data),
+            hash::sha3::sha3_512::Sha3_512::digest(&data).unwrap()
+        );
+        assert_eq!(
+            incremental_hash::<hash::blake3::Blake3>(&data),
+            hash::blake3::Blake3::digest(&data).unwrap()
+        );
+
+        let mut blake2b = hash::blake2b::Blake2b::new(None, 64).unwrap();
+        blake2b.update(&data).unwrap();
+        assert_eq!(
+            incremental_hash::<hash::blake2b::Blake2b>(&data),
+            blake2b.finalize().unwrap()
+        );
+
+        let mut blake2bp = hash::blake2b::blake2bp::Blake2bp::new(None, 64).unwrap();
+        blake2bp.update(&data).unwrap();
+        assert_eq!(
+            incremental_hash::<hash::blake2b::blake2bp::Blake2bp>(&data),
+            blake2bp.finalize().unwrap()
+        );
+
+        let mut blake2s = hash::blake2s::Blake2s::new(None, 32).unwrap();
+        blake2s.update(&data).unwrap();
+        assert_eq!(
+            incremental_hash::<hash::blake2s::Blake2s>(&data),
+            blake2s.finalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mac_matches_inherent() {
+        let data = [0x61u8; 300];
+
+        let secret_key = mac::hmac::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        assert_eq!(
+            mac::<mac::hmac::Hmac>(&secret_key, &data),
+            mac::hmac::Hmac::hmac(&secret_key, &data).unwrap()
+        );
+
+        let secret_key = mac::hmac::sha256::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        assert_eq!(
+            mac::<mac::hmac::sha256::HmacSha256>(&secret_key, &data),
+            mac::hmac::sha256::HmacSha256::hmac(&secret_key, &data).unwrap()
+        );
+
+        let secret_key = mac::hmac::sha384::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        assert_eq!(
+            mac::<mac::hmac::sha384::HmacSha384>(&secret_key, &data),
+            mac::hmac::sha384::HmacSha384::hmac(&secret_key, &data).unwrap()
+        );
+
+        let one_time_key = mac::poly1305::OneTimeKey::from_slice(&[1u8; 32]).unwrap();
+        assert_eq!(
+            mac::<mac::poly1305::Poly1305>(&one_time_key, &data),
+            mac::poly1305::Poly1305::poly1305(&one_time_key, &data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_xof_matches_inherent() {
+        let data = [0x61u8; 300];
+
+        let mut expected = [0u8; 100];
+        let mut shake128 = hash::sha3::shake128::Shake128::new();
+        shake128.update(&data).unwrap();
+        shake128.finalize().unwrap().squeeze(&mut expected).unwrap();
+        assert_eq!(
+            xof::<hash::sha3::shake128::Shake128>(&data)[..],
+            expected[..]
+        );
+
+        let mut shake256 = hash::sha3::shake256::Shake256::new();
+        shake256.update(&data).unwrap();
+        shake256.finalize().unwrap().squeeze(&mut expected).unwrap();
+        assert_eq!(
+            xof::<hash::sha3::shake256::Shake256>(&data)[..],
+            expected[..]
+        );
+
+        let mut blake2xb = hash::blake2b::blake2xb::Blake2xb::new(None, None).unwrap();
+        blake2xb.update(&data).unwrap();
+        blake2xb.finalize().unwrap().squeeze(&mut expected).unwrap();
+        assert_eq!(
+            xof::<hash::blake2b::blake2xb::Blake2xb>(&data)[..],
+            expected[..]
+        );
+
+        let mut blake3 = hash::blake3::Blake3::new();
+        blake3.update(&data).unwrap();
+        blake3
+            .finalize_xof()
+            .unwrap()
+            .squeeze(&mut expected)
+            .unwrap();
+        assert_eq!(xof::<hash::blake3::Blake3>(&data)[..], expected[..]);
+    }
+
+    #[test]
+    fn test_aead_round_trip() {
+        let secret_key = aead::chacha20poly1305::SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+        aead::<aead::chacha20poly1305::ChaCha20Poly1305>(
+            &secret_key,
+            &aead::chacha20poly1305::Nonce::from([0u8; 12]),
+        );
+        aead::<aead::xchacha20poly1305::XChaCha20Poly1305>(
+            &secret_key,
+            &aead::xchacha20poly1305::Nonce::from([0u8; 24]),
+        );
+    }
+
+    #[test]
+    fn test_aead_matches_inherent() {
+        let secret_key = aead::chacha20poly1305::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let nonce = aead::chacha20poly1305::Nonce::from([0u8; 12]);
+        let mut expected = [0u8; 14 + 16];
+        let mut actual = [0u8; 14 + 16];
+
+        aead::chacha20poly1305::seal(&secret_key, &nonce, b"Some plaintext", None, &mut expected)
+            .unwrap();
+        aead::chacha20poly1305::ChaCha20Poly1305::new(&secret_key)
+            .seal(&nonce, b"Some plaintext", None, &mut actual)
+            .unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_stream_cipher_round_trip() {
+        let secret_key = stream::chacha20::SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+        stream_cipher::<stream::chacha20::ChaCha20>(
+            &secret_key,
+            &stream::chacha20::Nonce::from([0u8; 12]),
+        );
+        stream_cipher::<stream::xchacha20::XChaCha20>(
+            &secret_key,
+            &stream::xchacha20::Nonce::from([0u8; 24]),
+        );
+
+        let secret_key = stream::salsa20::SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+        stream_cipher::<stream::salsa20::Salsa20>(
+            &secret_key,
+            &stream::salsa20::Nonce::from([0u8; 8]),
+        );
+        stream_cipher::<stream::xsalsa20::XSalsa20>(
+            &secret_key,
+            &stream::xsalsa20::Nonce::from([0u8; 24]),
+        );
+    }
+
+    #[test]
+    fn test_stream_cipher_matches_inherent() {
+        let secret_key = stream::xchacha20::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let nonce = stream::xchacha20::Nonce::from([0u8; 24]);
+        let mut expected = [0u8; 100];
+        let mut actual = [0u8; 100];
+
+        stream::xchacha20::encrypt(&secret_key, &nonce, 0, &[1u8; 100], &mut expected).unwrap();
+        stream::xchacha20::XChaCha20::new(&secret_key)
+            .encrypt(&nonce, 0, &[1u8; 100], &mut actual)
+            .unwrap();
+        assert_eq!(actual[..], expected[..]);
+    }
+}
@@ -36,12 +36,15 @@
 //! # Parameters:
 //! - `secret_key`: Secret key used to authenticate `data`.
 //! - `data`: Data to be authenticated.
+//! - `reader`: A reader from which the data to be authenticated is read until it
+//!   reaches EOF.
 //! - `expected`: The expected authentication [`Tag`].
 //!
 //! # Errors:
 //! An error will be returned if:
 //! - The calculated [`Tag`] does not match the expected.
 //! - The [`SecretKey`] supplied is less than 32 bytes or greater than 64 bytes.
+//! - An error is returned while reading from `reader`.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -62,6 +65,10 @@
 //!
 //! let expected_tag = auth::authenticate(&key, msg)?;
 //! assert!(auth::authenticate_verify(&expected_tag, &key, &msg).is_ok());
+//!
+//! // Authenticating data from a reader, such as a file or socket.
+//! let tag = auth::authenticate_reader(&key, &msg[..])?;
+//! assert_eq!(tag, expected_tag);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`SecretKey`]: struct.SecretKey.html
@@ -73,25 +80,43 @@ use crate::{
     errors::UnknownCryptoError,
     hazardous::hash::blake2b::{self, Blake2b, Digest},
 };
+use std::io::{self, Read};
 
 /// The Tag size (bytes) to be output by BLAKE2b in keyed mode.
 const BLAKE2B_TAG_SIZE: usize = 32;
 /// The minimum `SecretKey` size (bytes) to be used by BLAKE2b in keyed mode.
 const BLAKE2B_MIN_KEY_SIZE: usize = 32;
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// Authenticate a message using BLAKE2b-256 in keyed mode.
-pub fn authenticate(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+/// Initialize BLAKE2b-256 in keyed mode with `secret_key`.
+fn init_keyed_state(secret_key: &SecretKey) -> Result<Blake2b, UnknownCryptoError> {
     if secret_key.len() < BLAKE2B_MIN_KEY_SIZE {
         return Err(UnknownCryptoError);
     }
     let blake2b_secret_key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
-    let mut state = Blake2b::new(Some(&blake2b_secret_key), BLAKE2B_TAG_SIZE)?;
+    Blake2b::new(Some(&blake2b_secret_key), BLAKE2B_TAG_SIZE)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate a message using BLAKE2b-256 in keyed mode.
+pub fn authenticate(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+    let mut state = init_keyed_state(secret_key)?;
     state.update(data)?;
     let blake2b_digest = state.finalize()?;
     Tag::from_slice(blake2b_digest.as_ref())
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate a message read from `reader` using BLAKE2b-256 in keyed mode.
+pub fn authenticate_reader(
+    secret_key: &SecretKey,
+    mut reader: impl Read,
+) -> Result<Tag, UnknownCryptoError> {
+    let mut state = init_keyed_state(secret_key)?;
+    io::copy(&mut reader, &mut state).map_err(|_| UnknownCryptoError)?;
+    let blake2b_digest = state.finalize()?;
+    Tag::from_slice(blake2b_digest.as_ref())
+}
+
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Authenticate and verify a message using BLAKE2b-256 in keyed mode.
 pub fn authenticate_verify(
@@ -153,6 +178,45 @@ mod public {
         }
     }
 
+    mod test_authenticate_reader {
+        use super::*;
+
+        /// A reader that always fails.
+        struct ErrorReader;
+
+        impl Read for ErrorReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(UnknownCryptoError.into())
+            }
+        }
+
+        #[test]
+        fn test_authenticate_reader_same_as_authenticate() {
+            let sec_key = SecretKey::default();
+            let msg = [1u8; 20000];
+
+            assert_eq!(
+                authenticate_reader(&sec_key, &msg[..]).unwrap(),
+                authenticate(&sec_key, &msg).unwrap()
+            );
+            assert_eq!(
+                authenticate_reader(&sec_key, &b""[..]).unwrap(),
+                authenticate(&sec_key, b"").unwrap()
+            );
+        }
+
+        #[test]
+        fn test_authenticate_reader_key_too_small() {
+            let sec_key = SecretKey::generate(31).unwrap();
+            assert!(authenticate_reader(&sec_key, &b"Some message."[..]).is_err());
+        }
+
+        #[test]
+        fn test_authenticate_reader_read_err() {
+            assert!(authenticate_reader(&SecretKey::default(), ErrorReader).is_err());
+        }
+    }
+
     // Proptests. Only executed when NOT testing no_std.
     #[cfg(feature = "safe_api")]
     mod proptest {
@@ -191,6 +255,15 @@ mod public {
             }
         }
 
+        quickcheck! {
+            /// Authenticating from a reader should produce the same tag as
+            /// authenticating the same data directly.
+            fn prop_authenticate_reader_same_tag(input: Vec<u8>) -> bool {
+                let sk = SecretKey::default();
+                authenticate_reader(&sk, &input[..]).unwrap() == authenticate(&sk, &input[..]).unwrap()
+            }
+        }
+
         quickcheck! {
             /// Verify the bounds of 32..=64 (inclusive) for the `SecretKey` used
             /// in `authenticate/authenticate_verify`.
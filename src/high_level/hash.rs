@@ -31,9 +31,17 @@
 //!
 //! # About:
 //! - Uses BLAKE2b with an output size of 32 bytes (i.e BLAKE2b-256).
+//! - BLAKE3 is available in [`hazardous::hash::blake3`]. It is not used here,
+//!   since that would change every digest that `orion::hash` has produced.
 //!
 //! # Parameters:
 //! - `data`:  The data to be hashed.
+//! - `reader`: A reader from which the data to be hashed is read until it
+//!   reaches EOF.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - An error is returned while reading from `reader`.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -51,12 +59,20 @@
 //! use orion::hash::{digest, Digest};
 //!
 //! let hash: Digest = digest(b"Some data")?;
+//!
+//! // Hashing data from a reader, such as a file or socket.
+//! use orion::hash::digest_reader;
+//!
+//! let hash_reader: Digest = digest_reader(&b"Some data"[..])?;
+//! assert_eq!(hash, hash_reader);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`orion::pwhash`]: ../pwhash/index.html
+//! [`hazardous::hash::blake3`]: ../hazardous/hash/blake3/index.html
 
 pub use crate::hazardous::hash::blake2b::Digest;
 use crate::{errors::UnknownCryptoError, hazardous::hash::blake2b};
+use std::io::{self, Read};
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Hashing using BLAKE2b-256.
@@ -64,6 +80,14 @@ pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
     blake2b::Hasher::Blake2b256.digest(data)
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hashing using BLAKE2b-256, with the data read from `reader`.
+pub fn digest_reader(mut reader: impl Read) -> Result<Digest, UnknownCryptoError> {
+    let mut state = blake2b::Hasher::Blake2b256.init()?;
+    io::copy(&mut reader, &mut state).map_err(|_| UnknownCryptoError)?;
+    state.finalize()
+}
+
 // Testing public functions in the module.
 #[cfg(feature = "safe_api")]
 #[cfg(test)]
@@ -74,6 +98,27 @@ mod public {
     mod test_digest {
         use super::*;
 
+        /// A reader that always fails.
+        struct ErrorReader;
+
+        impl Read for ErrorReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(UnknownCryptoError.into())
+            }
+        }
+
+        #[test]
+        fn test_digest_reader_same_as_digest() {
+            let data = [1u8; 20000];
+            assert_eq!(digest_reader(&data[..]).unwrap(), digest(&data).unwrap());
+            assert_eq!(digest_reader(&b""[..]).unwrap(), digest(b"").unwrap());
+        }
+
+        #[test]
+        fn test_digest_reader_read_err() {
+            assert!(digest_reader(ErrorReader).is_err());
+        }
+
         // Proptests. Only executed when NOT testing no_std.
         #[cfg(feature = "safe_api")]
         mod proptest {
@@ -92,6 +137,14 @@ mod public {
                     digest(&input[..]).unwrap() !=  digest(b"Completely wrong input").unwrap()
                 }
             }
+
+            quickcheck! {
+                /// Hashing from a reader should produce the same output as
+                /// hashing the same data directly.
+                fn prop_digest_reader_same_result(input: Vec<u8>) -> bool {
+                    digest_reader(&input[..]).unwrap() == digest(&input[..]).unwrap()
+                }
+            }
         }
     }
 }
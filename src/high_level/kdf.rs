@@ -55,7 +55,8 @@
 //! # Security:
 //! - Choosing the correct cost parameters is important for security. Please refer to
 //!   [libsodium's docs](https://download.libsodium.org/doc/password_hashing/default_phf#guidelines-for-choosing-the-parameters)
-//! for a description of how to do this.
+//!   for a description of how to do this. The presets in [`pwhash::Params`] and
+//!   [`pwhash::calibrate()`] can also be used to choose them.
 //! - The salt should always be generated using a CSPRNG. [`Salt::default()`]
 //!   can be used for this, it will generate a [`Salt`] of 16 bytes.
 //! - The recommended minimum size for a salt is 16 bytes.
@@ -75,6 +76,8 @@
 //! ```
 //! [`Salt`]: struct.Salt.html
 //! [`Salt::default()`]: struct.Salt.html
+//! [`pwhash::Params`]: ../pwhash/struct.Params.html
+//! [`pwhash::calibrate()`]: ../pwhash/fn.calibrate.html
 
 pub use super::hltypes::{Password, Salt, SecretKey};
 use crate::{errors::UnknownCryptoError, hazardous::kdf::argon2i, pwhash::MIN_ITERATIONS};
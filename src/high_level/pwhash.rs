@@ -31,30 +31,74 @@
 //! in the user's actual passwords being disclosed as well.
 //!
 //! # About:
-//! - Uses Argon2i.
+//! - Uses Argon2id.
 //! - A salt of 16 bytes is automatically generated.
 //! - The password hash length is set to 32.
 //!
 //! [`PasswordHash`] provides two ways of retrieving the hashed password:
 //! - [`unprotected_as_encoded()`] returns the hashed password in an encoded form.
-//! The encoding specifies the settings used to hash the password.
+//!   The encoding specifies the settings used to hash the password.
 //! - [`unprotected_as_bytes()`] returns only the hashed password in raw bytes.
 //!
 //! The following is an example of how the encoded password hash might look:
 //! ```text
-//! $argon2i$v=19$m=8192,t=3,p=1$c21hbGxzYWx0$lmO1aPPy3x0CcvrKpFLi1TL/uSVJ/eO5hPHiWZFaWvY
+//! $argon2id$v=19$m=8192,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk
 //! ```
 //!
 //! See a more detailed description of the encoding format [here](https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md).
 //!
 //! # Note:
-//! This implementation only supports a single thread/lane.
+//! New password hashes are produced using Argon2id with a single lane. Encoded password hashes
+//! that use Argon2i, such as those produced by previous versions of orion, or that use more lanes,
+//! such as those produced by other Argon2 implementations, can still be verified.
+//! [`PasswordHash::from_slice()`] builds a password hash using Argon2i from its raw parts,
+//! while [`PasswordHash::from_slice_argon2id()`] does the same for Argon2id.
+//!
+//! Encoded password hashes produced with scrypt by other systems, of the form
+//! `$scrypt$ln=<log2(N)>,r=<r>,p=<p>$<salt>$<hash>`, can be verified with [`verify()`] as well.
+//! New password hashes are never produced with scrypt, so [`needs_rehash()`] always returns `true`
+//! for these.
+//!
+//! The same applies to encoded password hashes produced with bcrypt, of the form
+//! `$2b$<cost>$<salt><hash>`, where `2b` may also be `2a` or `2y`. This allows verifying the
+//! password of a user against a password hash from a legacy system, and then hashing it again
+//! with Argon2id, in a single code path.
+//!
+//! Encoded password hashes produced with PBKDF2 by Django, of the form
+//! `pbkdf2_sha256$<iterations>$<salt>$<hash>`, where `pbkdf2_sha256` may also be `pbkdf2_sha1`,
+//! and by passlib, of the form `$pbkdf2-sha256$<iterations>$<salt>$<hash>`, where `pbkdf2-sha256`
+//! may also be `pbkdf2` (using SHA1) or `pbkdf2-sha512`, can be verified in the same way.
+//!
+//! [`verify()`] verifies a password using the parameters stored in the [`PasswordHash`], so
+//! these need not be stored separately. A [`Policy`] can be given to reject password hashes
+//! whose parameters are too weak, or so expensive that verifying them would allow for
+//! denial-of-service attacks.
+//!
+//! [`Params`] provides presets for the cost parameters, and [`calibrate()`] can be used to
+//! find cost parameters that make hashing take a given amount of time on the current machine.
+//!
+//! [`hash_password_with_pepper()`] additionally mixes a [`Pepper`], a secret key kept outside of the
+//! database, into the password hash using the secret input of Argon2. The identifier of the pepper
+//! is stored in the encoded password hash as `keyid`, so that [`verify_with_pepper()`] can pick the
+//! right pepper among several, allowing peppers to be rotated while older password hashes can still be
+//! verified.
+//!
+//! [`needs_rehash()`] can be used to find out whether a password hash was produced with
+//! a different algorithm or different parameters than those currently in use. If so, the password
+//! should be hashed again with [`hash_password()`] the next time it is available, e.g. when
+//! the user logs in. [`needs_rehash_with_pepper()`] additionally checks that the password hash was
+//! produced with the current pepper.
 //!
 //! # Parameters:
 //! - `password`: The password to be hashed.
 //! - `expected`: The expected password hash.
-//! - `iterations`: Iterations cost parameter for Argon2i.
-//! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2i.
+//! - `iterations`: Iterations cost parameter for Argon2.
+//! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2.
+//! - `policy`: Optional bounds on the parameters of `expected`.
+//! - `pepper`: Secret key, and its identifier, used for hashing.
+//! - `peppers`: The peppers that `expected` may have been hashed with.
+//! - `target`: The amount of time hashing a password should take.
+//! - `max_memory`: The maximum amount of memory (in kibibytes (KiB)) calibrated parameters may use.
 //!
 //! # Errors:
 //! An error will be returned if:
@@ -62,6 +106,13 @@
 //! - `iterations` is less than 3.
 //! - The length of the `password` is greater than `u32::max_value()`.
 //! - The password hash does not match `expected`.
+//! - `expected` uses scrypt, bcrypt or PBKDF2 and is verified with [`hash_password_verify()`].
+//! - `expected` uses bcrypt or PBKDF2 and `policy` does not allow it.
+//! - The parameters of `expected` are outside the bounds of `policy`.
+//! - `expected` was hashed with a pepper and none of `peppers` has its identifier, or
+//!   it was hashed with a pepper and is verified without one.
+//! - `max_memory` is less than 8.
+//! - Hashing a password with 3 iterations and 8 KiB of memory takes longer than `target`.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -69,12 +120,20 @@
 //!
 //! # Security:
 //! - [`unprotected_as_encoded()`] and [`unprotected_as_bytes()`] should never
-//! be used to compare password hashes, as these will not run in constant-time.
-//! Either use [`pwhash::hash_password_verify`] or compare two [`PasswordHash`]es.
+//!   be used to compare password hashes, as these will not run in constant-time.
+//!   Either use [`pwhash::hash_password_verify`] or compare two [`PasswordHash`]es.
 //! - The base64 encoding and decoding operations that [`PasswordHash`] performs, do NOT run in constant-time.
 //! - Choosing the correct cost parameters is important for security. Please refer to
-//! [libsodium's docs](https://download.libsodium.org/doc/password_hashing/default_phf#guidelines-for-choosing-the-parameters)
-//! for a description of how to do this.
+//!   [libsodium's docs](https://download.libsodium.org/doc/password_hashing/default_phf#guidelines-for-choosing-the-parameters)
+//!   for a description of how to do this.
+//! - If the encoded password hashes may have been tampered with, always pass a [`Policy`] to [`verify()`].
+//!   Otherwise an attacker can choose parameters that make verification arbitrarily expensive.
+//! - A pepper must be stored separately from the password hashes, e.g. in a secrets manager or
+//!   an HSM, as it otherwise adds no protection should the database be disclosed. It should be
+//!   generated using a CSPRNG and be at least 32 bytes, which [`SecretKey::default()`] provides.
+//! - [`calibrate()`] measures a single hash for each memory size it tries, so the results vary
+//!   between runs. It should be run on the machine that will hash the passwords, and the returned
+//!   parameters should be stored along with the configuration, rather than calibrated on every startup.
 //!
 //! # Example:
 //! ```rust
@@ -84,6 +143,86 @@
 //!
 //! let hash = pwhash::hash_password(&password, 3, 1<<16)?;
 //! assert!(pwhash::hash_password_verify(&hash, &password, 3, 1<<16).is_ok());
+//!
+//! // The cost parameters have since been increased.
+//! if pwhash::needs_rehash(&hash, 4, 1<<16) {
+//!     let new_hash = pwhash::hash_password(&password, 4, 1<<16)?;
+//!     // Store `new_hash` in place of `hash`.
+//! #   assert!(!pwhash::needs_rehash(&new_hash, 4, 1<<16));
+//! }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Verifying using the parameters stored in the password hash:
+//! ```rust
+//! use orion::pwhash;
+//!
+//! let password = pwhash::Password::from_slice(b"Secret password")?;
+//! let hash = pwhash::hash_password(&password, 3, 1<<16)?;
+//!
+//! // Accept between 3 and 10 iterations and between 8 MiB and 1 GiB of memory.
+//! let policy = pwhash::Policy::new(3, 10, 1<<13, 1<<20)?;
+//! assert!(pwhash::verify(&hash, &password, Some(&policy)).is_ok());
+//!
+//! let stricter = pwhash::Policy::new(4, 10, 1<<13, 1<<20)?;
+//! assert!(pwhash::verify(&hash, &password, Some(&stricter)).is_err());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Choosing cost parameters:
+//! ```rust
+//! use orion::pwhash;
+//! use std::time::Duration;
+//!
+//! let password = pwhash::Password::from_slice(b"Secret password")?;
+//!
+//! // Use a preset.
+//! let params = pwhash::Params::INTERACTIVE;
+//! # let params = pwhash::Params::new(3, 1<<10)?;
+//! let hash = pwhash::hash_password(&password, params.iterations(), params.memory())?;
+//!
+//! // Or calibrate hashing to take around 50ms, using at most 64 MiB of memory.
+//! let params = pwhash::calibrate(Duration::from_millis(50), 1<<16)?;
+//! # let params = pwhash::Params::new(3, 1<<10)?;
+//! let hash = pwhash::hash_password(&password, params.iterations(), params.memory())?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Verifying a password hash from a legacy system, and hashing the password again with Argon2id:
+//! ```rust
+//! use orion::pwhash;
+//!
+//! let password = pwhash::Password::from_slice(b"password")?;
+//! let legacy = "$2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy";
+//! let hash = pwhash::PasswordHash::from_encoded(legacy)?;
+//!
+//! let policy = pwhash::Policy::new(3, 10, 1<<13, 1<<20)?.with_max_bcrypt_cost(12)?;
+//! pwhash::verify(&hash, &password, Some(&policy))?;
+//!
+//! if pwhash::needs_rehash(&hash, 3, 1<<16) {
+//!     let new_hash = pwhash::hash_password(&password, 3, 1<<16)?;
+//!     // Store `new_hash` in place of `hash`.
+//! }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Hashing with a pepper:
+//! ```rust
+//! use orion::pwhash::{self, Pepper, SecretKey};
+//!
+//! let password = pwhash::Password::from_slice(b"Secret password")?;
+//!
+//! let old_pepper = Pepper::new(b"2020", SecretKey::default())?;
+//! let hash = pwhash::hash_password_with_pepper(&password, &old_pepper, 3, 1<<16)?;
+//!
+//! // A new pepper is introduced, but hashes using the old one can still be verified.
+//! let new_pepper = Pepper::new(b"2021", SecretKey::default())?;
+//! let peppers = [new_pepper, old_pepper];
+//! assert!(pwhash::verify_with_pepper(&hash, &password, &peppers, None).is_ok());
+//! assert_eq!(hash.key_id(), Some(&b"2020"[..]));
+//!
+//! // The password should be hashed again with the new pepper.
+//! assert!(pwhash::needs_rehash_with_pepper(&hash, &peppers[0], 3, 1<<16));
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`PasswordHash`]: struct.PasswordHash.html
@@ -91,14 +230,32 @@
 //! [`unprotected_as_bytes()`]: struct.PasswordHash.html#method.unprotected_as_bytes
 //! [`pwhash::hash_password`]: fn.hash_password.html
 //! [`pwhash::hash_password_verify`]: fn.hash_password_verify.html
+//! [`hash_password()`]: fn.hash_password.html
+//! [`hash_password_verify()`]: fn.hash_password_verify.html
+//! [`needs_rehash()`]: fn.needs_rehash.html
+//! [`needs_rehash_with_pepper()`]: fn.needs_rehash_with_pepper.html
+//! [`verify()`]: fn.verify.html
+//! [`Policy`]: struct.Policy.html
+//! [`Params`]: struct.Params.html
+//! [`calibrate()`]: fn.calibrate.html
+//! [`Pepper`]: struct.Pepper.html
+//! [`hash_password_with_pepper()`]: fn.hash_password_with_pepper.html
+//! [`verify_with_pepper()`]: fn.verify_with_pepper.html
+//! [`SecretKey::default()`]: struct.SecretKey.html
+//! [`PasswordHash::from_slice()`]: struct.PasswordHash.html#method.from_slice
+//! [`PasswordHash::from_slice_argon2id()`]: struct.PasswordHash.html#method.from_slice_argon2id
 
-pub use super::hltypes::Password;
 use super::hltypes::Salt;
+pub use super::hltypes::{Password, SecretKey};
 use crate::{
     errors::UnknownCryptoError,
-    hazardous::kdf::argon2i::{self, LANES, MIN_MEMORY},
+    hazardous::kdf::{
+        argon2::{MIN_LANES, MIN_MEMORY},
+        argon2i, argon2id, bcrypt, pbkdf2, scrypt,
+    },
 };
-use base64::{decode_config, encode_config, STANDARD_NO_PAD};
+use base64::{decode_config, encode_config, BCRYPT, STANDARD, STANDARD_NO_PAD};
+use std::time::{Duration, Instant};
 use zeroize::Zeroizing;
 
 /// The length of the salt used for password hashing.
@@ -110,42 +267,153 @@ pub const PWHASH_LENGTH: usize = 32;
 /// Minimum amount of iterations.
 pub(crate) const MIN_ITERATIONS: u32 = 3;
 
-/// A type to represent the `PasswordHash` that Argon2i returns when used for password hashing.
+/// The amount of lanes used when hashing new passwords.
+const LANES: u32 = 1;
+
+/// The maximum amount of lanes accepted in an encoded password hash. Argon2
+/// itself allows up to `2^24-1` lanes, but encoded password hashes may come
+/// from untrusted storage and no realistic configuration uses more than this.
+pub(crate) const MAX_LANES: u32 = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The algorithms that a `PasswordHash` can be produced with.
+enum Algorithm {
+    Argon2i,
+    Argon2id,
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    Bcrypt { cost: u32 },
+    Pbkdf2 { prf: Pbkdf2Prf },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The HMAC variants that a `PasswordHash` using PBKDF2 can be produced with.
+enum Pbkdf2Prf {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Pbkdf2Prf {
+    /// The length of the password hash, which is the output size of the hash function.
+    fn hash_length(self) -> usize {
+        match self {
+            Pbkdf2Prf::Sha1 => 20,
+            Pbkdf2Prf::Sha256 => 32,
+            Pbkdf2Prf::Sha512 => 64,
+        }
+    }
+}
+
+impl Algorithm {
+    /// The identifier used for this algorithm in an encoded password hash.
+    fn identifier(self) -> &'static str {
+        match self {
+            Algorithm::Argon2i => "argon2i",
+            Algorithm::Argon2id => "argon2id",
+            Algorithm::Scrypt { .. } => "scrypt",
+            Algorithm::Bcrypt { .. } => "2b",
+            Algorithm::Pbkdf2 { prf } => match prf {
+                Pbkdf2Prf::Sha1 => "pbkdf2",
+                Pbkdf2Prf::Sha256 => "pbkdf2-sha256",
+                Pbkdf2Prf::Sha512 => "pbkdf2-sha512",
+            },
+        }
+    }
+}
+
+/// The algorithm used when hashing new passwords.
+const ALGORITHM: Algorithm = Algorithm::Argon2id;
+
+/// The maximum length of a pepper identifier.
+pub const MAX_KEY_ID_LENGTH: usize = 8;
+
+/// A secret key that is mixed into password hashes, along with an identifier
+/// that is stored in the encoded password hash.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `key_id` is empty or longer than [`MAX_KEY_ID_LENGTH`].
+///
+/// [`MAX_KEY_ID_LENGTH`]: constant.MAX_KEY_ID_LENGTH.html
+pub struct Pepper {
+    key_id: Vec<u8>,
+    secret: SecretKey,
+}
+
+impl Pepper {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a pepper from an identifier and secret key.
+    pub fn new(key_id: &[u8], secret: SecretKey) -> Result<Self, UnknownCryptoError> {
+        if key_id.is_empty() || key_id.len() > MAX_KEY_ID_LENGTH {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            key_id: key_id.into(),
+            secret,
+        })
+    }
+
+    /// Return the identifier of the pepper.
+    pub fn key_id(&self) -> &[u8] {
+        &self.key_id
+    }
+}
+
+impl core::fmt::Debug for Pepper {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Pepper {{ key_id: {:?}, secret: [***OMITTED***] }}",
+            self.key_id
+        )
+    }
+}
+
+/// A type to represent the `PasswordHash` that Argon2 returns when used for password hashing.
 ///
 ///  
 /// # Errors:
 /// An error will be returned if:
 /// - The encoded password hash contains whitespace.
-/// - The encoded password hash has a parallelism count other than 1.
-/// - The encoded password contains any other fields than: The algorithm name,
-/// version, m, t, p and the salt and password hash.
+/// - The encoded password hash has a parallelism count less than 1 or greater than 255.
+/// - The encoded password hash contains any other fields than: The algorithm name,
+///   version, m, t, p, an optional keyid and the salt and password hash.
+/// - The keyid is empty or longer than [`MAX_KEY_ID_LENGTH`] bytes.
 /// - The encoded password hash contains invalid Base64 encoding.
 /// - Any decimal parameter value, such as m, contains leading zeroes and is longer
-/// than a single character.
+///   than a single character.
 /// - `iterations` is less than 3.
-/// - `memory` is less than 8.
-/// - `password` is not 32 bytes.
-/// - `salt` is not 16 bytes.
+/// - `memory` is less than 8 times the parallelism count.
+/// - `password` is not 32 bytes, or 23 bytes for bcrypt, or the output size of the hash function for PBKDF2.
+/// - `salt` is not 16 bytes, or is empty for PBKDF2.
 /// - The encoded password hash contains numerical values that cannot
-/// be represented as a `u32`.
+///   be represented as a `u32`.
 /// - The encoded password hash length is less than [`MIN_ENCODED_LEN`] or greater than [`MAX_ENCODED_LEN`].
+/// - The encoded password hash uses another algorithm than Argon2i, Argon2id, scrypt, bcrypt or PBKDF2.
 /// - The parameters in the encoded password hash are not correctly ordered. The ordering must be:
-/// `$argon2i$v=19$m=<value>,t=<value>,p=<value>$<salt>$<hash>`
+///   `$argon2id$v=19$m=<value>,t=<value>,p=<value>[,keyid=<value>]$<salt>$<hash>`, where `argon2id` may also be `argon2i`,
+///   or `$scrypt$ln=<value>,r=<value>,p=<value>$<salt>$<hash>`, or `$2b$<cost>$<salt><hash>`, where `2b` may also be `2a` or `2y`,
+///   or `pbkdf2_sha256$<iterations>$<salt>$<hash>`, where `pbkdf2_sha256` may also be `pbkdf2_sha1`,
+///   or `$pbkdf2-sha256$<iterations>$<salt>$<hash>`, where `pbkdf2-sha256` may also be `pbkdf2` or `pbkdf2-sha512`.
+/// - The scrypt parameter ln is less than 1 or greater than 63, or r or p is less than 1.
+/// - The bcrypt cost is not two decimal digits, or is less than 4 or greater than 31.
+/// - The PBKDF2 iterations are 0.
 /// # Panics:
 /// A panic will occur if:
 /// - Overflowing calculations happen on `usize` when decoding the password and salt from Base64.
 ///
 /// # Security:
 /// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
-/// that the type implements.
+///   that the type implements.
 /// - Never use `unprotected_as_bytes()` or `unprotected_as_encoded()` to compare password hashes,
-/// as that will not run in constant-time. Compare `PasswordHash`es directly using `==` instead.
+///   as that will not run in constant-time. Compare `PasswordHash`es directly using `==` instead.
 /// - The base64 encoding and decoding operations that `PasswordHash` performs, do NOT run in constant-time.
 /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
-/// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
-/// is implemented in such a way that the comparison happens in constant time. Thus, users should
-/// prefer `SecretType == &[u8]` over `SecretType.unprotected_as_bytes() == &[u8]`.
-/// Examples are shown below. The examples apply to any type that implements `PartialEq<&'_ [u8]>`.
+///   to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
+///   is implemented in such a way that the comparison happens in constant time. Thus, users should
+///   prefer `SecretType == &[u8]` over `SecretType.unprotected_as_bytes() == &[u8]`.
+///   Examples are shown below. The examples apply to any type that implements `PartialEq<&'_ [u8]>`.
 /// ```rust
 /// use orion::hazardous::mac::hmac::Tag;
 /// # use orion::errors::UnknownCryptoError;
@@ -164,23 +432,30 @@ pub(crate) const MIN_ITERATIONS: u32 = 3;
 /// ```
 /// [`MIN_ENCODED_LEN`]: struct.PasswordHash.html#associatedconstant.MIN_ENCODED_LEN
 /// [`MAX_ENCODED_LEN`]: struct.PasswordHash.html#associatedconstant.MAX_ENCODED_LEN
+/// [`MAX_KEY_ID_LENGTH`]: constant.MAX_KEY_ID_LENGTH.html
 pub struct PasswordHash {
     encoded_password_hash: String,
+    algorithm: Algorithm,
     password_hash: Vec<u8>,
     salt: Salt,
     iterations: u32,
     memory: u32,
+    lanes: u32,
+    key_id: Option<Vec<u8>>,
 }
 
 #[allow(clippy::len_without_is_empty)]
 impl PasswordHash {
-    /// Given a 16-byte salt (22 characters encoded) and 32-byte password hash (43 characters encoded),
-    /// and parameters (m, t) in decimal representation of 1..10 in length, 92 is the minimum length for an encoded password hash.
-    pub const MIN_ENCODED_LEN: usize = 92;
+    /// Given a 1-byte salt (2 characters encoded), 20-byte password hash (27 characters encoded)
+    /// and a single iteration, 40 is the minimum length for an encoded password hash, which is
+    /// that of one using PBKDF2-HMAC-SHA1 in the format of passlib.
+    pub const MIN_ENCODED_LEN: usize = 40;
 
-    /// Given a 16-byte salt (22 characters encoded) and 32-byte password hash (43 characters encoded),
-    /// and parameters (m, t) in decimal representation of 1..10 in length, 110 is the maximum length for an encoded password hash.
-    pub const MAX_ENCODED_LEN: usize = 110;
+    /// Given a 16-byte salt (22 characters encoded), 64-byte password hash (86 characters encoded)
+    /// and iterations in decimal representation of 1..10 in length, 135 is the maximum length for an
+    /// encoded password hash, which is that of one using PBKDF2-HMAC-SHA512 in the format of passlib.
+    /// An encoded password hash using Argon2id, with an 8-byte keyid, is at most 131 in length.
+    pub const MAX_ENCODED_LEN: usize = 135;
 
     /// Parse a decimal parameter value to a u32. Returns an error on overflow
     /// and if the value has leading zeroes.
@@ -196,23 +471,83 @@ impl PasswordHash {
     }
 
     /// Encode password hash, salt and parameters for storage.
-    fn encode(password_hash: &[u8], salt: &[u8], iterations: u32, memory: u32) -> String {
+    fn encode(
+        algorithm: Algorithm,
+        password_hash: &[u8],
+        salt: &[u8],
+        iterations: u32,
+        memory: u32,
+        lanes: u32,
+        key_id: Option<&[u8]>,
+    ) -> String {
+        let key_id = match key_id {
+            Some(id) => format!(",keyid={}", encode_config(id, STANDARD_NO_PAD)),
+            None => String::new(),
+        };
+
         format!(
-            "$argon2i$v=19$m={},t={},p=1${}${}",
+            "${}$v=19$m={},t={},p={}{}${}${}",
+            algorithm.identifier(),
             memory,
             iterations,
+            lanes,
+            key_id,
             encode_config(salt, STANDARD_NO_PAD),
             encode_config(password_hash, STANDARD_NO_PAD)
         )
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    /// Construct from given byte slice and parameters.
+    /// Construct from given byte slice and parameters. The password hash is assumed to
+    /// have been produced with Argon2i, as by previous versions of orion. Use
+    /// [`from_slice_argon2id()`] for password hashes produced with Argon2id.
+    ///
+    /// [`from_slice_argon2id()`]: struct.PasswordHash.html#method.from_slice_argon2id
     pub fn from_slice(
         password_hash: &[u8],
         salt: &[u8],
         iterations: u32,
         memory: u32,
+    ) -> Result<Self, UnknownCryptoError> {
+        Self::from_slice_with_key_id(
+            Algorithm::Argon2i,
+            password_hash,
+            salt,
+            iterations,
+            memory,
+            None,
+        )
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Construct from given byte slice and parameters of a password hash produced with
+    /// Argon2id, such as by [`hash_password()`].
+    ///
+    /// [`hash_password()`]: fn.hash_password.html
+    pub fn from_slice_argon2id(
+        password_hash: &[u8],
+        salt: &[u8],
+        iterations: u32,
+        memory: u32,
+    ) -> Result<Self, UnknownCryptoError> {
+        Self::from_slice_with_key_id(
+            Algorithm::Argon2id,
+            password_hash,
+            salt,
+            iterations,
+            memory,
+            None,
+        )
+    }
+
+    /// Construct from given byte slice, parameters and the identifier of the pepper used.
+    fn from_slice_with_key_id(
+        algorithm: Algorithm,
+        password_hash: &[u8],
+        salt: &[u8],
+        iterations: u32,
+        memory: u32,
+        key_id: Option<&[u8]>,
     ) -> Result<Self, UnknownCryptoError> {
         if password_hash.len() != PWHASH_LENGTH {
             return Err(UnknownCryptoError);
@@ -227,14 +562,25 @@ impl PasswordHash {
             return Err(UnknownCryptoError);
         }
 
-        let encoded_password_hash = Self::encode(password_hash, salt, iterations, memory);
+        let encoded_password_hash = Self::encode(
+            algorithm,
+            password_hash,
+            salt,
+            iterations,
+            memory,
+            LANES,
+            key_id,
+        );
 
         Ok(Self {
             encoded_password_hash,
+            algorithm,
             password_hash: password_hash.into(),
             salt: Salt::from_slice(salt)?,
             iterations,
             memory,
+            lanes: LANES,
+            key_id: key_id.map(|id| id.into()),
         })
     }
 
@@ -252,6 +598,31 @@ impl PasswordHash {
         }
 
         let parts_split = password_hash.split('$').collect::<Vec<&str>>();
+        match parts_split.first() {
+            Some(&"pbkdf2_sha1") => {
+                return Self::from_encoded_django(password_hash, parts_split, Pbkdf2Prf::Sha1)
+            }
+            Some(&"pbkdf2_sha256") => {
+                return Self::from_encoded_django(password_hash, parts_split, Pbkdf2Prf::Sha256)
+            }
+            _ => (),
+        }
+        match parts_split.get(1) {
+            Some(&"scrypt") => return Self::from_encoded_scrypt(password_hash, parts_split),
+            Some(&"2a") | Some(&"2b") | Some(&"2y") => {
+                return Self::from_encoded_bcrypt(password_hash, parts_split)
+            }
+            Some(&"pbkdf2") => {
+                return Self::from_encoded_passlib(password_hash, parts_split, Pbkdf2Prf::Sha1)
+            }
+            Some(&"pbkdf2-sha256") => {
+                return Self::from_encoded_passlib(password_hash, parts_split, Pbkdf2Prf::Sha256)
+            }
+            Some(&"pbkdf2-sha512") => {
+                return Self::from_encoded_passlib(password_hash, parts_split, Pbkdf2Prf::Sha512)
+            }
+            _ => (),
+        }
         if parts_split.len() != 6 {
             return Err(UnknownCryptoError);
         }
@@ -259,20 +630,23 @@ impl PasswordHash {
         if parts.next() != Some("") {
             return Err(UnknownCryptoError);
         }
-        if parts.next() != Some("argon2i") {
-            return Err(UnknownCryptoError);
-        }
+        let algorithm = match parts.next() {
+            Some("argon2i") => Algorithm::Argon2i,
+            Some("argon2id") => Algorithm::Argon2id,
+            _ => return Err(UnknownCryptoError),
+        };
         if parts.next() != Some("v=19") {
             return Err(UnknownCryptoError);
         }
 
-        // Splits as ["m", "X", "t", "Y", "p", "Z"] where m=X, t=Y and p=Z.
+        // Splits as ["m", "X", "t", "Y", "p", "Z"] where m=X, t=Y and p=Z,
+        // optionally followed by ["keyid", "K"] where keyid=K.
         let param_parts_split = parts
             .next()
             .unwrap()
             .split(|v| v == '=' || v == ',')
             .collect::<Vec<&str>>();
-        if param_parts_split.len() != 6 {
+        if param_parts_split.len() != 6 && param_parts_split.len() != 8 {
             return Err(UnknownCryptoError);
         }
         let mut param_parts = param_parts_split.into_iter();
@@ -281,15 +655,12 @@ impl PasswordHash {
             return Err(UnknownCryptoError);
         }
 
-        let memory = Self::parse_decimal_value(&param_parts.next().unwrap())?;
-        if memory < MIN_MEMORY {
-            return Err(UnknownCryptoError);
-        }
+        let memory = Self::parse_decimal_value(param_parts.next().unwrap())?;
 
         if param_parts.next() != Some("t") {
             return Err(UnknownCryptoError);
         }
-        let iterations = Self::parse_decimal_value(&param_parts.next().unwrap())?;
+        let iterations = Self::parse_decimal_value(param_parts.next().unwrap())?;
         if iterations < MIN_ITERATIONS {
             return Err(UnknownCryptoError);
         }
@@ -297,26 +668,239 @@ impl PasswordHash {
         if param_parts.next() != Some("p") {
             return Err(UnknownCryptoError);
         }
-        let lanes = Self::parse_decimal_value(&param_parts.next().unwrap())?;
-        if lanes != LANES {
+        let lanes = Self::parse_decimal_value(param_parts.next().unwrap())?;
+        if !(MIN_LANES..=MAX_LANES).contains(&lanes) {
+            return Err(UnknownCryptoError);
+        }
+        if memory < MIN_MEMORY * lanes {
             return Err(UnknownCryptoError);
         }
 
+        let key_id = match param_parts.next() {
+            Some("keyid") => {
+                let key_id = decode_config(param_parts.next().unwrap(), STANDARD_NO_PAD)?;
+                if key_id.is_empty() || key_id.len() > MAX_KEY_ID_LENGTH {
+                    return Err(UnknownCryptoError);
+                }
+                Some(key_id)
+            }
+            None => None,
+            _ => return Err(UnknownCryptoError),
+        };
+
         let salt = decode_config(parts.next().unwrap(), STANDARD_NO_PAD)?;
         if salt.len() != SALT_LENGTH {
             return Err(UnknownCryptoError);
         }
-        let password_hash_raw = decode_config(&parts.next().unwrap(), STANDARD_NO_PAD)?;
+        let password_hash_raw = decode_config(parts.next().unwrap(), STANDARD_NO_PAD)?;
         if password_hash_raw.len() != PWHASH_LENGTH {
             return Err(UnknownCryptoError);
         }
 
         Ok(Self {
             encoded_password_hash: password_hash.into(),
+            algorithm,
             password_hash: password_hash_raw,
             salt: Salt::from_slice(&salt)?,
             iterations,
             memory,
+            lanes,
+            key_id,
+        })
+    }
+
+    /// Construct from an encoded password hash using scrypt, split at each `$`.
+    fn from_encoded_scrypt(
+        password_hash: &str,
+        parts_split: Vec<&str>,
+    ) -> Result<Self, UnknownCryptoError> {
+        if parts_split.len() != 5 {
+            return Err(UnknownCryptoError);
+        }
+        let mut parts = parts_split.into_iter().skip(2);
+
+        // Splits as ["ln", "X", "r", "Y", "p", "Z"] where ln=X, r=Y and p=Z.
+        let param_parts_split = parts
+            .next()
+            .unwrap()
+            .split(&['=', ','][..])
+            .collect::<Vec<&str>>();
+        if param_parts_split.len() != 6 {
+            return Err(UnknownCryptoError);
+        }
+        let mut param_parts = param_parts_split.into_iter();
+
+        if param_parts.next() != Some("ln") {
+            return Err(UnknownCryptoError);
+        }
+        let log_n = Self::parse_decimal_value(param_parts.next().unwrap())?;
+        if !(1..=63).contains(&log_n) {
+            return Err(UnknownCryptoError);
+        }
+
+        if param_parts.next() != Some("r") {
+            return Err(UnknownCryptoError);
+        }
+        let r = Self::parse_decimal_value(param_parts.next().unwrap())?;
+        if r < 1 {
+            return Err(UnknownCryptoError);
+        }
+
+        if param_parts.next() != Some("p") {
+            return Err(UnknownCryptoError);
+        }
+        let p = Self::parse_decimal_value(param_parts.next().unwrap())?;
+        if p < 1 {
+            return Err(UnknownCryptoError);
+        }
+
+        let salt = decode_config(parts.next().unwrap(), STANDARD_NO_PAD)?;
+        if salt.len() != SALT_LENGTH {
+            return Err(UnknownCryptoError);
+        }
+        let password_hash_raw = decode_config(parts.next().unwrap(), STANDARD_NO_PAD)?;
+        if password_hash_raw.len() != PWHASH_LENGTH {
+            return Err(UnknownCryptoError);
+        }
+
+        // scrypt uses 128 * r * 2^ln bytes of memory, and runs ROMix over it p times.
+        let memory = (u128::from(r) << (7 + log_n)) / 1024;
+
+        Ok(Self {
+            encoded_password_hash: password_hash.into(),
+            algorithm: Algorithm::Scrypt {
+                log_n: log_n as u8,
+                r,
+                p,
+            },
+            password_hash: password_hash_raw,
+            salt: Salt::from_slice(&salt)?,
+            iterations: p,
+            memory: core::cmp::min(memory, 0xFFFF_FFFF) as u32,
+            lanes: LANES,
+            key_id: None,
+        })
+    }
+
+    /// Construct from an encoded password hash using bcrypt, split at each `$`.
+    fn from_encoded_bcrypt(
+        password_hash: &str,
+        parts_split: Vec<&str>,
+    ) -> Result<Self, UnknownCryptoError> {
+        if parts_split.len() != 4 {
+            return Err(UnknownCryptoError);
+        }
+
+        // The cost is always two decimal digits, and may have a leading zero.
+        let cost = parts_split[2];
+        if cost.len() != 2 || !cost.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(UnknownCryptoError);
+        }
+        let cost = cost.parse::<u32>()?;
+        if !(bcrypt::MIN_COST..=bcrypt::MAX_COST).contains(&cost) {
+            return Err(UnknownCryptoError);
+        }
+
+        // The salt and password hash are not separated. Other implementations
+        // ignore the unused bits of the last character of each.
+        let salt_and_hash = parts_split[3];
+        if salt_and_hash.len() != 53 || !salt_and_hash.is_ascii() {
+            return Err(UnknownCryptoError);
+        }
+        let config = BCRYPT.decode_allow_trailing_bits(true);
+        let salt = decode_config(&salt_and_hash[..22], config)?;
+        let password_hash_raw = decode_config(&salt_and_hash[22..], config)?;
+        if salt.len() != bcrypt::SALT_LENGTH || password_hash_raw.len() != bcrypt::HASH_LENGTH {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            encoded_password_hash: password_hash.into(),
+            algorithm: Algorithm::Bcrypt { cost },
+            password_hash: password_hash_raw,
+            salt: Salt::from_slice(&salt)?,
+            iterations: cost,
+            // The state of Blowfish takes up around 4 KiB.
+            memory: 4,
+            lanes: LANES,
+            key_id: None,
+        })
+    }
+
+    /// Construct from an encoded password hash using PBKDF2 in the format of Django,
+    /// split at each `$`. The salt is stored as text, and the password hash in Base64 with padding.
+    fn from_encoded_django(
+        password_hash: &str,
+        parts_split: Vec<&str>,
+        prf: Pbkdf2Prf,
+    ) -> Result<Self, UnknownCryptoError> {
+        if parts_split.len() != 4 {
+            return Err(UnknownCryptoError);
+        }
+        // Decoding accepts Base64 without padding, which Django never omits. Both
+        // the SHA1 and SHA256 password hash lengths need padding.
+        if !parts_split[3].ends_with('=') {
+            return Err(UnknownCryptoError);
+        }
+        let iterations = Self::parse_decimal_value(parts_split[1])?;
+        let password_hash_raw = decode_config(parts_split[3], STANDARD)?;
+
+        Self::from_pbkdf2_parts(
+            password_hash,
+            prf,
+            iterations,
+            parts_split[2].as_bytes(),
+            password_hash_raw,
+        )
+    }
+
+    /// Construct from an encoded password hash using PBKDF2 in the format of passlib,
+    /// split at each `$`. The salt and password hash are stored in passlib's variant of
+    /// Base64, which uses `.` in place of `+` and omits padding.
+    fn from_encoded_passlib(
+        password_hash: &str,
+        parts_split: Vec<&str>,
+        prf: Pbkdf2Prf,
+    ) -> Result<Self, UnknownCryptoError> {
+        if parts_split.len() != 5 {
+            return Err(UnknownCryptoError);
+        }
+        let is_ab64 = |part: &str| !part.contains(&['+', '='][..]);
+        if !is_ab64(parts_split[3]) || !is_ab64(parts_split[4]) {
+            return Err(UnknownCryptoError);
+        }
+        let iterations = Self::parse_decimal_value(parts_split[2])?;
+        let salt = decode_config(parts_split[3].replace('.', "+"), STANDARD_NO_PAD)?;
+        let password_hash_raw = decode_config(parts_split[4].replace('.', "+"), STANDARD_NO_PAD)?;
+
+        Self::from_pbkdf2_parts(password_hash, prf, iterations, &salt, password_hash_raw)
+    }
+
+    /// Construct from the decoded parts of an encoded password hash using PBKDF2.
+    fn from_pbkdf2_parts(
+        password_hash: &str,
+        prf: Pbkdf2Prf,
+        iterations: u32,
+        salt: &[u8],
+        password_hash_raw: Vec<u8>,
+    ) -> Result<Self, UnknownCryptoError> {
+        if iterations < 1 {
+            return Err(UnknownCryptoError);
+        }
+        if salt.is_empty() || password_hash_raw.len() != prf.hash_length() {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            encoded_password_hash: password_hash.into(),
+            algorithm: Algorithm::Pbkdf2 { prf },
+            password_hash: password_hash_raw,
+            salt: Salt::from_slice(salt)?,
+            iterations,
+            // PBKDF2 only needs the state of HMAC.
+            memory: 1,
+            lanes: LANES,
+            key_id: None,
         })
     }
 
@@ -339,6 +923,12 @@ impl PasswordHash {
     pub fn len(&self) -> usize {
         self.password_hash.len()
     }
+
+    #[inline]
+    /// Return the identifier of the pepper the password was hashed with, if any.
+    pub fn key_id(&self) -> Option<&[u8]> {
+        self.key_id.as_deref()
+    }
 }
 
 impl core::fmt::Debug for PasswordHash {
@@ -354,155 +944,1081 @@ impl core::fmt::Debug for PasswordHash {
 
 impl_ct_partialeq_trait!(PasswordHash, unprotected_as_bytes);
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// Hash a password using Argon2i.
-pub fn hash_password(
-    password: &Password,
-    iterations: u32,
-    memory: u32,
-) -> Result<PasswordHash, UnknownCryptoError> {
-    if iterations < MIN_ITERATIONS {
-        return Err(UnknownCryptoError);
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Bounds on the parameters of a `PasswordHash` that [`verify()`] will accept.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `min_iterations` is less than 3 or greater than `max_iterations`.
+/// - `min_memory` is less than 8 or greater than `max_memory`.
+/// - `max_cost` is less than 4 or greater than 31, when allowing bcrypt.
+/// - `max_iterations` is 0, when allowing PBKDF2.
+/// - `max_lanes` is less than 1 or greater than 255.
+///
+/// # Note:
+/// The amount of lanes of password hashes using Argon2 must be at most `max_lanes`,
+/// which is 255 unless set with [`with_max_lanes()`]. With the `parallel` feature,
+/// this also bounds the amount of threads used to verify a password hash.
+///
+/// For password hashes using scrypt, the memory is `128 * r * 2^ln` bytes and
+/// the parallelization parameter `p` must be at most `max_iterations`, as it
+/// determines how many times that memory is processed. It must also be at most `max_lanes`.
+///
+/// Password hashes using bcrypt are rejected, unless allowed with [`with_max_bcrypt_cost()`].
+/// Only their cost is then checked, as bcrypt uses a fixed amount of memory.
+///
+/// Likewise, password hashes using PBKDF2 are rejected, unless allowed with
+/// [`with_max_pbkdf2_iterations()`]. Only their iterations are then checked.
+///
+/// [`verify()`]: fn.verify.html
+/// [`with_max_lanes()`]: struct.Policy.html#method.with_max_lanes
+/// [`with_max_bcrypt_cost()`]: struct.Policy.html#method.with_max_bcrypt_cost
+/// [`with_max_pbkdf2_iterations()`]: struct.Policy.html#method.with_max_pbkdf2_iterations
+pub struct Policy {
+    min_iterations: u32,
+    max_iterations: u32,
+    min_memory: u32,
+    max_memory: u32,
+    max_lanes: u32,
+    max_bcrypt_cost: Option<u32>,
+    max_pbkdf2_iterations: Option<u32>,
+}
+
+impl Policy {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a policy accepting iterations in `min_iterations..=max_iterations` and memory
+    /// (in kibibytes (KiB)) in `min_memory..=max_memory`.
+    pub fn new(
+        min_iterations: u32,
+        max_iterations: u32,
+        min_memory: u32,
+        max_memory: u32,
+    ) -> Result<Self, UnknownCryptoError> {
+        if min_iterations < MIN_ITERATIONS || min_iterations > max_iterations {
+            return Err(UnknownCryptoError);
+        }
+        if min_memory < MIN_MEMORY || min_memory > max_memory {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            min_iterations,
+            max_iterations,
+            min_memory,
+            max_memory,
+            max_lanes: MAX_LANES,
+            max_bcrypt_cost: None,
+            max_pbkdf2_iterations: None,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Only accept password hashes using at most `max_lanes` lanes, or scrypt with a
+    /// parallelization parameter `p` of at most `max_lanes`.
+    pub fn with_max_lanes(self, max_lanes: u32) -> Result<Self, UnknownCryptoError> {
+        if !(MIN_LANES..=MAX_LANES).contains(&max_lanes) {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self { max_lanes, ..self })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Additionally accept password hashes using bcrypt with a cost of at most `max_cost`.
+    pub fn with_max_bcrypt_cost(self, max_cost: u32) -> Result<Self, UnknownCryptoError> {
+        if !(bcrypt::MIN_COST..=bcrypt::MAX_COST).contains(&max_cost) {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            max_bcrypt_cost: Some(max_cost),
+            ..self
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Additionally accept password hashes using PBKDF2 with at most `max_iterations` iterations.
+    pub fn with_max_pbkdf2_iterations(
+        self,
+        max_iterations: u32,
+    ) -> Result<Self, UnknownCryptoError> {
+        if max_iterations < 1 {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            max_pbkdf2_iterations: Some(max_iterations),
+            ..self
+        })
+    }
+
+    /// Check whether the parameters of `password_hash` are within the bounds of this policy.
+    fn allows(&self, password_hash: &PasswordHash) -> bool {
+        let memory_allowed = (self.min_memory..=self.max_memory).contains(&password_hash.memory);
+
+        match password_hash.algorithm {
+            Algorithm::Bcrypt { cost } => match self.max_bcrypt_cost {
+                Some(max_cost) => cost <= max_cost,
+                None => false,
+            },
+            Algorithm::Pbkdf2 { .. } => match self.max_pbkdf2_iterations {
+                Some(max_iterations) => password_hash.iterations <= max_iterations,
+                None => false,
+            },
+            Algorithm::Scrypt { p, .. } => {
+                password_hash.iterations <= self.max_iterations
+                    && p <= self.max_lanes
+                    && memory_allowed
+            }
+            _ => {
+                (self.min_iterations..=self.max_iterations).contains(&password_hash.iterations)
+                    && password_hash.lanes <= self.max_lanes
+                    && memory_allowed
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Cost parameters for [`hash_password()`].
+///
+/// The presets are based on those of [libsodium](https://libsodium.gitbook.io/doc/password_hashing/default_phf#key-derivation),
+/// except `INTERACTIVE`, which uses 3 iterations instead of 2 as this is the minimum
+/// [`hash_password()`] allows.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `iterations` is less than 3.
+/// - `memory` is less than 8.
+///
+/// [`hash_password()`]: fn.hash_password.html
+pub struct Params {
+    iterations: u32,
+    memory: u32,
+}
+
+impl Params {
+    /// For interactive, online operations. 3 iterations and 64 MiB of memory.
+    pub const INTERACTIVE: Self = Self {
+        iterations: 3,
+        memory: 1 << 16,
+    };
+
+    /// For operations where a slower response is acceptable. 3 iterations and 256 MiB of memory.
+    pub const MODERATE: Self = Self {
+        iterations: 3,
+        memory: 1 << 18,
+    };
+
+    /// For highly sensitive, non-interactive operations. 4 iterations and 1 GiB of memory.
+    pub const SENSITIVE: Self = Self {
+        iterations: 4,
+        memory: 1 << 20,
+    };
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create cost parameters using `iterations` and `memory` (in kibibytes (KiB)).
+    pub fn new(iterations: u32, memory: u32) -> Result<Self, UnknownCryptoError> {
+        if iterations < MIN_ITERATIONS || memory < MIN_MEMORY {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self { iterations, memory })
+    }
+
+    /// Return the iterations cost parameter.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Return the memory (in kibibytes (KiB)) cost parameter.
+    pub fn memory(&self) -> u32 {
+        self.memory
+    }
+}
+
+/// Measure how long hashing a password with `iterations` and `memory` takes.
+fn measure(iterations: u32, memory: u32) -> Result<Duration, UnknownCryptoError> {
+    let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+    let start = Instant::now();
+    argon2id::derive_key_with_lanes(
+        &[0u8; PWHASH_LENGTH],
+        &[0u8; SALT_LENGTH],
+        iterations,
+        memory,
+        LANES,
+        None,
+        None,
+        buffer.as_mut(),
+    )?;
+
+    Ok(start.elapsed())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Find cost parameters that make [`hash_password()`] take approximately `target` on this machine,
+/// using at most `max_memory` kibibytes (KiB) of memory.
+///
+/// Memory is preferred over iterations: the memory is halved, starting from `max_memory`,
+/// until hashing with 3 iterations takes no longer than `target`. The iterations are then
+/// increased to use up the remaining time.
+///
+/// [`hash_password()`]: fn.hash_password.html
+pub fn calibrate(target: Duration, max_memory: u32) -> Result<Params, UnknownCryptoError> {
+    if max_memory < MIN_MEMORY {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut memory = max_memory;
+    let mut elapsed = measure(MIN_ITERATIONS, memory)?;
+    while elapsed > target {
+        if memory == MIN_MEMORY {
+            return Err(UnknownCryptoError);
+        }
+        memory = core::cmp::max(memory / 2, MIN_MEMORY);
+        elapsed = measure(MIN_ITERATIONS, memory)?;
+    }
+
+    // The time taken grows linearly with the amount of iterations.
+    let per_iteration = core::cmp::max(elapsed.as_nanos() / u128::from(MIN_ITERATIONS), 1);
+    let iterations = core::cmp::min(target.as_nanos() / per_iteration, 0xFFFF_FFFF) as u32;
+    let iterations = core::cmp::max(iterations, MIN_ITERATIONS);
+
+    Params::new(iterations, memory)
+}
+
+/// Hash a password using Argon2id, with the secret key of `pepper` if given.
+fn hash_password_inner(
+    password: &Password,
+    pepper: Option<&Pepper>,
+    iterations: u32,
+    memory: u32,
+) -> Result<PasswordHash, UnknownCryptoError> {
+    if iterations < MIN_ITERATIONS {
+        return Err(UnknownCryptoError);
+    }
+
+    // Cannot panic as this is a valid size.
+    let salt = Salt::generate(SALT_LENGTH).unwrap();
+    let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+
+    argon2id::derive_key_with_lanes(
+        password.unprotected_as_bytes(),
+        salt.as_ref(),
+        iterations,
+        memory,
+        LANES,
+        pepper.map(|p| p.secret.unprotected_as_bytes()),
+        None,
+        buffer.as_mut(),
+    )?;
+
+    PasswordHash::from_slice_with_key_id(
+        ALGORITHM,
+        buffer.as_ref(),
+        salt.as_ref(),
+        iterations,
+        memory,
+        pepper.map(|p| p.key_id()),
+    )
+}
+
+/// Hash and verify a password using the Argon2 variant that `expected` was produced with,
+/// with `secret` if given.
+fn hash_password_verify_inner(
+    expected: &PasswordHash,
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+    secret: Option<&[u8]>,
+) -> Result<(), UnknownCryptoError> {
+    if iterations < MIN_ITERATIONS {
+        return Err(UnknownCryptoError);
+    }
+
+    let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+
+    let verify = match expected.algorithm {
+        Algorithm::Argon2i => argon2i::verify_with_lanes,
+        Algorithm::Argon2id => argon2id::verify_with_lanes,
+        // The cost parameters of scrypt, bcrypt and PBKDF2 cannot be given as iterations and memory.
+        Algorithm::Scrypt { .. } | Algorithm::Bcrypt { .. } | Algorithm::Pbkdf2 { .. } => {
+            return Err(UnknownCryptoError)
+        }
+    };
+
+    verify(
+        expected.unprotected_as_bytes(),
+        password.unprotected_as_bytes(),
+        expected.salt.as_ref(),
+        iterations,
+        memory,
+        expected.lanes,
+        secret,
+        None,
+        buffer.as_mut(),
+    )
+}
+
+/// Verify a password using the algorithm and parameters stored in `expected`,
+/// with `secret` if given.
+fn verify_with_stored_params(
+    expected: &PasswordHash,
+    password: &Password,
+    secret: Option<&[u8]>,
+) -> Result<(), UnknownCryptoError> {
+    match expected.algorithm {
+        Algorithm::Scrypt { log_n, r, p } => {
+            // scrypt has no secret input, and encoded scrypt password hashes have no keyid.
+            if secret.is_some() {
+                return Err(UnknownCryptoError);
+            }
+
+            let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+            scrypt::verify(
+                expected.unprotected_as_bytes(),
+                password.unprotected_as_bytes(),
+                expected.salt.as_ref(),
+                log_n,
+                r,
+                p,
+                buffer.as_mut(),
+            )
+        }
+        Algorithm::Bcrypt { cost } => {
+            // bcrypt has no secret input, and encoded bcrypt password hashes have no keyid.
+            if secret.is_some() {
+                return Err(UnknownCryptoError);
+            }
+
+            let mut buffer = Zeroizing::new([0u8; bcrypt::HASH_LENGTH]);
+            bcrypt::verify(
+                expected.unprotected_as_bytes(),
+                password.unprotected_as_bytes(),
+                expected.salt.as_ref(),
+                cost,
+                buffer.as_mut(),
+            )
+        }
+        Algorithm::Pbkdf2 { prf } => {
+            // PBKDF2 has no secret input, and encoded PBKDF2 password hashes have no keyid.
+            if secret.is_some() {
+                return Err(UnknownCryptoError);
+            }
+
+            let mut buffer = Zeroizing::new([0u8; 64]);
+            let dst_out = &mut buffer[..prf.hash_length()];
+            let password = password.unprotected_as_bytes();
+            let salt = expected.salt.as_ref();
+            let iterations = expected.iterations as usize;
+            match prf {
+                Pbkdf2Prf::Sha1 => pbkdf2::sha1::verify(
+                    expected.unprotected_as_bytes(),
+                    &pbkdf2::sha1::Password::from_slice(password)?,
+                    salt,
+                    iterations,
+                    dst_out,
+                ),
+                Pbkdf2Prf::Sha256 => pbkdf2::sha256::verify(
+                    expected.unprotected_as_bytes(),
+                    &pbkdf2::sha256::Password::from_slice(password)?,
+                    salt,
+                    iterations,
+                    dst_out,
+                ),
+                Pbkdf2Prf::Sha512 => pbkdf2::verify(
+                    expected.unprotected_as_bytes(),
+                    &pbkdf2::Password::from_slice(password)?,
+                    salt,
+                    iterations,
+                    dst_out,
+                ),
+            }
+        }
+        _ => hash_password_verify_inner(
+            expected,
+            password,
+            expected.iterations,
+            expected.memory,
+            secret,
+        ),
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hash a password using Argon2id.
+pub fn hash_password(
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+) -> Result<PasswordHash, UnknownCryptoError> {
+    hash_password_inner(password, None, iterations, memory)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hash a password using Argon2id, with `pepper` as the secret input.
+pub fn hash_password_with_pepper(
+    password: &Password,
+    pepper: &Pepper,
+    iterations: u32,
+    memory: u32,
+) -> Result<PasswordHash, UnknownCryptoError> {
+    hash_password_inner(password, Some(pepper), iterations, memory)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hash and verify a password using the Argon2 variant that `expected` was produced with.
+///
+/// Password hashes using scrypt, bcrypt or PBKDF2 cannot be verified with this function, as their
+/// parameters are not iterations and memory. Use [`verify()`] for these instead.
+///
+/// [`verify()`]: fn.verify.html
+pub fn hash_password_verify(
+    expected: &PasswordHash,
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+) -> Result<(), UnknownCryptoError> {
+    if expected.key_id.is_some() {
+        return Err(UnknownCryptoError);
+    }
+
+    hash_password_verify_inner(expected, password, iterations, memory, None)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a password using the parameters stored in `expected`. If a `policy` is given,
+/// `expected` is rejected before any hashing happens if its parameters are outside the
+/// bounds of the policy.
+pub fn verify(
+    expected: &PasswordHash,
+    password: &Password,
+    policy: Option<&Policy>,
+) -> Result<(), UnknownCryptoError> {
+    if let Some(policy) = policy {
+        if !policy.allows(expected) {
+            return Err(UnknownCryptoError);
+        }
+    }
+
+    if expected.key_id.is_some() {
+        return Err(UnknownCryptoError);
+    }
+
+    verify_with_stored_params(expected, password, None)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a password using the parameters stored in `expected` and the pepper among `peppers`
+/// whose identifier matches that of `expected`. If `expected` was hashed without a pepper,
+/// it is verified without one. A `policy` is applied as in [`verify()`].
+///
+/// [`verify()`]: fn.verify.html
+pub fn verify_with_pepper(
+    expected: &PasswordHash,
+    password: &Password,
+    peppers: &[Pepper],
+    policy: Option<&Policy>,
+) -> Result<(), UnknownCryptoError> {
+    let secret = match expected.key_id() {
+        Some(key_id) => match peppers.iter().find(|p| p.key_id() == key_id) {
+            Some(pepper) => Some(pepper.secret.unprotected_as_bytes()),
+            None => return Err(UnknownCryptoError),
+        },
+        None => None,
+    };
+
+    if let Some(policy) = policy {
+        if !policy.allows(expected) {
+            return Err(UnknownCryptoError);
+        }
+    }
+
+    verify_with_stored_params(expected, password, secret)
+}
+
+/// Check whether `expected` differs in algorithm, parameters or pepper identifier
+/// from what hashing with `iterations`, `memory` and a pepper identified by `key_id` would produce.
+fn needs_rehash_with_key_id(
+    expected: &PasswordHash,
+    key_id: Option<&[u8]>,
+    iterations: u32,
+    memory: u32,
+) -> bool {
+    expected.algorithm != ALGORITHM
+        || expected.iterations != iterations
+        || expected.memory != memory
+        || expected.lanes != LANES
+        || expected.key_id() != key_id
+}
+
+#[must_use]
+/// Check whether `expected` was produced with another algorithm or other parameters
+/// than [`hash_password()`] would use with `iterations` and `memory`, or with a pepper.
+/// If `true` is returned, the password should be hashed again once it is available.
+///
+/// [`hash_password()`]: fn.hash_password.html
+pub fn needs_rehash(expected: &PasswordHash, iterations: u32, memory: u32) -> bool {
+    needs_rehash_with_key_id(expected, None, iterations, memory)
+}
+
+#[must_use]
+/// Check whether `expected` was produced with another algorithm, other parameters or
+/// another pepper than [`hash_password_with_pepper()`] would use with `pepper`, `iterations`
+/// and `memory`. Peppers are compared by their identifier only. If `true` is returned,
+/// the password should be hashed again once it is available, e.g. after the pepper was rotated.
+///
+/// [`hash_password_with_pepper()`]: fn.hash_password_with_pepper.html
+pub fn needs_rehash_with_pepper(
+    expected: &PasswordHash,
+    pepper: &Pepper,
+    iterations: u32,
+    memory: u32,
+) -> bool {
+    needs_rehash_with_key_id(expected, Some(pepper.key_id()), iterations, memory)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let valid = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+        let password_hash = PasswordHash::from_encoded(valid).unwrap();
+        let debug = format!("{:?}", password_hash);
+        let expected = "PasswordHash { encoded_password_hash: [***OMITTED***], password_hash: [***OMITTED***], iterations: 3, memory: 65536 }";
+        assert_eq!(debug, expected);
+    }
+
+    /// The tests herein were generated with the CLI tool from the reference implementation at:
+    /// https://github.com/P-H-C/phc-winner-argon2/commit/62358ba2123abd17fccf2a108a301d4b52c01a7c
+    mod test_encoding_from_ref {
+        use super::*;
+        use hex;
+
+        #[test]
+        fn test_encoding_and_verify_1() {
+            let iterations: u32 = 3;
+            let memory: u32 = 65536;
+            let password = Password::from_slice(b"password").unwrap();
+            let raw_hash =
+                hex::decode("7d1b1163d3c0b791fea802ae5d1ccbd3fe896c54a1b0277ad96e5a1f311293f7")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+
+            // Raw Argon2i password hashes can still be rebuilt with from_slice().
+            let from_slice =
+                PasswordHash::from_slice(&raw_hash, b"somesaltsomesalt", iterations, memory)
+                    .unwrap();
+            assert_eq!(from_slice.unprotected_as_encoded(), encoded_hash);
+            assert!(hash_password_verify(&from_slice, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_encoding_and_verify_2() {
+            let iterations: u32 = 3;
+            let memory: u32 = 65536;
+            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
+            let raw_hash =
+                hex::decode("ed4b0fd657e165f9ffe90f66ff315fbec878e629f03b2d6468d4b17a50c796aa")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$7UsP1lfhZfn/6Q9m/zFfvsh45inwOy1kaNSxelDHlqo";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_encoding_and_verify_3() {
+            let iterations: u32 = 3;
+            let memory: u32 = 65536;
+            // Different salt from test 2
+            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
+            let raw_hash =
+                hex::decode("fa9ea96fecd0998251d698c1303edda4df3889a39bfa87cd5e7b8656ef61b510")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$U29tZVNhbHRTb21lU2FsdA$+p6pb+zQmYJR1pjBMD7dpN84iaOb+ofNXnuGVu9htRA";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_encoding_and_verify_4() {
+            let iterations: u32 = 3;
+            let memory: u32 = 256;
+            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
+            let raw_hash =
+                hex::decode("fb3e0cdf7b10970bf6711c151861851566006f8986c9109ba2cdd5d98f9ca9d7")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=256,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$+z4M33sQlwv2cRwVGGGFFWYAb4mGyRCbos3V2Y+cqdc";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_encoding_and_verify_5() {
+            let iterations: u32 = 4;
+            let memory: u32 = 256;
+            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
+            let raw_hash =
+                hex::decode("9b134c4c1c34e66170d1088c18be3a8e0f4a1837d4c069703ce62f85248b1e8f")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=256,t=4,p=1$c29tZXNhbHRzb21lc2FsdA$mxNMTBw05mFw0QiMGL46jg9KGDfUwGlwPOYvhSSLHo8";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_encoding_and_verify_multiple_lanes() {
+            // Generated with the Argon2 implementation in OpenSSL 3.5.
+            let iterations: u32 = 3;
+            let memory: u32 = 65536;
+            let password = Password::from_slice(b"password").unwrap();
+            let raw_hash =
+                hex::decode("5daeaacf7d355b5480dfe174b93478830d5914cb713aabd5621e126b84465659")
+                    .unwrap();
+            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=4$c29tZXNhbHRzb21lc2FsdA$Xa6qz301W1SA3+F0uTR4gw1ZFMtxOqvVYh4Sa4RGVlk";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+    }
+
+    /// The tests herein were generated with the Argon2 implementation in OpenSSL 3.5.
+    mod test_encoding_argon2id {
+        use super::*;
+        use hex;
+
+        #[test]
+        fn test_encoding_and_verify_1() {
+            let iterations: u32 = 3;
+            let memory: u32 = 65536;
+            let password = Password::from_slice(b"password").unwrap();
+            let raw_hash =
+                hex::decode("7664ad4ba1a3c999fcdd0991ffc2270f78302d2383233db5e7befc85d1bb1819")
+                    .unwrap();
+            let encoded_hash = "$argon2id$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$dmStS6GjyZn83QmR/8InD3gwLSODIz215778hdG7GBk";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+
+            let from_slice = PasswordHash::from_slice_argon2id(
+                &raw_hash,
+                b"somesaltsomesalt",
+                iterations,
+                memory,
+            )
+            .unwrap();
+            assert_eq!(from_slice.unprotected_as_encoded(), encoded_hash);
+        }
+
+        #[test]
+        fn test_encoding_and_verify_2() {
+            let iterations: u32 = 3;
+            let memory: u32 = 8192;
+            let password = Password::from_slice(b"password").unwrap();
+            let raw_hash =
+                hex::decode("02950b5580d9c4909f78967fb3a6bfe9f0839ed64b932b118aba21ec89705b29")
+                    .unwrap();
+            let encoded_hash = "$argon2id$v=19$m=8192,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk";
+
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
+            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        }
+
+        #[test]
+        fn test_argon2i_hash_not_valid_as_argon2id() {
+            let password = Password::from_slice(b"password").unwrap();
+            let argon2i = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c";
+            let as_argon2id = "$argon2id$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c";
+
+            let expected = PasswordHash::from_encoded(argon2i).unwrap();
+            assert!(hash_password_verify(&expected, &password, 3, 65536).is_ok());
+            let expected = PasswordHash::from_encoded(as_argon2id).unwrap();
+            assert!(hash_password_verify(&expected, &password, 3, 65536).is_err());
+        }
+    }
+
+    /// The tests herein were generated with Python's hashlib.scrypt.
+    mod test_encoding_scrypt {
+        use super::*;
+
+        const SCRYPT_1: &str = "$scrypt$ln=10,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic";
+        const SCRYPT_2: &str = "$scrypt$ln=12,r=8,p=2$AAECAwQFBgcICQoLDA0ODw$FvTHk0ZlVc4Xn7FfG61bXsxGpBQUcu5m0QOiZFt2Tsw";
+
+        #[test]
+        fn test_verify_1() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(SCRYPT_1).unwrap();
+
+            assert!(verify(&expected, &password, None).is_ok());
+            assert!(verify_with_pepper(&expected, &password, &[], None).is_ok());
+            let bad_password = Password::from_slice(b"Password").unwrap();
+            assert!(verify(&expected, &bad_password, None).is_err());
+        }
+
+        #[test]
+        fn test_verify_2() {
+            let password = Password::from_slice(b"Secret password").unwrap();
+            let expected = PasswordHash::from_encoded(SCRYPT_2).unwrap();
+
+            assert!(verify(&expected, &password, None).is_ok());
+            assert_eq!(expected.unprotected_as_encoded(), SCRYPT_2);
+        }
+
+        #[test]
+        fn test_hash_password_verify_err() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(SCRYPT_1).unwrap();
+
+            assert!(hash_password_verify(&expected, &password, 3, 1024).is_err());
+        }
+
+        #[test]
+        fn test_needs_rehash() {
+            let expected = PasswordHash::from_encoded(SCRYPT_1).unwrap();
+
+            assert!(needs_rehash(&expected, 3, 1024));
+        }
+
+        #[test]
+        fn test_verify_policy() {
+            let password = Password::from_slice(b"password").unwrap();
+            // ln=10 and r=8 uses 1 MiB of memory.
+            let expected = PasswordHash::from_encoded(SCRYPT_1).unwrap();
+
+            let policy = Policy::new(3, 10, 1024, 1024).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_ok());
+            let policy = Policy::new(3, 10, 8, 1023).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+            let policy = Policy::new(3, 10, 1025, 2048).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+
+            // Only the maximum iterations applies to p, which is checked before hashing.
+            let expected = PasswordHash::from_encoded(
+                "$scrypt$ln=10,r=8,p=11$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+            )
+            .unwrap();
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+
+            // p is also bounded by the maximum lanes.
+            let expected = PasswordHash::from_encoded(SCRYPT_2).unwrap();
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert!(policy.allows(&expected));
+            assert!(!policy.with_max_lanes(1).unwrap().allows(&expected));
+            assert!(policy.with_max_lanes(2).unwrap().allows(&expected));
+        }
+
+        #[test]
+        fn test_bad_encoding_params() {
+            let bad = [
+                // ln out of range
+                "$scrypt$ln=0,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                "$scrypt$ln=64,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // r or p zero
+                "$scrypt$ln=10,r=0,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                "$scrypt$ln=10,r=8,p=0$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // leading zeroes
+                "$scrypt$ln=010,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // wrong order
+                "$scrypt$r=8,ln=10,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // missing or additional parameters
+                "$scrypt$ln=10,r=8$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                "$scrypt$ln=10,r=8,p=1,keyid=MTIz$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // version field
+                "$scrypt$v=19$ln=10,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                // salt or password hash of wrong length
+                "$scrypt$ln=10,r=8,p=1$c29tZXNhbHRzb21lc2Fs$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+                "$scrypt$ln=10,r=8,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjo",
+            ];
+
+            for encoded in bad.iter() {
+                assert!(PasswordHash::from_encoded(encoded).is_err());
+            }
+        }
+
+        #[test]
+        fn test_invalid_params_fail_verify() {
+            // ln must be less than 16 * r, which is only checked when hashing.
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(
+                "$scrypt$ln=16,r=1,p=1$c29tZXNhbHRzb21lc2FsdA$dj05BT7oUTq35qmxXqG/pksYG8IJr8uxtvAzbfGjoic",
+            )
+            .unwrap();
+
+            assert!(verify(&expected, &password, None).is_err());
+        }
+    }
+
+    /// The tests herein are from the test suite of Openwall's crypt_blowfish, except
+    /// those with cost 10, which were generated with libxcrypt.
+    mod test_encoding_bcrypt {
+        use super::*;
+
+        const BCRYPT: &str = "$2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy";
+
+        #[test]
+        fn test_verify() {
+            let encoded = [
+                (
+                    "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW",
+                    &b"U*U"[..],
+                ),
+                (
+                    "$2a$05$CCCCCCCCCCCCCCCCCCCCC.VGOzA784oUp/Z0DY336zx7pLYAy0lwK",
+                    &b"U*U*"[..],
+                ),
+                (
+                    "$2a$05$XXXXXXXXXXXXXXXXXXXXXOAcXxm9kjPGEMsLznoKqmqw7tc8WCx4a",
+                    &b"U*U*U"[..],
+                ),
+                (
+                    "$2y$05$/OK.fbVrR/bpIqNJ5ianF.Sa7shbm4.OzKpvFnX1pQLmQW96oUlCq",
+                    &b"\xa3"[..],
+                ),
+                (
+                    "$2b$05$/OK.fbVrR/bpIqNJ5ianF.Sa7shbm4.OzKpvFnX1pQLmQW96oUlCq",
+                    &b"\xa3"[..],
+                ),
+                (BCRYPT, &b"password"[..]),
+            ];
+
+            for (hash, password) in encoded.iter() {
+                let expected = PasswordHash::from_encoded(hash).unwrap();
+                let password = Password::from_slice(password).unwrap();
+                assert!(verify(&expected, &password, None).is_ok());
+                assert!(verify_with_pepper(&expected, &password, &[], None).is_ok());
+                assert_eq!(expected.len(), bcrypt::HASH_LENGTH);
+            }
+        }
+
+        #[test]
+        fn test_verify_err_modified_password() {
+            let expected = PasswordHash::from_encoded(BCRYPT).unwrap();
+            let password = Password::from_slice(b"Password").unwrap();
 
-    // Cannot panic as this is a valid size.
-    let salt = Salt::generate(SALT_LENGTH).unwrap();
-    let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+            assert!(verify(&expected, &password, None).is_err());
+        }
 
-    argon2i::derive_key(
-        password.unprotected_as_bytes(),
-        salt.as_ref(),
-        iterations,
-        memory,
-        None,
-        None,
-        buffer.as_mut(),
-    )?;
+        #[test]
+        fn test_verify_then_rehash() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(BCRYPT).unwrap();
 
-    PasswordHash::from_slice(buffer.as_ref(), salt.as_ref(), iterations, memory)
-}
+            assert!(hash_password_verify(&expected, &password, 10, 1 << 10).is_err());
+            assert!(verify(&expected, &password, None).is_ok());
+            assert!(needs_rehash(&expected, 3, 1 << 10));
 
-#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// Hash and verify a password using Argon2i.
-pub fn hash_password_verify(
-    expected: &PasswordHash,
-    password: &Password,
-    iterations: u32,
-    memory: u32,
-) -> Result<(), UnknownCryptoError> {
-    if iterations < MIN_ITERATIONS {
-        return Err(UnknownCryptoError);
-    }
+            let rehashed = hash_password(&password, 3, 1 << 10).unwrap();
+            assert!(!needs_rehash(&rehashed, 3, 1 << 10));
+            assert!(verify(&rehashed, &password, None).is_ok());
+        }
 
-    let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+        #[test]
+        fn test_verify_policy() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(BCRYPT).unwrap();
+
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+            let policy = policy.with_max_bcrypt_cost(10).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_ok());
+            let policy = policy.with_max_bcrypt_cost(9).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+
+            assert!(policy.with_max_bcrypt_cost(3).is_err());
+            assert!(policy.with_max_bcrypt_cost(4).is_ok());
+            assert!(policy.with_max_bcrypt_cost(31).is_ok());
+            assert!(policy.with_max_bcrypt_cost(32).is_err());
+        }
 
-    argon2i::verify(
-        expected.unprotected_as_bytes(),
-        password.unprotected_as_bytes(),
-        expected.salt.as_ref(),
-        iterations,
-        memory,
-        None,
-        None,
-        buffer.as_mut(),
-    )
-}
+        #[test]
+        fn test_trailing_bits_ignored() {
+            // The last character of the salt has unused bits set, which other
+            // implementations ignore.
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(
+                "$2b$10$c29tZXNhbHRzb21lc2FsdA8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+            )
+            .unwrap();
 
-// Testing public functions in the module.
-#[cfg(test)]
-mod public {
-    use super::*;
+            assert!(verify(&expected, &password, None).is_ok());
+        }
 
-    #[test]
-    #[cfg(feature = "safe_api")]
-    fn test_debug_impl() {
-        let valid = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
-        let password_hash = PasswordHash::from_encoded(valid).unwrap();
-        let debug = format!("{:?}", password_hash);
-        let expected = "PasswordHash { encoded_password_hash: [***OMITTED***], password_hash: [***OMITTED***], iterations: 3, memory: 65536 }";
-        assert_eq!(debug, expected);
+        #[test]
+        fn test_bad_encoding() {
+            let bad = [
+                // cost not two digits or out of range
+                "$2b$5$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2b$010$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2b$+9$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2b$03$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2b$32$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                // unsupported or missing prefix
+                "$2x$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                // salt and password hash of wrong length, or separated
+                "$2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjyy",
+                "$2b$10$c29tZXNhbHRzb21lc2Fsd$8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                // characters outside the bcrypt alphabet
+                "$2b$10$c29tZXNhbHRzb21lc2Fsd+8ty29KeCeXeGQNFfTErDtpeCxBNfFjy",
+                "$2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFj=",
+            ];
+
+            for encoded in bad.iter() {
+                assert!(PasswordHash::from_encoded(encoded).is_err());
+            }
+        }
     }
 
-    /// The tests herein were generated with the CLI tool from the reference implementation at:
-    /// https://github.com/P-H-C/phc-winner-argon2/commit/62358ba2123abd17fccf2a108a301d4b52c01a7c
-    mod test_encoding_from_ref {
+    /// The tests herein were generated with Python's hashlib, except for the one from passlib's
+    /// documentation at: https://passlib.readthedocs.io/en/stable/lib/passlib.hash.pbkdf2_digest.html
+    mod test_encoding_pbkdf2 {
         use super::*;
-        use hex;
 
-        #[test]
-        fn test_encoding_and_verify_1() {
-            let iterations: u32 = 3;
-            let memory: u32 = 65536;
-            let password = Password::from_slice(b"password").unwrap();
-            let raw_hash =
-                hex::decode("7d1b1163d3c0b791fea802ae5d1ccbd3fe896c54a1b0277ad96e5a1f311293f7")
-                    .unwrap();
-            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c";
+        const DJANGO: &str =
+            "pbkdf2_sha256$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=";
+        const PASSLIB: &str = "$pbkdf2-sha256$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c";
 
-            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
-            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
-            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+        #[test]
+        fn test_verify() {
+            let encoded = [
+                (
+                    "pbkdf2_sha1$1000$seasalt$ljleU4wBmTtz/MoG5YTwxpM0d7I=",
+                    "lètmein".as_bytes(),
+                    20,
+                ),
+                (DJANGO, "lètmein".as_bytes(), 32),
+                (
+                    "$pbkdf2$1000$AAECAwQFBgcICQoLDA0ODw$Awni/k4L3.fQ/kgo1BwjRBbi2b8",
+                    &b"password"[..],
+                    20,
+                ),
+                (PASSLIB, &b"password"[..], 32),
+                (
+                    "$pbkdf2-sha512$1000$AAECAwQFBgcICQoLDA0ODw$x05AgND7tB/uWGjA/2D9dayuJjghWYfl/1T46uIRM5ta0a9uOHvBLdOnC7blqQEIFBxfCONToumEQ5pDM8Qtbg",
+                    &b"password"[..],
+                    64,
+                ),
+                (
+                    "$pbkdf2-sha256$6400$0ZrzXitFSGltTQnBWOsdAw$Y11AchqV4b0sUisdZd0Xr97KWoymNE0LNNrnEgY4H9M",
+                    &b"password"[..],
+                    32,
+                ),
+            ];
+
+            for (hash, password, length) in encoded.iter() {
+                let expected = PasswordHash::from_encoded(hash).unwrap();
+                let password = Password::from_slice(password).unwrap();
+                assert!(verify(&expected, &password, None).is_ok());
+                assert!(verify_with_pepper(&expected, &password, &[], None).is_ok());
+                assert_eq!(expected.len(), *length);
+            }
         }
 
         #[test]
-        fn test_encoding_and_verify_2() {
-            let iterations: u32 = 3;
-            let memory: u32 = 65536;
-            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
-            let raw_hash =
-                hex::decode("ed4b0fd657e165f9ffe90f66ff315fbec878e629f03b2d6468d4b17a50c796aa")
-                    .unwrap();
-            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$7UsP1lfhZfn/6Q9m/zFfvsh45inwOy1kaNSxelDHlqo";
+        fn test_verify_err_modified_password() {
+            let password = Password::from_slice(b"Password").unwrap();
 
-            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
-            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
-            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+            let expected = PasswordHash::from_encoded(DJANGO).unwrap();
+            assert!(verify(&expected, &password, None).is_err());
+            let expected = PasswordHash::from_encoded(PASSLIB).unwrap();
+            assert!(verify(&expected, &password, None).is_err());
         }
 
         #[test]
-        fn test_encoding_and_verify_3() {
-            let iterations: u32 = 3;
-            let memory: u32 = 65536;
-            // Different salt from test 2
-            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
-            let raw_hash =
-                hex::decode("fa9ea96fecd0998251d698c1303edda4df3889a39bfa87cd5e7b8656ef61b510")
-                    .unwrap();
-            let encoded_hash = "$argon2i$v=19$m=65536,t=3,p=1$U29tZVNhbHRTb21lU2FsdA$+p6pb+zQmYJR1pjBMD7dpN84iaOb+ofNXnuGVu9htRA";
+        fn test_verify_seven_digit_iterations() {
+            // At 132 characters, this is longer than an encoded password hash using Argon2id.
+            let encoded = "$pbkdf2-sha512$1000000$AAECAwQFBgcICQoLDA0ODw$bx1hflh/kaPQztjjG6yvrxT0MQu/xzhpbO0kaogL58ebGVD6yekinxa3w9AYpBmw1NHfu8LnQM5z/s4jaZ2KHQ";
+            let password = Password::from_slice(b"password").unwrap();
 
-            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
-            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
-            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+            let expected = PasswordHash::from_encoded(encoded).unwrap();
+            assert_eq!(expected.iterations, 1_000_000);
+            assert_eq!(expected.len(), 64);
+            assert!(verify(&expected, &password, None).is_ok());
         }
 
         #[test]
-        fn test_encoding_and_verify_4() {
-            let iterations: u32 = 3;
-            let memory: u32 = 256;
-            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
-            let raw_hash =
-                hex::decode("fb3e0cdf7b10970bf6711c151861851566006f8986c9109ba2cdd5d98f9ca9d7")
-                    .unwrap();
-            let encoded_hash = "$argon2i$v=19$m=256,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$+z4M33sQlwv2cRwVGGGFFWYAb4mGyRCbos3V2Y+cqdc";
+        fn test_verify_then_rehash() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(PASSLIB).unwrap();
 
-            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
-            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
-            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+            assert!(hash_password_verify(&expected, &password, 1000, 1 << 10).is_err());
+            assert!(verify(&expected, &password, None).is_ok());
+            assert!(needs_rehash(&expected, 3, 1 << 10));
+
+            let rehashed = hash_password(&password, 3, 1 << 10).unwrap();
+            assert!(!needs_rehash(&rehashed, 3, 1 << 10));
+            assert!(verify(&rehashed, &password, None).is_ok());
         }
 
         #[test]
-        fn test_encoding_and_verify_5() {
-            let iterations: u32 = 4;
-            let memory: u32 = 256;
-            let password = Password::from_slice(b"passwordPASSWORDPassword").unwrap();
-            let raw_hash =
-                hex::decode("9b134c4c1c34e66170d1088c18be3a8e0f4a1837d4c069703ce62f85248b1e8f")
-                    .unwrap();
-            let encoded_hash = "$argon2i$v=19$m=256,t=4,p=1$c29tZXNhbHRzb21lc2FsdA$mxNMTBw05mFw0QiMGL46jg9KGDfUwGlwPOYvhSSLHo8";
+        fn test_verify_policy() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(PASSLIB).unwrap();
 
-            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
-            assert!(expected.unprotected_as_bytes() == &raw_hash[..]);
-            assert!(hash_password_verify(&expected, &password, iterations, memory).is_ok());
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+            let policy = policy.with_max_pbkdf2_iterations(1000).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_ok());
+            let policy = policy.with_max_pbkdf2_iterations(999).unwrap();
+            assert!(verify(&expected, &password, Some(&policy)).is_err());
+
+            assert!(policy.with_max_pbkdf2_iterations(0).is_err());
+            assert!(policy.with_max_pbkdf2_iterations(1).is_ok());
+        }
+
+        #[test]
+        fn test_bad_encoding() {
+            let bad = [
+                // iterations zero, with leading zeroes or missing
+                "pbkdf2_sha256$0$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "pbkdf2_sha256$01000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "pbkdf2_sha256$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "$pbkdf2-sha256$0$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c",
+                // empty salt
+                "pbkdf2_sha256$1000$$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "$pbkdf2-sha256$1000$$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c",
+                // unsupported or mismatched hash function
+                "pbkdf2_sha512$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "pbkdf2_sha1$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "$pbkdf2-sha384$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c",
+                "$pbkdf2-sha512$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c",
+                // Django's padding missing, or passlib's present
+                "pbkdf2_sha256$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A",
+                "$pbkdf2-sha256$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c=",
+                // `+` instead of `.` in passlib's variant of Base64
+                "$pbkdf2-sha256$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo+Qwv7UYtHHmeg9SK49fGkEamC2c",
+                // leading `$` in Django's format, or missing in passlib's
+                "$pbkdf2_sha256$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=",
+                "pbkdf2-sha256$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c",
+                // additional fields
+                "pbkdf2_sha256$1000$seasalt$JgZryXe2Ga8ysg6XbzkLpTdyPQrHqsinbL9BnnhgX4A=$",
+                "$pbkdf2-sha256$1000$AAECAwQFBgcICQoLDA0ODw$JeuGrMduQwGPGLmo.Qwv7UYtHHmeg9SK49fGkEamC2c$",
+            ];
+
+            for encoded in bad.iter() {
+                assert!(PasswordHash::from_encoded(encoded).is_err());
+            }
         }
     }
 
@@ -538,6 +2054,8 @@ mod public {
         fn test_valid_encoded_password() {
             let valid = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             assert!(PasswordHash::from_encoded(valid).is_ok());
+            let valid_id = "$argon2id$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            assert!(PasswordHash::from_encoded(valid_id).is_ok());
         }
 
         #[test]
@@ -594,9 +2112,17 @@ mod public {
             let zero = "$argon2i$v=19$m=65536,t=3,p=0$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let two = "$argon2i$v=19$m=65536,t=3,p=2$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
 
+            let max = "$argon2i$v=19$m=65536,t=3,p=255$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let above_max = "$argon2i$v=19$m=65536,t=3,p=256$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            // Allowed by Argon2, but rejected to bound the work of verifying.
+            let argon2_max = "$argon2i$v=19$m=134217720,t=3,p=16777215$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
             assert!(PasswordHash::from_encoded(one).is_ok());
             assert!(PasswordHash::from_encoded(zero).is_err());
-            assert!(PasswordHash::from_encoded(two).is_err());
+            assert!(PasswordHash::from_encoded(two).is_ok());
+            assert!(PasswordHash::from_encoded(max).is_ok());
+            assert!(PasswordHash::from_encoded(above_max).is_err());
+            assert!(PasswordHash::from_encoded(argon2_max).is_err());
         }
 
         #[test]
@@ -611,6 +2137,15 @@ mod public {
             assert!(PasswordHash::from_encoded(&u32_overflow).is_err());
         }
 
+        #[test]
+        fn test_bad_encoding_invalid_memory_for_lanes() {
+            let exact_min = "$argon2i$v=19$m=32,t=3,p=4$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let less = "$argon2i$v=19$m=31,t=3,p=4$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
+            assert!(PasswordHash::from_encoded(exact_min).is_ok());
+            assert!(PasswordHash::from_encoded(less).is_err());
+        }
+
         #[test]
         fn test_bad_encoding_invalid_iterations() {
             let exact_min = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
@@ -625,12 +2160,14 @@ mod public {
 
         #[test]
         fn test_bad_encoding_invalid_algo() {
-            let argon2id = "$argon2id$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let argon2d = "$argon2d$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let argon2 = "$argon2$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let uppercase = "$Argon2id$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let nothing = "$$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
 
             assert!(PasswordHash::from_encoded(argon2d).is_err());
-            assert!(PasswordHash::from_encoded(argon2id).is_err());
+            assert!(PasswordHash::from_encoded(argon2).is_err());
+            assert!(PasswordHash::from_encoded(uppercase).is_err());
             assert!(PasswordHash::from_encoded(nothing).is_err());
         }
 
@@ -732,22 +2269,33 @@ mod public {
 
         #[test]
         fn test_bounds_max_min_encoded_len() {
-            let minimum = "$argon2i$v=19$m=8,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let minimum = "$pbkdf2$1$cA$kHoeNn2U3it9elsZu7flWMe8FaQ";
             assert_eq!(minimum.len(), PasswordHash::MIN_ENCODED_LEN);
-            let maximum = "$argon2i$v=19$m=1111111111,t=1111111111,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let maximum = "$pbkdf2-sha512$1111111111$AAECAwQFBgcICQoLDA0ODw$x05AgND7tB/uWGjA/2D9dayuJjghWYfl/1T46uIRM5ta0a9uOHvBLdOnC7blqQEIFBxfCONToumEQ5pDM8Qtbg";
             assert_eq!(maximum.len(), PasswordHash::MAX_ENCODED_LEN);
+            let argon2id = "$argon2id$v=19$m=1111111111,t=1111111111,p=111,keyid=MTIzNDU2Nzg$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            assert_eq!(argon2id.len(), 131);
 
-            // salt removed one char
-            let less = "$argon2i$v=19$m=8,t=3,p=1$cHBwcHBwcHBwcHBwcHBwc$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            // password hash removed one char
+            let less = "$pbkdf2$1$cA$kHoeNn2U3it9elsZu7flWMe8Fa";
             assert_eq!(less.len(), PasswordHash::MIN_ENCODED_LEN - 1);
             // salt added one char
-            let more = "$argon2i$v=19$m=1111111111,t=1111111111,p=1$cHBwcHBwcHBwcHBwcHBwcAA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let more = "$pbkdf2-sha512$1111111111$AAECAwQFBgcICQoLDA0ODwA$x05AgND7tB/uWGjA/2D9dayuJjghWYfl/1T46uIRM5ta0a9uOHvBLdOnC7blqQEIFBxfCONToumEQ5pDM8Qtbg";
             assert_eq!(more.len(), PasswordHash::MAX_ENCODED_LEN + 1);
 
             assert!(PasswordHash::from_encoded(minimum).is_ok());
             assert!(PasswordHash::from_encoded(maximum).is_ok());
+            assert!(PasswordHash::from_encoded(argon2id).is_ok());
             assert!(PasswordHash::from_encoded(less).is_err());
             assert!(PasswordHash::from_encoded(more).is_err());
+
+            // bcrypt always has the same length.
+            let bcrypt = "$2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy";
+            assert!(PasswordHash::from_encoded(bcrypt).is_ok());
+
+            // The shortest possible encoding using scrypt.
+            let scrypt = "$scrypt$ln=1,r=1,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            assert!(PasswordHash::from_encoded(scrypt).is_ok());
         }
 
         #[test]
@@ -808,6 +2356,38 @@ mod public {
             assert!(hash_password_verify(&dk, &password, 3, 4096).is_ok());
         }
 
+        #[test]
+        fn test_hash_password_uses_argon2id() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let dk = hash_password(&password, 3, 4096).unwrap();
+
+            assert!(dk
+                .unprotected_as_encoded()
+                .starts_with("$argon2id$v=19$m=4096,t=3,p=1$"));
+        }
+
+        #[test]
+        fn test_needs_rehash() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let dk = hash_password(&password, 3, 4096).unwrap();
+
+            assert!(!needs_rehash(&dk, 3, 4096));
+            assert!(needs_rehash(&dk, 4, 4096));
+            assert!(needs_rehash(&dk, 3, 8192));
+            assert!(needs_rehash(&dk, 3, 2048));
+        }
+
+        #[test]
+        fn test_needs_rehash_algorithm_and_lanes() {
+            let argon2i = PasswordHash::from_encoded("$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c").unwrap();
+            let argon2id = PasswordHash::from_encoded("$argon2id$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$dmStS6GjyZn83QmR/8InD3gwLSODIz215778hdG7GBk").unwrap();
+            let four_lanes = PasswordHash::from_encoded("$argon2id$v=19$m=65536,t=3,p=4$c29tZXNhbHRzb21lc2FsdA$dmStS6GjyZn83QmR/8InD3gwLSODIz215778hdG7GBk").unwrap();
+
+            assert!(needs_rehash(&argon2i, 3, 65536));
+            assert!(!needs_rehash(&argon2id, 3, 65536));
+            assert!(needs_rehash(&four_lanes, 3, 65536));
+        }
+
         #[test]
         fn test_argon2i_verify_err_modified_password() {
             let password = Password::from_slice(&[0u8; 64]).unwrap();
@@ -815,7 +2395,8 @@ mod public {
             let dk = hash_password(&password, 3, 4096).unwrap();
             let mut pwd_mod = dk.unprotected_as_bytes().to_vec();
             pwd_mod[0..32].copy_from_slice(&[0u8; 32]);
-            let modified = PasswordHash::from_slice(&pwd_mod, dk.salt.as_ref(), 3, 4096).unwrap();
+            let modified =
+                PasswordHash::from_slice_argon2id(&pwd_mod, dk.salt.as_ref(), 3, 4096).unwrap();
 
             assert!(hash_password_verify(&modified, &password, 3, 4096).is_err());
         }
@@ -828,7 +2409,8 @@ mod public {
             let mut salt_mod = dk.salt.as_ref().to_vec();
             salt_mod[0..16].copy_from_slice(&[0u8; 16]);
             let modified =
-                PasswordHash::from_slice(&dk.unprotected_as_bytes(), &salt_mod, 3, 4096).unwrap();
+                PasswordHash::from_slice_argon2id(dk.unprotected_as_bytes(), &salt_mod, 3, 4096)
+                    .unwrap();
 
             assert!(hash_password_verify(&modified, &password, 3, 4096).is_err());
         }
@@ -842,7 +2424,7 @@ mod public {
             let mut salt_mod = dk.salt.as_ref().to_vec();
             pwd_mod[0..32].copy_from_slice(&[0u8; 32]);
             salt_mod[0..16].copy_from_slice(&[0u8; 16]);
-            let modified = PasswordHash::from_slice(&pwd_mod, &salt_mod, 3, 4096).unwrap();
+            let modified = PasswordHash::from_slice_argon2id(&pwd_mod, &salt_mod, 3, 4096).unwrap();
 
             assert!(hash_password_verify(&modified, &password, 3, 4096).is_err());
         }
@@ -871,4 +2453,285 @@ mod public {
             .is_err());
         }
     }
+    mod test_verify_with_policy {
+        use super::*;
+
+        #[test]
+        fn test_policy_new() {
+            assert!(Policy::new(3, 3, 8, 8).is_ok());
+            assert!(Policy::new(3, 10, 8, 1 << 20).is_ok());
+            assert!(Policy::new(MIN_ITERATIONS - 1, 10, 8, 1 << 20).is_err());
+            assert!(Policy::new(3, 10, MIN_MEMORY - 1, 1 << 20).is_err());
+            assert!(Policy::new(4, 3, 8, 1 << 20).is_err());
+            assert!(Policy::new(3, 10, 1 << 20, 8).is_err());
+
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert!(policy.with_max_lanes(0).is_err());
+            assert!(policy.with_max_lanes(1).is_ok());
+            assert!(policy.with_max_lanes(255).is_ok());
+            assert!(policy.with_max_lanes(256).is_err());
+        }
+
+        #[test]
+        fn test_verify_uses_stored_params() {
+            let password = Password::from_slice(b"password").unwrap();
+            let argon2i = PasswordHash::from_encoded("$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fRsRY9PAt5H+qAKuXRzL0/6JbFShsCd62W5aHzESk/c").unwrap();
+            let argon2id = PasswordHash::from_encoded("$argon2id$v=19$m=8192,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk").unwrap();
+
+            assert!(verify(&argon2i, &password, None).is_ok());
+            assert!(verify(&argon2id, &password, None).is_ok());
+
+            let wrong = Password::from_slice(b"Password").unwrap();
+            assert!(verify(&argon2i, &wrong, None).is_err());
+            assert!(verify(&argon2id, &wrong, None).is_err());
+        }
+
+        #[test]
+        fn test_verify_hash_password() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let dk = hash_password(&password, 4, 4096).unwrap();
+
+            assert!(verify(&dk, &password, None).is_ok());
+            assert!(verify(&dk, &Password::from_slice(&[1u8; 64]).unwrap(), None).is_err());
+        }
+
+        #[test]
+        fn test_verify_policy_bounds() {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = PasswordHash::from_encoded("$argon2id$v=19$m=8192,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk").unwrap();
+
+            let exact = Policy::new(3, 3, 8192, 8192).unwrap();
+            let wide = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            let iterations_too_low = Policy::new(4, 10, 8, 1 << 20).unwrap();
+            let memory_too_low = Policy::new(3, 10, 8193, 1 << 20).unwrap();
+            let memory_too_high = Policy::new(3, 10, 8, 8191).unwrap();
+
+            assert!(verify(&hash, &password, Some(&exact)).is_ok());
+            assert!(verify(&hash, &password, Some(&wide)).is_ok());
+            assert!(verify(&hash, &password, Some(&iterations_too_low)).is_err());
+            assert!(verify(&hash, &password, Some(&memory_too_low)).is_err());
+            assert!(verify(&hash, &password, Some(&memory_too_high)).is_err());
+        }
+
+        #[test]
+        fn test_verify_policy_max_lanes() {
+            let password = Password::from_slice(b"password").unwrap();
+            let one_lane = PasswordHash::from_encoded("$argon2id$v=19$m=8192,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk").unwrap();
+            // Not a valid hash for the password, but rejected before hashing.
+            let four_lanes = PasswordHash::from_encoded("$argon2id$v=19$m=8192,t=3,p=4$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk").unwrap();
+
+            let default = Policy::new(3, 10, 8, 1 << 20).unwrap();
+            assert_eq!(default.max_lanes, MAX_LANES);
+            assert!(default.allows(&four_lanes));
+
+            let three = default.with_max_lanes(3).unwrap();
+            let four = default.with_max_lanes(4).unwrap();
+            assert!(three.allows(&one_lane));
+            assert!(!three.allows(&four_lanes));
+            assert!(four.allows(&four_lanes));
+            assert!(verify(&one_lane, &password, Some(&three)).is_ok());
+            assert!(verify(&four_lanes, &password, Some(&three)).is_err());
+        }
+
+        #[test]
+        fn test_verify_policy_rejects_expensive_hash() {
+            // If this were not rejected before hashing, it would attempt to use 4 TiB of memory.
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = PasswordHash::from_encoded("$argon2id$v=19$m=4294967295,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$ApULVYDZxJCfeJZ/s6a/6fCDntZLkysRiroh7IlwWyk").unwrap();
+            let policy = Policy::new(3, 10, 8, 1 << 20).unwrap();
+
+            assert!(verify(&hash, &password, Some(&policy)).is_err());
+        }
+    }
+    mod test_params {
+        use super::*;
+
+        #[test]
+        fn test_params_new() {
+            assert!(Params::new(MIN_ITERATIONS, MIN_MEMORY).is_ok());
+            assert!(Params::new(MIN_ITERATIONS - 1, MIN_MEMORY).is_err());
+            assert!(Params::new(MIN_ITERATIONS, MIN_MEMORY - 1).is_err());
+
+            let params = Params::new(4, 4096).unwrap();
+            assert_eq!(params.iterations(), 4);
+            assert_eq!(params.memory(), 4096);
+        }
+
+        #[test]
+        fn test_presets_are_valid() {
+            for preset in [Params::INTERACTIVE, Params::MODERATE, Params::SENSITIVE].iter() {
+                assert_eq!(
+                    Params::new(preset.iterations(), preset.memory()).unwrap(),
+                    *preset
+                );
+            }
+
+            assert!(Params::INTERACTIVE.memory() < Params::MODERATE.memory());
+            assert!(Params::MODERATE.memory() < Params::SENSITIVE.memory());
+        }
+
+        #[test]
+        fn test_calibrate_within_max_memory() {
+            let params = calibrate(Duration::from_millis(100), 256).unwrap();
+            assert!(params.memory() <= 256);
+            assert!(params.iterations() >= MIN_ITERATIONS);
+
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = hash_password(&password, params.iterations(), params.memory()).unwrap();
+            assert!(verify(&hash, &password, None).is_ok());
+        }
+
+        #[test]
+        fn test_calibrate_lowers_memory() {
+            // No amount of memory can hash within zero time, so the memory
+            // is halved down to the minimum before failing.
+            assert!(calibrate(Duration::from_secs(0), 64).is_err());
+        }
+
+        #[test]
+        fn test_calibrate_invalid_max_memory() {
+            assert!(calibrate(Duration::from_millis(100), MIN_MEMORY - 1).is_err());
+            assert!(calibrate(Duration::from_millis(100), 0).is_err());
+        }
+    }
+    mod test_pepper {
+        use super::*;
+
+        // Generated with the Argon2 implementation in OpenSSL 3.5, using a secret of 32 bytes of 0x01.
+        const PEPPERED: &str = "$argon2id$v=19$m=4096,t=3,p=1,keyid=a2V5MQ$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+
+        #[test]
+        fn test_pepper_new() {
+            let secret = || SecretKey::from_slice(&[1u8; 32]).unwrap();
+            assert!(Pepper::new(b"", secret()).is_err());
+            assert!(Pepper::new(b"1", secret()).is_ok());
+            assert!(Pepper::new(&[0u8; MAX_KEY_ID_LENGTH], secret()).is_ok());
+            assert!(Pepper::new(&[0u8; MAX_KEY_ID_LENGTH + 1], secret()).is_err());
+            assert_eq!(Pepper::new(b"key1", secret()).unwrap().key_id(), b"key1");
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_pepper_debug_omits_secret() {
+            let pepper = Pepper::new(b"1", SecretKey::from_slice(&[1u8; 32]).unwrap()).unwrap();
+            let debug = format!("{:?}", pepper);
+            assert_eq!(debug, "Pepper { key_id: [49], secret: [***OMITTED***] }");
+        }
+
+        #[test]
+        fn test_encoding_with_key_id() {
+            let hash = PasswordHash::from_encoded(PEPPERED).unwrap();
+            assert_eq!(hash.key_id(), Some(&b"key1"[..]));
+            assert_eq!(hash.unprotected_as_encoded(), PEPPERED);
+
+            let no_key_id = PasswordHash::from_encoded("$argon2id$v=19$m=4096,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY").unwrap();
+            assert_eq!(no_key_id.key_id(), None);
+        }
+
+        #[test]
+        fn test_bad_encoding_key_id() {
+            let empty = "$argon2id$v=19$m=4096,t=3,p=1,keyid=$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let too_long = "$argon2id$v=19$m=4096,t=3,p=1,keyid=MTIzNDU2Nzg5$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let max = "$argon2id$v=19$m=4096,t=3,p=1,keyid=MTIzNDU2Nzg$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let wrong_name = "$argon2id$v=19$m=4096,t=3,p=1,kid=a2V5MQ$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let before_p = "$argon2id$v=19$m=4096,t=3,keyid=a2V5MQ,p=1$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let with_data = "$argon2id$v=19$m=4096,t=3,p=1,keyid=a2V5MQ,data=a2V5MQ$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+            let invalid_base64 = "$argon2id$v=19$m=4096,t=3,p=1,keyid=a2V5M!$c29tZXNhbHRzb21lc2FsdA$fAJ9yEUMaMVmkbzbgxkXVXH1JDtbXbeDAdCwQdzqptY";
+
+            assert!(PasswordHash::from_encoded(empty).is_err());
+            assert!(PasswordHash::from_encoded(too_long).is_err());
+            assert!(PasswordHash::from_encoded(max).is_ok());
+            assert!(PasswordHash::from_encoded(wrong_name).is_err());
+            assert!(PasswordHash::from_encoded(before_p).is_err());
+            assert!(PasswordHash::from_encoded(with_data).is_err());
+            assert!(PasswordHash::from_encoded(invalid_base64).is_err());
+        }
+
+        #[test]
+        fn test_verify_with_pepper() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(PEPPERED).unwrap();
+            let key1 = Pepper::new(b"key1", SecretKey::from_slice(&[1u8; 32]).unwrap()).unwrap();
+            let key2 = Pepper::new(b"key2", SecretKey::from_slice(&[2u8; 32]).unwrap()).unwrap();
+            let key1_wrong =
+                Pepper::new(b"key1", SecretKey::from_slice(&[2u8; 32]).unwrap()).unwrap();
+
+            assert!(verify_with_pepper(&expected, &password, &[key1], None).is_ok());
+            assert!(verify_with_pepper(&expected, &password, &[key2], None).is_err());
+            assert!(verify_with_pepper(&expected, &password, &[key1_wrong], None).is_err());
+            assert!(verify_with_pepper(&expected, &password, &[], None).is_err());
+        }
+
+        #[test]
+        fn test_verify_with_pepper_selects_by_key_id() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(PEPPERED).unwrap();
+            let peppers = [
+                Pepper::new(b"key2", SecretKey::from_slice(&[2u8; 32]).unwrap()).unwrap(),
+                Pepper::new(b"key1", SecretKey::from_slice(&[1u8; 32]).unwrap()).unwrap(),
+            ];
+
+            assert!(verify_with_pepper(&expected, &password, &peppers, None).is_ok());
+
+            let policy = Policy::new(3, 10, 8, 2048).unwrap();
+            assert!(verify_with_pepper(&expected, &password, &peppers, Some(&policy)).is_err());
+        }
+
+        #[test]
+        fn test_peppered_hash_requires_pepper() {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(PEPPERED).unwrap();
+
+            assert!(hash_password_verify(&expected, &password, 3, 4096).is_err());
+            assert!(verify(&expected, &password, None).is_err());
+        }
+
+        #[test]
+        fn test_unpeppered_hash_with_peppers() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let pepper = Pepper::new(b"key1", SecretKey::default()).unwrap();
+            let dk = hash_password(&password, 3, 4096).unwrap();
+
+            assert!(verify_with_pepper(&dk, &password, &[pepper], None).is_ok());
+        }
+
+        #[test]
+        fn test_hash_password_with_pepper() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let pepper = Pepper::new(b"key1", SecretKey::default()).unwrap();
+            let other = Pepper::new(b"key1", SecretKey::default()).unwrap();
+            let dk = hash_password_with_pepper(&password, &pepper, 3, 4096).unwrap();
+
+            assert!(dk
+                .unprotected_as_encoded()
+                .starts_with("$argon2id$v=19$m=4096,t=3,p=1,keyid=a2V5MQ$"));
+            assert_eq!(dk.key_id(), Some(&b"key1"[..]));
+            assert_eq!(
+                PasswordHash::from_encoded(dk.unprotected_as_encoded()).unwrap(),
+                dk
+            );
+
+            assert!(verify_with_pepper(&dk, &password, &[pepper], None).is_ok());
+            assert!(verify_with_pepper(&dk, &password, &[other], None).is_err());
+            assert!(verify(&dk, &password, None).is_err());
+        }
+
+        #[test]
+        fn test_needs_rehash_with_pepper() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let key1 = Pepper::new(b"key1", SecretKey::default()).unwrap();
+            let key2 = Pepper::new(b"key2", SecretKey::default()).unwrap();
+            let peppered = hash_password_with_pepper(&password, &key1, 3, 4096).unwrap();
+            let unpeppered = hash_password(&password, 3, 4096).unwrap();
+
+            assert!(!needs_rehash_with_pepper(&peppered, &key1, 3, 4096));
+            assert!(needs_rehash_with_pepper(&peppered, &key2, 3, 4096));
+            assert!(needs_rehash_with_pepper(&peppered, &key1, 4, 4096));
+            assert!(needs_rehash_with_pepper(&peppered, &key1, 3, 8192));
+            assert!(needs_rehash_with_pepper(&unpeppered, &key1, 3, 4096));
+
+            assert!(needs_rehash(&peppered, 3, 4096));
+            assert!(!needs_rehash(&unpeppered, 3, 4096));
+        }
+    }
 }
@@ -27,7 +27,7 @@
 //! XChaCha20Poly1305.
 //!
 //! ## Password hashing and verification
-//! [`orion::pwhash`] offers password hashing and verification using Argon2i.
+//! [`orion::pwhash`] offers password hashing and verification using Argon2id.
 //!
 //! ## Key derivation
 //! [`orion::kdf`] offers key derivation using Argon2i.
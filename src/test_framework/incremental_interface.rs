@@ -72,51 +72,6 @@ where
     // The number 37 has no particular meaning.
     const DEFAULT_INPUT: [u8; 37] = [255u8; 37];
 
-    #[cfg(feature = "safe_api")]
-    /// Run all consistency tests given some input data.
-    /// Usually used with quickcheck.
-    pub fn run_all_tests_property(&self, data: &[u8]) {
-        self.consistency(data);
-        self.consistency(&[0u8; 0]);
-        self.produces_same_state(data);
-
-        // Following test requires std.
-        self.incremental_and_one_shot(data);
-
-        self.double_finalize_with_reset_no_update_ok(data);
-        self.double_finalize_with_reset_ok(data);
-        self.double_finalize_err(data);
-        self.update_after_finalize_with_reset_ok(data);
-        self.update_after_finalize_err(data);
-        self.double_reset_ok(data);
-        self.immediate_finalize();
-        Self::verify_same_input_ok(data);
-        Self::verify_diff_input_err(data);
-    }
-
-    #[cfg(feature = "safe_api")]
-    /// Used when quickcheck is not available to generate input.
-    /// Default input data is used instead. Requires std.
-    pub fn run_all_tests(&self) {
-        self.consistency(&Self::DEFAULT_INPUT);
-        self.consistency(&[0u8; 0]);
-        self.produces_same_state(&Self::DEFAULT_INPUT);
-
-        // Following test requires std.
-        self.incremental_processing_with_leftover();
-
-        self.incremental_and_one_shot(&Self::DEFAULT_INPUT);
-        self.double_finalize_with_reset_no_update_ok(&Self::DEFAULT_INPUT);
-        self.double_finalize_with_reset_ok(&Self::DEFAULT_INPUT);
-        self.double_finalize_err(&Self::DEFAULT_INPUT);
-        self.update_after_finalize_with_reset_ok(&Self::DEFAULT_INPUT);
-        self.update_after_finalize_err(&Self::DEFAULT_INPUT);
-        self.double_reset_ok(&Self::DEFAULT_INPUT);
-        self.immediate_finalize();
-        Self::verify_same_input_ok(&Self::DEFAULT_INPUT);
-        Self::verify_diff_input_err(&Self::DEFAULT_INPUT);
-    }
-
     #[cfg(not(feature = "safe_api"))]
     /// Used when quickcheck is not available to generate input.
     /// Default input data is used instead. Without std.
@@ -341,3 +296,84 @@ where
         assert!(T::verify_result(&expected, b"Bad data").is_err());
     }
 }
+
+#[cfg(feature = "safe_api")]
+impl<R, T> StreamingContextConsistencyTester<R, T>
+where
+    R: PartialEq + core::fmt::Debug,
+    T: TestableStreamingContext<R> + Clone + std::io::Write,
+{
+    /// Run all consistency tests given some input data.
+    /// Usually used with quickcheck.
+    pub fn run_all_tests_property(&self, data: &[u8]) {
+        self.consistency(data);
+        self.consistency(&[0u8; 0]);
+        self.produces_same_state(data);
+
+        // Following test requires std.
+        self.incremental_and_one_shot(data);
+
+        self.double_finalize_with_reset_no_update_ok(data);
+        self.double_finalize_with_reset_ok(data);
+        self.double_finalize_err(data);
+        self.update_after_finalize_with_reset_ok(data);
+        self.update_after_finalize_err(data);
+        self.double_reset_ok(data);
+        self.immediate_finalize();
+        Self::verify_same_input_ok(data);
+        Self::verify_diff_input_err(data);
+        self.io_write_and_update(data);
+        self.io_write_after_finalize_err(data);
+    }
+
+    /// Used when quickcheck is not available to generate input.
+    /// Default input data is used instead. Requires std.
+    pub fn run_all_tests(&self) {
+        self.consistency(&Self::DEFAULT_INPUT);
+        self.consistency(&[0u8; 0]);
+        self.produces_same_state(&Self::DEFAULT_INPUT);
+
+        // Following test requires std.
+        self.incremental_processing_with_leftover();
+
+        self.incremental_and_one_shot(&Self::DEFAULT_INPUT);
+        self.double_finalize_with_reset_no_update_ok(&Self::DEFAULT_INPUT);
+        self.double_finalize_with_reset_ok(&Self::DEFAULT_INPUT);
+        self.double_finalize_err(&Self::DEFAULT_INPUT);
+        self.update_after_finalize_with_reset_ok(&Self::DEFAULT_INPUT);
+        self.update_after_finalize_err(&Self::DEFAULT_INPUT);
+        self.double_reset_ok(&Self::DEFAULT_INPUT);
+        self.immediate_finalize();
+        Self::verify_same_input_ok(&Self::DEFAULT_INPUT);
+        Self::verify_diff_input_err(&Self::DEFAULT_INPUT);
+        self.io_write_and_update(&Self::DEFAULT_INPUT);
+        self.io_write_after_finalize_err(&Self::DEFAULT_INPUT);
+    }
+
+    /// Writing with `std::io::Write`, in pieces or all at once, is the same as
+    /// update().
+    fn io_write_and_update(&self, data: &[u8]) {
+        let mut state = self._initial_context.clone();
+        state.update(data).unwrap();
+        let expected = state.finalize().unwrap();
+
+        let mut state = self._initial_context.clone();
+        std::io::copy(&mut &data[..], &mut state).unwrap();
+        state.flush().unwrap();
+        assert!(state.finalize().unwrap() == expected);
+
+        let mut state = self._initial_context.clone();
+        for chunk in data.chunks(self.blocksize + 1) {
+            assert_eq!(state.write(chunk).unwrap(), chunk.len());
+        }
+        assert!(state.finalize().unwrap() == expected);
+    }
+
+    /// new(), finalize(), write(): ERR
+    fn io_write_after_finalize_err(&self, data: &[u8]) {
+        let mut state = self._initial_context.clone();
+        let _ = state.finalize().unwrap();
+        assert!(state.write(data).is_err());
+        assert!(state.write_all(b"Extra").is_err());
+    }
+}
@@ -125,6 +125,27 @@ macro_rules! impl_from_trait (($name:ident, $size:expr) => (
     }
 ));
 
+/// Macro that implements the `std::io::Write` trait on a streaming state
+/// called `$name`, which has an `update()` function. Writing to the state
+/// updates it with the written data, and any error from `update()` is returned
+/// as an `std::io::Error`. Only available with `safe_api`.
+macro_rules! impl_io_write_trait (($name:ident) => (
+    #[cfg(feature = "safe_api")]
+    impl std::io::Write for $name {
+        /// Update the state with `bytes`. All of `bytes` is always processed.
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.update(bytes)?;
+            Ok(bytes.len())
+        }
+
+        /// No-op, since the state does not buffer anything that `update()`
+        /// would not.
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+));
+
 ///
 /// Function implementation macros
 
@@ -136,13 +157,12 @@ macro_rules! impl_from_trait (($name:ident, $size:expr) => (
 /// $upper_bound.
 macro_rules! func_from_slice (($name:ident, $lower_bound:expr, $upper_bound:expr) => (
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    #[allow(clippy::double_comparisons)]
     /// Construct from a given byte slice.
     pub fn from_slice(slice: &[u8]) -> Result<$name, UnknownCryptoError> {
 
         let slice_len = slice.len();
 
-        if slice_len < $lower_bound || slice_len > $upper_bound {
+        if !($lower_bound..=$upper_bound).contains(&slice_len) {
             return Err(UnknownCryptoError);
         }
 
@@ -191,6 +211,16 @@ macro_rules! func_len (() => (
     }
 ));
 
+/// Macro to implement an `is_empty()` function which will return `true` if
+/// `len()` is zero.
+macro_rules! func_is_empty (() => (
+    #[inline]
+    /// Return `true` if the object has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.original_length == 0
+    }
+));
+
 /// Macro to implement a `generate()` function for objects that benefit from
 /// having a CSPRNG available to generate data of a fixed length $size.
 macro_rules! func_generate (($name:ident, $upper_bound:expr, $gen_length:expr) => (
@@ -287,6 +317,9 @@ macro_rules! test_as_bytes_and_get_length (($name:ident, $lower_bound:expr, $upp
         assert!(test_lower.$bytes_function().len() == test_lower.len());
         assert!(test_lower.len() == $lower_bound);
 
+        assert!(!test_upper.is_empty());
+        assert_eq!(test_lower.is_empty(), $lower_bound == 0);
+
         // Test non-fixed-length definitions
         if $lower_bound != $upper_bound {
             let test_upper = $name::from_slice(&[0u8; $upper_bound - 1]).unwrap();
@@ -431,6 +464,7 @@ macro_rules! construct_secret_key {
             func_unprotected_as_bytes!();
             func_generate!($name, $upper_bound, $gen_length);
             func_len!();
+            func_is_empty!();
         }
 
         #[cfg(test)]
@@ -487,6 +521,7 @@ macro_rules! construct_public {
         impl $name {
             func_from_slice!($name, $lower_bound, $upper_bound);
             func_len!();
+            func_is_empty!();
         }
 
         #[cfg(test)]
@@ -527,6 +562,7 @@ macro_rules! construct_public {
             func_from_slice!($name, $lower_bound, $upper_bound);
             func_generate!($name, $upper_bound, $gen_length);
             func_len!();
+            func_is_empty!();
         }
 
         #[cfg(test)]
@@ -593,6 +629,7 @@ macro_rules! construct_tag {
             func_from_slice!($name, $lower_bound, $upper_bound);
             func_unprotected_as_bytes!();
             func_len!();
+            func_is_empty!();
         }
 
         #[cfg(test)]
@@ -617,10 +654,11 @@ macro_rules! construct_tag {
 }
 
 /// Macro to construct a secret key used for HMAC. This pre-pads the given key
-/// to the required length specified by the HMAC specifications.
+/// to the required length specified by the HMAC specifications. Keys longer than
+/// the blocksize are first hashed with the given hash function.
 macro_rules! construct_hmac_key {
     ($(#[$meta:meta])*
-    ($name:ident, $test_module_name:ident, $size:expr)) => (
+    ($name:ident, $test_module_name:ident, $hash:ty, $size:expr)) => (
         $(#[$meta])*
         ///
         /// # Security:
@@ -657,14 +695,13 @@ macro_rules! construct_hmac_key {
             #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
             /// Construct from a given byte slice.
             pub fn from_slice(slice: &[u8]) -> Result<$name, UnknownCryptoError> {
-                use crate::hazardous::hash::sha512::{Sha512, SHA512_OUTSIZE};
-
                 let mut secret_key = [0u8; $size];
 
                 let slice_len = slice.len();
 
                 if slice_len > $size {
-                    secret_key[..SHA512_OUTSIZE].copy_from_slice(&Sha512::digest(slice)?.as_ref());
+                    let digest = <$hash>::digest(slice)?;
+                    secret_key[..digest.as_ref().len()].copy_from_slice(digest.as_ref());
                 } else {
                     secret_key[..slice_len].copy_from_slice(slice);
                 }
@@ -675,6 +712,7 @@ macro_rules! construct_hmac_key {
             func_unprotected_as_bytes!();
             func_generate!($name, $size, $size);
             func_len!();
+            func_is_empty!();
         }
 
         #[cfg(test)]
@@ -751,6 +789,7 @@ macro_rules! construct_secret_key_variable_size {
             func_from_slice_variable_size!($name);
             func_unprotected_as_bytes!();
             func_len!();
+            func_is_empty!();
             func_generate_variable_size!($name);
         }
 
@@ -789,6 +828,7 @@ macro_rules! construct_salt_variable_size {
         impl $name {
             func_from_slice_variable_size!($name);
             func_len!();
+            func_is_empty!();
             func_generate_variable_size!($name);
         }
 
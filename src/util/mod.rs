@@ -35,6 +35,7 @@ macro_rules! xor_slices {
 }
 
 pub(crate) mod endianness;
+pub(crate) mod state_export;
 pub(crate) mod u32x4;
 pub(crate) mod u64x4;
 
@@ -0,0 +1,205 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! The encoding of exported streaming states.
+//!
+//! An exported state starts with the version of the encoding and an identifier
+//! of the type that exported it, followed by the fields of the state in
+//! little-endian byte order. Every type has a fixed length for its exported
+//! states.
+
+use crate::errors::UnknownCryptoError;
+
+/// The version of the encoding of exported states.
+pub(crate) const STATE_EXPORT_VERSION: u8 = 1;
+/// The length of the version and type identifier that start an exported state.
+pub(crate) const STATE_EXPORT_HEADERSIZE: usize = 2;
+
+/// The identifiers of the types that can export their state.
+pub(crate) const STATE_ID_SHA512: u8 = 1;
+pub(crate) const STATE_ID_BLAKE2B: u8 = 2;
+pub(crate) const STATE_ID_HMAC_SHA512: u8 = 3;
+pub(crate) const STATE_ID_POLY1305: u8 = 4;
+
+/// Writes the fields of a state into a destination buffer.
+pub(crate) struct StateWriter<'a> {
+    dst: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> StateWriter<'a> {
+    /// Start writing a state of the type `id` into `dst`, which must be exactly
+    /// `size` bytes.
+    pub(crate) fn new(dst: &'a mut [u8], id: u8, size: usize) -> Result<Self, UnknownCryptoError> {
+        if dst.len() != size {
+            return Err(UnknownCryptoError);
+        }
+
+        dst[0] = STATE_EXPORT_VERSION;
+        dst[1] = id;
+
+        Ok(Self {
+            dst,
+            position: STATE_EXPORT_HEADERSIZE,
+        })
+    }
+
+    pub(crate) fn put_bytes(&mut self, bytes: &[u8]) {
+        self.dst[self.position..self.position + bytes.len()].copy_from_slice(bytes);
+        self.position += bytes.len();
+    }
+
+    pub(crate) fn put_u8(&mut self, value: u8) {
+        self.put_bytes(&[value]);
+    }
+
+    pub(crate) fn put_bool(&mut self, value: bool) {
+        self.put_u8(value as u8);
+    }
+
+    pub(crate) fn put_u32(&mut self, value: u32) {
+        self.put_bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_u64(&mut self, value: u64) {
+        self.put_bytes(&value.to_le_bytes());
+    }
+
+    /// Check that the whole destination buffer has been written.
+    pub(crate) fn finish(self) {
+        debug_assert_eq!(self.position, self.dst.len());
+    }
+}
+
+/// Reads the fields of a state from an exported state.
+pub(crate) struct StateReader<'a> {
+    src: &'a [u8],
+    position: usize,
+}
+
+impl<'a> StateReader<'a> {
+    /// Start reading a state of the type `id` from `src`, which must be exactly
+    /// `size` bytes and of the current version.
+    pub(crate) fn new(src: &'a [u8], id: u8, size: usize) -> Result<Self, UnknownCryptoError> {
+        if src.len() != size || src[0] != STATE_EXPORT_VERSION || src[1] != id {
+            return Err(UnknownCryptoError);
+        }
+
+        Ok(Self {
+            src,
+            position: STATE_EXPORT_HEADERSIZE,
+        })
+    }
+
+    pub(crate) fn get_bytes(&mut self, dst: &mut [u8]) {
+        dst.copy_from_slice(&self.src[self.position..self.position + dst.len()]);
+        self.position += dst.len();
+    }
+
+    pub(crate) fn get_u8(&mut self) -> u8 {
+        let mut bytes = [0u8; 1];
+        self.get_bytes(&mut bytes);
+        bytes[0]
+    }
+
+    /// Read a `bool`, which must be encoded as 0 or 1.
+    pub(crate) fn get_bool(&mut self) -> Result<bool, UnknownCryptoError> {
+        match self.get_u8() {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(UnknownCryptoError),
+        }
+    }
+
+    pub(crate) fn get_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.get_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    pub(crate) fn get_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.get_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Check that the whole exported state has been read.
+    pub(crate) fn finish(self) {
+        debug_assert_eq!(self.position, self.src.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writer_length_err() {
+        assert!(StateWriter::new(&mut [0u8; 9], STATE_ID_SHA512, 10).is_err());
+        assert!(StateWriter::new(&mut [0u8; 11], STATE_ID_SHA512, 10).is_err());
+        assert!(StateWriter::new(&mut [0u8; 10], STATE_ID_SHA512, 10).is_ok());
+    }
+
+    #[test]
+    fn test_reader_header_err() {
+        let mut exported = [0u8; 10];
+        let mut writer = StateWriter::new(&mut exported, STATE_ID_SHA512, 10).unwrap();
+        writer.put_u64(1);
+        writer.finish();
+
+        assert!(StateReader::new(&exported, STATE_ID_SHA512, 10).is_ok());
+        assert!(StateReader::new(&exported[..9], STATE_ID_SHA512, 9).is_ok());
+        assert!(StateReader::new(&exported[..9], STATE_ID_SHA512, 10).is_err());
+        assert!(StateReader::new(&exported, STATE_ID_BLAKE2B, 10).is_err());
+
+        exported[0] = STATE_EXPORT_VERSION + 1;
+        assert!(StateReader::new(&exported, STATE_ID_SHA512, 10).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut exported = [0u8; 2 + 1 + 1 + 4 + 8 + 3];
+        let mut writer = StateWriter::new(&mut exported, STATE_ID_POLY1305, 19).unwrap();
+        writer.put_u8(7);
+        writer.put_bool(true);
+        writer.put_u32(0x0102_0304);
+        writer.put_u64(0x0506_0708_090A_0B0C);
+        writer.put_bytes(b"abc");
+        writer.finish();
+
+        let mut reader = StateReader::new(&exported, STATE_ID_POLY1305, 19).unwrap();
+        assert_eq!(reader.get_u8(), 7);
+        assert_eq!(reader.get_bool(), Ok(true));
+        assert_eq!(reader.get_u32(), 0x0102_0304);
+        assert_eq!(reader.get_u64(), 0x0506_0708_090A_0B0C);
+        let mut bytes = [0u8; 3];
+        reader.get_bytes(&mut bytes);
+        assert_eq!(&bytes, b"abc");
+        reader.finish();
+    }
+
+    #[test]
+    fn test_bool_err() {
+        let exported = [STATE_EXPORT_VERSION, STATE_ID_SHA512, 2];
+        let mut reader = StateReader::new(&exported, STATE_ID_SHA512, 3).unwrap();
+        assert!(reader.get_bool().is_err());
+    }
+}
@@ -20,7 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub(crate) struct U32x4(
     pub(crate) u32,
     pub(crate) u32,
@@ -42,6 +42,15 @@ impl core::ops::BitXor for U32x4 {
     }
 }
 
+impl core::ops::BitXorAssign for U32x4 {
+    fn bitxor_assign(&mut self, _rhs: Self) {
+        self.0 ^= _rhs.0;
+        self.1 ^= _rhs.1;
+        self.2 ^= _rhs.2;
+        self.3 ^= _rhs.3;
+    }
+}
+
 impl zeroize::Zeroize for U32x4 {
     fn zeroize(&mut self) {
         self.0.zeroize();
@@ -51,6 +60,13 @@ impl zeroize::Zeroize for U32x4 {
     }
 }
 
+#[cfg(test)]
+impl PartialEq<U32x4> for U32x4 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1 && self.2 == other.2 && self.3 == other.3
+    }
+}
+
 impl U32x4 {
     #[must_use]
     pub(crate) const fn wrapping_add(self, _rhs: Self) -> Self {
@@ -87,6 +103,16 @@ impl U32x4 {
         )
     }
 
+    #[must_use]
+    pub(crate) const fn rotate_right(self, n: u32) -> Self {
+        Self(
+            self.0.rotate_right(n),
+            self.1.rotate_right(n),
+            self.2.rotate_right(n),
+            self.3.rotate_right(n),
+        )
+    }
+
     pub(crate) fn store_into_le(&self, slice_in: &mut [u8]) {
         debug_assert!(slice_in.len() == core::mem::size_of::<u32>() * 4);
         let mut iter = slice_in.chunks_exact_mut(core::mem::size_of::<u32>());
@@ -0,0 +1,68 @@
+// MIT License
+
+// Copyright (c) 2018-2020 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// Test vectors generated with libsodium's crypto_secretbox_easy(), covering
+// messages that end before, at and after the boundaries of the first and
+// second XSalsa20 keystream blocks.
+
+extern crate hex;
+extern crate orion;
+extern crate serde_json;
+
+use self::hex::decode;
+use self::serde_json::{Deserializer, Value};
+use std::{fs::File, io::BufReader};
+
+use orion::hazardous::aead::xsalsa20poly1305::*;
+
+fn run_tests_from_json(path_to_vectors: &str) {
+    let file = File::open(path_to_vectors).unwrap();
+    let reader = BufReader::new(file);
+    let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+    for test_file in stream {
+        for test_case in test_file.unwrap().as_array().unwrap() {
+            let key = decode(test_case.get("key").unwrap().as_str().unwrap()).unwrap();
+            let nonce = decode(test_case.get("nonce").unwrap().as_str().unwrap()).unwrap();
+            let message = decode(test_case.get("message").unwrap().as_str().unwrap()).unwrap();
+            let ciphertext =
+                decode(test_case.get("ciphertext").unwrap().as_str().unwrap()).unwrap();
+
+            let secret_key = SecretKey::from_slice(&key).unwrap();
+            let nonce = Nonce::from_slice(&nonce).unwrap();
+
+            let mut dst_out_ct = vec![0u8; ciphertext.len()];
+            let mut dst_out_pt = vec![0u8; message.len()];
+
+            seal(&secret_key, &nonce, &message, &mut dst_out_ct).unwrap();
+            open(&secret_key, &nonce, &ciphertext, &mut dst_out_pt).unwrap();
+
+            assert_eq!(dst_out_ct, ciphertext);
+            assert_eq!(dst_out_pt, message);
+        }
+    }
+}
+
+#[test]
+fn test_libsodium_secretbox() {
+    run_tests_from_json("./tests/test_data/libsodium_secretbox_generated.json");
+}
@@ -0,0 +1,200 @@
+use super::*;
+
+#[cfg(test)]
+mod reference_blake2bp {
+    // The unkeyed BLAKE2bp test vectors from the BLAKE2 reference KATs, computed
+    // using the tree hashing parameters of BLAKE2b.
+    extern crate hex;
+    extern crate serde_json;
+
+    use self::hex::decode;
+    use self::serde_json::{Deserializer, Value};
+    use super::*;
+    use std::{fs::File, io::BufReader};
+
+    /// The number of leaves in BLAKE2bp.
+    const LEAVES: usize = 4;
+    /// The blocksize of BLAKE2b.
+    const BLOCKSIZE: usize = 128;
+
+    fn blake2bp_from_params(input: &[u8]) -> blake2b::Digest {
+        let leaf_params = |offset: u64| {
+            blake2b::Params::new(64)
+                .unwrap()
+                .with_fanout(LEAVES as u8)
+                .with_max_depth(2)
+                .unwrap()
+                .with_inner_length(64)
+                .unwrap()
+                .with_node_offset(offset)
+                .with_last_node(offset == (LEAVES - 1) as u64)
+        };
+
+        let mut root_input = [0u8; LEAVES * 64];
+        for (leaf, leaf_digest) in root_input.chunks_mut(64).enumerate() {
+            let mut state = blake2b::Blake2b::with_params(None, &leaf_params(leaf as u64)).unwrap();
+            for stripe in input.chunks(BLOCKSIZE).skip(leaf).step_by(LEAVES) {
+                state.update(stripe).unwrap();
+            }
+            leaf_digest.copy_from_slice(state.finalize().unwrap().as_ref());
+        }
+
+        let root_params = leaf_params(0).with_node_depth(1).with_last_node(true);
+        let mut root = blake2b::Blake2b::with_params(None, &root_params).unwrap();
+        root.update(&root_input).unwrap();
+        root.finalize().unwrap()
+    }
+
+    #[test]
+    fn test_blake2bp_kat() {
+        let file = File::open("./tests/test_data/third_party/blake2-kat.json").unwrap();
+        let reader = BufReader::new(file);
+        let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+        for test_collection in stream {
+            if let Some(test_object) = test_collection.unwrap().as_array() {
+                for test_case in test_object {
+                    if test_case.get("hash").unwrap() == "blake2bp"
+                        && test_case.get("key").unwrap() == ""
+                    {
+                        let input = decode(test_case.get("in").unwrap().as_str().unwrap()).unwrap();
+                        let output =
+                            decode(test_case.get("out").unwrap().as_str().unwrap()).unwrap();
+
+                        assert_eq!(blake2bp_from_params(&input).as_ref(), &output[..]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hashlib_generated {
+    // These known-answer tests have been generated with Python's hashlib.blake2b,
+    // which exposes every field of the parameter block.
+    extern crate hex;
+
+    use self::hex::decode;
+    use super::*;
+
+    /// The input 0x00, 0x01, .., (len - 1) as bytes.
+    fn counting_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_salt() {
+        let salt = counting_input(16);
+        let params = blake2b::Params::new(64).unwrap().with_salt(&salt).unwrap();
+        let expected = decode("026d34896f691fd4e5577618f5a71193cb3ed1c9df63ba2c68cf6513f0d6e8311d3832d94f4fd1ade2936f087405efaf91069ddb89230f80a5958106e74c86c8").unwrap();
+
+        blake2b_params_test_runner(b"abc", &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_personal() {
+        let params = blake2b::Params::new(50)
+            .unwrap()
+            .with_personal(b"ZcashPoW\x01\x00\x00\x00\x09\x00\x00\x00")
+            .unwrap();
+        let expected = decode("490f7c57f2780a96f8abcb1554f94a1a08bc3ab8cd5192294377f2a3c1ad8c66b3cd3f10fb157710b54e9b0b3b44fc6620d5").unwrap();
+
+        blake2b_params_test_runner(b"", &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_short_salt_and_personal() {
+        let params = blake2b::Params::new(32)
+            .unwrap()
+            .with_salt(b"salt")
+            .unwrap()
+            .with_personal(b"personal")
+            .unwrap();
+        let expected =
+            decode("06fdc37681b33f3da56d72617049b6215c482f691bbc177929d7ad66821630c3").unwrap();
+
+        blake2b_params_test_runner(
+            b"The quick brown fox jumps over the lazy dog",
+            &[0u8; 0],
+            &params,
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_keyed_salt_and_personal() {
+        let params = blake2b::Params::new(64)
+            .unwrap()
+            .with_salt(&counting_input(32)[16..])
+            .unwrap()
+            .with_personal(&counting_input(48)[32..])
+            .unwrap();
+        let expected = decode("2a84c34652c1e22e372bab417732bc192e57b1748c7e8cd2b3019460db3d72661c0846881fc8eb942a29f4485e91f68dae3538bfcf5df3b314736c8e036ab5ee").unwrap();
+
+        blake2b_params_test_runner(
+            &counting_input(256),
+            &counting_input(64),
+            &params,
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_tree_leaf() {
+        let params = blake2b::Params::new(64)
+            .unwrap()
+            .with_fanout(2)
+            .with_max_depth(3)
+            .unwrap()
+            .with_leaf_length(4096)
+            .with_node_offset(5)
+            .with_node_depth(0)
+            .with_inner_length(32)
+            .unwrap();
+        let expected = decode("bb7d73c1cf4ed52c5bd25b750b9c3b09aef2ed247fa055b3910a21f3bbb8363c7b21675f2c2f73b4aec6d01be58a9287cd78c5d1296947f9d94ffdd189131ac1").unwrap();
+
+        blake2b_params_test_runner(&counting_input(200), &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_tree_last_node_max_values() {
+        let params = blake2b::Params::new(32)
+            .unwrap()
+            .with_fanout(0)
+            .with_max_depth(255)
+            .unwrap()
+            .with_leaf_length(0xFFFF_FFFF)
+            .with_node_offset(0xFFFF_FFFF_FFFF_FFFF)
+            .with_node_depth(255)
+            .with_inner_length(64)
+            .unwrap()
+            .with_last_node(true);
+        let expected =
+            decode("eca2180445a81a4a76936f9e7428f86fb585c4fe436b396510b1c07bf190bc92").unwrap();
+
+        blake2b_params_test_runner(&counting_input(129), &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_keyed_all_parameters() {
+        let params = blake2b::Params::new(48)
+            .unwrap()
+            .with_salt(b"saltsaltsaltsalt")
+            .unwrap()
+            .with_personal(b"personalpersonal")
+            .unwrap()
+            .with_fanout(4)
+            .with_max_depth(2)
+            .unwrap()
+            .with_leaf_length(1)
+            .with_node_offset(3)
+            .with_node_depth(1)
+            .with_inner_length(48)
+            .unwrap()
+            .with_last_node(true);
+        let expected = decode("671d85c6f8b78ded3b0a0c639d8a12a8532e522a07f18729dae13b52c746f069f71983722d656531915713d622c3d7dc").unwrap();
+
+        blake2b_params_test_runner(&[b'a'; 300], b"key", &params, &expected);
+    }
+}
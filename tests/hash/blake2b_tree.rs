@@ -0,0 +1,129 @@
+use super::*;
+
+#[cfg(test)]
+mod reference_generated {
+    // These known-answer tests have been generated with a Python implementation
+    // of BLAKE2b tree hashing, building the whole tree at once, with every field
+    // of the parameter block set explicitly for each node.
+    extern crate hex;
+
+    use self::hex::decode;
+    use super::*;
+
+    /// The input 0 % 251, 1 % 251, .., (len - 1) % 251 as bytes.
+    fn input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn params(
+        size: usize,
+        fanout: u8,
+        max_depth: u8,
+        leaf_length: u32,
+        inner_length: usize,
+    ) -> blake2b::Params {
+        blake2b::Params::new(size)
+            .unwrap()
+            .with_fanout(fanout)
+            .with_max_depth(max_depth)
+            .unwrap()
+            .with_leaf_length(leaf_length)
+            .with_inner_length(inner_length)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let expected = decode("c9bb5d044822bb07754577dc79e0fc53fd4bc4dc495f68ea05fa985cfee5dc182c212e22f656748fb0f36cada13e5b15e265213f469ce85d5c7c6cf9d7404a5f").unwrap();
+
+        blake2b_tree_test_runner(b"", &[0u8; 0], &params(64, 2, 255, 4096, 64), &expected);
+    }
+
+    #[test]
+    fn test_one_leaf() {
+        let expected =
+            decode("7ab71145339be0bbe04799f09ccc741656a5233d6f8fa8c2ae309dc34f5a29e2").unwrap();
+
+        blake2b_tree_test_runner(
+            &(0..100).collect::<Vec<u8>>(),
+            &[0u8; 0],
+            &params(32, 2, 255, 128, 32),
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_exactly_two_leaves() {
+        let expected = decode("3b342551180d4b1883ad11b775250e5e73bd41429d6581845ff99ccb54f8ad0218dad9fc464c42b24f0daa676ec57c9420bb0261c606defcc1042d7c57708936").unwrap();
+
+        blake2b_tree_test_runner(
+            &input(256),
+            &[0u8; 0],
+            &params(64, 2, 255, 128, 64),
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_binary_tree() {
+        let expected =
+            decode("2ef109c55d39f1e937cfddd9d0c751c51af3bfe89e2d15570c239acfd657878a").unwrap();
+
+        blake2b_tree_test_runner(
+            &input(1000),
+            &[0u8; 0],
+            &params(32, 2, 255, 64, 32),
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_keyed_salt_personal_fanout_3() {
+        let key: Vec<u8> = (0..32).collect();
+        let params = params(48, 3, 6, 100, 40)
+            .with_salt(b"salt")
+            .unwrap()
+            .with_personal(b"person")
+            .unwrap();
+        let expected = decode("de8a59491a9f85874a04ca0d79c90b45cfdf8bb6019840b7d563cda56e5c3cbe2f8f42d32c693117a0ab99aac8b23fbe").unwrap();
+
+        blake2b_tree_test_runner(&input(3000), &key, &params, &expected);
+    }
+
+    #[test]
+    fn test_unlimited_fanout() {
+        let expected = decode("b5472614b304d90257b65a9ce4efab06074f319174344a437c70039753e55182968ee93391b6a5bde1ddefae4640ed4e495cc49b44f9aebae27f75587110cd67").unwrap();
+
+        blake2b_tree_test_runner(
+            &input(2000),
+            &[0u8; 0],
+            &params(64, 0, 2, 128, 64),
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_unlimited_leaf_length() {
+        let expected =
+            decode("2e5da1381faac6373f9070cb023e535106aa811536375fa4a7f4a48e4fd55b95").unwrap();
+
+        blake2b_tree_test_runner(&input(2000), &[0u8; 0], &params(32, 4, 2, 0, 64), &expected);
+    }
+
+    #[test]
+    fn test_too_deep_err() {
+        // 1000 bytes in leaves of 64 bytes need a binary tree with the root at depth 4.
+        let mut state = tree::Blake2bTree::new(None, &params(32, 2, 4, 64, 32)).unwrap();
+        assert!(state.update(&input(1000)).is_err());
+
+        let mut state = tree::Blake2bTree::new(None, &params(32, 2, 5, 64, 32)).unwrap();
+        state.update(&input(1000)).unwrap();
+        assert!(state.finalize().is_ok());
+
+        // 513 bytes need 9 leaves, where the last one only adds the highest level
+        // when finalizing.
+        let mut state = tree::Blake2bTree::new(None, &params(32, 2, 4, 64, 32)).unwrap();
+        state.update(&input(513)).unwrap();
+        assert!(state.finalize().is_err());
+    }
+}
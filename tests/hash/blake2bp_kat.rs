@@ -0,0 +1,76 @@
+extern crate hex;
+extern crate serde_json;
+
+use self::hex::decode;
+use super::*;
+
+use self::serde_json::{Deserializer, Value};
+use std::{fs::File, io::BufReader};
+
+#[test]
+fn test_blake2bp_kat() {
+    let file = File::open("./tests/test_data/third_party/blake2-kat.json").unwrap();
+    let reader = BufReader::new(file);
+    let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+    for test_collection in stream {
+        if let Some(test_object) = test_collection.unwrap().as_array() {
+            for test_case in test_object {
+                // Only test BLAKE2bp test vectors
+                if test_case.get("hash").unwrap() == "blake2bp" {
+                    blake2bp_test_runner(
+                        &decode(test_case.get("in").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("key").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("out").unwrap().as_str().unwrap()).unwrap(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod reference_generated {
+    // The BLAKE2 reference KATs only use an output length of 64 and short inputs.
+    // These known-answer tests have been generated with a Python implementation
+    // of the reference BLAKE2bp, which passes all of the reference KATs. The long
+    // inputs are large enough for the leaves to be updated on their own threads,
+    // when the `parallel` feature is enabled.
+    use super::*;
+
+    /// The input 0 % 251, 1 % 251, .., (len - 1) % 251 as bytes.
+    fn input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_long_input() {
+        let expected = decode("3deaca558e7a78b637e434024358f75193d7a8e1a67cd433f7e0fd3fb7e066abc20347e4803c9edb589de0396432f3bd0464e6eb70dd6598e71f8b0f4ecf5cdc").unwrap();
+
+        blake2bp_test_runner(&input(300 * 1024 + 77), &[0u8; 0], &expected);
+    }
+
+    #[test]
+    fn test_long_input_keyed_size_32() {
+        let key: Vec<u8> = (0..64).collect();
+        let expected =
+            decode("f330faa2f094968db6183a68edd2ca30873bfe03b9d377e1148b68075f02d9dd").unwrap();
+
+        blake2bp_test_runner(&input(300 * 1024 + 77), &key, &expected);
+    }
+
+    #[test]
+    fn test_size_20() {
+        let expected = decode("11e6e8d8d4953f20ebdc836eb360bb9cc02a9dcd").unwrap();
+
+        blake2bp_test_runner(b"abc", &[0u8; 0], &expected);
+    }
+
+    #[test]
+    fn test_keyed_size_48() {
+        let data: Vec<u8> = (0..256).map(|i| i as u8).cycle().take(768).collect();
+        let expected = decode("10d8c8f9f27aa4e5f5bb5cdcc686d9be3e665e38e362318c13f057df12b3d19498a0dc111320c8cd770c5601901a29df").unwrap();
+
+        blake2bp_test_runner(&data, b"key", &expected);
+    }
+}
@@ -0,0 +1,38 @@
+extern crate hex;
+extern crate serde_json;
+
+use self::hex::decode;
+use super::*;
+
+use self::serde_json::{Deserializer, Value};
+use std::{fs::File, io::BufReader};
+
+#[test]
+fn test_blake2s_kat() {
+    let file = File::open("./tests/test_data/third_party/blake2-kat.json").unwrap();
+    let reader = BufReader::new(file);
+    let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+    for test_collection in stream {
+        if let Some(test_object) = test_collection.unwrap().as_array() {
+            for test_case in test_object {
+                // Only test BLAKE2s test vectors
+                if test_case.get("hash").unwrap() == "blake2s" {
+                    blake2s_test_runner(
+                        &decode(test_case.get("in").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("key").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("out").unwrap().as_str().unwrap()).unwrap(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rfc7693_appendix_b() {
+    let expected =
+        decode("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982").unwrap();
+
+    blake2s_test_runner(b"abc", &[0u8; 0], &expected);
+}
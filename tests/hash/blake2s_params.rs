@@ -0,0 +1,185 @@
+use super::*;
+
+#[cfg(test)]
+mod reference_blake2sp {
+    // The unkeyed BLAKE2sp test vectors from the BLAKE2 reference KATs, computed
+    // using the tree hashing parameters of BLAKE2s.
+    extern crate hex;
+    extern crate serde_json;
+
+    use self::hex::decode;
+    use self::serde_json::{Deserializer, Value};
+    use super::*;
+    use std::{fs::File, io::BufReader};
+
+    /// The number of leaves in BLAKE2sp.
+    const LEAVES: usize = 8;
+    /// The blocksize of BLAKE2s.
+    const BLOCKSIZE: usize = 64;
+
+    fn blake2sp_from_params(input: &[u8]) -> blake2s::Digest {
+        let leaf_params = |offset: u64| {
+            blake2s::Params::new(32)
+                .unwrap()
+                .with_fanout(LEAVES as u8)
+                .with_max_depth(2)
+                .unwrap()
+                .with_inner_length(32)
+                .unwrap()
+                .with_node_offset(offset)
+                .unwrap()
+                .with_last_node(offset == (LEAVES - 1) as u64)
+        };
+
+        let mut root_input = [0u8; LEAVES * 32];
+        for (leaf, leaf_digest) in root_input.chunks_mut(32).enumerate() {
+            let mut state = blake2s::Blake2s::with_params(None, &leaf_params(leaf as u64)).unwrap();
+            for stripe in input.chunks(BLOCKSIZE).skip(leaf).step_by(LEAVES) {
+                state.update(stripe).unwrap();
+            }
+            leaf_digest.copy_from_slice(state.finalize().unwrap().as_ref());
+        }
+
+        let root_params = leaf_params(0).with_node_depth(1).with_last_node(true);
+        let mut root = blake2s::Blake2s::with_params(None, &root_params).unwrap();
+        root.update(&root_input).unwrap();
+        root.finalize().unwrap()
+    }
+
+    #[test]
+    fn test_blake2sp_kat() {
+        let file = File::open("./tests/test_data/third_party/blake2-kat.json").unwrap();
+        let reader = BufReader::new(file);
+        let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+        for test_collection in stream {
+            if let Some(test_object) = test_collection.unwrap().as_array() {
+                for test_case in test_object {
+                    if test_case.get("hash").unwrap() == "blake2sp"
+                        && test_case.get("key").unwrap() == ""
+                    {
+                        let input = decode(test_case.get("in").unwrap().as_str().unwrap()).unwrap();
+                        let output =
+                            decode(test_case.get("out").unwrap().as_str().unwrap()).unwrap();
+
+                        assert_eq!(blake2sp_from_params(&input).as_ref(), &output[..]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hashlib_generated {
+    // These known-answer tests have been generated with Python's hashlib.blake2s,
+    // which exposes every field of the parameter block.
+    extern crate hex;
+
+    use self::hex::decode;
+    use super::*;
+
+    /// The input 0x00, 0x01, .., (len - 1) as bytes.
+    fn counting_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_salt() {
+        let params = blake2s::Params::new(32)
+            .unwrap()
+            .with_salt(&counting_input(8))
+            .unwrap();
+        let expected =
+            decode("e97f81d103fc42502e198ec52dc5c10749b642c48655c1b453e2fcd2ff62ee2f").unwrap();
+
+        blake2s_params_test_runner(b"abc", &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_personal() {
+        let params = blake2s::Params::new(32)
+            .unwrap()
+            .with_personal(b"WireGrd!")
+            .unwrap();
+        let expected =
+            decode("f3adf3ae56f6eb1d2e7b32e4e99f217a124c00e8129ad550847e175d48ac6f6f").unwrap();
+
+        blake2s_params_test_runner(b"", &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_short_salt_and_personal() {
+        let params = blake2s::Params::new(16)
+            .unwrap()
+            .with_salt(b"salt")
+            .unwrap()
+            .with_personal(b"me")
+            .unwrap();
+        let expected = decode("c12967e1333868a9ef0ce90889f1ec07").unwrap();
+
+        blake2s_params_test_runner(
+            b"The quick brown fox jumps over the lazy dog",
+            &[0u8; 0],
+            &params,
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_keyed_salt_and_personal() {
+        let params = blake2s::Params::new(32)
+            .unwrap()
+            .with_salt(&counting_input(16)[8..])
+            .unwrap()
+            .with_personal(&counting_input(24)[16..])
+            .unwrap();
+        let expected =
+            decode("925591eab6af40e6f72316ffe1ce77b54ec4cdd05006c87ae7b52011ff17f1e8").unwrap();
+
+        blake2s_params_test_runner(
+            &counting_input(256),
+            &counting_input(32),
+            &params,
+            &expected,
+        );
+    }
+
+    #[test]
+    fn test_tree_leaf() {
+        let params = blake2s::Params::new(32)
+            .unwrap()
+            .with_fanout(2)
+            .with_max_depth(3)
+            .unwrap()
+            .with_leaf_length(4096)
+            .with_node_offset(5)
+            .unwrap()
+            .with_node_depth(0)
+            .with_inner_length(16)
+            .unwrap();
+        let expected =
+            decode("8b7dd0f5f6702aaefaa1bdc0f503b1c47bd6a5e94b601515ccd79282d39e35eb").unwrap();
+
+        blake2s_params_test_runner(&counting_input(200), &[0u8; 0], &params, &expected);
+    }
+
+    #[test]
+    fn test_tree_last_node_max_values() {
+        let params = blake2s::Params::new(28)
+            .unwrap()
+            .with_fanout(0)
+            .with_max_depth(255)
+            .unwrap()
+            .with_leaf_length(0xFFFF_FFFF)
+            .with_node_offset(0xFFFF_FFFF_FFFF)
+            .unwrap()
+            .with_node_depth(255)
+            .with_inner_length(32)
+            .unwrap()
+            .with_last_node(true);
+        let expected = decode("ab23becac2922fcbef6ab3c4a39a5b19b678f9629a5cdc0849983e66").unwrap();
+
+        blake2s_params_test_runner(&counting_input(65), &[0u8; 0], &params, &expected);
+    }
+}
@@ -0,0 +1,75 @@
+extern crate hex;
+extern crate serde_json;
+
+use self::hex::decode;
+use super::*;
+
+use self::serde_json::{Deserializer, Value};
+use std::{fs::File, io::BufReader};
+
+#[test]
+fn test_blake2xb_kat() {
+    let file = File::open("./tests/test_data/third_party/blake2-kat.json").unwrap();
+    let reader = BufReader::new(file);
+    let stream = Deserializer::from_reader(reader).into_iter::<Value>();
+
+    for test_collection in stream {
+        if let Some(test_object) = test_collection.unwrap().as_array() {
+            for test_case in test_object {
+                // Only test BLAKE2Xb test vectors
+                if test_case.get("hash").unwrap() == "blake2xb" {
+                    blake2xb_test_runner(
+                        &decode(test_case.get("in").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("key").unwrap().as_str().unwrap()).unwrap(),
+                        &decode(test_case.get("out").unwrap().as_str().unwrap()).unwrap(),
+                        true,
+                    )
+                }
+            }
+        }
+    }
+}
+
+// The test vectors below are generated with a Python implementation of BLAKE2Xb
+// that was checked against the test vectors above.
+mod reference_generated {
+    use super::*;
+
+    fn test_data() -> Vec<u8> {
+        (0..1000).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_unknown_length() {
+        let expected = decode(
+            "ae080c1efbcf7f60ed52a04161d02b7ee63bed362534f0661da02c6e40cd208946d066b86b3dff620e57acea9cd72d3056cf6cb0c18341452a17ce2cced67b702669bf0bed358c1b708e97de2533b294cdd5e9e229678be36399b5b28d6541c4bc4e3079fb8a0fbdf6023a65f36c654947ce7c114a243670dad347f03275b5c5bd383e8d53fd0fe8f387ea3d6445fc6510c8a3b9fc5cced503b824504f0471bd3ac19514bdaf7a3c021dc44ca8ff6d656a6007d43b552f07560e8b79217060c1387971e8e3ee97d9",
+        )
+        .unwrap();
+
+        blake2xb_test_runner(b"abc", &[0u8; 0], &expected, false);
+    }
+
+    #[test]
+    fn test_unknown_length_keyed() {
+        let expected = decode(
+            "3c1f28b7bb67c6b1a4751aa2b4d55c5282580dd7b65e59b0a1a04aae0a09ca3a0525be6047ba0094a5f7bd38a888dc7cf1563ea495e348ba426c7bc904b56dbb34ab75ed0a7919ee0c2d24434ff6e89cd5dd9582a161536d45970a8aedd3bdfd82bcf10665d93d43f88020bde09967337c6a811404139a30b42e1c24b2f8c17ab982",
+        )
+        .unwrap();
+        let key: Vec<u8> = (0..32).collect();
+
+        blake2xb_test_runner(&test_data(), &key, &expected, false);
+    }
+
+    #[test]
+    fn test_long_output_keyed() {
+        let expected_last_block = decode(
+            "9949665bd8872cd7ef69b6aa7cfe51ea53cbed3237507e0f0fd2511c51cc168118348647c095a32732755e71864992774376e97203b46f182a774ae21bfb34fd",
+        )
+        .unwrap();
+        let key = blake2b::SecretKey::from_slice(&(0..32).collect::<Vec<u8>>()).unwrap();
+
+        let mut actual = vec![0u8; 1000];
+        blake2xb::Blake2xb::digest(Some(&key), &test_data(), &mut actual).unwrap();
+        assert_eq!(&actual[936..], &expected_last_block[..]);
+    }
+}
@@ -0,0 +1,45 @@
+extern crate hex;
+extern crate serde_json;
+
+use self::hex::decode;
+use super::*;
+
+use self::serde_json::Value;
+use std::{fs::File, io::BufReader};
+
+#[test]
+fn test_blake3_test_vectors() {
+    let file = File::open("./tests/test_data/third_party/blake3/test_vectors.json").unwrap();
+    let reader = BufReader::new(file);
+    let test_vectors: Value = serde_json::from_reader(reader).unwrap();
+
+    let key =
+        blake3::SecretKey::from_slice(test_vectors["key"].as_str().unwrap().as_bytes()).unwrap();
+    let context = test_vectors["context_string"].as_str().unwrap();
+
+    for test_case in test_vectors["cases"].as_array().unwrap() {
+        // The input is a repeating sequence of the bytes 0 to 250.
+        let input_len = test_case["input_len"].as_u64().unwrap() as usize;
+        let input: Vec<u8> = (0..input_len).map(|i| (i % 251) as u8).collect();
+
+        let hash = decode(test_case["hash"].as_str().unwrap()).unwrap();
+        let keyed_hash = decode(test_case["keyed_hash"].as_str().unwrap()).unwrap();
+        let derive_key = decode(test_case["derive_key"].as_str().unwrap()).unwrap();
+
+        blake3_test_runner(blake3::Blake3::new(), &input, &hash);
+        blake3_test_runner(blake3::Blake3::new_keyed(&key), &input, &keyed_hash);
+        blake3_test_runner(
+            blake3::Blake3::new_derive_key(context).unwrap(),
+            &input,
+            &derive_key,
+        );
+
+        // The one-shot functions.
+        assert!(blake3::Blake3::digest(&input).unwrap().as_ref() == &hash[..32]);
+        let expected_mac = blake3::Digest::from_slice(&keyed_hash[..32]).unwrap();
+        assert!(blake3::Blake3::verify(&expected_mac, &key, &input).is_ok());
+        let mut derived_key = vec![0u8; derive_key.len()];
+        blake3::Blake3::derive_key(context, &input, &mut derived_key).unwrap();
+        assert!(derived_key == derive_key);
+    }
+}
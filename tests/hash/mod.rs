@@ -1,9 +1,22 @@
 pub mod blake2b_kat;
+pub mod blake2b_params;
+pub mod blake2b_tree;
+pub mod blake2bp_kat;
+pub mod blake2s_kat;
+pub mod blake2s_params;
+pub mod blake2xb_kat;
+pub mod blake3_test_vectors;
 pub mod other_blake2b;
+pub mod sha256_nist_cavp;
+pub mod sha384_nist_cavp;
+pub mod sha3_nist_cavp;
 pub mod sha512_nist_cavp;
+pub mod shake_nist_cavp;
 
 extern crate orion;
-use self::orion::hazardous::hash::{blake2b, sha512};
+use self::orion::hazardous::hash::blake2b::{blake2bp, blake2xb, tree};
+use self::orion::hazardous::hash::sha3::{sha3_256, sha3_512, shake128, shake256};
+use self::orion::hazardous::hash::{blake2b, blake2s, blake3, sha256, sha384, sha512};
 
 fn blake2b_test_runner(input: &[u8], key: &[u8], output: &[u8]) {
     // Only make SecretKey if test case key value is not empty.
@@ -20,6 +33,217 @@ fn blake2b_test_runner(input: &[u8], key: &[u8], output: &[u8]) {
     assert!(digest.as_ref() == &output[..]);
 }
 
+fn blake2b_params_test_runner(input: &[u8], key: &[u8], params: &blake2b::Params, output: &[u8]) {
+    // Only make SecretKey if test case key value is not empty.
+    let secret_key = if key.is_empty() {
+        None
+    } else {
+        Some(blake2b::SecretKey::from_slice(key).unwrap())
+    };
+
+    let mut state = blake2b::Blake2b::with_params(secret_key.as_ref(), params).unwrap();
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.len() == output.len());
+    assert!(digest.as_ref() == &output[..]);
+
+    // The parameters must survive a reset.
+    state.reset(secret_key.as_ref()).unwrap();
+    state.update(input).unwrap();
+    assert!(state.finalize().unwrap().as_ref() == &output[..]);
+}
+
+fn blake2bp_test_runner(input: &[u8], key: &[u8], output: &[u8]) {
+    // Only make SecretKey if test case key value is not empty.
+    let secret_key = if key.is_empty() {
+        None
+    } else {
+        Some(blake2b::SecretKey::from_slice(key).unwrap())
+    };
+
+    let mut state = blake2bp::Blake2bp::new(secret_key.as_ref(), output.len()).unwrap();
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.len() == output.len());
+    assert!(digest.as_ref() == &output[..]);
+
+    // Updating with chunks that do not line up with the blocks of the leaves.
+    state.reset(secret_key.as_ref()).unwrap();
+    for chunk in input.chunks(100) {
+        state.update(chunk).unwrap();
+    }
+    assert!(state.finalize().unwrap().as_ref() == &output[..]);
+}
+
+fn blake2b_tree_test_runner(input: &[u8], key: &[u8], params: &blake2b::Params, output: &[u8]) {
+    // Only make SecretKey if test case key value is not empty.
+    let secret_key = if key.is_empty() {
+        None
+    } else {
+        Some(blake2b::SecretKey::from_slice(key).unwrap())
+    };
+
+    let mut state = tree::Blake2bTree::new(secret_key.as_ref(), params).unwrap();
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.len() == output.len());
+    assert!(digest.as_ref() == &output[..]);
+
+    // Updating with chunks that do not line up with the leaves.
+    state.reset(secret_key.as_ref()).unwrap();
+    for chunk in input.chunks(33) {
+        state.update(chunk).unwrap();
+    }
+    assert!(state.finalize().unwrap().as_ref() == &output[..]);
+}
+
+fn blake2xb_test_runner(input: &[u8], key: &[u8], output: &[u8], is_known_length: bool) {
+    // Only make SecretKey if test case key value is not empty.
+    let secret_key = if key.is_empty() {
+        None
+    } else {
+        Some(blake2b::SecretKey::from_slice(key).unwrap())
+    };
+    let output_size = if is_known_length {
+        Some(output.len())
+    } else {
+        None
+    };
+
+    let mut state = blake2xb::Blake2xb::new(secret_key.as_ref(), output_size).unwrap();
+    state.update(input).unwrap();
+    let mut actual = vec![0u8; output.len()];
+    state.finalize().unwrap().squeeze(&mut actual).unwrap();
+    assert!(actual == output);
+
+    // Read the output in pieces that do not line up with the blocks.
+    state.reset(secret_key.as_ref()).unwrap();
+    state.update(input).unwrap();
+    let mut reader = state.finalize().unwrap();
+    let mut actual = vec![0u8; output.len()];
+    for chunk in actual.chunks_mut(33) {
+        reader.squeeze(chunk).unwrap();
+    }
+    assert!(actual == output);
+
+    if is_known_length {
+        let mut actual_one_shot = vec![0u8; output.len()];
+        blake2xb::Blake2xb::digest(secret_key.as_ref(), input, &mut actual_one_shot).unwrap();
+        assert!(actual_one_shot == output);
+    }
+}
+
+fn blake2s_test_runner(input: &[u8], key: &[u8], output: &[u8]) {
+    // Only make SecretKey if test case key value is not empty.
+    let mut state = if key.is_empty() {
+        blake2s::Blake2s::new(None, output.len()).unwrap()
+    } else {
+        let secret_key = blake2s::SecretKey::from_slice(key).unwrap();
+        blake2s::Blake2s::new(Some(&secret_key), output.len()).unwrap()
+    };
+
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.len() == output.len());
+    assert!(digest.as_ref() == &output[..]);
+}
+
+fn blake2s_params_test_runner(input: &[u8], key: &[u8], params: &blake2s::Params, output: &[u8]) {
+    // Only make SecretKey if test case key value is not empty.
+    let secret_key = if key.is_empty() {
+        None
+    } else {
+        Some(blake2s::SecretKey::from_slice(key).unwrap())
+    };
+
+    let mut state = blake2s::Blake2s::with_params(secret_key.as_ref(), params).unwrap();
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.len() == output.len());
+    assert!(digest.as_ref() == &output[..]);
+
+    // The parameters must survive a reset.
+    state.reset(secret_key.as_ref()).unwrap();
+    state.update(input).unwrap();
+    assert!(state.finalize().unwrap().as_ref() == &output[..]);
+}
+
+/// Hash `input` with `state` as the two subtrees of the root, as a BLAKE3
+/// implementation that splits its input between several threads or machines would.
+fn blake3_subtrees_test_runner(state: &blake3::Blake3, input: &[u8], output: &[u8]) {
+    // The left subtree holds the largest power of two amount of chunks that
+    // leaves data for the right subtree.
+    let mut left_len = blake3::BLAKE3_CHUNKSIZE;
+    while left_len * 2 < input.len() {
+        left_len *= 2;
+    }
+
+    let mut left = state.clone();
+    left.update(&input[..left_len]).unwrap();
+    let mut right = state.clone();
+    right.set_input_offset(left_len as u64).unwrap();
+    right.update(&input[left_len..]).unwrap();
+
+    let left_cv = left.finalize_non_root().unwrap();
+    let right_cv = right.finalize_non_root().unwrap();
+
+    let digest = state.merge_subtrees_root(&left_cv, &right_cv);
+    assert!(digest.as_ref() == &output[..blake3::BLAKE3_OUTSIZE]);
+
+    let mut actual = vec![0u8; output.len()];
+    state
+        .merge_subtrees_root_xof(&left_cv, &right_cv)
+        .squeeze(&mut actual)
+        .unwrap();
+    assert!(actual == output);
+}
+
+fn blake3_test_runner(mut state: blake3::Blake3, input: &[u8], output: &[u8]) {
+    let initial_state = state.clone();
+
+    state.update(input).unwrap();
+    let digest = state.finalize().unwrap();
+    assert!(digest.as_ref() == &output[..blake3::BLAKE3_OUTSIZE]);
+
+    // Read the output in pieces that do not line up with the blocks.
+    state.reset();
+    for chunk in input.chunks(1000) {
+        state.update(chunk).unwrap();
+    }
+    let mut reader = state.finalize_xof().unwrap();
+    let mut actual = vec![0u8; output.len()];
+    for chunk in actual.chunks_mut(33) {
+        reader.squeeze(chunk).unwrap();
+    }
+    assert!(actual == output);
+
+    if input.len() > blake3::BLAKE3_CHUNKSIZE {
+        blake3_subtrees_test_runner(&initial_state, input, output);
+    }
+}
+
+fn sha256_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = sha256::Sha256::new();
+    state.update(data).unwrap();
+    let digest = state.finalize().unwrap();
+
+    let digest_one_shot = sha256::Sha256::digest(data).unwrap();
+
+    assert!(digest.as_ref() == digest_one_shot.as_ref());
+    assert!(digest.as_ref() == output);
+}
+
+fn sha384_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = sha384::Sha384::new();
+    state.update(data).unwrap();
+    let digest = state.finalize().unwrap();
+
+    let digest_one_shot = sha384::Sha384::digest(data).unwrap();
+
+    assert!(digest.as_ref() == digest_one_shot.as_ref());
+    assert!(digest.as_ref() == output);
+}
+
 fn sha512_test_runner(data: &[u8], output: &[u8]) {
     let mut state = sha512::Sha512::new();
     state.update(data).unwrap();
@@ -30,3 +254,59 @@ fn sha512_test_runner(data: &[u8], output: &[u8]) {
     assert!(digest.as_ref() == digest_one_shot.as_ref());
     assert!(digest.as_ref() == output);
 }
+
+fn sha3_256_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = sha3_256::Sha3_256::new();
+    state.update(data).unwrap();
+    let digest = state.finalize().unwrap();
+
+    let digest_one_shot = sha3_256::Sha3_256::digest(data).unwrap();
+
+    assert!(digest.as_ref() == digest_one_shot.as_ref());
+    assert!(digest.as_ref() == output);
+}
+
+fn sha3_512_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = sha3_512::Sha3_512::new();
+    state.update(data).unwrap();
+    let digest = state.finalize().unwrap();
+
+    let digest_one_shot = sha3_512::Sha3_512::digest(data).unwrap();
+
+    assert!(digest.as_ref() == digest_one_shot.as_ref());
+    assert!(digest.as_ref() == output);
+}
+
+fn shake128_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = shake128::Shake128::new();
+    state.update(data).unwrap();
+    let mut reader = state.finalize().unwrap();
+    // Read the output in two parts, to check that this gives the same output.
+    let mut actual = vec![0u8; output.len()];
+    let (first, second) = actual.split_at_mut(output.len() / 3);
+    reader.squeeze(first).unwrap();
+    reader.squeeze(second).unwrap();
+
+    let mut actual_one_shot = vec![0u8; output.len()];
+    shake128::Shake128::digest(data, &mut actual_one_shot).unwrap();
+
+    assert!(actual == actual_one_shot);
+    assert!(actual == output);
+}
+
+fn shake256_test_runner(data: &[u8], output: &[u8]) {
+    let mut state = shake256::Shake256::new();
+    state.update(data).unwrap();
+    let mut reader = state.finalize().unwrap();
+    // Read the output in two parts, to check that this gives the same output.
+    let mut actual = vec![0u8; output.len()];
+    let (first, second) = actual.split_at_mut(output.len() / 3);
+    reader.squeeze(first).unwrap();
+    reader.squeeze(second).unwrap();
+
+    let mut actual_one_shot = vec![0u8; output.len()];
+    shake256::Shake256::digest(data, &mut actual_one_shot).unwrap();
+
+    assert!(actual == actual_one_shot);
+    assert!(actual == output);
+}
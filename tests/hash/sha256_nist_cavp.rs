@@ -0,0 +1,79 @@
+// Examples from FIPS 180-2, as well as the NIST CAVP
+// SHA256 ShortMsg and LongMsg byte-oriented test vectors.
+use crate::hash::sha256;
+use crate::hash::sha256_test_runner;
+use crate::TestCaseReader;
+
+#[test]
+fn test_streaming_1() {
+    let expected = [
+        186, 120, 22, 191, 143, 1, 207, 234, 65, 65, 64, 222, 93, 174, 34, 35, 176, 3, 97, 163,
+        150, 23, 122, 156, 180, 16, 255, 97, 242, 0, 21, 173,
+    ];
+
+    let mut state = sha256::Sha256::new();
+    state.update(b"abc").unwrap();
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+#[test]
+fn test_streaming_2() {
+    let expected = [
+        36, 141, 106, 97, 210, 6, 56, 184, 229, 192, 38, 147, 12, 62, 96, 57, 163, 60, 228, 89,
+        100, 255, 33, 103, 246, 236, 237, 212, 25, 219, 6, 193,
+    ];
+
+    let mut state = sha256::Sha256::new();
+    state
+        .update(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")
+        .unwrap();
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+#[test]
+fn test_streaming_3() {
+    let expected = [
+        205, 199, 110, 92, 153, 20, 251, 146, 129, 161, 199, 226, 132, 215, 62, 103, 241, 128, 154,
+        72, 164, 151, 32, 14, 4, 109, 57, 204, 199, 17, 44, 208,
+    ];
+
+    let mut state = sha256::Sha256::new();
+    for _ in 0..1000 {
+        state.update(&[b'a'; 1000]).unwrap();
+    }
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+fn nist_cavp_runner(path: &str) {
+    let nist_cavp_fields: Vec<String> = vec!["Len".into(), "Msg".into(), "MD".into()];
+    let mut nist_cavp_reader = TestCaseReader::new(path, nist_cavp_fields, "=");
+
+    let mut test_case = nist_cavp_reader.next();
+    while test_case.is_some() {
+        let tc = test_case.unwrap();
+
+        let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
+        let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data("MD"));
+
+        sha256_test_runner(&input[..], &expected_output[..]);
+
+        // Read the next one
+        test_case = nist_cavp_reader.next();
+    }
+}
+
+#[test]
+fn test_nist_cavp_long_msg() {
+    nist_cavp_runner("./tests/test_data/third_party/nist/SHA256LongMsg.rsp");
+}
+
+#[test]
+fn test_nist_cavp_short_msg() {
+    nist_cavp_runner("./tests/test_data/third_party/nist/SHA256ShortMsg.rsp");
+}
@@ -0,0 +1,80 @@
+// Examples from FIPS 180-2, as well as the NIST CAVP
+// SHA384 ShortMsg and LongMsg byte-oriented test vectors.
+use crate::hash::sha384;
+use crate::hash::sha384_test_runner;
+use crate::TestCaseReader;
+
+#[test]
+fn test_streaming_1() {
+    let expected = [
+        203, 0, 117, 63, 69, 163, 94, 139, 181, 160, 61, 105, 154, 198, 80, 7, 39, 44, 50, 171, 14,
+        222, 209, 99, 26, 139, 96, 90, 67, 255, 91, 237, 128, 134, 7, 43, 161, 231, 204, 35, 88,
+        186, 236, 161, 52, 200, 37, 167,
+    ];
+
+    let mut state = sha384::Sha384::new();
+    state.update(b"abc").unwrap();
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+#[test]
+fn test_streaming_2() {
+    let expected = [
+        9, 51, 12, 51, 247, 17, 71, 232, 61, 25, 47, 199, 130, 205, 27, 71, 83, 17, 27, 23, 59, 59,
+        5, 210, 47, 160, 128, 134, 227, 176, 247, 18, 252, 199, 199, 26, 85, 126, 45, 185, 102,
+        195, 233, 250, 145, 116, 96, 57,
+    ];
+
+    let mut state = sha384::Sha384::new();
+    state.update(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu").unwrap();
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+#[test]
+fn test_streaming_3() {
+    let expected = [
+        157, 14, 24, 9, 113, 100, 116, 203, 8, 110, 131, 78, 49, 10, 74, 28, 237, 20, 158, 156, 0,
+        242, 72, 82, 121, 114, 206, 197, 112, 76, 42, 91, 7, 184, 179, 220, 56, 236, 196, 235, 174,
+        151, 221, 216, 127, 61, 137, 133,
+    ];
+
+    let mut state = sha384::Sha384::new();
+    for _ in 0..1000 {
+        state.update(&[b'a'; 1000]).unwrap();
+    }
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+fn nist_cavp_runner(path: &str) {
+    let nist_cavp_fields: Vec<String> = vec!["Len".into(), "Msg".into(), "MD".into()];
+    let mut nist_cavp_reader = TestCaseReader::new(path, nist_cavp_fields, "=");
+
+    let mut test_case = nist_cavp_reader.next();
+    while test_case.is_some() {
+        let tc = test_case.unwrap();
+
+        let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
+        let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data("MD"));
+
+        sha384_test_runner(&input[..], &expected_output[..]);
+
+        // Read the next one
+        test_case = nist_cavp_reader.next();
+    }
+}
+
+#[test]
+fn test_nist_cavp_long_msg() {
+    nist_cavp_runner("./tests/test_data/third_party/nist/SHA384LongMsg.rsp");
+}
+
+#[test]
+fn test_nist_cavp_short_msg() {
+    nist_cavp_runner("./tests/test_data/third_party/nist/SHA384ShortMsg.rsp");
+}
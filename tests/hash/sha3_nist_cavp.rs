@@ -0,0 +1,117 @@
+// The test_nist_cavp_* tests use official data: an excerpt of the NIST CAVP
+// SHA3-256 and SHA3-512 ShortMsg byte-oriented test vectors.
+// The test_generated_* tests use derived data: the messages of the NIST CAVP
+// SHA-512 ShortMsg and LongMsg files, with SHA3 outputs generated with Python
+// hashlib and cross-checked against OpenSSL. The remaining tests use outputs for
+// well-known messages.
+use crate::hash::sha3_256;
+use crate::hash::sha3_512;
+use crate::hash::{sha3_256_test_runner, sha3_512_test_runner};
+use crate::TestCaseReader;
+
+#[test]
+fn test_sha3_256_abc() {
+    let expected = [
+        58, 152, 93, 167, 79, 226, 37, 178, 4, 92, 23, 45, 107, 211, 144, 189, 133, 95, 8, 110, 62,
+        157, 82, 91, 70, 191, 226, 69, 17, 67, 21, 50,
+    ];
+
+    let mut state = sha3_256::Sha3_256::new();
+    state.update(b"abc").unwrap();
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+#[test]
+fn test_sha3_512_million_a() {
+    let expected = [
+        60, 58, 135, 109, 161, 64, 52, 171, 96, 98, 124, 7, 123, 185, 143, 126, 18, 10, 42, 83,
+        112, 33, 45, 255, 179, 56, 90, 24, 212, 243, 136, 89, 237, 49, 29, 10, 157, 81, 65, 206,
+        156, 197, 198, 110, 230, 137, 178, 102, 168, 170, 24, 172, 232, 40, 42, 14, 13, 181, 150,
+        201, 11, 10, 123, 135,
+    ];
+
+    let mut state = sha3_512::Sha3_512::new();
+    for _ in 0..1000 {
+        state.update(&[b'a'; 1000]).unwrap();
+    }
+    let res = state.finalize().unwrap();
+
+    assert_eq!(&expected[..], res.as_ref());
+}
+
+/// Runs test vectors in the format of NISTs SHA Long/Short message files.
+fn nist_cavp_runner(path: &str, field: &str, runner: fn(&[u8], &[u8])) {
+    let nist_cavp_fields: Vec<String> = vec!["Len".into(), "Msg".into(), field.into()];
+    let mut nist_cavp_reader = TestCaseReader::new(path, nist_cavp_fields, "=");
+
+    let mut test_case = nist_cavp_reader.next();
+    while test_case.is_some() {
+        let tc = test_case.unwrap();
+
+        let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
+        let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data(field));
+
+        runner(&input[..], &expected_output[..]);
+
+        // Read the next one
+        test_case = nist_cavp_reader.next();
+    }
+}
+
+#[test]
+fn test_nist_cavp_sha3_256_short_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/third_party/nist/SHA3_256ShortMsg_excerpt.rsp",
+        "MD",
+        sha3_256_test_runner,
+    );
+}
+
+#[test]
+fn test_nist_cavp_sha3_512_short_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/third_party/nist/SHA3_512ShortMsg_excerpt.rsp",
+        "MD",
+        sha3_512_test_runner,
+    );
+}
+
+// The messages of NISTs SHA-512 Long/Short message files, with digests
+// generated by Python's hashlib.
+#[test]
+fn test_generated_sha3_256_long_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/SHA3_256LongMsg_generated.rsp",
+        "MD",
+        sha3_256_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_sha3_256_short_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/SHA3_256ShortMsg_generated.rsp",
+        "MD",
+        sha3_256_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_sha3_512_long_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/SHA3_512LongMsg_generated.rsp",
+        "MD",
+        sha3_512_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_sha3_512_short_msg() {
+    nist_cavp_runner(
+        "./tests/test_data/SHA3_512ShortMsg_generated.rsp",
+        "MD",
+        sha3_512_test_runner,
+    );
+}
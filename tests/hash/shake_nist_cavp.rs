@@ -0,0 +1,132 @@
+// The test_nist_cavp_* tests use official data: an excerpt of the NIST CAVP
+// SHAKE128 and SHAKE256 VariableOut byte-oriented test vectors.
+// The test_generated_* tests use derived data: the messages of the NIST CAVP
+// SHA-512 ShortMsg and LongMsg files, with SHAKE outputs generated with Python
+// hashlib and cross-checked against OpenSSL. The remaining tests use outputs for
+// well-known messages.
+extern crate hex;
+use crate::hash::shake128;
+use crate::hash::shake256;
+use crate::hash::{shake128_test_runner, shake256_test_runner};
+use crate::TestCaseReader;
+
+#[test]
+fn test_shake128_long_output() {
+    // Output longer than the rate of SHAKE128 is squeezed in several permutations.
+    let expected = hex::decode("5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc844c50af32acd3f2cdd066568706f509bc1bdde58295dae3f891a9a0fca5783789a41f8611214ce612394df286a62d1a2252aa94db9c538956c717dc2bed4f232a0294c857c730aa16067ac1062f1201fb0d377cfb9cde4c63599b27f3462bba4a0ed296c801f9ff7f57302bb3076ee145f97a32ae68e76ab66c48d51675bd49acc29082f5647584e6aa01b3f5af057805f973ff8ecb8b226ac32ada6f01c1fcd4818cb006aa5b4cdb3611eb1e533c8964cacfdf31012cd3fb744d02225b988b475375faad996eb1b9176ecb0f8b2871723d6dbb804e23357e50732f5cfc904b1319795000d7361d9e5e1b77b4b8f5774aa1482cfa58f83096bdb2e06a3eed543a38919b57ecbec737f4086be007f8ef80094ceea8807193d46e9be540b6e99b4c1c71507095028a024e8d39aa8f4c5854cedd50d30a223e7d54e9a24f0a2526b31002afbd1b4ebea69c8400c3deb4c1c35d6dbb75651b284076f5fde47b4a0586ee173e30bd4d08f2bc59c6114bdd745").unwrap();
+
+    let mut state = shake128::Shake128::new();
+    state.update(b"abc").unwrap();
+    let mut reader = state.finalize().unwrap();
+    let mut actual = vec![0u8; expected.len()];
+    for chunk in actual.chunks_mut(33) {
+        reader.squeeze(chunk).unwrap();
+    }
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_shake256_long_output() {
+    // Output longer than the rate of SHAKE256 is squeezed in several permutations.
+    let expected = hex::decode("483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e41385141204f329979fd3047a13c5657724ada64d2470157b3cdc288620944d78dbcddbd912993f0913f164fb2ce95131a2d09a3e6d51cbfc622720d7a75c6334e8a2d7ec71a7cc29cf0ea610eeff1a588290a53000faa79932becec0bd3cd0b33a7e5d397fed1ada9442b99903f4dcfd8559ed3950faf40fe6f3b5d710ed3b677513771af6bfe11934817e8762d9896ba579d88d84ba7aa3cdc7055f6796f195bd9ae788f2f5bb96100d6bbaff7fbc6eea24d4449a2477d172a5507dcc931412fc346b1bb39b878330e026b12ddf384af3334560ea1d363966caa7d8ddcbec7da52b42215c11d5f8ee57f341e399343ce63a752fc5edec99124a0eb314403e5f358b8b83d05be2d2970099284b00dcc33d7c753d1f752ab743325bc53d91aa671e50f9c3f93abf6e9662f90145c61954f2abbd26edad1553ea3a626f359e8f79ade16384e151755c47e822fc74c5d710").unwrap();
+
+    let mut state = shake256::Shake256::new();
+    state.update(b"abc").unwrap();
+    let mut reader = state.finalize().unwrap();
+    let mut actual = vec![0u8; expected.len()];
+    for chunk in actual.chunks_mut(33) {
+        reader.squeeze(chunk).unwrap();
+    }
+
+    assert_eq!(expected, actual);
+}
+
+/// Runs test vectors in the format of NISTs SHAKE Variable Output files.
+fn nist_cavp_variable_out_runner(path: &str, runner: fn(&[u8], &[u8])) {
+    // "Outputlen" cannot be a field, as it begins with "Output". The output
+    // length is implied by the expected output.
+    let nist_cavp_fields: Vec<String> = vec!["Msg".into(), "Output".into()];
+    let mut nist_cavp_reader = TestCaseReader::new(path, nist_cavp_fields, "=");
+
+    let mut test_case = nist_cavp_reader.next();
+    while test_case.is_some() {
+        let tc = test_case.unwrap();
+
+        let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
+        let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Output"));
+
+        runner(&input[..], &expected_output[..]);
+
+        // Read the next one
+        test_case = nist_cavp_reader.next();
+    }
+}
+
+/// Runs the messages of NISTs SHA-512 Long/Short message files, with outputs
+/// generated by Python's hashlib.
+fn generated_runner(path: &str, runner: fn(&[u8], &[u8])) {
+    let fields: Vec<String> = vec!["Len".into(), "Msg".into(), "Output".into()];
+    let mut reader = TestCaseReader::new(path, fields, "=");
+
+    let mut test_case = reader.next();
+    while test_case.is_some() {
+        let tc = test_case.unwrap();
+
+        let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
+        let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Output"));
+
+        runner(&input[..], &expected_output[..]);
+
+        // Read the next one
+        test_case = reader.next();
+    }
+}
+
+#[test]
+fn test_nist_cavp_shake128_variable_out() {
+    nist_cavp_variable_out_runner(
+        "./tests/test_data/third_party/nist/SHAKE128VariableOut_excerpt.rsp",
+        shake128_test_runner,
+    );
+}
+
+#[test]
+fn test_nist_cavp_shake256_variable_out() {
+    nist_cavp_variable_out_runner(
+        "./tests/test_data/third_party/nist/SHAKE256VariableOut_excerpt.rsp",
+        shake256_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_shake128_long_msg() {
+    generated_runner(
+        "./tests/test_data/SHAKE128LongMsg_generated.rsp",
+        shake128_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_shake128_short_msg() {
+    generated_runner(
+        "./tests/test_data/SHAKE128ShortMsg_generated.rsp",
+        shake128_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_shake256_long_msg() {
+    generated_runner(
+        "./tests/test_data/SHAKE256LongMsg_generated.rsp",
+        shake256_test_runner,
+    );
+}
+
+#[test]
+fn test_generated_shake256_short_msg() {
+    generated_runner(
+        "./tests/test_data/SHAKE256ShortMsg_generated.rsp",
+        shake256_test_runner,
+    );
+}
@@ -0,0 +1,77 @@
+// Test vectors from the test suite of Openwall's crypt_blowfish: https://www.openwall.com/crypt/
+// The salts and password hashes have been decoded from their bcrypt Base64 encoding.
+// The last test vector was generated with libxcrypt.
+
+#[cfg(test)]
+mod openwall_bcrypt {
+
+    extern crate hex;
+    extern crate orion;
+
+    use self::orion::hazardous::kdf::bcrypt;
+
+    fn bcrypt_test_runner(password: &[u8], salt: &str, cost: u32, expected: &str) {
+        let salt = hex::decode(salt).unwrap();
+        let expected = hex::decode(expected).unwrap();
+        let mut actual = [0u8; bcrypt::HASH_LENGTH];
+
+        bcrypt::hash(password, &salt, cost, &mut actual).unwrap();
+        assert_eq!(&actual[..], &expected[..]);
+        assert!(bcrypt::verify(&expected, password, &salt, cost, &mut actual).is_ok());
+    }
+
+    #[test]
+    fn test_case_1() {
+        // $2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW
+        bcrypt_test_runner(
+            b"U*U",
+            "10410410410410410410410410410410",
+            5,
+            "1bb69143f9a8d304c8d23d99ab049a77a68e2ccc744206",
+        );
+    }
+
+    #[test]
+    fn test_case_2() {
+        // $2a$05$CCCCCCCCCCCCCCCCCCCCC.7uG0VCzI2bS7j6ymqJi9CdcdxiRTWNy
+        bcrypt_test_runner(
+            b"",
+            "10410410410410410410410410410410",
+            5,
+            "f702365c4d4ae1d53d97cd28b0b93f11f79fce44d560fd",
+        );
+    }
+
+    #[test]
+    fn test_case_3() {
+        // $2a$05$abcdefghijklmnopqrstuu5s2v8.iXieOjg/.AySBTTZIIVFJeBui
+        bcrypt_test_runner(
+            b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789chars after 72 are ignored",
+            "71d79f8218a39259a7a29aabb2dbafc3",
+            5,
+            "eeee31f80919920425881002d140d555b28a5c72e00f09",
+        );
+    }
+
+    #[test]
+    fn test_case_4() {
+        // $2y$05$/OK.fbVrR/bpIqNJ5ianF.nRht2l/HRhr6zmCp9vYUvvsqynflf9e
+        bcrypt_test_runner(
+            b"\xff\xa3345",
+            "05030085d5ed4c176b2ac3cbee47291c",
+            5,
+            "a538efe270494e3b7cd6812bff1696c71bacd2986787f8",
+        );
+    }
+
+    #[test]
+    fn test_case_5() {
+        // $2b$10$c29tZXNhbHRzb21lc2Fsd.8ty29KeCeXeGQNFfTErDtpeCxBNfFjy
+        bcrypt_test_runner(
+            b"password",
+            "7b8fef6d93e37494f5778de77b81ee7c",
+            10,
+            "fafd38fcc80481980848f1e1546b45beb804cc33e11e5d",
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// Test vectors from RFC 9106: https://www.rfc-editor.org/rfc/rfc9106.html#section-5
+// The remaining test vectors, which use a memory size that is not a multiple of 4 * lanes,
+// were generated with the Argon2 implementation in OpenSSL 3.5.
+
+#[cfg(test)]
+mod argon2_kat {
+
+    extern crate hex;
+    extern crate orion;
+
+    use self::orion::hazardous::kdf::{argon2d, argon2i, argon2id};
+
+    #[test]
+    fn test_argon2d_rfc() {
+        let memory: u32 = 32;
+        let iterations: u32 = 3;
+        let lanes: u32 = 4;
+        let password =
+            hex::decode("0101010101010101010101010101010101010101010101010101010101010101")
+                .unwrap();
+        let salt = hex::decode("02020202020202020202020202020202").unwrap();
+        let secret = hex::decode("0303030303030303").unwrap();
+        let ad = hex::decode("040404040404040404040404").unwrap();
+        let expected_hash =
+            hex::decode("512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2d::verify_with_lanes(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut actual
+        )
+        .is_ok());
+
+        let mut blocks = vec![[0u64; 128]; memory as usize];
+        assert!(argon2d::verify_with_memory(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut blocks,
+            &mut actual
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_argon2i_rfc() {
+        let memory: u32 = 32;
+        let iterations: u32 = 3;
+        let lanes: u32 = 4;
+        let password =
+            hex::decode("0101010101010101010101010101010101010101010101010101010101010101")
+                .unwrap();
+        let salt = hex::decode("02020202020202020202020202020202").unwrap();
+        let secret = hex::decode("0303030303030303").unwrap();
+        let ad = hex::decode("040404040404040404040404").unwrap();
+        let expected_hash =
+            hex::decode("c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2i::verify_with_lanes(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut actual
+        )
+        .is_ok());
+
+        let mut blocks = vec![[0u64; 128]; memory as usize];
+        assert!(argon2i::verify_with_memory(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut blocks,
+            &mut actual
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_argon2id_rfc() {
+        let memory: u32 = 32;
+        let iterations: u32 = 3;
+        let lanes: u32 = 4;
+        let password =
+            hex::decode("0101010101010101010101010101010101010101010101010101010101010101")
+                .unwrap();
+        let salt = hex::decode("02020202020202020202020202020202").unwrap();
+        let secret = hex::decode("0303030303030303").unwrap();
+        let ad = hex::decode("040404040404040404040404").unwrap();
+        let expected_hash =
+            hex::decode("0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2id::verify_with_lanes(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut actual
+        )
+        .is_ok());
+
+        let mut blocks = vec![[0u64; 128]; memory as usize];
+        assert!(argon2id::verify_with_memory(
+            &expected_hash,
+            &password,
+            &salt,
+            iterations,
+            memory,
+            lanes,
+            Some(&secret),
+            Some(&ad),
+            &mut blocks,
+            &mut actual
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_argon2d_uneven_memory() {
+        let memory: u32 = 100;
+        let iterations: u32 = 2;
+        let lanes: u32 = 3;
+        let password = b"password";
+        let salt = b"somesaltsomesalt";
+        let expected_hash =
+            hex::decode("70fa478d69791ffd2ffa6bba92f4fe49b9d9eaeef1828c6381d0485b9ac86e43")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2d::verify_with_lanes(
+            &expected_hash,
+            password,
+            salt,
+            iterations,
+            memory,
+            lanes,
+            None,
+            None,
+            &mut actual
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_argon2i_uneven_memory() {
+        let memory: u32 = 100;
+        let iterations: u32 = 2;
+        let lanes: u32 = 3;
+        let password = b"password";
+        let salt = b"somesaltsomesalt";
+        let expected_hash =
+            hex::decode("f56d694bc52dc8cb78690afe3831d23a8c1298cc878c5945a50ccbdccd6f0b1a")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2i::verify_with_lanes(
+            &expected_hash,
+            password,
+            salt,
+            iterations,
+            memory,
+            lanes,
+            None,
+            None,
+            &mut actual
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_argon2id_uneven_memory() {
+        let memory: u32 = 100;
+        let iterations: u32 = 2;
+        let lanes: u32 = 3;
+        let password = b"password";
+        let salt = b"somesaltsomesalt";
+        let expected_hash =
+            hex::decode("b2e09be5fde45e9e39e720f17158c86353c3455f75a01f3cc290f14f6dda51a7")
+                .unwrap();
+
+        let mut actual = vec![0u8; expected_hash.len()];
+        assert!(argon2id::verify_with_lanes(
+            &expected_hash,
+            password,
+            salt,
+            iterations,
+            memory,
+            lanes,
+            None,
+            None,
+            &mut actual
+        )
+        .is_ok());
+    }
+}
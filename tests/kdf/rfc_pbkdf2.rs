@@ -0,0 +1,110 @@
+// Testing against RFC 6070 test vectors for PBKDF2-HMAC-SHA1, except the
+// test case with 16777216 iterations, and the IEEE 802.11i (WPA2) PSK test
+// vectors.
+
+#[cfg(test)]
+mod rfc6070 {
+
+    extern crate hex;
+    extern crate orion;
+
+    use self::{hex::decode, orion::hazardous::kdf::pbkdf2::sha1::*};
+
+    #[test]
+    fn test_case_1() {
+        let password = Password::from_slice("password".as_bytes()).unwrap();
+        let salt = "salt".as_bytes();
+        let iter = 1;
+        let mut dk_out = [0u8; 20];
+
+        let expected_dk = decode("0c60c80f961f0e71f3a9b524af6012062fe037a6").unwrap();
+
+        // verify() also runs derive_key()
+        assert!(verify(&expected_dk, &password, salt, iter, &mut dk_out).is_ok());
+    }
+
+    #[test]
+    fn test_case_2() {
+        let password = Password::from_slice("password".as_bytes()).unwrap();
+        let salt = "salt".as_bytes();
+        let iter = 2;
+        let mut dk_out = [0u8; 20];
+
+        let expected_dk = decode("ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957").unwrap();
+
+        // verify() also runs derive_key()
+        assert!(verify(&expected_dk, &password, salt, iter, &mut dk_out).is_ok());
+    }
+
+    #[test]
+    fn test_case_3() {
+        let password = Password::from_slice("password".as_bytes()).unwrap();
+        let salt = "salt".as_bytes();
+        let iter = 4096;
+        let mut dk_out = [0u8; 20];
+
+        let expected_dk = decode("4b007901b765489abead49d926f721d065a429c1").unwrap();
+
+        // verify() also runs derive_key()
+        assert!(verify(&expected_dk, &password, salt, iter, &mut dk_out).is_ok());
+    }
+
+    #[test]
+    fn test_case_4() {
+        let password = Password::from_slice("passwordPASSWORDpassword".as_bytes()).unwrap();
+        let salt = "saltSALTsaltSALTsaltSALTsaltSALTsalt".as_bytes();
+        let iter = 4096;
+        let mut dk_out = [0u8; 25];
+
+        let expected_dk = decode("3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038").unwrap();
+
+        // verify() also runs derive_key()
+        assert!(verify(&expected_dk, &password, salt, iter, &mut dk_out).is_ok());
+    }
+
+    #[test]
+    fn test_case_5() {
+        let password = Password::from_slice("pass\0word".as_bytes()).unwrap();
+        let salt = "sa\0lt".as_bytes();
+        let iter = 4096;
+        let mut dk_out = [0u8; 16];
+
+        let expected_dk = decode("56fa6aa75548099dcc37d7f03425e0c3").unwrap();
+
+        // verify() also runs derive_key()
+        assert!(verify(&expected_dk, &password, salt, iter, &mut dk_out).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod ieee_802_11i {
+
+    extern crate hex;
+    extern crate orion;
+
+    use self::{hex::decode, orion::hazardous::kdf::pbkdf2::sha1::*};
+
+    #[test]
+    fn test_case_1() {
+        let password = Password::from_slice("password".as_bytes()).unwrap();
+        let ssid = "IEEE".as_bytes();
+        let mut psk_out = [0u8; 32];
+
+        let expected_psk =
+            decode("f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e").unwrap();
+
+        assert!(verify(&expected_psk, &password, ssid, 4096, &mut psk_out).is_ok());
+    }
+
+    #[test]
+    fn test_case_2() {
+        let password = Password::from_slice("ThisIsAPassword".as_bytes()).unwrap();
+        let ssid = "ThisIsASSID".as_bytes();
+        let mut psk_out = [0u8; 32];
+
+        let expected_psk =
+            decode("0dc0d6eb90555ed6419756b9a15ec3e3209b63df707dd508d14581f8982721af").unwrap();
+
+        assert!(verify(&expected_psk, &password, ssid, 4096, &mut psk_out).is_ok());
+    }
+}
@@ -1,15 +1,19 @@
 pub mod boringssl_poly1305;
 pub mod nist_cavp_hmac;
+pub mod nist_kmac;
 pub mod other_poly1305;
 pub mod rfc_hmac;
 pub mod rfc_poly1305;
+pub mod wycheproof_hmac_sha256;
 pub mod wycheproof_hmac_sha512;
 
 extern crate orion;
 
 use self::{
-    orion::hazardous::hash::sha512::SHA512_OUTSIZE,
-    orion::hazardous::mac::{hmac, poly1305},
+    orion::hazardous::hash::{
+        sha256::SHA256_OUTSIZE, sha384::SHA384_OUTSIZE, sha512::SHA512_OUTSIZE,
+    },
+    orion::hazardous::mac::{hmac, kmac, poly1305},
     poly1305::{OneTimeKey, Tag},
 };
 
@@ -49,6 +53,74 @@ fn hmac_test_runner(
     }
 }
 
+fn hmac_sha256_test_runner(
+    expected: &[u8],
+    secret_key: &[u8],
+    data: &[u8],
+    len_bytes: Option<usize>,
+    valid_result: bool,
+) {
+    let len = match len_bytes {
+        Some(length) => length,
+        None => SHA256_OUTSIZE,
+    };
+
+    let key = hmac::sha256::SecretKey::from_slice(secret_key).unwrap();
+
+    if len == SHA256_OUTSIZE {
+        let expected_tag = hmac::sha256::Tag::from_slice(expected).unwrap();
+        let res = hmac::sha256::HmacSha256::verify(&expected_tag, &key, data);
+        if valid_result {
+            assert!(res.is_ok());
+        } else {
+            assert!(res.is_err());
+        }
+    } else {
+        let mut ctx = hmac::sha256::HmacSha256::new(&key);
+        ctx.update(data).unwrap();
+        let actual = ctx.finalize().unwrap();
+        if valid_result {
+            assert_eq!(expected, actual.unprotected_as_bytes()[..len].as_ref());
+        } else {
+            assert_ne!(expected, actual.unprotected_as_bytes()[..len].as_ref());
+        }
+    }
+}
+
+fn hmac_sha384_test_runner(
+    expected: &[u8],
+    secret_key: &[u8],
+    data: &[u8],
+    len_bytes: Option<usize>,
+    valid_result: bool,
+) {
+    let len = match len_bytes {
+        Some(length) => length,
+        None => SHA384_OUTSIZE,
+    };
+
+    let key = hmac::sha384::SecretKey::from_slice(secret_key).unwrap();
+
+    if len == SHA384_OUTSIZE {
+        let expected_tag = hmac::sha384::Tag::from_slice(expected).unwrap();
+        let res = hmac::sha384::HmacSha384::verify(&expected_tag, &key, data);
+        if valid_result {
+            assert!(res.is_ok());
+        } else {
+            assert!(res.is_err());
+        }
+    } else {
+        let mut ctx = hmac::sha384::HmacSha384::new(&key);
+        ctx.update(data).unwrap();
+        let actual = ctx.finalize().unwrap();
+        if valid_result {
+            assert_eq!(expected, actual.unprotected_as_bytes()[..len].as_ref());
+        } else {
+            assert_ne!(expected, actual.unprotected_as_bytes()[..len].as_ref());
+        }
+    }
+}
+
 fn poly1305_test_runner(key: &[u8], input: &[u8], output: &[u8]) {
     let sk = OneTimeKey::from_slice(key).unwrap();
 
@@ -62,3 +134,47 @@ fn poly1305_test_runner(key: &[u8], input: &[u8], output: &[u8]) {
     assert!(tag_one_shot == output);
     assert!(poly1305::Poly1305::verify(&Tag::from_slice(&output).unwrap(), &sk, input).is_ok());
 }
+
+fn kmac128_test_runner(
+    expected: &[u8],
+    secret_key: &[u8],
+    customization: &[u8],
+    data: &[u8],
+    is_xof: bool,
+) {
+    let key = kmac::SecretKey::from_slice(secret_key).unwrap();
+    let mut state = kmac::Kmac128::new(&key, customization).unwrap();
+    state.update(data).unwrap();
+    let mut actual = vec![0u8; expected.len()];
+
+    if is_xof {
+        state.finalize_xof().unwrap().squeeze(&mut actual).unwrap();
+    } else {
+        state.finalize(&mut actual).unwrap();
+        assert!(kmac::Kmac128::verify(expected, &key, customization, data).is_ok());
+    }
+
+    assert_eq!(expected, &actual[..]);
+}
+
+fn kmac256_test_runner(
+    expected: &[u8],
+    secret_key: &[u8],
+    customization: &[u8],
+    data: &[u8],
+    is_xof: bool,
+) {
+    let key = kmac::SecretKey::from_slice(secret_key).unwrap();
+    let mut state = kmac::Kmac256::new(&key, customization).unwrap();
+    state.update(data).unwrap();
+    let mut actual = vec![0u8; expected.len()];
+
+    if is_xof {
+        state.finalize_xof().unwrap().squeeze(&mut actual).unwrap();
+    } else {
+        state.finalize(&mut actual).unwrap();
+        assert!(kmac::Kmac256::verify(expected, &key, customization, data).is_ok());
+    }
+
+    assert_eq!(expected, &actual[..]);
+}
@@ -1,8 +1,9 @@
-use crate::mac::hmac_test_runner;
+use crate::mac::{hmac_sha256_test_runner, hmac_sha384_test_runner, hmac_test_runner};
 use crate::TestCaseReader;
 
-#[test]
-fn test_nist_cavp() {
+/// Run the test cases of a `section` in NISTs HMAC.rsp, such as `[L=32]`,
+/// where `L` is the output size of the hash function in bytes.
+fn nist_cavp_runner(section: &str, runner: fn(&[u8], &[u8], &[u8], Option<usize>, bool)) {
     let nist_cavp_fields: Vec<String> = vec![
         "Count".into(),
         "Klen".into(),
@@ -17,23 +18,30 @@ fn test_nist_cavp() {
         "=",
     );
 
-    // Skip ahead in the file until [L=64] is reached so that we read
-    // only SHA512 test cases.
+    // Skip ahead in the file until the section is reached so that we read
+    // only test cases for the given hash function.
     let mut line = nist_cavp_reader.lines.next().unwrap().unwrap();
-    while line != "[L=64]" {
+    while line != section {
         line = nist_cavp_reader.lines.next().unwrap().unwrap();
     }
 
     let mut test_case = nist_cavp_reader.next();
+    let mut is_first = true;
     while test_case.is_some() {
         let tc = test_case.unwrap();
 
+        // The count restarts at zero when the next section begins.
+        if !is_first && tc.get_data("Count") == "0" {
+            break;
+        }
+        is_first = false;
+
         let key: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Key"));
         let input: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Msg"));
         let tag_length: usize = tc.get_data("Tlen").parse::<usize>().unwrap();
         let expected_output: Vec<u8> = TestCaseReader::default_parse(tc.get_data("Mac"));
 
-        hmac_test_runner(
+        runner(
             &expected_output[..],
             &key[..],
             &input[..],
@@ -45,3 +53,18 @@ fn test_nist_cavp() {
         test_case = nist_cavp_reader.next();
     }
 }
+
+#[test]
+fn test_nist_cavp() {
+    nist_cavp_runner("[L=64]", hmac_test_runner);
+}
+
+#[test]
+fn test_nist_cavp_sha256() {
+    nist_cavp_runner("[L=32]", hmac_sha256_test_runner);
+}
+
+#[test]
+fn test_nist_cavp_sha384() {
+    nist_cavp_runner("[L=48]", hmac_sha384_test_runner);
+}
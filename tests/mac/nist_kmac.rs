@@ -0,0 +1,158 @@
+// Testing against the KMAC and KMACXOF samples of NIST SP 800-185, from:
+// https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values
+#[cfg(test)]
+mod nist_sp800_185 {
+
+    extern crate hex;
+
+    use self::hex::decode;
+    use crate::mac::{kmac128_test_runner, kmac256_test_runner};
+
+    /// The key of all samples: the bytes 0x40 to 0x5F.
+    fn key() -> Vec<u8> {
+        (0x40..0x60).collect()
+    }
+
+    /// The data of the samples: the bytes 0x00 to 0x03, or 0x00 to 0xC7.
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn test_kmac_sample_1() {
+        let expected =
+            decode("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e").unwrap();
+
+        kmac128_test_runner(&expected, &key(), b"", &data(4), false);
+    }
+
+    #[test]
+    fn test_kmac_sample_2() {
+        let expected =
+            decode("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5").unwrap();
+
+        kmac128_test_runner(&expected, &key(), b"My Tagged Application", &data(4), false);
+    }
+
+    #[test]
+    fn test_kmac_sample_3() {
+        let expected =
+            decode("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230").unwrap();
+
+        kmac128_test_runner(
+            &expected,
+            &key(),
+            b"My Tagged Application",
+            &data(200),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_kmac_sample_4() {
+        let expected = decode(
+            "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7\
+             f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd",
+        )
+        .unwrap();
+
+        kmac256_test_runner(&expected, &key(), b"My Tagged Application", &data(4), false);
+    }
+
+    #[test]
+    fn test_kmac_sample_5() {
+        let expected = decode(
+            "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691\
+             589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69",
+        )
+        .unwrap();
+
+        kmac256_test_runner(&expected, &key(), b"", &data(200), false);
+    }
+
+    #[test]
+    fn test_kmac_sample_6() {
+        let expected = decode(
+            "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9\
+             70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965",
+        )
+        .unwrap();
+
+        kmac256_test_runner(
+            &expected,
+            &key(),
+            b"My Tagged Application",
+            &data(200),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_kmacxof_sample_1() {
+        let expected =
+            decode("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35").unwrap();
+
+        kmac128_test_runner(&expected, &key(), b"", &data(4), true);
+    }
+
+    #[test]
+    fn test_kmacxof_sample_2() {
+        let expected =
+            decode("31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c").unwrap();
+
+        kmac128_test_runner(&expected, &key(), b"My Tagged Application", &data(4), true);
+    }
+
+    #[test]
+    fn test_kmacxof_sample_3() {
+        let expected =
+            decode("47026c7cd793084aa0283c253ef658490c0db61438b8326fe9bddf281b83ae0f").unwrap();
+
+        kmac128_test_runner(
+            &expected,
+            &key(),
+            b"My Tagged Application",
+            &data(200),
+            true,
+        );
+    }
+
+    #[test]
+    fn test_kmacxof_sample_4() {
+        let expected = decode(
+            "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9\
+             6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b",
+        )
+        .unwrap();
+
+        kmac256_test_runner(&expected, &key(), b"My Tagged Application", &data(4), true);
+    }
+
+    #[test]
+    fn test_kmacxof_sample_5() {
+        let expected = decode(
+            "ff7b171f1e8a2b24683eed37830ee797538ba8dc563f6da1e667391a75edc02c\
+             a633079f81ce12a25f45615ec89972031d18337331d24ceb8f8ca8e6a19fd98b",
+        )
+        .unwrap();
+
+        kmac256_test_runner(&expected, &key(), b"", &data(200), true);
+    }
+
+    #[test]
+    fn test_kmacxof_sample_6() {
+        let expected = decode(
+            "d5be731c954ed7732846bb59dbe3a8e30f83e77a4bff4459f2f1c2b4ecebb8ce\
+             67ba01c62e8ab8578d2d499bd1bb276768781190020a306a97de281dcc30305d",
+        )
+        .unwrap();
+
+        kmac256_test_runner(
+            &expected,
+            &key(),
+            b"My Tagged Application",
+            &data(200),
+            true,
+        );
+    }
+}
@@ -127,3 +127,245 @@ mod rfc4231 {
         hmac_test_runner(&expected, &secret_key, &data, None, true);
     }
 }
+
+// Testing against RFC 4231 test vectors for HMAC-SHA256
+#[cfg(test)]
+mod rfc4231_sha256 {
+
+    extern crate hex;
+
+    use self::hex::decode;
+    use crate::mac::hmac_sha256_test_runner;
+
+    #[test]
+    fn test_case_1() {
+        let secret_key = decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b").unwrap();
+        let data = "Hi There".as_bytes().to_vec();
+        let expected =
+            decode("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_2() {
+        let secret_key = "Jefe".as_bytes().to_vec();
+        let data = "what do ya want for nothing?".as_bytes().to_vec();
+        let expected =
+            decode("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_3() {
+        let secret_key = decode("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        let data = decode(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd\
+             dddddddddddddddddddddddddddddddddddd",
+        )
+        .unwrap();
+        let expected =
+            decode("773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_4() {
+        let secret_key = decode("0102030405060708090a0b0c0d0e0f10111213141516171819").unwrap();
+        let data = decode(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd\
+             cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+        )
+        .unwrap();
+        let expected =
+            decode("82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_5() {
+        let secret_key = decode("0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c").unwrap();
+        let data = decode("546573742057697468205472756e636174696f6e").unwrap();
+        let expected = decode("a3b6167473100ee06e0c796c2955552b").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, Some(expected.len()), true);
+    }
+
+    #[test]
+    fn test_case_6() {
+        let secret_key = decode(
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaa",
+        )
+        .unwrap();
+        let data = decode(
+            "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a\
+             65204b6579202d2048617368204b6579204669727374",
+        )
+        .unwrap();
+        let expected =
+            decode("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_7() {
+        let secret_key = decode(
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaa",
+        )
+        .unwrap();
+        let data = decode(
+            "5468697320697320612074657374207573696e672061206c6172676572207468\
+             616e20626c6f636b2d73697a65206b657920616e642061206c61726765722074\
+             68616e20626c6f636b2d73697a6520646174612e20546865206b6579206e6565\
+             647320746f20626520686173686564206265666f7265206265696e6720757365\
+             642062792074686520484d414320616c676f726974686d2e",
+        )
+        .unwrap();
+        let expected =
+            decode("9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2").unwrap();
+
+        hmac_sha256_test_runner(&expected, &secret_key, &data, None, true);
+    }
+}
+
+// Testing against RFC 4231 test vectors for HMAC-SHA384
+#[cfg(test)]
+mod rfc4231_sha384 {
+
+    extern crate hex;
+
+    use self::hex::decode;
+    use crate::mac::hmac_sha384_test_runner;
+
+    #[test]
+    fn test_case_1() {
+        let secret_key = decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b").unwrap();
+        let data = "Hi There".as_bytes().to_vec();
+        let expected = decode(
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+             faea9ea9076ede7f4af152e8b2fa9cb6",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_2() {
+        let secret_key = "Jefe".as_bytes().to_vec();
+        let data = "what do ya want for nothing?".as_bytes().to_vec();
+        let expected = decode(
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+             8e2240ca5e69e2c78b3239ecfab21649",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_3() {
+        let secret_key = decode("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        let data = decode(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd\
+             dddddddddddddddddddddddddddddddddddd",
+        )
+        .unwrap();
+        let expected = decode(
+            "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b\
+             2a5ab39dc13814b94e3ab6e101a34f27",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_4() {
+        let secret_key = decode("0102030405060708090a0b0c0d0e0f10111213141516171819").unwrap();
+        let data = decode(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd\
+             cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+        )
+        .unwrap();
+        let expected = decode(
+            "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e\
+             6801dd23c4a7d679ccf8a386c674cffb",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_5() {
+        let secret_key = decode("0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c").unwrap();
+        let data = decode("546573742057697468205472756e636174696f6e").unwrap();
+        let expected = decode("3abf34c3503b2a23a46efc619baef897").unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, Some(expected.len()), true);
+    }
+
+    #[test]
+    fn test_case_6() {
+        let secret_key = decode(
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaa",
+        )
+        .unwrap();
+        let data = decode(
+            "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a\
+             65204b6579202d2048617368204b6579204669727374",
+        )
+        .unwrap();
+        let expected = decode(
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c6\
+             0c2ef6ab4030fe8296248df163f44952",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+
+    #[test]
+    fn test_case_7() {
+        let secret_key = decode(
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+             aaaaaa",
+        )
+        .unwrap();
+        let data = decode(
+            "5468697320697320612074657374207573696e672061206c6172676572207468\
+             616e20626c6f636b2d73697a65206b657920616e642061206c61726765722074\
+             68616e20626c6f636b2d73697a6520646174612e20546865206b6579206e6565\
+             647320746f20626520686173686564206265666f7265206265696e6720757365\
+             642062792074686520484d414320616c676f726974686d2e",
+        )
+        .unwrap();
+        let expected = decode(
+            "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5\
+             a678cc31e799176d3860e6110c46523e",
+        )
+        .unwrap();
+
+        hmac_sha384_test_runner(&expected, &secret_key, &data, None, true);
+    }
+}
@@ -0,0 +1,79 @@
+// Testing against Google Wycheproof test vectors
+// These are the valid test cases of testvectors/hmac_sha256_test.json in https://github.com/google/wycheproof,
+// as distributed by the RustCrypto hmac crate (v0.12.1, tests/data/wycheproof-sha256.blb, MIT OR Apache-2.0)
+// in the blobby format: https://docs.rs/blobby/0.3.1/blobby/
+// Each test case is a key, message and tag. Tags of 16 bytes are truncated HMAC-SHA256 tags.
+use crate::mac::hmac_sha256_test_runner;
+use std::fs;
+
+/// Read a variable-length quantity starting at `data[*pos]`.
+fn read_vlq(data: &[u8], pos: &mut usize) -> usize {
+    let mut byte = data[*pos];
+    *pos += 1;
+    let mut value = (byte & 0x7F) as usize;
+    while byte & 0x80 != 0 {
+        byte = data[*pos];
+        *pos += 1;
+        value = ((value + 1) << 7) + (byte & 0x7F) as usize;
+    }
+
+    value
+}
+
+/// Read all blobs in a blobby file, resolving the deduplicated ones.
+fn read_blobs(data: &[u8]) -> Vec<&[u8]> {
+    let mut pos = 0;
+    let mut dedup = Vec::new();
+    for _ in 0..read_vlq(data, &mut pos) {
+        let len = read_vlq(data, &mut pos);
+        dedup.push(&data[pos..pos + len]);
+        pos += len;
+    }
+
+    let mut blobs = Vec::new();
+    while pos < data.len() {
+        let value = read_vlq(data, &mut pos);
+        if value & 1 == 1 {
+            blobs.push(dedup[value >> 1]);
+        } else {
+            let len = value >> 1;
+            blobs.push(&data[pos..pos + len]);
+            pos += len;
+        }
+    }
+
+    blobs
+}
+
+fn wycheproof_runner(path: &str) {
+    let data = fs::read(path).unwrap();
+    let blobs = read_blobs(&data);
+    assert_eq!(blobs.len() % 3, 0);
+
+    let mut tcid = 0;
+    for test_case in blobs.chunks_exact(3) {
+        let (key, msg, tag) = (test_case[0], test_case[1], test_case[2]);
+        tcid += 1;
+        println!("test case: {}, len: {}", tcid, tag.len());
+
+        // Truncated tags (128 bits) are compared against the same-length
+        // prefix of the full tag.
+        hmac_sha256_test_runner(tag, key, msg, Some(tag.len()), true);
+
+        // Any modification of the tag must be rejected.
+        for idx in [0, tag.len() - 1].iter() {
+            let mut modified_tag = tag.to_vec();
+            modified_tag[*idx] ^= 1;
+            hmac_sha256_test_runner(&modified_tag, key, msg, Some(tag.len()), false);
+        }
+    }
+    // hmac_sha256_test.json has 66 valid test cases.
+    assert_eq!(tcid, 66);
+}
+
+#[test]
+fn test_wycheproof_hmac_sha256() {
+    wycheproof_runner(
+        "./tests/test_data/third_party/google/wycheproof/wycheproof_hmac_sha256_valid.blb",
+    );
+}
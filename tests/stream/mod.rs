@@ -1,3 +1,4 @@
+pub mod nacl_xsalsa20;
 pub mod other_chacha20;
 pub mod rfc_chacha20;
 pub mod rfc_xchacha20;
@@ -8,12 +9,36 @@ use self::{
     chacha20::SecretKey,
     orion::hazardous::stream::{
         chacha20::{self, IETF_CHACHA_NONCESIZE},
+        salsa20::{self, SALSA_NONCESIZE},
         xchacha20::{self, XCHACHA_NONCESIZE},
+        xsalsa20::{self, XSALSA_NONCESIZE},
     },
 };
 use orion::hazardous::stream::chacha20::CHACHA_KEYSIZE;
 use orion::test_framework::streamcipher_interface::StreamCipherTestRunner;
 
+/// Apply the keystream to `input` in two parts, splitting it in the middle of
+/// a block, and then seek back to check decryption.
+macro_rules! keystream_test_runner {
+    ($keystream:expr, $init_block_count:expr, $input:expr, $output:expr $(,)?) => {{
+        let keystream = $keystream;
+        let start = $init_block_count as u64 * 64;
+        let split = core::cmp::min($input.len(), 37);
+        let mut actual = $input.to_vec();
+
+        keystream.seek(start).unwrap();
+        keystream.apply_keystream(&mut actual[..split]).unwrap();
+        keystream.apply_keystream(&mut actual[split..]).unwrap();
+        assert_eq!(&actual[..], $output);
+        assert_eq!(keystream.position(), start + $input.len() as u64);
+
+        let mut decrypted = vec![0u8; $input.len()];
+        keystream.seek(start).unwrap();
+        keystream.decrypt(&actual, &mut decrypted).unwrap();
+        assert_eq!(&decrypted[..], $input);
+    }};
+}
+
 pub fn chacha_test_runner(
     key: &[u8],
     nonce: &[u8],
@@ -34,6 +59,12 @@ pub fn chacha_test_runner(
     // Selecting variant based on nonce size
     if nonce.len() == IETF_CHACHA_NONCESIZE {
         let n = chacha20::Nonce::from_slice(&nonce).unwrap();
+        keystream_test_runner!(
+            &mut chacha20::ChaCha20Keystream::new(&sk, &n),
+            init_block_count,
+            input,
+            output,
+        );
         StreamCipherTestRunner(
             chacha20::encrypt,
             chacha20::decrypt,
@@ -45,6 +76,12 @@ pub fn chacha_test_runner(
         );
     } else if nonce.len() == XCHACHA_NONCESIZE {
         let n = xchacha20::Nonce::from_slice(&nonce).unwrap();
+        keystream_test_runner!(
+            &mut xchacha20::XChaCha20Keystream::new(&sk, &n),
+            init_block_count,
+            input,
+            output,
+        );
         StreamCipherTestRunner(
             xchacha20::encrypt,
             xchacha20::decrypt,
@@ -59,3 +96,45 @@ pub fn chacha_test_runner(
         assert!(xchacha20::Nonce::from_slice(&nonce).is_err());
     }
 }
+
+pub fn salsa_test_runner(
+    key: &[u8],
+    nonce: &[u8],
+    init_block_count: u32,
+    input: &[u8],
+    output: &[u8],
+) {
+    if input.is_empty() || output.is_empty() {
+        return;
+    }
+
+    let sk = salsa20::SecretKey::from_slice(&key).unwrap();
+
+    // Selecting variant based on nonce size
+    if nonce.len() == SALSA_NONCESIZE {
+        let n = salsa20::Nonce::from_slice(&nonce).unwrap();
+        StreamCipherTestRunner(
+            salsa20::encrypt,
+            salsa20::decrypt,
+            sk,
+            n,
+            init_block_count,
+            input,
+            Some(output),
+        );
+    } else if nonce.len() == XSALSA_NONCESIZE {
+        let n = xsalsa20::Nonce::from_slice(&nonce).unwrap();
+        StreamCipherTestRunner(
+            xsalsa20::encrypt,
+            xsalsa20::decrypt,
+            sk,
+            n,
+            init_block_count,
+            input,
+            Some(output),
+        );
+    } else {
+        assert!(salsa20::Nonce::from_slice(&nonce).is_err());
+        assert!(xsalsa20::Nonce::from_slice(&nonce).is_err());
+    }
+}